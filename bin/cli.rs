@@ -0,0 +1,260 @@
+//! Command line administration of a deployed registry.
+//!
+//! Each subcommand builds its transaction with `client::instructions`, signs
+//! it with one keypair (the Solana CLI default unless `--keypair` is given)
+//! and sends it to the config the program's config link points at (the
+//! config PDA before initialization). Registries that need accounts the CLI
+//! cannot build on its own, such as blocklist proofs, commit-reveal or
+//! multisig cosigners, are refused up front rather than failing on chain.
+//!
+//! Usage: `cli [--url RPC_URL] [--keypair KEYPAIR] <PROGRAM_ID> <COMMAND> [ARGS]`
+//! (defaults to devnet), where `COMMAND` is one of
+//!
+//! - `init <REGISTRATION_FEE>`: initialize the registry, signed by the
+//!   upgrade authority;
+//! - `register <NAME>`: register a top-level name to the signer;
+//! - `resolve <NAME>`: print the address a top-level name resolves to;
+//! - `set-fee <REGISTRATION_FEE>`: set a flat registration fee;
+//! - `transfer-owner <NEW_OWNER>`: propose a new config owner, who accepts
+//!   with `accept_program_ownership`;
+//! - `withdraw <AMOUNT|all> [DESTINATION]`: withdraw fees, to the signer by
+//!   default.
+//!
+//! Fees and amounts are in lamports. Admin commands on a registry with an
+//! admin timelock queue the change instead of applying it.
+
+use std::{env, process, str::FromStr};
+
+use instant_folio::{
+    client::{instructions, read::ReadTx},
+    pda::{find_config_address, find_name_address},
+    state::{ConfigLink, ProgramConfig, UsdPricing},
+};
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+const USAGE: &str = "usage: cli [--url RPC_URL] [--keypair KEYPAIR] <PROGRAM_ID> <COMMAND> [ARGS]
+
+commands:
+    init <REGISTRATION_FEE>
+    register <NAME>
+    resolve <NAME>
+    set-fee <REGISTRATION_FEE>
+    transfer-owner <NEW_OWNER>
+    withdraw <AMOUNT|all> [DESTINATION]";
+
+type CliResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
+
+enum Command {
+    Init { registration_fee: u64 },
+    Register { name: String },
+    Resolve { name: String },
+    SetFee { registration_fee: u64 },
+    TransferOwner { new_owner: Pubkey },
+    Withdraw { amount: Option<u64>, destination: Option<Pubkey> },
+}
+
+impl Command {
+    fn parse(command: &str, args: &[String]) -> Option<Self> {
+        let pubkey = |arg: &String| Pubkey::from_str(arg).ok();
+        let lamports = |arg: &String| arg.parse().ok();
+        Some(match (command, args) {
+            ("init", [fee]) => Command::Init { registration_fee: lamports(fee)? },
+            ("register", [name]) => Command::Register { name: name.clone() },
+            ("resolve", [name]) => Command::Resolve { name: name.clone() },
+            ("set-fee", [fee]) => Command::SetFee { registration_fee: lamports(fee)? },
+            ("transfer-owner", [new_owner]) => Command::TransferOwner { new_owner: pubkey(new_owner)? },
+            ("withdraw", [amount, destination @ ..]) if destination.len() <= 1 => Command::Withdraw {
+                amount: if amount == "all" { None } else { Some(lamports(amount)?) },
+                destination: match destination.first() {
+                    Some(destination) => Some(pubkey(destination)?),
+                    None => None,
+                },
+            },
+            _ => return None,
+        })
+    }
+}
+
+struct Cli {
+    rpc: RpcClient,
+    program_id: Pubkey,
+    config: Pubkey,
+    signer: Keypair,
+}
+
+impl Cli {
+    fn send(&self, instruction: Instruction) -> CliResult {
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.signer.pubkey()),
+            &[&self.signer],
+            self.rpc.get_latest_blockhash()?,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        println!("signature: {}", signature);
+        Ok(())
+    }
+
+    fn config_state(&self) -> CliResult<ProgramConfig> {
+        let data = self.rpc.get_account_data(&self.config)?;
+        Ok(ProgramConfig::unpack(&data)?)
+    }
+
+    /// Routes an admin instruction through the admin timelock when the
+    /// registry has one. Multisig registries need cosigners the CLI has no
+    /// keys for.
+    fn admin(&self, instruction: Instruction) -> CliResult<Instruction> {
+        let config_state = self.config_state()?;
+        if config_state.multisig_enabled {
+            return Err("the registry requires multisig cosigners, which the CLI does not support".into());
+        }
+        if config_state.admin_timelock > 0 {
+            println!("queueing behind the {}s admin timelock", config_state.admin_timelock);
+            return Ok(instructions::with_timelock(instruction, &self.program_id, &self.config));
+        }
+        Ok(instruction)
+    }
+
+    fn register(&self, name: &str) -> CliResult {
+        let config_state = self.config_state()?;
+        if config_state.commit_reveal_required {
+            return Err("the registry requires commit-reveal registration, which the CLI does not support".into());
+        }
+        if config_state.blocklist_enabled {
+            return Err("the registry requires blocklist proofs, which the CLI does not support".into());
+        }
+        let registrant = self.signer.pubkey();
+        let mut ix = instructions::register_name(&self.program_id, &registrant, &self.config, name);
+        if config_state.tombstone_period > 0 {
+            ix = instructions::with_tombstone(ix, &self.program_id);
+        }
+        if config_state.registry_pages_enabled {
+            let page = config_state.registry_page();
+            ix = instructions::with_registry_page(ix, &self.program_id, &self.config, page);
+        }
+        if config_state.usd_pricing_enabled {
+            let pricing = UsdPricing::find_address(&self.config, &self.program_id).0;
+            let pricing: UsdPricing = UsdPricing::unpack(&self.rpc.get_account_data(&pricing)?)?;
+            ix = instructions::with_usd_pricing(ix, &self.program_id, &self.config, &pricing.price_feed);
+        }
+        if config_state.enforce_unique_address {
+            ix = instructions::with_address_claim(ix, &self.program_id, &registrant);
+        }
+        if config_state.fee_vault_generation > 0 {
+            let generation = config_state.fee_vault_generation;
+            ix = instructions::with_fee_vault_generation(ix, &self.program_id, &self.config, generation);
+        }
+        self.send(ix)?;
+        println!("registered {} at {}", name, find_name_address(name, &self.program_id).0);
+        Ok(())
+    }
+
+    fn resolve(&self, name: &str) -> CliResult<Pubkey> {
+        let name_account = find_name_address(name, &self.program_id).0;
+        let read = ReadTx::new(self.program_id).resolve_address(name_account);
+        let transaction = read.transaction(&self.signer.pubkey(), self.rpc.get_latest_blockhash()?);
+        let simulation = self.rpc.simulate_transaction_with_config(
+            &transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                commitment: Some(self.rpc.commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )?;
+        if let Some(err) = simulation.value.err {
+            return Err(format!("cannot resolve {}: {}", name, err).into());
+        }
+        let results = read.decode(&simulation.value.logs.unwrap_or_default())?;
+        Ok(results.resolved_addresses[0].1)
+    }
+
+    fn run(&self, command: Command) -> CliResult {
+        let (program_id, signer, config) = (&self.program_id, &self.signer.pubkey(), &self.config);
+        match command {
+            Command::Init { registration_fee } => {
+                self.send(instructions::initialize(program_id, signer, config, registration_fee))?;
+                println!("initialized config {}", config);
+            }
+            Command::Register { name } => self.register(&name)?,
+            Command::Resolve { name } => println!("{}", self.resolve(&name)?),
+            Command::SetFee { registration_fee } => {
+                let ix = instructions::set_registration_fee(program_id, signer, config, registration_fee);
+                self.send(self.admin(ix)?)?;
+            }
+            Command::TransferOwner { new_owner } => {
+                let ix = instructions::change_program_owner(program_id, signer, config, new_owner);
+                self.send(self.admin(ix)?)?;
+                println!("{} can now accept ownership of {}", new_owner, config);
+            }
+            Command::Withdraw { amount, destination } => {
+                if self.config_state()?.multisig_enabled {
+                    return Err("the registry requires multisig cosigners, which the CLI does not support".into());
+                }
+                let destination = destination.unwrap_or(*signer);
+                self.send(instructions::withdraw(program_id, signer, config, &destination, amount))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Registries from before the config PDA keep their config elsewhere; the
+/// config link names it either way.
+fn config_address(rpc: &RpcClient, program_id: &Pubkey) -> Pubkey {
+    let link = ConfigLink::find_address(program_id).0;
+    match rpc.get_account_data(&link).map(|data| ConfigLink::unpack(&data)) {
+        Ok(Ok(link)) => link.config,
+        _ => find_config_address(program_id).0,
+    }
+}
+
+fn default_keypair_path() -> String {
+    let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    format!("{}/.config/solana/id.json", home)
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn main() {
+    let mut rpc_url = DEFAULT_RPC_URL.to_string();
+    let mut keypair_path = default_keypair_path();
+    let mut positional = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--url" => rpc_url = args.next().unwrap_or_else(|| usage()),
+            "--keypair" => keypair_path = args.next().unwrap_or_else(|| usage()),
+            _ => positional.push(arg),
+        }
+    }
+    let (program_id, command) = match positional.as_slice() {
+        [program_id, command, args @ ..] => match (Pubkey::from_str(program_id), Command::parse(command, args)) {
+            (Ok(program_id), Some(command)) => (program_id, command),
+            _ => usage(),
+        },
+        _ => usage(),
+    };
+    let signer = read_keypair_file(&keypair_path).unwrap_or_else(|err| {
+        eprintln!("cannot read keypair {}: {}", keypair_path, err);
+        process::exit(2);
+    });
+
+    let rpc = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    let config = config_address(&rpc, &program_id);
+    let cli = Cli { rpc, program_id, config, signer };
+    if let Err(err) = cli.run(command) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,74 @@
+//! Snapshot of every registered name and the address it resolves to.
+//!
+//! Scans the program's name accounts over RPC, decodes them with the
+//! registry's own state types and prints them sorted by name, as CSV or
+//! JSON, for periodic off-chain backups. Accounts still in the version 1
+//! layout are included; anything that does not decode as a registered name
+//! is skipped.
+//!
+//! Usage: `export <PROGRAM_ID> <csv|json> [RPC_URL] > snapshot`
+//! (defaults to devnet).
+
+use std::{env, process, str::FromStr};
+
+use instant_folio::{
+    client::{
+        export::{name_entries, to_csv, to_json},
+        filters::{name_accounts, unmigrated_accounts_of},
+    },
+    state::NameAccount,
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::RpcFilterType,
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig};
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+
+type ExportResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
+
+fn scan(rpc: &RpcClient, program_id: &Pubkey, filters: Vec<RpcFilterType>) -> ExportResult<Vec<(Pubkey, Account)>> {
+    Ok(rpc.get_program_accounts_with_config(
+        program_id,
+        RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        },
+    )?)
+}
+
+fn run(rpc: &RpcClient, program_id: &Pubkey, json: bool) -> ExportResult {
+    let mut accounts = scan(rpc, program_id, name_accounts())?;
+    accounts.extend(scan(rpc, program_id, unmigrated_accounts_of::<NameAccount>())?);
+    let entries = name_entries(program_id, accounts);
+    print!("{}", if json { to_json(&entries) } else { to_csv(&entries) });
+    eprintln!("exported {} names", entries.len());
+    Ok(())
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let (program_id, json) = match (args.next().map(|arg| Pubkey::from_str(&arg)), args.next().as_deref()) {
+        (Some(Ok(program_id)), Some("csv")) => (program_id, false),
+        (Some(Ok(program_id)), Some("json")) => (program_id, true),
+        _ => {
+            eprintln!("usage: export <PROGRAM_ID> <csv|json> [RPC_URL]");
+            process::exit(2);
+        }
+    };
+    let rpc_url = args.next().unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+
+    let rpc = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    if let Err(err) = run(&rpc, &program_id, json) {
+        eprintln!("export failed: {}", err);
+        process::exit(1);
+    }
+}
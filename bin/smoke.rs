@@ -0,0 +1,296 @@
+//! End-to-end smoke test against a deployed registry.
+//!
+//! Runs init → register → resolve → transfer → resolve → withdraw with a
+//! throwaway keypair funded by airdrop, asserting the on-chain state after
+//! every step. Only the upgrade authority can initialize the registry, so
+//! the first run does so with its keypair and later runs reuse the config. Withdraw sweeps that config's fees to the authority: point it at
+//! a test deployment, never production.
+//!
+//! Usage: `smoke <PROGRAM_ID> <UPGRADE_AUTHORITY_KEYPAIR> [RPC_URL]`
+//! (defaults to devnet).
+
+use std::{env, process, str::FromStr, thread, time::Duration};
+
+use borsh::BorshSerialize;
+use instant_folio::{
+    client::read::ReadTx,
+    instruction::NameRegistryInstruction,
+    pda::{
+        fee_bucket, find_address_account_address, find_config_address, find_name_address, find_program_data_address,
+        find_treasury_address, FEE_BUCKETS,
+    },
+    state::{ConfigLink, FeeSchedule, NameAccount, PendingUpdateAccount, ProgramConfig, WalletSettings},
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    system_instruction, system_program,
+};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    native_token::LAMPORTS_PER_SOL,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+const DEFAULT_RPC_URL: &str = "https://api.devnet.solana.com";
+const REGISTRATION_FEE: u64 = LAMPORTS_PER_SOL / 1_000;
+const AIRDROP: u64 = LAMPORTS_PER_SOL;
+
+type SmokeResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
+
+struct Smoke {
+    rpc: RpcClient,
+    program_id: Pubkey,
+    payer: Keypair,
+    authority: Keypair,
+}
+
+impl Smoke {
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> SmokeResult {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.rpc.send_and_confirm_transaction(&transaction)?;
+        Ok(())
+    }
+
+    fn create_account_ix(&self, account: &Keypair, space: usize) -> SmokeResult<Instruction> {
+        Ok(system_instruction::create_account(
+            &self.payer.pubkey(),
+            &account.pubkey(),
+            self.rpc.get_minimum_balance_for_rent_exemption(space)?,
+            space as u64,
+            &self.program_id,
+        ))
+    }
+
+    fn registry_ix(&self, instruction: NameRegistryInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: instruction.try_to_vec().unwrap(),
+        }
+    }
+
+    fn state<T: Pack + IsInitialized>(&self, address: &Pubkey) -> SmokeResult<T> {
+        let data = self.rpc.get_account_data(address)?;
+        Ok(T::unpack(&data)?)
+    }
+
+    fn resolve(&self, name_account: &Pubkey) -> SmokeResult<Pubkey> {
+        let read = ReadTx::new(self.program_id).resolve_address(*name_account);
+        let transaction = read.transaction(&self.payer.pubkey(), self.rpc.get_latest_blockhash()?);
+        let simulation = self.rpc.simulate_transaction_with_config(
+            &transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                commitment: Some(self.rpc.commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )?;
+        if let Some(err) = simulation.value.err {
+            return Err(format!("resolve simulation failed: {}", err).into());
+        }
+        let results = read.decode(&simulation.value.logs.unwrap_or_default())?;
+        Ok(results.resolved_addresses[0].1)
+    }
+
+    fn airdrop(&self, to: &Pubkey, lamports: u64) -> SmokeResult {
+        let signature = self.rpc.request_airdrop(to, lamports)?;
+        for _ in 0..60 {
+            if self.rpc.confirm_transaction(&signature)? {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+        Err("airdrop was not confirmed".into())
+    }
+}
+
+fn check(condition: bool, message: &str) -> SmokeResult {
+    if condition {
+        Ok(())
+    } else {
+        Err(format!("assertion failed: {}", message).into())
+    }
+}
+
+fn run(smoke: &Smoke) -> SmokeResult {
+    let payer = smoke.payer.pubkey();
+    smoke.airdrop(&payer, AIRDROP)?;
+    println!("funded throwaway payer {}", payer);
+
+    // init
+    let authority = smoke.authority.pubkey();
+    let config = find_config_address(&smoke.program_id).0;
+    if smoke.state::<ProgramConfig>(&config).is_ok() {
+        println!("init: reusing config {}", config);
+    } else {
+        let treasuries = (0..FEE_BUCKETS)
+            .map(|bucket| AccountMeta::new(find_treasury_address(&config, bucket, &smoke.program_id).0, false));
+        smoke.send(
+            &[smoke.registry_ix(
+                NameRegistryInstruction::Initialize { registration_fee: REGISTRATION_FEE },
+                [
+                    AccountMeta::new(authority, true),
+                    AccountMeta::new(config, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                    AccountMeta::new_readonly(find_program_data_address(&smoke.program_id).0, false),
+                    AccountMeta::new(ConfigLink::find_address(&smoke.program_id).0, false),
+                ]
+                .into_iter()
+                .chain(treasuries)
+                .collect(),
+            )],
+            &[&smoke.authority],
+        )?;
+        let config_state: ProgramConfig = smoke.state(&config)?;
+        check(config_state.fee_schedule == FeeSchedule::flat(REGISTRATION_FEE), "registration fee stored")?;
+        println!("init: config {}", config);
+    }
+    let config_state: ProgramConfig = smoke.state(&config)?;
+    check(config_state.owner == authority, "config owner is the upgrade authority")?;
+    let treasuries: Vec<Pubkey> = (0..FEE_BUCKETS)
+        .map(|bucket| find_treasury_address(&config, bucket, &smoke.program_id).0)
+        .collect();
+    let treasury_metas = || treasuries.iter().map(|treasury| AccountMeta::new(*treasury, false));
+
+    // register
+    let name = format!("smoke-{}", &Keypair::new().pubkey().to_string()[..8].to_lowercase());
+    let name_account = find_name_address(&name, &smoke.program_id).0;
+    let treasury = treasuries[fee_bucket(&name) as usize];
+    let address_account = find_address_account_address(&name_account, &smoke.program_id).0;
+    let registration_fee = config_state.fee_schedule.fee_for(&name);
+    let treasury_before = smoke.rpc.get_balance(&treasury)?;
+    smoke.send(
+        &[smoke.registry_ix(
+            NameRegistryInstruction::RegisterName { name: name.clone(), proof: None },
+            vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(name_account, false),
+                AccountMeta::new(address_account, false),
+                AccountMeta::new(config, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(treasury, false),
+            ],
+        )],
+        &[],
+    )?;
+    let name_state: NameAccount = smoke.state(&name_account)?;
+    check(name_state.name == name, "name stored")?;
+    check(name_state.owner == payer, "registrant owns the name")?;
+    println!("register: {} at {}", name, name_account);
+
+    // resolve
+    check(smoke.resolve(&name_account)? == payer, "name resolves to the registrant")?;
+    println!("resolve: {} -> {}", name, payer);
+
+    // transfer
+    let new_owner = Keypair::new();
+    let pending = Keypair::new();
+    smoke.send(
+        &[
+            system_instruction::transfer(&payer, &new_owner.pubkey(), LAMPORTS_PER_SOL / 100),
+            smoke.create_account_ix(&pending, PendingUpdateAccount::LEN)?,
+            smoke.registry_ix(
+                NameRegistryInstruction::RequestAddressUpdate { new_address: new_owner.pubkey() },
+                vec![
+                    AccountMeta::new(payer, true),
+                    AccountMeta::new(name_account, false),
+                    AccountMeta::new(pending.pubkey(), false),
+                    AccountMeta::new_readonly(config, false),
+                    AccountMeta::new(WalletSettings::find_address(&payer, &smoke.program_id).0, false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            ),
+        ],
+        &[&pending],
+    )?;
+    smoke.send(
+        &[smoke.registry_ix(
+            NameRegistryInstruction::CompleteAddressUpdate,
+            vec![
+                AccountMeta::new(new_owner.pubkey(), true),
+                AccountMeta::new(name_account, false),
+                AccountMeta::new(address_account, false),
+                AccountMeta::new(pending.pubkey(), false),
+                AccountMeta::new_readonly(config, false),
+                AccountMeta::new(WalletSettings::find_address(&payer, &smoke.program_id).0, false),
+                AccountMeta::new(find_treasury_address(&config, fee_bucket(&name), &smoke.program_id).0, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        )],
+        &[&new_owner],
+    )?;
+    let name_state: NameAccount = smoke.state(&name_account)?;
+    check(name_state.owner == new_owner.pubkey(), "name moved to the new owner")?;
+    check(
+        smoke.resolve(&name_account)? == new_owner.pubkey(),
+        "name resolves to the new owner",
+    )?;
+    println!("transfer: {} -> {}", name, new_owner.pubkey());
+
+    // withdraw
+    let treasury_rent = smoke.rpc.get_minimum_balance_for_rent_exemption(0)?;
+    let treasury_balance = smoke.rpc.get_balance(&treasury)?;
+    check(treasury_balance == treasury_before + registration_fee, "treasury holds the registration fee")?;
+    smoke.send(
+        &[smoke.registry_ix(
+            NameRegistryInstruction::Withdraw { amount: None },
+            [
+                AccountMeta::new_readonly(authority, true),
+                AccountMeta::new(authority, false),
+                AccountMeta::new_readonly(config, false),
+            ]
+            .into_iter()
+            .chain(treasury_metas())
+            .collect(),
+        )],
+        &[&smoke.authority],
+    )?;
+    check(
+        smoke.rpc.get_balance(&treasury)? == treasury_rent,
+        "withdraw leaves only the rent reserve",
+    )?;
+    println!("withdraw: {} lamports from {}", treasury_balance - treasury_rent, treasury);
+
+    Ok(())
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let (program_id, authority) = match (args.next().map(|arg| Pubkey::from_str(&arg)), args.next()) {
+        (Some(Ok(program_id)), Some(authority)) => (program_id, authority),
+        _ => {
+            eprintln!("usage: smoke <PROGRAM_ID> <UPGRADE_AUTHORITY_KEYPAIR> [RPC_URL]");
+            process::exit(2);
+        }
+    };
+    let authority = read_keypair_file(&authority).unwrap_or_else(|err| {
+        eprintln!("cannot read upgrade authority keypair {}: {}", authority, err);
+        process::exit(2);
+    });
+    let rpc_url = args.next().unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+
+    let smoke = Smoke {
+        rpc: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()),
+        program_id,
+        payer: Keypair::new(),
+        authority,
+    };
+    if let Err(err) = run(&smoke) {
+        eprintln!("smoke test failed: {}", err);
+        process::exit(1);
+    }
+    println!("smoke test passed");
+}
@@ -0,0 +1,54 @@
+//! Example program building on the InstantFolio resolver.
+//!
+//! It resolves a name through the registry's `ResolveAddress` CPI with the
+//! `cpi` helpers and fails unless the name points at the address given in
+//! the instruction data, the way a payment or access-control program would
+//! before acting on a name.
+//!
+//! Instruction data is the 32-byte expected address. Accounts expected:
+//! 0. `[]` The registry program
+//! 1. `[]` The name account
+//! 2. `[]` For subnames, every ancestor name account, nearest first
+
+use instant_folio::cpi;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+};
+
+/// Registry deployment this program trusts. Return data can be set by any
+/// program, so a consumer must pin the registry it resolves through; point
+/// this at the deployment of your cluster.
+pub const REGISTRY_PROGRAM_ID: Pubkey = pubkey!("9kpemZtY6iEWk3WW6WozfEutuBYASamVJ4uqYxtBrQCF");
+
+/// Custom error returned when the name resolves elsewhere.
+pub const ADDRESS_MISMATCH: u32 = 0;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let expected = Pubkey::try_from(data).map_err(|_| ProgramError::InvalidInstructionData)?;
+    let account_info_iter = &mut accounts.iter();
+    let registry = next_account_info(account_info_iter)?;
+    let name_account = next_account_info(account_info_iter)?;
+    let ancestors = account_info_iter.as_slice();
+
+    if registry.key != &REGISTRY_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    // Invokes the registry and decodes its return data, checking that the
+    // registry rather than some earlier program set it
+    let address = cpi::resolve_address(registry, name_account, ancestors)?;
+    if address != expected {
+        msg!("{} resolves to {}, not {}", name_account.key, address, expected);
+        return Err(ProgramError::Custom(ADDRESS_MISMATCH));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use solana_program::{
+    bpf_loader_upgradeable,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use instant_folio::{
+    client::instructions,
+    pda::{find_config_address, find_name_address, find_program_data_address},
+};
+use resolver_consumer::{ADDRESS_MISMATCH, REGISTRY_PROGRAM_ID};
+
+async fn process(context: &mut ProgramTestContext, ix: Instruction, signer: &Keypair) -> Result<(), BanksClientError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&signer.pubkey()), &[signer], blockhash);
+    context.banks_client.process_transaction(tx).await
+}
+
+fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, error) => assert_eq!(error, expected),
+        error => panic!("unexpected error: {error:?}"),
+    }
+}
+
+fn check_address(consumer: &Pubkey, registry: &Pubkey, name_account: &Pubkey, expected: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *consumer,
+        accounts: vec![
+            AccountMeta::new_readonly(*registry, false),
+            AccountMeta::new_readonly(*name_account, false),
+        ],
+        data: expected.to_bytes().to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn test_consumer_checks_address_through_registry() {
+    let consumer = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "instant_folio",
+        REGISTRY_PROGRAM_ID,
+        processor!(instant_folio::process_instruction),
+    );
+    program_test.add_program("resolver_consumer", consumer, processor!(resolver_consumer::process_instruction));
+
+    let owner = Keypair::new();
+    let config = find_config_address(&REGISTRY_PROGRAM_ID).0;
+    program_test.add_account(
+        owner.pubkey(),
+        Account { lamports: 1_000_000_000, owner: solana_program::system_program::id(), ..Account::default() },
+    );
+    // Initialize checks its signer is the registry's upgrade authority
+    let mut program_data = vec![3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+    program_data.extend_from_slice(owner.pubkey().as_ref());
+    program_test.add_account(
+        find_program_data_address(&REGISTRY_PROGRAM_ID).0,
+        Account {
+            lamports: Rent::default().minimum_balance(program_data.len()),
+            data: program_data,
+            owner: bpf_loader_upgradeable::id(),
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let ix = instructions::initialize(&REGISTRY_PROGRAM_ID, &owner.pubkey(), &config, 0);
+    process(&mut context, ix, &owner).await.unwrap();
+    let ix = instructions::register_name(&REGISTRY_PROGRAM_ID, &owner.pubkey(), &config, "alice");
+    process(&mut context, ix, &owner).await.unwrap();
+    let name_account = find_name_address("alice", &REGISTRY_PROGRAM_ID).0;
+
+    // New names resolve to their registrant
+    let ix = check_address(&consumer, &REGISTRY_PROGRAM_ID, &name_account, &owner.pubkey());
+    process(&mut context, ix, &owner).await.unwrap();
+
+    let ix = check_address(&consumer, &REGISTRY_PROGRAM_ID, &name_account, &Pubkey::new_unique());
+    let result = process(&mut context, ix, &owner).await;
+    assert_instruction_error(result, InstructionError::Custom(ADDRESS_MISMATCH));
+
+    // Return data from any other program is not trusted
+    let ix = check_address(&consumer, &consumer, &name_account, &owner.pubkey());
+    let result = process(&mut context, ix, &owner).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
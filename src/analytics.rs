@@ -0,0 +1,49 @@
+//! Anonymized registration analytics.
+//!
+//! When enabled in the config, RegisterName logs one line describing the
+//! shape of the registered name, never the name itself:
+//!
+//! `analytics: event=register name_length=<n> charset=<class> hyphen=<bool>`
+//!
+//! Operators collect these from transaction logs; nothing is stored on chain.
+
+use std::fmt;
+
+use solana_program::msg;
+
+use crate::formats::registration_analytics_event;
+
+/// Characters a name is made of, ignoring hyphens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharsetClass {
+    Alphabetic,
+    Numeric,
+    Alphanumeric,
+}
+
+impl fmt::Display for CharsetClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            CharsetClass::Alphabetic => "alphabetic",
+            CharsetClass::Numeric => "numeric",
+            CharsetClass::Alphanumeric => "alphanumeric",
+        })
+    }
+}
+
+pub fn charset_class(name: &str) -> CharsetClass {
+    let has_letters = name.chars().any(|c| c.is_alphabetic());
+    let has_digits = name.chars().any(|c| c.is_ascii_digit());
+    match (has_letters, has_digits) {
+        (false, true) => CharsetClass::Numeric,
+        (true, true) => CharsetClass::Alphanumeric,
+        _ => CharsetClass::Alphabetic,
+    }
+}
+
+pub fn log_registration(name: &str) {
+    msg!(
+        "{}",
+        registration_analytics_event(name.len(), charset_class(name), name.contains('-')),
+    );
+}
@@ -0,0 +1,54 @@
+//! Proofs that a name is missing from a config's blocklist.
+//!
+//! A blocklist is committed to as a merkle tree over the gaps between its
+//! names rather than over the names themselves. Each name maps to a 32-byte
+//! key, the keys are sorted, and every pair of neighbours `(low, high)`, with
+//! all-zero and all-0xff keys closing the ends, becomes a leaf. A name is off
+//! the list exactly when some leaf has `low < key < high`, so showing that
+//! takes a single merkle path. Siblings are hashed in sorted order, so paths
+//! carry no leaf index.
+//!
+//! The program only checks proofs; `client::blocklist` builds the tree.
+
+use solana_program::hash::hashv;
+
+use crate::{instruction::NameProof, pda::normalize_name};
+
+/// Keys closing the first and last gap of every blocklist.
+pub const LOWEST_KEY: [u8; 32] = [0; 32];
+pub const HIGHEST_KEY: [u8; 32] = [0xff; 32];
+
+const NAME_PREFIX: &[u8] = b"blocklist_name";
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Key `name` is listed under, shared by every spelling that normalizes to
+/// the same name.
+pub fn name_key(name: &str) -> [u8; 32] {
+    hashv(&[NAME_PREFIX, normalize_name(name).as_bytes()]).to_bytes()
+}
+
+/// Hash of the leaf for the gap between the listed keys `low` and `high`.
+pub fn leaf_hash(low: &[u8; 32], high: &[u8; 32]) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, low, high]).to_bytes()
+}
+
+/// Hash of the parent of two nodes, whichever side each is on.
+pub fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Whether `proof` shows `name` is not on the blocklist with merkle root
+/// `root`.
+pub fn proves_unlisted(root: &[u8; 32], name: &str, proof: &NameProof) -> bool {
+    let key = name_key(name);
+    if proof.low >= key || key >= proof.high {
+        return false;
+    }
+    let computed = proof
+        .path
+        .iter()
+        .fold(leaf_hash(&proof.low, &proof.high), |node, sibling| node_hash(&node, sibling));
+    &computed == root
+}
@@ -0,0 +1,74 @@
+//! Building blocklists and the proofs registrations carry against them.
+//!
+//! Operators keep the full list off-chain and publish only its root with
+//! SetBlocklistRoot; registrants fetch a proof for their name from whoever
+//! serves the list. See the crate's `blocklist` module for the layout.
+
+use crate::{
+    blocklist::{leaf_hash, name_key, node_hash, HIGHEST_KEY, LOWEST_KEY},
+    instruction::NameProof,
+};
+
+/// Merkle tree over the gaps between the keys of a list of names.
+#[derive(Clone, Debug)]
+pub struct BlocklistTree {
+    /// Listed keys, sorted and deduplicated.
+    keys: Vec<[u8; 32]>,
+    /// Node hashes from the leaves up; the last level holds the root.
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl BlocklistTree {
+    pub fn new<'a>(names: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut keys: Vec<[u8; 32]> = names.into_iter().map(name_key).collect();
+        keys.sort();
+        keys.dedup();
+
+        let mut bounds = Vec::with_capacity(keys.len() + 2);
+        bounds.push(LOWEST_KEY);
+        bounds.extend_from_slice(&keys);
+        bounds.push(HIGHEST_KEY);
+        let mut levels = vec![bounds.windows(2).map(|gap| leaf_hash(&gap[0], &gap[1])).collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            // An odd node out moves up unchanged
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { keys, levels }
+    }
+
+    /// The root to publish with SetBlocklistRoot.
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.keys.binary_search(&name_key(name)).is_ok()
+    }
+
+    /// Proof that `name` is not on the list, or `None` if it is.
+    pub fn prove_unlisted(&self, name: &str) -> Option<NameProof> {
+        let leaf = self.keys.binary_search(&name_key(name)).err()?;
+        let low = if leaf == 0 { LOWEST_KEY } else { self.keys[leaf - 1] };
+        let high = self.keys.get(leaf).copied().unwrap_or(HIGHEST_KEY);
+
+        let mut path = Vec::with_capacity(self.levels.len() - 1);
+        let mut index = leaf;
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                path.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(NameProof { low, high, path })
+    }
+}
@@ -0,0 +1,89 @@
+//! Up-front cost estimates for registering a name.
+
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::Pack,
+    rent::Rent,
+};
+
+use crate::{
+    state::{NameAccount, ReceiptAccount, ReverseRecord, WalletSettings},
+    validation::validate_name,
+};
+
+/// Lamports charged per signature by the default fee rate governor.
+pub const DEFAULT_LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+#[derive(Debug, Clone)]
+pub struct CostOptions {
+    /// Rent parameters, ideally read from the cluster's rent sysvar.
+    pub rent: Rent,
+    /// Registration fee of the name, see
+    /// `ProgramConfig::effective_registration_fee`.
+    pub registration_fee: u64,
+    pub lamports_per_signature: u64,
+    /// Signatures across all transactions of the flow. The default covers the
+    /// registrant, since RegisterName creates its own accounts.
+    pub signatures: u64,
+    /// Whether the flow also enables privacy mode for a wallet that has no
+    /// settings account yet.
+    pub create_wallet_settings: bool,
+    /// Whether the registration issues a fee receipt, see `with_receipt`.
+    pub create_receipt: bool,
+}
+
+impl Default for CostOptions {
+    fn default() -> Self {
+        Self {
+            rent: Rent::default(),
+            registration_fee: 0,
+            lamports_per_signature: DEFAULT_LAMPORTS_PER_SIGNATURE,
+            signatures: 1,
+            create_wallet_settings: false,
+            create_receipt: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CostEstimate {
+    /// Rent-exempt deposits for every account the flow creates.
+    pub rent: u64,
+    pub registration_fee: u64,
+    pub transaction_fees: u64,
+    pub total: u64,
+}
+
+/// Computes the lamports a wallet needs to register `name`.
+pub fn estimate_costs(name: &str, options: &CostOptions) -> Result<CostEstimate, ProgramError> {
+    validate_name(name)?;
+
+    let mut account_sizes = vec![NameAccount::LEN, ReverseRecord::LEN];
+    if options.create_wallet_settings {
+        account_sizes.push(WalletSettings::LEN);
+    }
+    if options.create_receipt {
+        account_sizes.push(ReceiptAccount::LEN);
+    }
+
+    let rent = account_sizes
+        .into_iter()
+        .map(|size| options.rent.minimum_balance(size))
+        .try_fold(0u64, |total, deposit| total.checked_add(deposit))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let transaction_fees = options
+        .lamports_per_signature
+        .checked_mul(options.signatures)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let total = rent
+        .checked_add(options.registration_fee)
+        .and_then(|total| total.checked_add(transaction_fees))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(CostEstimate {
+        rent,
+        registration_fee: options.registration_fee,
+        transaction_fees,
+        total,
+    })
+}
@@ -0,0 +1,91 @@
+//! Off-chain backups of the name → address mapping.
+//!
+//! `name_entries` turns `getProgramAccounts` results (see
+//! `filters::name_accounts`) into one entry per registered name, checked like
+//! `view::name_from_account_data`. `to_csv` and `to_json` render them for the
+//! `export` binary. Entries come sorted by name, so snapshots taken at
+//! different times diff cleanly.
+
+use std::fmt::Write;
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+
+use crate::client::view::name_from_account_data;
+
+/// One registered name in a snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameEntry {
+    pub name: String,
+    pub name_account: Pubkey,
+    pub owner: Pubkey,
+    pub address: Pubkey,
+}
+
+/// Decodes registered names, skipping accounts that hold none or are not
+/// the PDA of the name they hold.
+pub fn name_entries(program_id: &Pubkey, accounts: Vec<(Pubkey, Account)>) -> Vec<NameEntry> {
+    let mut entries: Vec<_> = accounts
+        .into_iter()
+        .filter_map(|(name_account, account)| {
+            let name = name_from_account_data(program_id, &name_account, &account.data).ok()?;
+            Some(NameEntry { name: name.name, name_account, owner: name.owner, address: name.address })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
+/// `name,name_account,owner,address` rows under a header line.
+pub fn to_csv(entries: &[NameEntry]) -> String {
+    let mut csv = String::from("name,name_account,owner,address\n");
+    for entry in entries {
+        writeln!(csv, "{},{},{},{}", csv_field(&entry.name), entry.name_account, entry.owner, entry.address).unwrap();
+    }
+    csv
+}
+
+/// A JSON array of `{"name", "name_account", "owner", "address"}` objects.
+pub fn to_json(entries: &[NameEntry]) -> String {
+    let mut json = String::from("[");
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write!(
+            json,
+            "\n  {{\"name\": {}, \"name_account\": \"{}\", \"owner\": \"{}\", \"address\": \"{}\"}}",
+            json_string(&entry.name),
+            entry.name_account,
+            entry.owner,
+            entry.address,
+        )
+        .unwrap();
+    }
+    json.push_str(if entries.is_empty() { "]\n" } else { "\n]\n" });
+    json
+}
+
+/// Validated names hold no separators, but quote anyway rather than trust
+/// every account on chain.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -0,0 +1,89 @@
+//! Ready-made `getProgramAccounts` filters and decoders for registry state.
+//!
+//! Account types are told apart by their leading discriminator and fixed
+//! allocation size, and only fields that precede the variable-length name
+//! have a stable offset, so filters are limited to those fields.
+
+use solana_program::{
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+};
+use solana_rpc_client_api::filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::Account;
+
+#[allow(deprecated)]
+use crate::state::AddressAccount;
+use crate::state::{AccountType, NameAccount, PendingUpdateAccount, ReverseRecord, LAYOUT_VERSION};
+
+fn initialized_accounts_of<T: Pack + AccountType>() -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(T::LEN as u64),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![T::DISCRIMINATOR, LAYOUT_VERSION, 1])),
+    ]
+}
+
+/// Accounts of type `T` still in the version 1 layout, for MigrateAccount.
+pub fn unmigrated_accounts_of<T: Pack + AccountType>() -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(T::LEN as u64 - 1),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![T::DISCRIMINATOR])),
+    ]
+}
+
+/// All registered names.
+pub fn name_accounts() -> Vec<RpcFilterType> {
+    initialized_accounts_of::<NameAccount>()
+}
+
+/// All published reverse records.
+pub fn reverse_records() -> Vec<RpcFilterType> {
+    initialized_accounts_of::<ReverseRecord>()
+}
+
+/// Initialized reverse records not yet migrated from the legacy layout.
+#[deprecated(note = "use reverse_records; migrate accounts with MigrateAddressAccount")]
+#[allow(deprecated)]
+pub fn address_accounts() -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(AddressAccount::LEN as u64),
+        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![1])),
+    ]
+}
+
+/// All armed pending address updates.
+pub fn pending_update_accounts() -> Vec<RpcFilterType> {
+    initialized_accounts_of::<PendingUpdateAccount>()
+}
+
+/// Names owned by `owner`.
+pub fn names_by_owner(owner: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = name_accounts();
+    filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+        NameAccount::OWNER_OFFSET,
+        owner.to_bytes().to_vec(),
+    )));
+    filters
+}
+
+/// Decodes `getProgramAccounts` results, skipping accounts that do not hold
+/// initialized `T` state.
+pub fn decode_accounts<T: Pack + IsInitialized>(accounts: Vec<(Pubkey, Account)>) -> Vec<(Pubkey, T)> {
+    accounts
+        .into_iter()
+        .filter_map(|(address, account)| T::unpack(&account.data).ok().map(|state| (address, state)))
+        .collect()
+}
+
+pub fn decode_name_accounts(accounts: Vec<(Pubkey, Account)>) -> Vec<(Pubkey, NameAccount)> {
+    decode_accounts(accounts)
+}
+
+pub fn decode_reverse_records(accounts: Vec<(Pubkey, Account)>) -> Vec<(Pubkey, ReverseRecord)> {
+    decode_accounts(accounts)
+}
+
+#[deprecated(note = "use decode_reverse_records")]
+#[allow(deprecated)]
+pub fn decode_address_accounts(accounts: Vec<(Pubkey, Account)>) -> Vec<(Pubkey, AddressAccount)> {
+    decode_accounts(accounts)
+}
@@ -0,0 +1,1695 @@
+//! Instruction builders.
+//!
+//! Each builder returns a ready-to-sign `Instruction` with its accounts in
+//! the order documented on `NameRegistryInstruction`, deriving every PDA it
+//! can from its arguments. Optional trailing accounts (wallet settings,
+//! subname ancestors) can be pushed onto `accounts` afterwards.
+//!
+//! Builders that derive a name PDA panic on names longer than a PDA seed
+//! allows; check input with `validation::validate_name` first.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::{
+    instruction::{NameProof, NameRegistryInstruction, PromoClaimEntry},
+    pda::{
+        fee_bucket, find_address_account_address, find_auction_address, find_auction_escrow_address,
+        find_commitment_address, find_config_address, find_fee_vault_address, find_name_address,
+        find_name_mint_address, find_note_address, find_program_data_address, find_promo_claim_address,
+        find_record_address, find_record_index_address, find_primary_name_address, find_treasury_address,
+        name_commitment, promo_claim_commitment, FEE_BUCKETS,
+    },
+    state::{
+        AddressClaim, BannerMessage, Blocklist, ConfigLink, FeeSchedule, MultisigConfig, NameCosigner, NameOperator,
+        NameTombstone, NamespaceAccount, OperatorScope, PaidOperation, PendingAdminAction, PendingUpdateAccount,
+        ReceiptAccount, RegistryPage, ReservedName, Role, RoleGrant, UsdPricing, WalletSettings,
+    },
+    token_metadata, voucher,
+};
+
+fn instruction(program_id: &Pubkey, data: NameRegistryInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: data.try_to_vec().unwrap(),
+    }
+}
+
+/// Every treasury bucket of `config`, in bucket order.
+pub fn treasury_buckets(config: &Pubkey, program_id: &Pubkey) -> Vec<Pubkey> {
+    (0..FEE_BUCKETS)
+        .map(|bucket| find_treasury_address(config, bucket, program_id).0)
+        .collect()
+}
+
+fn writable_treasuries(config: &Pubkey, program_id: &Pubkey) -> impl Iterator<Item = AccountMeta> {
+    treasury_buckets(config, program_id)
+        .into_iter()
+        .map(|treasury| AccountMeta::new(treasury, false))
+}
+
+/// Owner and config only, shared by the admin instructions.
+/// Account a registration of `name` pays its fee into: the namespace of its
+/// suffix for a namespaced name, its treasury bucket otherwise.
+fn fee_recipient(program_id: &Pubkey, config: &Pubkey, name: &str) -> Pubkey {
+    match name.split_once('.') {
+        Some((_, suffix)) => NamespaceAccount::find_address(&find_name_address(suffix, program_id).0, program_id).0,
+        None => find_treasury_address(config, fee_bucket(name), program_id).0,
+    }
+}
+
+fn admin(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, data: NameRegistryInstruction) -> Instruction {
+    instruction(
+        program_id,
+        data,
+        vec![AccountMeta::new_readonly(*owner, true), AccountMeta::new(*config, false)],
+    )
+}
+
+/// `initializer` must be the program's upgrade authority.
+pub fn initialize(program_id: &Pubkey, initializer: &Pubkey, config: &Pubkey, registration_fee: u64) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*initializer, true),
+        AccountMeta::new(*config, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(find_program_data_address(program_id).0, false),
+        AccountMeta::new(ConfigLink::find_address(program_id).0, false),
+    ];
+    accounts.extend(writable_treasuries(config, program_id));
+    instruction(program_id, NameRegistryInstruction::Initialize { registration_fee }, accounts)
+}
+
+pub fn register_name(program_id: &Pubkey, registrant: &Pubkey, config: &Pubkey, name: &str) -> Instruction {
+    let name_account = find_name_address(name, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::RegisterName { name: name.to_string(), proof: None },
+        vec![
+            AccountMeta::new(*registrant, true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(find_address_account_address(&name_account, program_id).0, false),
+            AccountMeta::new(*config, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(fee_recipient(program_id, config, name), false),
+        ],
+    )
+}
+
+/// Like `register_name`, paying `referrer` its share of the fee.
+pub fn register_name_with_referrer(
+    program_id: &Pubkey,
+    registrant: &Pubkey,
+    config: &Pubkey,
+    name: &str,
+    referrer: &Pubkey,
+) -> Instruction {
+    let mut ix = register_name(program_id, registrant, config, name);
+    ix.accounts.push(AccountMeta::new_readonly(WalletSettings::find_address(registrant, program_id).0, false));
+    ix.accounts.push(AccountMeta::new(*referrer, false));
+    ix
+}
+
+/// Appends the USD pricing PDA of `config` and its `price_feed` to a
+/// registration instruction, for configs pricing in USD. Apply it before
+/// `with_address_claim` and `with_name_proof`.
+pub fn with_usd_pricing(mut ix: Instruction, program_id: &Pubkey, config: &Pubkey, price_feed: &Pubkey) -> Instruction {
+    let position = match NameRegistryInstruction::try_from_slice(&ix.data) {
+        Ok(NameRegistryInstruction::RegisterName { .. })
+        | Ok(NameRegistryInstruction::RegisterNameFor { .. }) => 6,
+        Ok(NameRegistryInstruction::RevealAndRegister { .. })
+        | Ok(NameRegistryInstruction::RegisterNameAsRegistrar { .. })
+        | Ok(NameRegistryInstruction::RegisterWithVoucher { .. }) => 7,
+        Ok(NameRegistryInstruction::RegisterNameWithRecords { records, .. }) => 7 + records.len(),
+        _ => panic!("a registration instruction"),
+    };
+    ix.accounts.splice(
+        position..position,
+        [
+            AccountMeta::new_readonly(UsdPricing::find_address(config, program_id).0, false),
+            AccountMeta::new_readonly(*price_feed, false),
+        ],
+    );
+    ix
+}
+
+/// Has a RegisterName or RevealAndRegister instruction, with or without a
+/// referrer, issue the registrant a receipt for the fee.
+pub fn with_receipt(mut ix: Instruction, program_id: &Pubkey, registrant: &Pubkey, name: &str) -> Instruction {
+    // The treasury bucket is the last required account
+    let system_program = ix.accounts.iter().position(|meta| meta.pubkey == system_program::id());
+    let treasury = system_program.expect("a registration instruction") + 1;
+    if ix.accounts.len() == treasury + 1 {
+        ix.accounts.push(AccountMeta::new_readonly(WalletSettings::find_address(registrant, program_id).0, false));
+    }
+    if ix.accounts.len() == treasury + 2 {
+        ix.accounts.push(AccountMeta::new_readonly(*program_id, false));
+    }
+    let name_account = find_name_address(name, program_id).0;
+    let receipt = ReceiptAccount::find_address(registrant, &name_account, PaidOperation::Registration, program_id).0;
+    ix.accounts.push(AccountMeta::new(receipt, false));
+    ix
+}
+
+/// `name_hash` is `pda::name_commitment(name, salt, committer)`.
+pub fn commit_name(program_id: &Pubkey, committer: &Pubkey, name_hash: [u8; 32]) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::CommitName { name_hash },
+        vec![
+            AccountMeta::new(*committer, true),
+            AccountMeta::new(find_commitment_address(committer, &name_hash, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn reveal_and_register(
+    program_id: &Pubkey,
+    registrant: &Pubkey,
+    config: &Pubkey,
+    name: &str,
+    salt: [u8; 32],
+) -> Instruction {
+    let name_hash = name_commitment(name, &salt, registrant);
+    let mut accounts = vec![AccountMeta::new(find_commitment_address(registrant, &name_hash, program_id).0, false)];
+    accounts.extend(register_name(program_id, registrant, config, name).accounts);
+    let data = NameRegistryInstruction::RevealAndRegister { name: name.to_string(), salt, proof: None };
+    instruction(program_id, data, accounts)
+}
+
+pub fn set_commit_reveal_required(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, required: bool) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetCommitRevealRequired { required })
+}
+
+pub fn request_address_update(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    config: &Pubkey,
+    new_address: Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::RequestAddressUpdate { new_address },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new(PendingUpdateAccount::find_address(name_account, program_id).0, false),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(WalletSettings::find_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(name_account, program_id).0, false),
+        ],
+    )
+}
+
+/// `owner` is the current name owner, who requested the update.
+pub fn complete_address_update(
+    program_id: &Pubkey,
+    new_address: &Pubkey,
+    owner: &Pubkey,
+    name: &str,
+    config: &Pubkey,
+) -> Instruction {
+    let name_account = find_name_address(name, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::CompleteAddressUpdate,
+        vec![
+            AccountMeta::new(*new_address, true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(find_address_account_address(&name_account, program_id).0, false),
+            AccountMeta::new(PendingUpdateAccount::find_address(&name_account, program_id).0, false),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(WalletSettings::find_address(owner, program_id).0, false),
+            AccountMeta::new(find_treasury_address(config, fee_bucket(name), program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Aborts the update armed on `name_account`, closing its pending update
+/// account to refund the rent to `owner` when `close` is set.
+pub fn cancel_address_update(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey, close: bool) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::CancelAddressUpdate { close },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(PendingUpdateAccount::find_address(name_account, program_id).0, false),
+            AccountMeta::new(WalletSettings::find_address(owner, program_id).0, false),
+        ],
+    )
+}
+
+pub fn rename_name(program_id: &Pubkey, owner: &Pubkey, old_name: &str, config: &Pubkey, new_name: &str) -> Instruction {
+    let old_account = find_name_address(old_name, program_id).0;
+    let new_account = find_name_address(new_name, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::RenameName { new_name: new_name.to_string(), proof: None },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(old_account, false),
+            AccountMeta::new(new_account, false),
+            AccountMeta::new(find_address_account_address(&old_account, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(NameCosigner::find_address(&old_account, program_id).0, false),
+            AccountMeta::new(NameCosigner::find_address(&new_account, program_id).0, false),
+            AccountMeta::new(find_treasury_address(config, fee_bucket(new_name), program_id).0, false),
+        ],
+    )
+}
+
+pub fn set_registration_fee(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, new_fee: u64) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetRegistrationFee { new_fee })
+}
+
+pub fn set_fee_schedule(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, schedule: FeeSchedule) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetFeeSchedule { schedule })
+}
+
+pub fn set_rename_fee(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, fee: u64) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetRenameFee { fee })
+}
+
+pub fn set_update_fee(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, fee: u64) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetUpdateFee { fee })
+}
+
+pub fn change_program_owner(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, new_owner: Pubkey) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::ChangeProgramOwner { new_owner })
+}
+
+pub fn accept_program_ownership(program_id: &Pubkey, pending_owner: &Pubkey, config: &Pubkey) -> Instruction {
+    admin(program_id, pending_owner, config, NameRegistryInstruction::AcceptProgramOwnership)
+}
+
+/// `ancestors` are the parent name accounts of a subname, nearest first;
+/// empty for top-level names, which get their tombstone PDA instead.
+pub fn resolve_address(program_id: &Pubkey, name_account: &Pubkey, ancestors: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(*name_account, false)];
+    accounts.extend(ancestors.iter().map(|ancestor| AccountMeta::new_readonly(*ancestor, false)));
+    if ancestors.is_empty() {
+        let tombstone = NameTombstone::find_address(name_account, program_id).0;
+        accounts.push(AccountMeta::new_readonly(tombstone, false));
+    }
+    instruction(program_id, NameRegistryInstruction::ResolveAddress, accounts)
+}
+
+/// `resolve_address` with the name passed writable, counting the resolution
+/// in its `resolve_count`.
+pub fn resolve_address_counted(program_id: &Pubkey, name_account: &Pubkey, ancestors: &[Pubkey]) -> Instruction {
+    let mut ix = resolve_address(program_id, name_account, ancestors);
+    ix.accounts[0].is_writable = true;
+    ix
+}
+
+/// `ancestors` are the ancestors of `parent_name` itself, nearest first.
+pub fn resolve_subname(program_id: &Pubkey, label: &str, parent_name: &str, ancestors: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(find_name_address(&format!("{}.{}", label, parent_name), program_id).0, false),
+        AccountMeta::new_readonly(find_name_address(parent_name, program_id).0, false),
+    ];
+    accounts.extend(ancestors.iter().map(|ancestor| AccountMeta::new_readonly(*ancestor, false)));
+    instruction(program_id, NameRegistryInstruction::ResolveSubname { label: label.to_string() }, accounts)
+}
+
+pub fn set_wildcard_subdomains(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey, enabled: bool) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetWildcardSubdomains { enabled },
+        vec![AccountMeta::new_readonly(*owner, true), AccountMeta::new(*name_account, false)],
+    )
+}
+
+/// Like `resolve_address`, returning a `cpi::ResolveResult`.
+pub fn resolve_details(program_id: &Pubkey, name_account: &Pubkey, ancestors: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(*name_account, false)];
+    accounts.extend(ancestors.iter().map(|ancestor| AccountMeta::new_readonly(*ancestor, false)));
+    instruction(program_id, NameRegistryInstruction::ResolveDetails, accounts)
+}
+
+pub fn get_contract_owner(program_id: &Pubkey, config: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::GetContractOwner,
+        vec![AccountMeta::new_readonly(*config, false)],
+    )
+}
+
+pub fn get_registration_fee(program_id: &Pubkey, config: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::GetRegistrationFee,
+        vec![AccountMeta::new_readonly(*config, false)],
+    )
+}
+
+pub fn get_pending_contract_owner(program_id: &Pubkey, config: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::GetPendingContractOwner,
+        vec![AccountMeta::new_readonly(*config, false)],
+    )
+}
+
+pub fn get_cooldown_bounds(program_id: &Pubkey) -> Instruction {
+    instruction(program_id, NameRegistryInstruction::GetCooldownBounds, vec![])
+}
+
+/// Withdraws `amount` lamports of fees, or all of them when `None`, to
+/// `destination`.
+pub fn withdraw(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    destination: &Pubkey,
+    amount: Option<u64>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(*config, false),
+    ];
+    accounts.extend(writable_treasuries(config, program_id));
+    instruction(program_id, NameRegistryInstruction::Withdraw { amount }, accounts)
+}
+
+/// Appends the multisig PDA of `config` and `cosigners` to an instruction
+/// the program owner authorizes, for registries with a multisig set.
+/// Apply it after the helpers that append other accounts.
+pub fn with_multisig(mut ix: Instruction, program_id: &Pubkey, config: &Pubkey, cosigners: &[Pubkey]) -> Instruction {
+    ix.accounts.push(AccountMeta::new_readonly(MultisigConfig::find_address(config, program_id).0, false));
+    ix.accounts.extend(cosigners.iter().map(|cosigner| AccountMeta::new_readonly(*cosigner, true)));
+    ix
+}
+
+/// Inserts the admin action PDA of `config` and the system program into a
+/// SetRegistrationFee, SetFeeSchedule, SetRenameFee, SetUpdateFee,
+/// SchedulePromo, SetReferralBps, ChangeProgramOwner, SetAdminTimelock or
+/// RotateFeeVault instruction, for registries with an admin timelock set.
+/// The signer becomes writable to pay the PDA's rent. A RotateFeeVault
+/// drops its buckets, which go to ExecuteAdminAction with
+/// `with_fee_vault_rotation` instead. Combines with `with_multisig` in
+/// either order.
+pub fn with_timelock(mut ix: Instruction, program_id: &Pubkey, config: &Pubkey) -> Instruction {
+    ix.accounts[0].is_writable = true;
+    if let Ok(NameRegistryInstruction::RotateFeeVault) = NameRegistryInstruction::try_from_slice(&ix.data) {
+        ix.accounts.drain(2..3 + 2 * FEE_BUCKETS as usize);
+    }
+    ix.accounts.splice(
+        2..2,
+        [
+            AccountMeta::new(PendingAdminAction::find_address(config, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+    ix
+}
+
+pub fn set_admin_timelock(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, seconds: u32) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetAdminTimelock { seconds })
+}
+
+pub fn execute_admin_action(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey) -> Instruction {
+    close_admin_action(program_id, owner, config, NameRegistryInstruction::ExecuteAdminAction)
+}
+
+/// Inserts the system program and the treasury buckets into an
+/// ExecuteAdminAction applying a queued RotateFeeVault; `generation` as for
+/// `rotate_fee_vault`. Combines with `with_multisig` in either order.
+pub fn with_fee_vault_rotation(
+    mut ix: Instruction,
+    program_id: &Pubkey,
+    config: &Pubkey,
+    generation: u8,
+) -> Instruction {
+    ix.accounts.splice(3..3, fee_vault_rotation_accounts(program_id, config, generation));
+    ix
+}
+
+pub fn cancel_admin_action(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey) -> Instruction {
+    close_admin_action(program_id, owner, config, NameRegistryInstruction::CancelAdminAction)
+}
+
+fn close_admin_action(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, data: NameRegistryInstruction) -> Instruction {
+    instruction(
+        program_id,
+        data,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*config, false),
+            AccountMeta::new(PendingAdminAction::find_address(config, program_id).0, false),
+        ],
+    )
+}
+
+/// `cosigners` are the further members of the current multisig, if one is
+/// set.
+pub fn set_multisig(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+    cosigners: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*config, false),
+        AccountMeta::new(MultisigConfig::find_address(config, program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(cosigners.iter().map(|cosigner| AccountMeta::new_readonly(*cosigner, true)));
+    instruction(program_id, NameRegistryInstruction::SetMultisig { signers, threshold }, accounts)
+}
+
+pub fn set_privacy_mode(program_id: &Pubkey, wallet: &Pubkey, enabled: bool) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetPrivacyMode { enabled },
+        vec![
+            AccountMeta::new(*wallet, true),
+            AccountMeta::new(WalletSettings::find_address(wallet, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn set_banner_message(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, message: &str) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetBannerMessage { message: message.to_string() },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(BannerMessage::find_address(config, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn schedule_promo(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    start: i64,
+    end: i64,
+    fee_override_bps: u16,
+) -> Instruction {
+    admin(
+        program_id,
+        owner,
+        config,
+        NameRegistryInstruction::SchedulePromo { start, end, fee_override_bps },
+    )
+}
+
+pub fn set_primary_name(program_id: &Pubkey, wallet: &Pubkey, name: &str) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetPrimaryName,
+        vec![
+            AccountMeta::new(*wallet, true),
+            AccountMeta::new_readonly(find_name_address(name, program_id).0, false),
+            AccountMeta::new(find_primary_name_address(wallet, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `name` is the wallet's recorded primary name.
+pub fn resolve_name(program_id: &Pubkey, wallet: &Pubkey, name: &str) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::ResolveName,
+        vec![
+            AccountMeta::new_readonly(find_primary_name_address(wallet, program_id).0, false),
+            AccountMeta::new_readonly(find_name_address(name, program_id).0, false),
+        ],
+    )
+}
+
+pub fn set_analytics(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, enabled: bool) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetAnalytics { enabled })
+}
+
+pub fn set_max_pending_operations(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    max_pending_operations: u16,
+) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetMaxPendingOperations { max_pending_operations })
+}
+
+pub fn set_referral_bps(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, referral_bps: u16) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetReferralBps { referral_bps })
+}
+
+pub fn set_max_total_names(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, max_total_names: u64) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetMaxTotalNames { max_total_names })
+}
+
+pub fn set_cooldown(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, seconds: u32) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetCooldown { seconds })
+}
+
+pub fn set_usd_pricing(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    price_feed: Pubkey,
+    fee_schedule: FeeSchedule,
+    max_price_age: u32,
+    max_confidence_bps: u16,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetUsdPricing { price_feed, fee_schedule, max_price_age, max_confidence_bps },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*config, false),
+            AccountMeta::new(UsdPricing::find_address(config, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn set_pending_update_ttl(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, seconds: u32) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetPendingUpdateTtl { seconds })
+}
+
+pub fn set_tombstone_period(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, seconds: u32) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetTombstonePeriod { seconds })
+}
+
+pub fn set_enforce_unique_address(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, enabled: bool) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetEnforceUniqueAddress { enabled })
+}
+
+pub fn set_registry_pages(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, enabled: bool) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetRegistryPages { enabled })
+}
+
+pub fn freeze_name(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, name_account: &Pubkey) -> Instruction {
+    name_freeze(program_id, owner, config, name_account, NameRegistryInstruction::FreezeName)
+}
+
+pub fn unfreeze_name(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, name_account: &Pubkey) -> Instruction {
+    name_freeze(program_id, owner, config, name_account, NameRegistryInstruction::UnfreezeName)
+}
+
+fn name_freeze(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    name_account: &Pubkey,
+    data: NameRegistryInstruction,
+) -> Instruction {
+    instruction(
+        program_id,
+        data,
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(*name_account, false),
+        ],
+    )
+}
+
+pub fn set_unicode_names(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, enabled: bool) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::SetUnicodeNames { enabled })
+}
+
+pub fn register_subname(
+    program_id: &Pubkey,
+    parent_owner: &Pubkey,
+    parent_name: &str,
+    config: &Pubkey,
+    label: &str,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::RegisterSubname { label: label.to_string() },
+        vec![
+            AccountMeta::new(*parent_owner, true),
+            AccountMeta::new_readonly(find_name_address(parent_name, program_id).0, false),
+            AccountMeta::new(find_name_address(&format!("{}.{}", label, parent_name), program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*config, false),
+        ],
+    )
+}
+
+/// `subname_owner` is the current owner of the subname, whose pending
+/// transfer offer, if any, is released.
+pub fn revoke_subname(
+    program_id: &Pubkey,
+    parent_owner: &Pubkey,
+    parent_name: &str,
+    label: &str,
+    subname_owner: &Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::RevokeSubname,
+        vec![
+            AccountMeta::new(*parent_owner, true),
+            AccountMeta::new_readonly(find_name_address(parent_name, program_id).0, false),
+            AccountMeta::new(find_name_address(&format!("{}.{}", label, parent_name), program_id).0, false),
+            AccountMeta::new(WalletSettings::find_address(subname_owner, program_id).0, false),
+        ],
+    )
+}
+
+pub fn set_record(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey, kind: &str, value: &str) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetRecord { kind: kind.to_string(), value: value.to_string() },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(find_record_address(name_account, kind, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(find_record_index_address(name_account, program_id).0, false),
+        ],
+    )
+}
+
+pub fn delete_record(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey, kind: &str) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::DeleteRecord { kind: kind.to_string() },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(find_record_address(name_account, kind, program_id).0, false),
+            AccountMeta::new(find_record_index_address(name_account, program_id).0, false),
+        ],
+    )
+}
+
+pub fn grant_record_writer(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    kind: &str,
+    wallet: &Pubkey,
+) -> Instruction {
+    let data = NameRegistryInstruction::GrantRecordWriter { kind: kind.to_string(), wallet: *wallet };
+    record_writer(program_id, owner, name_account, kind, data)
+}
+
+pub fn revoke_record_writer(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    kind: &str,
+    wallet: &Pubkey,
+) -> Instruction {
+    let data = NameRegistryInstruction::RevokeRecordWriter { kind: kind.to_string(), wallet: *wallet };
+    record_writer(program_id, owner, name_account, kind, data)
+}
+
+fn record_writer(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    kind: &str,
+    data: NameRegistryInstruction,
+) -> Instruction {
+    instruction(
+        program_id,
+        data,
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(find_record_address(name_account, kind, program_id).0, false),
+        ],
+    )
+}
+
+pub fn list_record_keys(program_id: &Pubkey, name_account: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::ListRecordKeys,
+        vec![
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new_readonly(find_record_index_address(name_account, program_id).0, false),
+        ],
+    )
+}
+
+pub fn set_note(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey, data: Vec<u8>) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetNote { data },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(find_note_address(name_account, owner, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn delete_note(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::DeleteNote,
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(find_note_address(name_account, owner, program_id).0, false),
+        ],
+    )
+}
+
+/// Moves `config` to the config PDA, with its fees.
+pub fn migrate_config(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey) -> Instruction {
+    let new_config = find_config_address(program_id).0;
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*config, false),
+        AccountMeta::new(new_config, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(writable_treasuries(config, program_id));
+    accounts.extend(writable_treasuries(&new_config, program_id));
+    accounts.push(AccountMeta::new(ConfigLink::find_address(program_id).0, false));
+    instruction(program_id, NameRegistryInstruction::MigrateConfig, accounts)
+}
+
+pub fn transfer_name(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    config: &Pubkey,
+    new_owner: Pubkey,
+    require_acceptance: bool,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::TransferName { new_owner, require_acceptance, wipe_records: false },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(WalletSettings::find_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(name_account, program_id).0, false),
+        ],
+    )
+}
+
+/// Appends the co-signer's signature to a TransferName, RenameName,
+/// RequestAddressUpdate or UnregisterName instruction on a name with a
+/// co-signer set.
+pub fn with_cosigner(mut ix: Instruction, cosigner: &Pubkey) -> Instruction {
+    ix.accounts.push(AccountMeta::new_readonly(*cosigner, true));
+    ix
+}
+
+/// `signers` are the new co-signer, unless `cosigner` is the default
+/// pubkey, and the current one, if one is set.
+pub fn set_cosigner(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    cosigner: Pubkey,
+    signers: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*name_account, false),
+        AccountMeta::new(NameCosigner::find_address(name_account, program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(signers.iter().map(|signer| AccountMeta::new_readonly(*signer, true)));
+    instruction(program_id, NameRegistryInstruction::SetCosigner { cosigner }, accounts)
+}
+
+/// `address_account` is the name's reverse record: its PDA, unless the name
+/// was renamed, in which case the record created with the original name.
+pub fn unregister_name(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    address_account: &Pubkey,
+    config: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::UnregisterName,
+        vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new(*address_account, false),
+            AccountMeta::new(*config, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(find_record_index_address(name_account, program_id).0, false),
+            AccountMeta::new(WalletSettings::find_address(owner, program_id).0, false),
+            AccountMeta::new(NameCosigner::find_address(name_account, program_id).0, false),
+        ],
+    )
+}
+
+/// Adds the tombstone PDA of the name, for configs with a
+/// `tombstone_period`. An UnregisterName instruction gets it appended with
+/// the system program, the owner becoming writable to pay its rent, and a
+/// StartAuction one gets it appended read-only. A RegisterName, RevealAndRegister, RegisterNameAsRegistrar,
+/// RegisterNameWithRecords, RegisterNameFor or RegisterWithVoucher
+/// instruction gets it inserted; apply it there after `with_receipt` and
+/// the like, but before `with_registry_page`.
+pub fn with_tombstone(mut ix: Instruction, program_id: &Pubkey) -> Instruction {
+    let position = match NameRegistryInstruction::try_from_slice(&ix.data) {
+        Ok(NameRegistryInstruction::UnregisterName) => {
+            ix.accounts[0].is_writable = true;
+            let tombstone = NameTombstone::find_address(&ix.accounts[1].pubkey, program_id).0;
+            ix.accounts.push(AccountMeta::new(tombstone, false));
+            ix.accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+            return ix;
+        }
+        Ok(NameRegistryInstruction::StartAuction { .. }) => {
+            let tombstone = NameTombstone::find_address(&ix.accounts[3].pubkey, program_id).0;
+            ix.accounts.push(AccountMeta::new_readonly(tombstone, false));
+            return ix;
+        }
+        Ok(NameRegistryInstruction::RegisterName { .. })
+        | Ok(NameRegistryInstruction::RegisterNameFor { .. }) => 6,
+        Ok(NameRegistryInstruction::RevealAndRegister { .. })
+        | Ok(NameRegistryInstruction::RegisterNameAsRegistrar { .. })
+        | Ok(NameRegistryInstruction::RegisterWithVoucher { .. }) => 7,
+        Ok(NameRegistryInstruction::RegisterNameWithRecords { records, .. }) => 7 + records.len(),
+        _ => panic!("an UnregisterName, StartAuction or registration instruction"),
+    };
+    // The name account comes second of the RegisterName accounts
+    let tombstone = NameTombstone::find_address(&ix.accounts[position - 5].pubkey, program_id).0;
+    ix.accounts.insert(position, AccountMeta::new_readonly(tombstone, false));
+    ix
+}
+
+/// `payer` is the tombstone's `payer`, who gets its rent back.
+pub fn close_tombstone(program_id: &Pubkey, name_account: &Pubkey, payer: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::CloseTombstone,
+        vec![
+            AccountMeta::new(NameTombstone::find_address(name_account, program_id).0, false),
+            AccountMeta::new(*payer, false),
+        ],
+    )
+}
+
+pub fn close_receipts(program_id: &Pubkey, payer: &Pubkey, receipts: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    accounts.extend(receipts.iter().map(|receipt| AccountMeta::new(*receipt, false)));
+    instruction(program_id, NameRegistryInstruction::CloseReceipts, accounts)
+}
+
+pub fn reserve_name(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, name: &str) -> Instruction {
+    let name_account = find_name_address(name, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::ReserveName { name: name.to_string() },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(ReservedName::find_address(&name_account, program_id).0, false),
+        ],
+    )
+}
+
+pub fn create_namespace(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    suffix: &str,
+    fee: u64,
+    admin: Pubkey,
+) -> Instruction {
+    let name_account = find_name_address(suffix, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::CreateNamespace { suffix: suffix.to_string(), fee, admin },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(NamespaceAccount::find_address(&name_account, program_id).0, false),
+        ],
+    )
+}
+
+pub fn set_namespace_policy(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    suffix: &str,
+    fee: u64,
+    new_admin: Pubkey,
+    paused: bool,
+) -> Instruction {
+    let namespace = NamespaceAccount::find_address(&find_name_address(suffix, program_id).0, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetNamespacePolicy { fee, admin: new_admin, paused },
+        vec![AccountMeta::new_readonly(*admin, true), AccountMeta::new(namespace, false)],
+    )
+}
+
+/// `delegate_program` is the default pubkey to take the namespace back.
+pub fn delegate_namespace_authority(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    suffix: &str,
+    delegate_program: Pubkey,
+) -> Instruction {
+    let namespace = NamespaceAccount::find_address(&find_name_address(suffix, program_id).0, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::DelegateNamespaceAuthority { program_id: delegate_program },
+        vec![AccountMeta::new_readonly(*admin, true), AccountMeta::new(namespace, false)],
+    )
+}
+
+pub fn withdraw_namespace_fees(program_id: &Pubkey, admin: &Pubkey, suffix: &str) -> Instruction {
+    let namespace = NamespaceAccount::find_address(&find_name_address(suffix, program_id).0, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::WithdrawNamespaceFees,
+        vec![AccountMeta::new(*admin, true), AccountMeta::new(namespace, false)],
+    )
+}
+
+/// Without an `assignee` the name is freed; with one it is registered to
+/// the assignee.
+pub fn release_reserved_name(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    name: &str,
+    assignee: Option<Pubkey>,
+) -> Instruction {
+    let name_account = find_name_address(name, program_id).0;
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(*config, false),
+        AccountMeta::new(ReservedName::find_address(&name_account, program_id).0, false),
+        AccountMeta::new(name_account, false),
+    ];
+    if let Some(assignee) = assignee {
+        accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+        accounts.push(AccountMeta::new(find_address_account_address(&name_account, program_id).0, false));
+        accounts.push(AccountMeta::new_readonly(WalletSettings::find_address(&assignee, program_id).0, false));
+    }
+    instruction(program_id, NameRegistryInstruction::ReleaseReservedName { assignee }, accounts)
+}
+
+/// `root` is `client::blocklist::BlocklistTree::root`, or zero to turn the
+/// blocklist off.
+pub fn set_blocklist_root(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, root: [u8; 32]) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::SetBlocklistRoot { root },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*config, false),
+            AccountMeta::new(Blocklist::find_address(config, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Has a RegisterName, RevealAndRegister, RegisterNameAsRegistrar,
+/// RegisterNameWithRecords, RegisterNameFor, RegisterWithVoucher or RenameName instruction prove its name is missing from the blocklist of `config`, adding the blocklist PDA.
+/// Apply it last, after `with_receipt` and the like.
+pub fn with_name_proof(mut ix: Instruction, program_id: &Pubkey, config: &Pubkey, proof: NameProof) -> Instruction {
+    let proof = Some(proof);
+    let (data, position) = match NameRegistryInstruction::try_from_slice(&ix.data) {
+        Ok(NameRegistryInstruction::RegisterName { name, .. }) => {
+            (NameRegistryInstruction::RegisterName { name, proof }, 6)
+        }
+        Ok(NameRegistryInstruction::RevealAndRegister { name, salt, .. }) => {
+            (NameRegistryInstruction::RevealAndRegister { name, salt, proof }, 7)
+        }
+        Ok(NameRegistryInstruction::RenameName { new_name, .. }) => {
+            (NameRegistryInstruction::RenameName { new_name, proof }, 9)
+        }
+        Ok(NameRegistryInstruction::RegisterNameAsRegistrar { name, owner, .. }) => {
+            (NameRegistryInstruction::RegisterNameAsRegistrar { name, owner, proof }, 7)
+        }
+        Ok(NameRegistryInstruction::RegisterNameWithRecords { name, records, .. }) => {
+            let position = 7 + records.len();
+            (NameRegistryInstruction::RegisterNameWithRecords { name, records, proof }, position)
+        }
+        Ok(NameRegistryInstruction::RegisterNameFor { name, owner, .. }) => {
+            (NameRegistryInstruction::RegisterNameFor { name, owner, proof }, 6)
+        }
+        Ok(NameRegistryInstruction::RegisterWithVoucher { name, discount_bps, expiry, signature, .. }) => {
+            (NameRegistryInstruction::RegisterWithVoucher { name, discount_bps, expiry, signature, proof }, 7)
+        }
+        _ => panic!("a registration or RenameName instruction"),
+    };
+    ix.data = data.try_to_vec().unwrap();
+    let blocklist = Blocklist::find_address(config, program_id).0;
+    ix.accounts.insert(position, AccountMeta::new_readonly(blocklist, false));
+    ix
+}
+
+/// Adds the address claim PDA of `owner`, which registrations need while
+/// the config has `enforce_unique_address`, to a RegisterName,
+/// RevealAndRegister, RegisterNameAsRegistrar, RegisterNameWithRecords,
+/// RegisterNameFor or RegisterWithVoucher instruction. Apply it after `with_receipt` and the like,
+/// but before `with_name_proof`. A ClaimPromoName, ReleaseReservedName or
+/// SettleAuction instruction, which registers the name to `owner` too, gets
+/// it the same way.
+pub fn with_address_claim(mut ix: Instruction, program_id: &Pubkey, owner: &Pubkey) -> Instruction {
+    let position = match NameRegistryInstruction::try_from_slice(&ix.data) {
+        Ok(NameRegistryInstruction::RegisterName { .. })
+        | Ok(NameRegistryInstruction::RegisterNameFor { .. }) => 6,
+        Ok(NameRegistryInstruction::RevealAndRegister { .. })
+        | Ok(NameRegistryInstruction::RegisterNameAsRegistrar { .. })
+        | Ok(NameRegistryInstruction::RegisterWithVoucher { .. }) => 7,
+        Ok(NameRegistryInstruction::RegisterNameWithRecords { records, .. }) => 7 + records.len(),
+        Ok(NameRegistryInstruction::ReleaseReservedName { .. }) => 6,
+        Ok(NameRegistryInstruction::ClaimPromoName { .. }) => 7,
+        Ok(NameRegistryInstruction::SettleAuction) => 9,
+        _ => panic!("a registration instruction"),
+    };
+    let claim = AddressClaim::find_address(owner, program_id).0;
+    ix.accounts.insert(position, AccountMeta::new(claim, false));
+    ix
+}
+
+/// Adds the address claim PDAs of `previous_owner` and `new_owner`, which
+/// a TransferName without acceptance, AcceptNameTransfer,
+/// CompleteAddressUpdate or SyncTokenizedOwner instruction needs while the
+/// config has `enforce_unique_address`, to move the name's claim. A
+/// SyncTokenizedOwner has `new_owner`, the token holder, sign and pay for
+/// the claim. Apply it after `with_record_wipe`.
+pub fn with_moved_address_claim(
+    mut ix: Instruction,
+    program_id: &Pubkey,
+    previous_owner: &Pubkey,
+    new_owner: &Pubkey,
+) -> Instruction {
+    let claims = [
+        AccountMeta::new(AddressClaim::find_address(previous_owner, program_id).0, false),
+        AccountMeta::new(AddressClaim::find_address(new_owner, program_id).0, false),
+    ];
+    let (position, accounts) = match NameRegistryInstruction::try_from_slice(&ix.data) {
+        Ok(NameRegistryInstruction::TransferName { .. }) => (6, claims.to_vec()),
+        Ok(NameRegistryInstruction::AcceptNameTransfer { .. }) => {
+            // `with_record_wipe` brings the offering owner along already
+            ix.accounts[0].is_writable = true;
+            if ix.accounts.len() == 4 {
+                ix.accounts.push(AccountMeta::new(*previous_owner, false));
+            }
+            let mut accounts = vec![AccountMeta::new_readonly(system_program::id(), false)];
+            accounts.extend(claims);
+            (5, accounts)
+        }
+        Ok(NameRegistryInstruction::CompleteAddressUpdate) => {
+            let mut accounts = vec![AccountMeta::new(*previous_owner, false)];
+            accounts.extend(claims);
+            (8, accounts)
+        }
+        Ok(NameRegistryInstruction::SyncTokenizedOwner) => {
+            let mut accounts = vec![
+                AccountMeta::new(*new_owner, true),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(*previous_owner, false),
+            ];
+            accounts.extend(claims);
+            (3, accounts)
+        }
+        _ => panic!("a TransferName, AcceptNameTransfer, CompleteAddressUpdate or SyncTokenizedOwner instruction"),
+    };
+    ix.accounts.splice(position..position, accounts);
+    ix
+}
+
+/// Inserts the registry page of `config` numbered `page` (see
+/// `ProgramConfig::registry_page`), which registrations need while the config
+/// has `registry_pages_enabled`, into a RegisterName, RevealAndRegister,
+/// RegisterNameAsRegistrar, RegisterNameWithRecords, RegisterNameFor or
+/// RegisterWithVoucher instruction. Apply it after `with_receipt`,
+/// `with_tombstone` and the like, but before `with_usd_pricing`,
+/// `with_address_claim` and `with_name_proof`.
+pub fn with_registry_page(mut ix: Instruction, program_id: &Pubkey, config: &Pubkey, page: u32) -> Instruction {
+    let position = match NameRegistryInstruction::try_from_slice(&ix.data) {
+        Ok(NameRegistryInstruction::RegisterName { .. })
+        | Ok(NameRegistryInstruction::RegisterNameFor { .. }) => 6,
+        Ok(NameRegistryInstruction::RevealAndRegister { .. })
+        | Ok(NameRegistryInstruction::RegisterNameAsRegistrar { .. })
+        | Ok(NameRegistryInstruction::RegisterWithVoucher { .. }) => 7,
+        Ok(NameRegistryInstruction::RegisterNameWithRecords { records, .. }) => 7 + records.len(),
+        _ => panic!("a registration instruction"),
+    };
+    let page = RegistryPage::find_address(config, page, program_id).0;
+    ix.accounts.insert(position, AccountMeta::new(page, false));
+    ix
+}
+
+/// Closes the address claim of `wallet`, which must no longer own
+/// `name_account`.
+pub fn release_address_claim(program_id: &Pubkey, wallet: &Pubkey, name_account: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::ReleaseAddressClaim,
+        vec![
+            AccountMeta::new(AddressClaim::find_address(wallet, program_id).0, false),
+            AccountMeta::new(*wallet, false),
+            AccountMeta::new_readonly(*name_account, false),
+        ],
+    )
+}
+
+pub fn approve_operator(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    operator: Pubkey,
+    scope: OperatorScope,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::ApproveOperator { operator, scope },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(NameOperator::find_address(name_account, scope, &operator, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn revoke_operator(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    operator: Pubkey,
+    scope: OperatorScope,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::RevokeOperator { operator, scope },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(NameOperator::find_address(name_account, scope, &operator, program_id).0, false),
+        ],
+    )
+}
+
+/// Appends the signer's operator PDA to a SetRecord or DeleteRecord
+/// instruction signed by an operator rather than the name owner.
+pub fn with_operator(mut ix: Instruction, program_id: &Pubkey) -> Instruction {
+    let scope = match NameRegistryInstruction::try_from_slice(&ix.data) {
+        Ok(NameRegistryInstruction::SetRecord { .. }) | Ok(NameRegistryInstruction::DeleteRecord { .. }) => {
+            OperatorScope::Records
+        }
+        _ => panic!("a SetRecord or DeleteRecord instruction"),
+    };
+    let (operator, name_account) = (ix.accounts[0].pubkey, ix.accounts[1].pubkey);
+    let approval = NameOperator::find_address(&name_account, scope, &operator, program_id).0;
+    ix.accounts.push(AccountMeta::new_readonly(approval, false));
+    ix
+}
+
+/// `account` pays `fee_bps` of the registration fee when registering as a
+/// registrar.
+pub fn grant_role(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    role: Role,
+    account: Pubkey,
+    fee_bps: u16,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::GrantRole { role, account, fee_bps },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(RoleGrant::find_address(config, role, &account, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn revoke_role(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, role: Role, account: Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::RevokeRole { role, account },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(RoleGrant::find_address(config, role, &account, program_id).0, false),
+        ],
+    )
+}
+
+/// Registers `name` for `owner`, paid for by `registrar`.
+pub fn register_name_as_registrar(
+    program_id: &Pubkey,
+    registrar: &Pubkey,
+    config: &Pubkey,
+    name: &str,
+    owner: Pubkey,
+) -> Instruction {
+    let role = RoleGrant::find_address(config, Role::Registrar, registrar, program_id).0;
+    let mut accounts = vec![AccountMeta::new_readonly(role, false)];
+    accounts.extend(register_name(program_id, registrar, config, name).accounts);
+    accounts.push(AccountMeta::new_readonly(WalletSettings::find_address(&owner, program_id).0, false));
+    let data = NameRegistryInstruction::RegisterNameAsRegistrar { name: name.to_string(), owner, proof: None };
+    instruction(program_id, data, accounts)
+}
+
+/// Registers `name` for `owner`, paid for by `registrant`.
+pub fn register_name_for(
+    program_id: &Pubkey,
+    registrant: &Pubkey,
+    config: &Pubkey,
+    name: &str,
+    owner: Pubkey,
+) -> Instruction {
+    let mut accounts = register_name(program_id, registrant, config, name).accounts;
+    accounts.push(AccountMeta::new_readonly(WalletSettings::find_address(&owner, program_id).0, false));
+    let data = NameRegistryInstruction::RegisterNameFor { name: name.to_string(), owner, proof: None };
+    instruction(program_id, data, accounts)
+}
+
+/// Registers `name` under a voucher of the program owner. The transaction
+/// must carry `voucher_verify` for the same voucher right before it.
+pub fn register_with_voucher(
+    program_id: &Pubkey,
+    registrant: &Pubkey,
+    config: &Pubkey,
+    name: &str,
+    discount_bps: u16,
+    expiry: i64,
+    signature: [u8; 64],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(sysvar::instructions::id(), false)];
+    accounts.extend(register_name(program_id, registrant, config, name).accounts);
+    let data =
+        NameRegistryInstruction::RegisterWithVoucher { name: name.to_string(), discount_bps, expiry, signature, proof: None };
+    instruction(program_id, data, accounts)
+}
+
+/// Ed25519 program instruction verifying the voucher `program_owner` signed
+/// for `registrant`, to go right before `register_with_voucher`.
+pub fn voucher_verify(
+    program_owner: &Pubkey,
+    config: &Pubkey,
+    registrant: &Pubkey,
+    name: &str,
+    discount_bps: u16,
+    expiry: i64,
+    signature: [u8; 64],
+) -> Instruction {
+    let message = voucher::voucher_message(config, registrant, name, discount_bps, expiry);
+    voucher::verify_instruction(program_owner, &message, &signature)
+}
+
+/// Registers `name` with `records`, as `(kind, value)` pairs, already set.
+pub fn register_name_with_records(
+    program_id: &Pubkey,
+    registrant: &Pubkey,
+    config: &Pubkey,
+    name: &str,
+    records: &[(&str, &str)],
+) -> Instruction {
+    let name_account = find_name_address(name, program_id).0;
+    let mut accounts = vec![AccountMeta::new(find_record_index_address(&name_account, program_id).0, false)];
+    for (kind, _) in records {
+        accounts.push(AccountMeta::new(find_record_address(&name_account, kind, program_id).0, false));
+    }
+    accounts.extend(register_name(program_id, registrant, config, name).accounts);
+    let records = records.iter().map(|(kind, value)| (kind.to_string(), value.to_string())).collect();
+    let data = NameRegistryInstruction::RegisterNameWithRecords { name: name.to_string(), records, proof: None };
+    instruction(program_id, data, accounts)
+}
+
+/// Mints the token of `name_account` to `owner`'s associated token account.
+/// Add Metaplex metadata with `with_token_metadata`.
+pub fn tokenize_name(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey, config: &Pubkey) -> Instruction {
+    let mint = find_name_mint_address(name_account, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::TokenizeName { uri: String::new() },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(get_associated_token_address(owner, &mint), false),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+    )
+}
+
+/// Has a TokenizeName instruction also create the token's metadata,
+/// pointing at `uri`.
+pub fn with_token_metadata(mut ix: Instruction, uri: &str) -> Instruction {
+    let mint = ix.accounts[2].pubkey;
+    ix.data = NameRegistryInstruction::TokenizeName { uri: uri.to_string() }.try_to_vec().unwrap();
+    ix.accounts.push(AccountMeta::new(token_metadata::find_metadata_address(&mint).0, false));
+    ix.accounts.push(AccountMeta::new_readonly(token_metadata::ID, false));
+    ix
+}
+
+/// Syncs the owner of tokenized `name_account` to `holder`, whose
+/// associated token account must hold the name's token.
+pub fn sync_tokenized_owner(program_id: &Pubkey, name_account: &Pubkey, config: &Pubkey, holder: &Pubkey) -> Instruction {
+    let mint = find_name_mint_address(name_account, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::SyncTokenizedOwner,
+        vec![
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new_readonly(get_associated_token_address(holder, &mint), false),
+            AccountMeta::new_readonly(*config, false),
+        ],
+    )
+}
+
+/// Resolves a tokenized name through the token in `holder`'s associated
+/// token account.
+pub fn resolve_token_holder(program_id: &Pubkey, name_account: &Pubkey, holder: &Pubkey) -> Instruction {
+    let mint = find_name_mint_address(name_account, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::ResolveTokenHolder,
+        vec![
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new_readonly(get_associated_token_address(holder, &mint), false),
+        ],
+    )
+}
+
+pub fn finalize_burned_name(program_id: &Pubkey, name_account: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::FinalizeBurnedName,
+        vec![
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new_readonly(find_name_mint_address(name_account, program_id).0, false),
+        ],
+    )
+}
+
+/// `generation` is the config's current `fee_vault_generation`.
+pub fn rotate_fee_vault(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, generation: u8) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(*owner, true), AccountMeta::new(*config, false)];
+    accounts.extend(fee_vault_rotation_accounts(program_id, config, generation));
+    instruction(program_id, NameRegistryInstruction::RotateFeeVault, accounts)
+}
+
+/// The system program, then every treasury bucket of fee vault
+/// `generation` and every one of the next.
+fn fee_vault_rotation_accounts(program_id: &Pubkey, config: &Pubkey, generation: u8) -> Vec<AccountMeta> {
+    let mut accounts = vec![AccountMeta::new_readonly(system_program::id(), false)];
+    for generation in [generation, generation + 1] {
+        accounts.extend((0..FEE_BUCKETS).map(|bucket| {
+            AccountMeta::new(find_fee_vault_address(config, generation, bucket, program_id).0, false)
+        }));
+    }
+    accounts
+}
+
+/// Points the treasury buckets of `config` in any instruction built here at
+/// fee vault `generation`, for registries whose fees RotateFeeVault moved.
+pub fn with_fee_vault_generation(
+    mut ix: Instruction,
+    program_id: &Pubkey,
+    config: &Pubkey,
+    generation: u8,
+) -> Instruction {
+    for bucket in 0..FEE_BUCKETS {
+        let first = find_treasury_address(config, bucket, program_id).0;
+        let current = find_fee_vault_address(config, generation, bucket, program_id).0;
+        for account in ix.accounts.iter_mut().filter(|account| account.pubkey == first) {
+            account.pubkey = current;
+        }
+    }
+    ix
+}
+
+/// `owner` is the current name owner, who made the offer.
+pub fn accept_name_transfer(
+    program_id: &Pubkey,
+    pending_owner: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    config: &Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::AcceptNameTransfer { wipe_records: false },
+        vec![
+            AccountMeta::new_readonly(*pending_owner, true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(WalletSettings::find_address(owner, program_id).0, false),
+        ],
+    )
+}
+
+/// Has a TransferName or AcceptNameTransfer instruction delete all of the
+/// name's `records` (see `RecordIndex`), refunding their rent to `owner`,
+/// the name's current owner. Apply it after `with_cosigner`.
+pub fn with_record_wipe(mut ix: Instruction, program_id: &Pubkey, owner: &Pubkey, records: &[Pubkey]) -> Instruction {
+    match NameRegistryInstruction::try_from_slice(&ix.data) {
+        Ok(NameRegistryInstruction::TransferName { new_owner, require_acceptance, .. }) => {
+            let data = NameRegistryInstruction::TransferName { new_owner, require_acceptance, wipe_records: true };
+            ix.data = data.try_to_vec().unwrap();
+            ix.accounts[0].is_writable = true;
+        }
+        Ok(NameRegistryInstruction::AcceptNameTransfer { .. }) => {
+            ix.data = NameRegistryInstruction::AcceptNameTransfer { wipe_records: true }.try_to_vec().unwrap();
+            ix.accounts.push(AccountMeta::new(*owner, false));
+        }
+        _ => panic!("a TransferName or AcceptNameTransfer instruction"),
+    }
+    let name_account = ix.accounts[1].pubkey;
+    ix.accounts.push(AccountMeta::new(find_record_index_address(&name_account, program_id).0, false));
+    ix.accounts.extend(records.iter().map(|record| AccountMeta::new(*record, false)));
+    ix
+}
+
+pub fn pause(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::Pause)
+}
+
+pub fn unpause(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey) -> Instruction {
+    admin(program_id, owner, config, NameRegistryInstruction::Unpause)
+}
+
+/// `claims` pairs each secret preimage with the name it unlocks; only the
+/// commitments go on chain.
+pub fn create_promo_claims(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    expires_at: i64,
+    claims: &[(&[u8], &str)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*owner, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    let mut entries = Vec::with_capacity(claims.len());
+    for (preimage, name) in claims {
+        let commitment = promo_claim_commitment(preimage);
+        accounts.push(AccountMeta::new(find_promo_claim_address(config, &commitment, program_id).0, false));
+        accounts.push(AccountMeta::new(find_name_address(name, program_id).0, false));
+        entries.push(PromoClaimEntry { commitment, name: name.to_string(), proof: None });
+    }
+    instruction(
+        program_id,
+        NameRegistryInstruction::CreatePromoClaims { expires_at, claims: entries },
+        accounts,
+    )
+}
+
+/// Has a CreatePromoClaims instruction prove its names are missing from the
+/// blocklist of `config`, one proof per claim in order, adding the blocklist
+/// PDA.
+pub fn with_promo_name_proofs(
+    mut ix: Instruction,
+    program_id: &Pubkey,
+    config: &Pubkey,
+    proofs: Vec<NameProof>,
+) -> Instruction {
+    let Ok(NameRegistryInstruction::CreatePromoClaims { expires_at, mut claims }) =
+        NameRegistryInstruction::try_from_slice(&ix.data)
+    else {
+        panic!("a CreatePromoClaims instruction");
+    };
+    for (entry, proof) in claims.iter_mut().zip(proofs) {
+        entry.proof = Some(proof);
+    }
+    ix.data = NameRegistryInstruction::CreatePromoClaims { expires_at, claims }.try_to_vec().unwrap();
+    ix.accounts.insert(3, AccountMeta::new_readonly(Blocklist::find_address(config, program_id).0, false));
+    ix
+}
+
+/// `program_owner` receives the claim account's rent; with a multisig set,
+/// pass the name's treasury bucket PDA instead.
+pub fn claim_promo_name(
+    program_id: &Pubkey,
+    claimer: &Pubkey,
+    config: &Pubkey,
+    program_owner: &Pubkey,
+    name: &str,
+    preimage: &[u8],
+) -> Instruction {
+    let name_account = find_name_address(name, program_id).0;
+    let claim = find_promo_claim_address(config, &promo_claim_commitment(preimage), program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::ClaimPromoName { preimage: preimage.to_vec() },
+        vec![
+            AccountMeta::new(*claimer, true),
+            AccountMeta::new(*config, false),
+            AccountMeta::new(*program_owner, false),
+            AccountMeta::new(claim, false),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(find_address_account_address(&name_account, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `claims` pairs each expired claim PDA with its reserved name.
+pub fn reclaim_promo_names(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, claims: &[(Pubkey, &str)]) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(*owner, true), AccountMeta::new_readonly(*config, false)];
+    for (claim, name) in claims {
+        accounts.push(AccountMeta::new(*claim, false));
+        accounts.push(AccountMeta::new(find_name_address(name, program_id).0, false));
+    }
+    instruction(program_id, NameRegistryInstruction::ReclaimPromoNames, accounts)
+}
+
+/// `address_account` is the legacy reverse record: the name's PDA, or for
+/// older registrations the account that was passed to RegisterName.
+pub fn migrate_address_account(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    name_account: &Pubkey,
+    address_account: &Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::MigrateAddressAccount,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(*address_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Upgrades `account` from the version 1 layout, with `payer` covering the
+/// rent of the extra byte.
+pub fn migrate_account(program_id: &Pubkey, payer: &Pubkey, account: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        NameRegistryInstruction::MigrateAccount,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn start_auction(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, name: &str, min_bid: u64) -> Instruction {
+    let name_account = find_name_address(name, program_id).0;
+    let auction = find_auction_address(&name_account, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::StartAuction { name: name.to_string(), min_bid },
+        vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(find_auction_escrow_address(&auction, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(ReservedName::find_address(&name_account, program_id).0, false),
+        ],
+    )
+}
+
+/// `highest_bidder` is the bidder to refund, `None` before the first bid.
+pub fn place_bid(
+    program_id: &Pubkey,
+    bidder: &Pubkey,
+    config: &Pubkey,
+    name: &str,
+    highest_bidder: Option<&Pubkey>,
+    amount: u64,
+) -> Instruction {
+    let auction = find_auction_address(&find_name_address(name, program_id).0, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::PlaceBid { amount },
+        vec![
+            AccountMeta::new(*bidder, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(find_auction_escrow_address(&auction, program_id).0, false),
+            AccountMeta::new(*highest_bidder.unwrap_or(bidder), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `program_owner` receives the auction's rent; with a multisig set, pass
+/// the name's treasury bucket PDA instead.
+pub fn settle_auction(program_id: &Pubkey, payer: &Pubkey, config: &Pubkey, program_owner: &Pubkey, name: &str) -> Instruction {
+    let name_account = find_name_address(name, program_id).0;
+    let auction = find_auction_address(&name_account, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::SettleAuction,
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*config, false),
+            AccountMeta::new(*program_owner, false),
+            AccountMeta::new(auction, false),
+            AccountMeta::new(find_auction_escrow_address(&auction, program_id).0, false),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(find_address_account_address(&name_account, program_id).0, false),
+            AccountMeta::new(find_treasury_address(config, fee_bucket(name), program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Extension accounts are defined by the extension itself.
+pub fn extension(program_id: &Pubkey, tag: u16, payload: Vec<u8>, accounts: Vec<AccountMeta>) -> Instruction {
+    instruction(program_id, NameRegistryInstruction::Extension { tag, payload }, accounts)
+}
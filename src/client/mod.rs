@@ -0,0 +1,13 @@
+//! Off-chain helpers for building and interpreting registry transactions.
+
+pub mod blocklist;
+pub mod costs;
+pub mod export;
+pub mod filters;
+pub mod instructions;
+pub mod read;
+pub mod vanity;
+pub mod view;
+
+pub use costs::{estimate_costs, CostEstimate, CostOptions};
+pub use vanity::grind_vanity_seed;
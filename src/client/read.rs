@@ -0,0 +1,153 @@
+//! Batched getter "read transactions".
+//!
+//! Each getter instruction reports its result through return data, but a
+//! simulated transaction only surfaces the return data of its last
+//! instruction. The runtime does however log every instruction's return data
+//! as `Program return: <program_id> <base64>`, so a `ReadTx` packs several
+//! getters into one unsigned transaction and decodes the results from the
+//! simulation logs, in instruction order.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshSerialize;
+use solana_program::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use solana_sdk::transaction::Transaction;
+use thiserror::Error;
+
+use crate::{formats, instruction::NameRegistryInstruction};
+
+const RETURN_LOG_PREFIX: &str = "Program return: ";
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ReadError {
+    #[error("Missing return data for read {0}")]
+    MissingReturnData(usize),
+
+    #[error("Invalid return data for read {0}")]
+    InvalidReturnData(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Read {
+    ContractOwner,
+    RegistrationFee,
+    PendingContractOwner,
+    ResolveAddress(Pubkey),
+}
+
+/// Decoded results of a `ReadTx`. Fields are `None`/empty for reads that
+/// were not requested.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadResults {
+    pub contract_owner: Option<Pubkey>,
+    pub registration_fee: Option<u64>,
+    pub pending_contract_owner: Option<Pubkey>,
+    /// `(name account, resolved address)` pairs in request order.
+    pub resolved_addresses: Vec<(Pubkey, Pubkey)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadTx {
+    program_id: Pubkey,
+    instructions: Vec<Instruction>,
+    reads: Vec<Read>,
+}
+
+impl ReadTx {
+    pub fn new(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            instructions: Vec::new(),
+            reads: Vec::new(),
+        }
+    }
+
+    pub fn contract_owner(self, config_account: Pubkey) -> Self {
+        self.push(Read::ContractOwner, NameRegistryInstruction::GetContractOwner, config_account)
+    }
+
+    pub fn registration_fee(self, config_account: Pubkey) -> Self {
+        self.push(Read::RegistrationFee, NameRegistryInstruction::GetRegistrationFee, config_account)
+    }
+
+    pub fn pending_contract_owner(self, config_account: Pubkey) -> Self {
+        self.push(
+            Read::PendingContractOwner,
+            NameRegistryInstruction::GetPendingContractOwner,
+            config_account,
+        )
+    }
+
+    pub fn resolve_address(self, name_account: Pubkey) -> Self {
+        self.push(
+            Read::ResolveAddress(name_account),
+            NameRegistryInstruction::ResolveAddress,
+            name_account,
+        )
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Builds the unsigned transaction to pass to `simulateTransaction`
+    /// (with signature verification disabled).
+    pub fn transaction(&self, payer: &Pubkey, recent_blockhash: Hash) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(&self.instructions, Some(payer));
+        transaction.message.recent_blockhash = recent_blockhash;
+        transaction
+    }
+
+    /// Decodes the simulation logs of the transaction built by `transaction`.
+    pub fn decode(&self, logs: &[String]) -> Result<ReadResults, ReadError> {
+        let prefix = format!("{}{} ", RETURN_LOG_PREFIX, self.program_id);
+        let mut payloads = logs
+            .iter()
+            .filter_map(|log| log.strip_prefix(prefix.as_str()));
+
+        let mut results = ReadResults::default();
+        for (index, read) in self.reads.iter().enumerate() {
+            let encoded = payloads.next().ok_or(ReadError::MissingReturnData(index))?;
+            let data = STANDARD
+                .decode(encoded.trim())
+                .map_err(|_| ReadError::InvalidReturnData(index))?;
+
+            match read {
+                Read::ContractOwner => {
+                    results.contract_owner = Some(decode_pubkey(&data, index)?);
+                }
+                Read::RegistrationFee => {
+                    let fee = formats::decode_fee(&data).ok_or(ReadError::InvalidReturnData(index))?;
+                    results.registration_fee = Some(fee);
+                }
+                Read::PendingContractOwner => {
+                    results.pending_contract_owner = Some(decode_pubkey(&data, index)?);
+                }
+                Read::ResolveAddress(name_account) => {
+                    results
+                        .resolved_addresses
+                        .push((*name_account, decode_pubkey(&data, index)?));
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    fn push(mut self, read: Read, instruction: NameRegistryInstruction, account: Pubkey) -> Self {
+        self.instructions.push(Instruction {
+            program_id: self.program_id,
+            accounts: vec![AccountMeta::new_readonly(account, false)],
+            data: instruction.try_to_vec().unwrap(),
+        });
+        self.reads.push(read);
+        self
+    }
+}
+
+fn decode_pubkey(data: &[u8], index: usize) -> Result<Pubkey, ReadError> {
+    formats::decode_address(data).ok_or(ReadError::InvalidReturnData(index))
+}
@@ -0,0 +1,102 @@
+//! Grinding seeds for vanity account addresses.
+//!
+//! Most registry PDAs are fixed by what they hold: a name account by its
+//! name, a record by its name and kind. Only a few take bytes the user is
+//! free to pick, and those can be varied until the address starts with a
+//! chosen base58 prefix:
+//!
+//! - the `salt` of a name commitment, see `grind_commitment_salt`;
+//! - the preimage of a promo claim, see `grind_promo_claim_preimage`.
+//!
+//! Both seeds are also secrets, so grinding starts from a random seed the
+//! caller supplies and only varies its first eight bytes. Each extra prefix
+//! character makes a match about 58 times rarer.
+
+use std::time::{Duration, Instant};
+
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::pda::{find_commitment_address, find_promo_claim_address, name_commitment, promo_claim_commitment};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+/// Longest base58 rendering of a 32-byte address.
+const MAX_PREFIX_LENGTH: usize = 44;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum VanityError {
+    #[error("{0:?} is not a base58 character")]
+    InvalidCharacter(char),
+
+    #[error("Prefix is longer than any address")]
+    PrefixTooLong,
+}
+
+/// A seed whose address starts with the requested prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VanitySeed {
+    pub seed: [u8; 32],
+    pub address: Pubkey,
+    /// Seeds tried, including the matching one.
+    pub attempts: u64,
+}
+
+/// Tries seeds derived from `start` until `derive` maps one to an address
+/// starting with `prefix`, or until `budget` runs out, in which case it
+/// returns `Ok(None)`.
+pub fn grind_vanity_seed(
+    prefix: &str,
+    start: [u8; 32],
+    budget: Duration,
+    mut derive: impl FnMut(&[u8; 32]) -> Pubkey,
+) -> Result<Option<VanitySeed>, VanityError> {
+    if let Some(invalid) = prefix.chars().find(|c| !BASE58_ALPHABET.contains(*c)) {
+        return Err(VanityError::InvalidCharacter(invalid));
+    }
+    if prefix.len() > MAX_PREFIX_LENGTH {
+        return Err(VanityError::PrefixTooLong);
+    }
+
+    let deadline = Instant::now() + budget;
+    let counter = u64::from_le_bytes(start[..8].try_into().unwrap());
+    let mut seed = start;
+    let mut attempts = 0;
+    while Instant::now() < deadline {
+        seed[..8].copy_from_slice(&counter.wrapping_add(attempts).to_le_bytes());
+        attempts += 1;
+        let address = derive(&seed);
+        if address.to_string().starts_with(prefix) {
+            return Ok(Some(VanitySeed { seed, address, attempts }));
+        }
+    }
+    Ok(None)
+}
+
+/// Grinds the salt of `committer`'s commitment to `name`; the address is
+/// the commitment PDA CommitName creates.
+pub fn grind_commitment_salt(
+    program_id: &Pubkey,
+    committer: &Pubkey,
+    name: &str,
+    prefix: &str,
+    start: [u8; 32],
+    budget: Duration,
+) -> Result<Option<VanitySeed>, VanityError> {
+    grind_vanity_seed(prefix, start, budget, |salt| {
+        find_commitment_address(committer, &name_commitment(name, salt, committer), program_id).0
+    })
+}
+
+/// Grinds a 32-byte promo claim preimage; the address is the promo claim
+/// PDA of `config` that CreatePromoClaims creates for it.
+pub fn grind_promo_claim_preimage(
+    program_id: &Pubkey,
+    config: &Pubkey,
+    prefix: &str,
+    start: [u8; 32],
+    budget: Duration,
+) -> Result<Option<VanitySeed>, VanityError> {
+    grind_vanity_seed(prefix, start, budget, |preimage| {
+        find_promo_claim_address(config, &promo_claim_commitment(preimage), program_id).0
+    })
+}
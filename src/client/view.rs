@@ -0,0 +1,68 @@
+//! Read-only views over raw account data.
+//!
+//! The getter instructions (`GetContractOwner`, `GetRegistrationFee`,
+//! `GetPendingContractOwner`, `ResolveAddress`) stay for CPI consumers, but an
+//! RPC client already holding the account bytes from `getAccountInfo` can
+//! answer the same questions here without sending or simulating a
+//! transaction. Each view applies the checks of the matching getter, so both
+//! paths agree on what they accept.
+
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+use crate::{
+    error::NameRegistryError,
+    pda::find_name_address,
+    state::{NameAccount, ProgramConfig},
+};
+
+/// Decodes a config account, rejecting one that has been migrated to a
+/// successor.
+pub fn config_from_account_data(data: &[u8]) -> Result<ProgramConfig, ProgramError> {
+    let config = ProgramConfig::unpack(data)?;
+    if config.successor != Pubkey::default() {
+        return Err(NameRegistryError::ConfigMigrated.into());
+    }
+    Ok(config)
+}
+
+/// What `GetContractOwner` returns.
+pub fn contract_owner(config_data: &[u8]) -> Result<Pubkey, ProgramError> {
+    Ok(config_from_account_data(config_data)?.owner)
+}
+
+/// What `GetPendingContractOwner` returns; the default key when no transfer
+/// is pending.
+pub fn pending_contract_owner(config_data: &[u8]) -> Result<Pubkey, ProgramError> {
+    Ok(config_from_account_data(config_data)?.pending_owner)
+}
+
+/// What `GetRegistrationFee` returns at `unix_timestamp`: the fee for names
+/// of five or more characters, with any running promo applied.
+pub fn registration_fee(config_data: &[u8], unix_timestamp: i64) -> Result<u64, ProgramError> {
+    let config = config_from_account_data(config_data)?;
+    Ok(config.apply_promo(config.fee_schedule.five_plus_char, unix_timestamp))
+}
+
+/// Decodes a name account stored at `name_account`, checking that the
+/// address is the PDA of the name it holds.
+pub fn name_from_account_data(
+    program_id: &Pubkey,
+    name_account: &Pubkey,
+    data: &[u8],
+) -> Result<NameAccount, ProgramError> {
+    let name = NameAccount::unpack_unchecked(data)?;
+    if !name.is_initialized {
+        return Err(NameRegistryError::NameNotFound.into());
+    }
+    if name_account != &find_name_address(&name.name, program_id).0 {
+        return Err(NameRegistryError::WrongDerivedAccount.into());
+    }
+    Ok(name)
+}
+
+/// What `ResolveAddress` returns for a top-level name. A subname only
+/// resolves while its parents exist, which this view cannot see; fetch the
+/// chain and check each parent with `name_from_account_data`.
+pub fn resolve_address(program_id: &Pubkey, name_account: &Pubkey, data: &[u8]) -> Result<Pubkey, ProgramError> {
+    Ok(name_from_account_data(program_id, name_account, data)?.address)
+}
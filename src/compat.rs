@@ -0,0 +1,55 @@
+//! Wrappers over the solana-program APIs that differ between SDK releases.
+//!
+//! Handlers reach return data, account resizing, sysvars and the CPI stack
+//! height only through here, so building against another SDK line means
+//! adjusting this module rather than every call site. The wrappers follow
+//! the 1.17 and 1.18 APIs, the range the SPL dependencies accept; 2.x
+//! renames `AccountInfo::realloc` to `resize` and moves the sysvar getters
+//! into their own crates.
+
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
+    program,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+
+/// Sets the data the current instruction returns to its caller.
+pub fn set_return_data(data: &[u8]) {
+    program::set_return_data(data)
+}
+
+/// Data returned by the last instruction invoked, with the program that set
+/// it.
+pub fn get_return_data() -> Option<(Pubkey, Vec<u8>)> {
+    program::get_return_data()
+}
+
+/// Resizes `account` to `new_len` bytes, zeroing any bytes it gains.
+pub fn resize_account(account: &AccountInfo, new_len: usize) -> ProgramResult {
+    account.realloc(new_len, true)
+}
+
+pub fn clock() -> Result<Clock, ProgramError> {
+    Clock::get()
+}
+
+/// Current cluster time, in Unix seconds.
+pub fn unix_timestamp() -> Result<i64, ProgramError> {
+    Ok(clock()?.unix_timestamp)
+}
+
+pub fn rent() -> Result<Rent, ProgramError> {
+    Rent::get()
+}
+
+/// Whether the current instruction was invoked by another program rather
+/// than directly by the transaction.
+pub fn is_cpi() -> bool {
+    get_stack_height() > TRANSACTION_LEVEL_STACK_HEIGHT
+}
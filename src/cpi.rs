@@ -0,0 +1,90 @@
+//! Helpers for on-chain programs resolving names through CPI.
+//!
+//! Each helper invokes the registry and decodes its return data, checking
+//! that the data was set by the registry itself rather than left over from
+//! an earlier instruction. The payload layouts are those documented in
+//! `formats`. The helpers invoke whatever program `registry` is, so callers
+//! check its key against the registry they trust first.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{compat::get_return_data, formats, instruction::NameRegistryInstruction};
+
+/// Version of the `ResolveResult` layout written by `ResolveDetails`.
+pub const RESOLVE_RESULT_VERSION: u8 = 2;
+
+/// `ResolveResult::expires_at` of a name that never expires.
+pub const NEVER_EXPIRES: i64 = 0;
+
+/// What `ResolveDetails` returns about a name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResolveResult {
+    /// Layout version the registry wrote. Later versions only append
+    /// fields, so decoders read the ones they know.
+    pub version: u8,
+    pub address: Pubkey,
+    pub owner: Pubkey,
+    /// Unix time the name expires, or `NEVER_EXPIRES`, which is every name
+    /// until expiry is implemented.
+    pub expires_at: i64,
+    /// Whether the name or one of its parents is frozen, from version 2 on.
+    /// Such names do not resolve with `ResolveAddress`; callers moving funds
+    /// to `address` should refuse them too.
+    pub frozen: bool,
+}
+
+/// Resolves `name_account` to its address with `ResolveAddress`. Subnames
+/// also need every ancestor name account, nearest first.
+pub fn resolve_address<'a>(
+    registry: &AccountInfo<'a>,
+    name_account: &AccountInfo<'a>,
+    ancestors: &[AccountInfo<'a>],
+) -> Result<Pubkey, ProgramError> {
+    let data = invoke_resolve(registry, name_account, ancestors, NameRegistryInstruction::ResolveAddress)?;
+    formats::decode_address(&data).ok_or(ProgramError::InvalidAccountData)
+}
+
+/// Resolves `name_account` to its address, owner, expiry and frozen flag
+/// with `ResolveDetails`. Takes the same accounts as `resolve_address`.
+pub fn resolve_details<'a>(
+    registry: &AccountInfo<'a>,
+    name_account: &AccountInfo<'a>,
+    ancestors: &[AccountInfo<'a>],
+) -> Result<ResolveResult, ProgramError> {
+    let data = invoke_resolve(registry, name_account, ancestors, NameRegistryInstruction::ResolveDetails)?;
+    formats::decode_resolve_result(&data).ok_or(ProgramError::InvalidAccountData)
+}
+
+fn invoke_resolve<'a>(
+    registry: &AccountInfo<'a>,
+    name_account: &AccountInfo<'a>,
+    ancestors: &[AccountInfo<'a>],
+    instruction: NameRegistryInstruction,
+) -> Result<Vec<u8>, ProgramError> {
+    let mut accounts = vec![AccountMeta::new_readonly(*name_account.key, false)];
+    accounts.extend(ancestors.iter().map(|ancestor| AccountMeta::new_readonly(*ancestor.key, false)));
+    let mut infos = vec![name_account.clone()];
+    infos.extend(ancestors.iter().cloned());
+    infos.push(registry.clone());
+
+    invoke(
+        &Instruction {
+            program_id: *registry.key,
+            accounts,
+            data: instruction.try_to_vec()?,
+        },
+        &infos,
+    )?;
+
+    match get_return_data() {
+        Some((program_id, data)) if program_id == *registry.key => Ok(data),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
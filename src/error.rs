@@ -1,59 +1,314 @@
-use solana_program::program_error::ProgramError;
-use thiserror::Error;
-
-#[derive(Error, Debug, Copy, Clone)]
-pub enum NameRegistryError {
-    #[error("Invalid name format")]
-    InvalidNameFormat,
-    
-    #[error("Name already taken")]
-    NameTaken,
-    
-    #[error("Insufficient fee")]
-    InsufficientFee,
-    
-    #[error("Name already registered for address")]
-    NameAlreadyRegistered,
-    
-    #[error("Not name owner")]
-    NotNameOwner,
-    
-    #[error("Invalid address")]
-    InvalidAddress,
-    
-    #[error("Cooldown period not over")]
-    CooldownNotOver,
-    
-    #[error("No pending update")]
-    NoPendingUpdate,
-    
-    #[error("Not the pending address")]
-    NotPendingAddress,
-    
-    #[error("Not contract owner")]
-    NotContractOwner,
-    
-    #[error("Invalid new owner")]
-    InvalidNewOwner,
-    
-    #[error("Not the pending contract owner")]
-    NotPendingContractOwner,
-    
-    #[error("Account not initialized")]
-    NotInitialized,
-    
-    #[error("Account already initialized")]
-    AlreadyInitialized,
-    
-    #[error("Name not found")]
-    NameNotFound,
-    
-    #[error("Nothing to withdraw")]
-    NothingToWithdraw,
-}
-
-impl From<NameRegistryError> for ProgramError {
-    fn from(e: NameRegistryError) -> Self {
-        ProgramError::Custom(e as u32)
-    }
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+/// Errors of the registry, returned as `ProgramError::Custom` with the
+/// variant's code. Codes start at `ERROR_CODE_OFFSET`, clear of the small
+/// codes other programs use, and never change once released: new variants
+/// take the next free code and retired ones keep theirs.
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq, FromPrimitive)]
+pub enum NameRegistryError {
+    #[error("Invalid name format")]
+    InvalidNameFormat = 6000,
+    
+    #[error("Name already taken")]
+    NameTaken = 6001,
+    
+    #[error("Insufficient fee")]
+    InsufficientFee = 6002,
+    
+    #[error("Name already registered for address")]
+    NameAlreadyRegistered = 6003,
+    
+    #[error("Not name owner")]
+    NotNameOwner = 6004,
+    
+    #[error("Invalid address")]
+    InvalidAddress = 6005,
+    
+    #[error("Cooldown period not over")]
+    CooldownNotOver = 6006,
+    
+    #[error("No pending update")]
+    NoPendingUpdate = 6007,
+    
+    #[error("Not the pending address")]
+    NotPendingAddress = 6008,
+    
+    #[error("Not contract owner")]
+    NotContractOwner = 6009,
+    
+    #[error("Invalid new owner")]
+    InvalidNewOwner = 6010,
+    
+    #[error("Not the pending contract owner")]
+    NotPendingContractOwner = 6011,
+    
+    #[error("Account not initialized")]
+    NotInitialized = 6012,
+    
+    #[error("Account already initialized")]
+    AlreadyInitialized = 6013,
+    
+    #[error("Name not found")]
+    NameNotFound = 6014,
+    
+    #[error("Nothing to withdraw")]
+    NothingToWithdraw = 6015,
+
+    #[error("Account not writable")]
+    AccountNotWritable = 6016,
+
+    #[error("Banner message too long")]
+    BannerMessageTooLong = 6017,
+
+    #[error("Invalid promo window")]
+    InvalidPromo = 6018,
+
+    #[error("Account not rent exempt")]
+    NotRentExempt = 6019,
+
+    #[error("Unknown extension")]
+    UnknownExtension = 6020,
+
+    #[error("Name does not resolve to this wallet")]
+    PrimaryNameMismatch = 6021,
+
+    #[error("Account is not the parent of this subname")]
+    InvalidParent = 6022,
+
+    #[error("Operation not supported on subnames")]
+    NotTopLevelName = 6023,
+
+    #[error("Invalid record kind or value")]
+    InvalidRecord = 6024,
+
+    #[error("Signer is not the pending owner of this name")]
+    NotPendingNameOwner = 6025,
+
+    #[error("Program is paused")]
+    ProgramPaused = 6026,
+
+    #[error("Account is not derived from the name it holds")]
+    WrongDerivedAccount = 6027,
+
+    #[error("Preimage does not open a live promo claim")]
+    InvalidPromoClaim = 6028,
+
+    #[error("Promo claim has expired")]
+    PromoClaimExpired = 6029,
+
+    #[error("Promo claim has not expired yet")]
+    PromoClaimNotExpired = 6030,
+
+    #[error("Account uses a legacy layout; migrate it first")]
+    LegacyAccountLayout = 6031,
+
+    #[error("Account is already migrated")]
+    AlreadyMigrated = 6032,
+
+    #[error("Wallet has too many pending operations open")]
+    TooManyPendingOperations = 6033,
+
+    #[error("Account is not a live auction of this registry")]
+    InvalidAuction = 6034,
+
+    #[error("Auction has ended")]
+    AuctionEnded = 6035,
+
+    #[error("Auction has not ended yet")]
+    AuctionNotEnded = 6036,
+
+    #[error("Bid is below the minimum or the current highest bid")]
+    BidTooLow = 6037,
+
+    #[error("Invalid referral share or referrer")]
+    InvalidReferral = 6038,
+
+    #[error("Registry has reached its name cap")]
+    RegistryFull = 6039,
+
+    #[error("Names must be registered through commit-reveal")]
+    CommitRevealRequired = 6040,
+
+    #[error("Invalid name commitment")]
+    InvalidCommitment = 6041,
+
+    #[error("Name commitment is too recent to reveal")]
+    CommitmentTooNew = 6042,
+
+    #[error("Note is too long")]
+    NoteTooLong = 6043,
+
+    #[error("Too many entries in one instruction")]
+    BatchTooLarge = 6044,
+
+    #[error("Instruction data is too large")]
+    InstructionTooLarge = 6045,
+
+    #[error("Account holds a different type of registry state")]
+    InvalidAccountType = 6046,
+
+    #[error("Config was migrated; use its successor")]
+    ConfigMigrated = 6047,
+
+    #[error("Invalid multisig signers or threshold")]
+    InvalidMultisig = 6048,
+
+    #[error("Not enough multisig signers approved")]
+    MultisigThresholdNotMet = 6049,
+
+    #[error("Name holds the maximum number of records")]
+    TooManyRecords = 6050,
+
+    #[error("Invalid admin timelock")]
+    InvalidTimelock = 6051,
+
+    #[error("An admin action is already queued")]
+    AdminActionPending = 6052,
+
+    #[error("Admin action is still timelocked")]
+    AdminActionNotReady = 6053,
+
+    #[error("Name co-signer did not sign")]
+    CosignerRequired = 6054,
+
+    #[error("Name still has records")]
+    NameHasRecords = 6055,
+
+    #[error("Receipt was issued to another payer")]
+    NotReceiptPayer = 6056,
+
+    #[error("Cooldown period out of bounds")]
+    InvalidCooldownPeriod = 6057,
+
+    #[error("Name is not reserved")]
+    NameNotReserved = 6058,
+
+    #[error("Name must be proven missing from the blocklist")]
+    NameProofRequired = 6059,
+
+    #[error("Name proof does not match the blocklist")]
+    InvalidNameProof = 6060,
+
+    #[error("Account does not hold the role")]
+    RoleNotGranted = 6061,
+
+    #[error("Role grant fee exceeds 100%")]
+    InvalidRoleGrant = 6062,
+
+    #[error("Name is tokenized and changes hands with its token")]
+    NameTokenized = 6063,
+
+    #[error("Token account does not hold the name's token")]
+    InvalidNameToken = 6064,
+
+    #[error("Token metadata URI too long")]
+    TokenUriTooLong = 6065,
+
+    #[error("Name has an open transfer offer")]
+    TransferOfferOpen = 6066,
+
+    #[error("Pending update has expired")]
+    PendingUpdateExpired = 6067,
+
+    #[error("Signer is not an approved operator of this name")]
+    OperatorNotApproved = 6068,
+
+    #[error("Namespace not found")]
+    NamespaceNotFound = 6069,
+
+    #[error("Namespace is paused")]
+    NamespacePaused = 6070,
+
+    #[error("Not the namespace admin")]
+    NotNamespaceAdmin = 6071,
+
+    #[error("Invalid price feed")]
+    InvalidPriceFeed = 6072,
+
+    #[error("Price feed is stale")]
+    StalePrice = 6073,
+
+    #[error("Price feed confidence too low")]
+    PriceTooUncertain = 6074,
+
+    #[error("Not allowed while an admin timelock is set")]
+    TimelockActive = 6075,
+
+    #[error("Invalid registration voucher")]
+    InvalidVoucher = 6076,
+
+    #[error("Registration voucher has expired")]
+    VoucherExpired = 6077,
+
+    #[error("Signer is not the program's upgrade authority")]
+    NotUpgradeAuthority = 6078,
+
+    #[error("Config account is not the one linked to the program")]
+    ConfigNotLinked = 6079,
+
+    #[error("Withdrawal exceeds the withdrawable fees")]
+    WithdrawExceedsBalance = 6080,
+
+    #[error("Name is frozen")]
+    NameFrozen = 6081,
+
+    #[error("Name is not frozen")]
+    NameNotFrozen = 6082,
+
+    #[error("Name was recently unregistered")]
+    NameRecentlyReleased = 6083,
+
+    #[error("Tombstone has not expired")]
+    TombstoneNotExpired = 6084,
+
+    #[error("Namespace registrations go through its delegate program")]
+    NotNamespaceAuthority = 6085,
+
+    #[error("Not allowed while a multisig is set")]
+    MultisigActive = 6086,
+
+    #[error("The name's token has not been burned")]
+    NameTokenNotBurned = 6087,
+
+    #[error("The record has too many writers")]
+    TooManyRecordWriters = 6088,
+}
+
+/// Code of the first registry error.
+pub const ERROR_CODE_OFFSET: u32 = 6000;
+
+impl NameRegistryError {
+    /// The error a `ProgramError::Custom` code stands for, if it is one of
+    /// the registry's.
+    pub fn from_u32(code: u32) -> Option<Self> {
+        <Self as FromPrimitive>::from_u32(code)
+    }
+}
+
+impl From<NameRegistryError> for ProgramError {
+    fn from(e: NameRegistryError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for NameRegistryError {
+    fn type_of() -> &'static str {
+        "NameRegistryError"
+    }
+}
+
+impl PrintProgramError for NameRegistryError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,30 @@
+//! Dispatcher for `NameRegistryInstruction::Extension`.
+//!
+//! Experimental features ship as tagged extensions instead of new variants of
+//! the primary instruction enum, so their wire format can change or be
+//! dropped without renumbering instructions that deployed clients depend on.
+//! Each feature registers its handler under a fixed tag in `EXTENSIONS`; tags
+//! are never reused.
+
+use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
+
+use crate::error::NameRegistryError;
+
+pub type ExtensionHandler = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+/// Registered extensions as `(tag, handler)` pairs.
+pub const EXTENSIONS: &[(u16, ExtensionHandler)] = &[];
+
+pub fn process_extension(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    tag: u16,
+    payload: &[u8],
+) -> ProgramResult {
+    let handler = EXTENSIONS
+        .iter()
+        .find(|(registered, _)| *registered == tag)
+        .map(|(_, handler)| handler)
+        .ok_or(NameRegistryError::UnknownExtension)?;
+    handler(program_id, accounts, payload)
+}
@@ -0,0 +1,179 @@
+//! Byte layouts of every return-data payload and log event, version 2.
+//!
+//! These are the formats third-party SDKs decode, so the program encodes
+//! through the same functions and `tests/golden/` pins their output. Any
+//! change to a layout must bump `FORMATS_VERSION` and update the golden files.
+//!
+//! Return data (all integers little-endian):
+//!
+//! | Instruction               | Payload                         |
+//! |---------------------------|---------------------------------|
+//! | `ResolveAddress`          | resolved address, 32 bytes      |
+//! | `ResolveDetails`          | `cpi::ResolveResult`: version `u8`, address, owner, `expires_at` `i64`, `frozen` `u8` |
+//! | `GetContractOwner`        | owner, 32 bytes                 |
+//! | `GetPendingContractOwner` | pending owner, 32 bytes (zeroes if none) |
+//! | `GetRegistrationFee`      | effective fee in lamports, `u64` |
+//! | `GetCooldownBounds`       | min then max cooldown in seconds, `u32` each |
+//! | `ResolveName`             | primary name, UTF-8, no length prefix |
+//! | `ListRecordKeys`          | record addresses, 32 bytes each |
+//!
+//! The runtime drops trailing zero bytes from return data, so decoders of
+//! fixed-size payloads right-pad with zeroes before decoding.
+//!
+//! Log events are single `msg!` lines:
+//!
+//! - `Resolved <name> to <address>` from `ResolveAddress` and
+//!   `ResolveDetails`, unless invoked through CPI.
+//! - `analytics: event=register name_length=<n> charset=<class> hyphen=<bool>`
+//!   from `RegisterName` when analytics are enabled.
+//! - `Referral <name> paid <lamports> to <referrer>` from `RegisterName`
+//!   when a referrer earns a share of the fee.
+//! - `registry: event=fee name=<name> lamports=<n>` from `RegisterName`,
+//!   `RevealAndRegister` and `SettleAuction`, with the fee charged.
+//! - `registry: event=cooldown name=<name> cooldown_until=<unix>` from
+//!   `TransferName` and `AcceptNameTransfer` when a cooldown starts, and
+//!   from handlers rejecting a change with `CooldownNotOver`.
+//! - `registry: event=expiry name=<name> expires_at=<unix>` from
+//!   `CreatePromoClaims`, once per claimable name.
+//!
+//! The `registry:` lines are `key=value` pairs separated by single spaces;
+//! builds with the `no-log-ix-name` feature leave them out.
+
+use solana_program::pubkey::Pubkey;
+
+use crate::{analytics::CharsetClass, cpi::ResolveResult};
+
+pub const FORMATS_VERSION: u32 = 2;
+
+pub const ADDRESS_PAYLOAD_LEN: usize = 32;
+pub const FEE_PAYLOAD_LEN: usize = 8;
+pub const COOLDOWN_BOUNDS_PAYLOAD_LEN: usize = 8;
+pub const RESOLVE_RESULT_PAYLOAD_LEN: usize = 1 + 32 + 32 + 8 + 1;
+
+/// Payload of `ResolveAddress`, `GetContractOwner` and
+/// `GetPendingContractOwner`.
+pub fn encode_address(address: &Pubkey) -> [u8; ADDRESS_PAYLOAD_LEN] {
+    address.to_bytes()
+}
+
+pub fn decode_address(data: &[u8]) -> Option<Pubkey> {
+    padded::<ADDRESS_PAYLOAD_LEN>(data).map(Pubkey::new_from_array)
+}
+
+/// Payload of `GetRegistrationFee`.
+pub fn encode_fee(fee: u64) -> [u8; FEE_PAYLOAD_LEN] {
+    fee.to_le_bytes()
+}
+
+pub fn decode_fee(data: &[u8]) -> Option<u64> {
+    padded::<FEE_PAYLOAD_LEN>(data).map(u64::from_le_bytes)
+}
+
+/// Payload of `ResolveDetails`.
+pub fn encode_resolve_result(result: &ResolveResult) -> [u8; RESOLVE_RESULT_PAYLOAD_LEN] {
+    let mut payload = [0; RESOLVE_RESULT_PAYLOAD_LEN];
+    payload[0] = result.version;
+    payload[1..33].copy_from_slice(result.address.as_ref());
+    payload[33..65].copy_from_slice(result.owner.as_ref());
+    payload[65..73].copy_from_slice(&result.expires_at.to_le_bytes());
+    payload[73] = result.frozen.into();
+    payload
+}
+
+/// Reads the fields of version 2 and ignores any a later version appends.
+/// Version 1 payloads lack `frozen`, which reads as unset.
+pub fn decode_resolve_result(data: &[u8]) -> Option<ResolveResult> {
+    let known = &data[..data.len().min(RESOLVE_RESULT_PAYLOAD_LEN)];
+    let payload = padded::<RESOLVE_RESULT_PAYLOAD_LEN>(known).filter(|payload| payload[0] != 0)?;
+    Some(ResolveResult {
+        version: payload[0],
+        address: Pubkey::try_from(&payload[1..33]).ok()?,
+        owner: Pubkey::try_from(&payload[33..65]).ok()?,
+        expires_at: i64::from_le_bytes(payload[65..73].try_into().ok()?),
+        frozen: payload[73] != 0,
+    })
+}
+
+/// Payload of `GetCooldownBounds`.
+pub fn encode_cooldown_bounds(min: u32, max: u32) -> [u8; COOLDOWN_BOUNDS_PAYLOAD_LEN] {
+    let mut payload = [0; COOLDOWN_BOUNDS_PAYLOAD_LEN];
+    payload[..4].copy_from_slice(&min.to_le_bytes());
+    payload[4..].copy_from_slice(&max.to_le_bytes());
+    payload
+}
+
+pub fn decode_cooldown_bounds(data: &[u8]) -> Option<(u32, u32)> {
+    let payload = padded::<COOLDOWN_BOUNDS_PAYLOAD_LEN>(data)?;
+    let (min, max) = payload.split_at(4);
+    Some((u32::from_le_bytes(min.try_into().ok()?), u32::from_le_bytes(max.try_into().ok()?)))
+}
+
+/// Payload of `ResolveName`.
+pub fn encode_primary_name(name: &str) -> &[u8] {
+    name.as_bytes()
+}
+
+pub fn decode_primary_name(data: &[u8]) -> Option<String> {
+    String::from_utf8(data.to_vec()).ok()
+}
+
+/// Payload of `ListRecordKeys`.
+pub fn encode_record_keys(records: &[Pubkey]) -> Vec<u8> {
+    records.iter().flat_map(|record| record.to_bytes()).collect()
+}
+
+/// Record addresses are never all zeroes, so the length of a trimmed
+/// payload still rounds up to the number of records.
+pub fn decode_record_keys(data: &[u8]) -> Vec<Pubkey> {
+    data.chunks(ADDRESS_PAYLOAD_LEN)
+        .filter_map(decode_address)
+        .collect()
+}
+
+/// Log line of a direct `ResolveAddress` or `ResolveDetails`.
+pub fn resolved_event(name: &str, address: &Pubkey) -> String {
+    format!("Resolved {} to {}", name, address)
+}
+
+/// Log line of a referral payout, for partners reconciling their earnings.
+pub fn referral_event(name: &str, referrer: &Pubkey, lamports: u64) -> String {
+    format!("Referral {} paid {} to {}", name, lamports, referrer)
+}
+
+/// Log line of an anonymized registration.
+pub fn registration_analytics_event(name_length: usize, charset: CharsetClass, hyphen: bool) -> String {
+    format!(
+        "analytics: event=register name_length={} charset={} hyphen={}",
+        name_length, charset, hyphen,
+    )
+}
+
+/// Log line of the fee charged for a name.
+pub fn fee_event(name: &str, lamports: u64) -> String {
+    format!("registry: event=fee name={} lamports={}", name, lamports)
+}
+
+/// Log line of the time until which a name's owner and address are locked.
+pub fn cooldown_event(name: &str, cooldown_until: i64) -> String {
+    format!("registry: event=cooldown name={} cooldown_until={}", name, cooldown_until)
+}
+
+/// Log line of a FreezeName or UnfreezeName, naming the program owner who
+/// signed it.
+pub fn freeze_event(name: &str, frozen: bool, authority: &Pubkey) -> String {
+    format!("registry: event=freeze name={} frozen={} authority={}", name, frozen, authority)
+}
+
+/// Log line of the time after which a promo claim can be reclaimed.
+pub fn expiry_event(name: &str, expires_at: i64) -> String {
+    format!("registry: event=expiry name={} expires_at={}", name, expires_at)
+}
+
+fn padded<const N: usize>(data: &[u8]) -> Option<[u8; N]> {
+    if data.len() > N {
+        return None;
+    }
+    let mut bytes = [0; N];
+    bytes[..data.len()].copy_from_slice(data);
+    Some(bytes)
+}
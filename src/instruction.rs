@@ -1,109 +1,1383 @@
-use solana_program::{
-    program_error::ProgramError,
-    pubkey::Pubkey,
-};
-use borsh::{BorshDeserialize, BorshSerialize};
-
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
-pub enum NameRegistryInstruction {
-    /// Initialize the program
-    /// Accounts expected:
-    /// 0. `[signer]` The account of the person initializing the program
-    /// 1. `[writable]` The program config account
-    /// 2. `[]` The system program
-    Initialize {
-        registration_fee: u64,
-    },
-
-    /// Register a new name
-    /// Accounts expected:
-    /// 0. `[signer]` The account of the person registering the name
-    /// 1. `[writable]` The name account
-    /// 2. `[writable]` The address account
-    /// 3. `[writable]` The program config account
-    /// 4. `[]` The system program
-    RegisterName {
-        name: String,
-    },
-
-    /// Request an address update
-    /// Accounts expected:
-    /// 0. `[signer]` The current name owner
-    /// 1. `[writable]` The name account
-    /// 2. `[writable]` The pending update account
-    RequestAddressUpdate {
-        new_address: Pubkey,
-    },
-
-    /// Complete an address update
-    /// Accounts expected:
-    /// 0. `[signer]` The new address owner
-    /// 1. `[writable]` The name account
-    /// 2. `[writable]` The address account
-    /// 3. `[writable]` The pending update account
-    CompleteAddressUpdate,
-
-    /// Rename a name
-    /// Accounts expected:
-    /// 0. `[signer]` The current name owner
-    /// 1. `[writable]` The old name account
-    /// 2. `[writable]` The new name account
-    /// 3. `[writable]` The address account
-    RenameName {
-        new_name: String,
-    },
-
-    /// Update registration fee
-    /// Accounts expected:
-    /// 0. `[signer]` The program owner
-    /// 1. `[writable]` The program config account
-    SetRegistrationFee {
-        new_fee: u64,
-    },
-
-    /// Change program owner
-    /// Accounts expected:
-    /// 0. `[signer]` The current program owner
-    /// 1. `[writable]` The program config account
-    ChangeProgramOwner {
-        new_owner: Pubkey,
-    },
-
-    /// Accept program ownership
-    /// Accounts expected:
-    /// 0. `[signer]` The pending program owner
-    /// 1. `[writable]` The program config account
-    AcceptProgramOwnership,
-
-    /// Resolve address by name
-    /// Accounts expected:
-    /// 0. `[]` The name account
-    ResolveAddress,
-
-    /// Get contract owner
-    /// Accounts expected:
-    /// 0. `[]` The program config account
-    GetContractOwner,
-
-    /// Get registration fee
-    /// Accounts expected:
-    /// 0. `[]` The program config account
-    GetRegistrationFee,
-
-    /// Get pending contract owner
-    /// Accounts expected:
-    /// 0. `[]` The program config account
-    GetPendingContractOwner,
-
-    /// Withdraw accumulated fees
-    /// Accounts expected:
-    /// 0. `[signer]` The program owner
-    /// 1. `[writable]` The program config account
-    Withdraw,
-}
-
-impl NameRegistryInstruction {
-    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
-        Self::try_from_slice(input).map_err(|_| ProgramError::InvalidInstructionData)
-    }
+use solana_program::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{
+    error::NameRegistryError,
+    state::{FeeSchedule, OperatorScope, Role},
+    validation::{
+        validate_claim_preimage, validate_name_proof, validate_note, validate_token_uri, MAX_EXTENSION_PAYLOAD_LENGTH,
+        MAX_INSTRUCTION_DATA_LENGTH, MAX_MULTISIG_SIGNERS, MAX_PROMO_CLAIMS_PER_INSTRUCTION, MAX_RECORDS_PER_NAME,
+    },
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub enum NameRegistryInstruction {
+    /// Initialize the program, charging `registration_fee` for names of every
+    /// length until SetFeeSchedule. Only the program's upgrade authority can,
+    /// and only once: the config link records the config for good.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program's upgrade authority (pays rent
+    ///    for the config link and treasury)
+    /// 1. `[writable]` The config PDA `["config"]`
+    /// 2. `[]` The system program
+    /// 3. `[]` The program's program data account, held by the upgradeable
+    ///    loader
+    /// 4. `[writable]` The config link PDA `["config_link"]`
+    /// 5. `[writable]` The treasury bucket PDAs `["treasury", config, bucket]`
+    ///    for every bucket below `FEE_BUCKETS`, in order
+    Initialize {
+        registration_fee: u64,
+    },
+
+    /// Register a new name. Refused while the config requires commit-reveal.
+    /// A name `<label>.<suffix>` is registered in the namespace of `suffix`
+    /// and charged the namespace's fee.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The account of the person registering the name
+    ///    (pays rent for the name account and reverse record)
+    /// 1. `[writable]` The name account PDA `["name", name]`
+    /// 2. `[writable]` The reverse record PDA `["address", name_account]`
+    /// 3. `[writable]` The program config account, which counts the name
+    ///    against `max_total_names`
+    /// 4. `[]` The system program
+    /// 5. `[writable]` The treasury bucket PDA of the name (see `fee_bucket`),
+    ///    which receives the registration fee; for a namespaced name, the
+    ///    namespace PDA instead
+    /// 6. `[]` (optional) The registrant's wallet settings PDA; required,
+    ///    though it may not exist yet, when a referrer or receipt follows
+    /// 7. `[writable]` (optional) The referrer, paid `referral_bps` of the
+    ///    fee out of the registrant's payment; must not be the registrant.
+    ///    The program id in its place stands for no referrer
+    /// 8. `[writable]` (optional) The receipt PDA
+    ///    `["receipt", registrant, name_account, Registration]`, created to
+    ///    record the fee paid (registrant pays rent)
+    ///
+    /// While the config has a blocklist, `proof` must show the name is not
+    /// on it and the blocklist PDA `["blocklist", config]` comes sixth,
+    /// before the optional accounts. While it has `enforce_unique_address`,
+    /// the owner's address claim PDA `["address_claim", owner]` comes next,
+    /// and registering fails with `NameAlreadyRegistered` if the claim
+    /// already holds a name; otherwise it is created (registrant pays rent)
+    /// and holds this one. While it prices in USD, the USD pricing PDA
+    /// `["usd_pricing", config]` and its price feed come next. While it has
+    /// `registry_pages_enabled`, the writable registry page PDA
+    /// `["registry_page", config, config.registry_page()]` comes next; the
+    /// name is appended to it, and a new page is created (registrant pays
+    /// rent) when the last one is full. While it has a `tombstone_period`,
+    /// the name's tombstone PDA `["tombstone", name_account]` comes last of
+    /// these, which may not exist, and registering fails with
+    /// `NameRecentlyReleased` while it is live.
+    RegisterName {
+        name: String,
+        proof: Option<NameProof>,
+    },
+
+    /// Request an address update. Counts as a pending operation of the owner
+    /// until completed (see `SetMaxPendingOperations`). Replaces an update
+    /// left by an earlier owner of the name.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The current name owner (pays rent for their
+    ///    wallet settings and the pending update)
+    /// 1. `[writable]` The name account
+    /// 2. `[writable]` The pending update PDA
+    ///    `["pending_update", name_account]`, created if missing
+    /// 3. `[]` The config account
+    /// 4. `[writable]` The owner's wallet settings PDA
+    /// 5. `[]` The system program
+    /// 6. `[]` The name's co-signer PDA `["cosigner", name_account]`, which
+    ///    may not exist
+    /// 7. `[signer]` The name's co-signer, if one is set
+    RequestAddressUpdate {
+        new_address: Pubkey,
+    },
+
+    /// Complete an address update, charging the config's update fee
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The new address owner (pays the update fee)
+    /// 1. `[writable]` The name account
+    /// 2. `[writable]` The reverse record
+    /// 3. `[writable]` The pending update PDA of the name
+    /// 4. `[]` The config account
+    /// 5. `[writable]` The previous owner's wallet settings PDA, which is
+    ///    released from the update and any open transfer offer
+    /// 6. `[writable]` The treasury bucket PDA of the name
+    /// 7. `[]` The system program
+    /// 8. `[writable]` The previous owner, if the config has
+    ///    `enforce_unique_address`, followed by their address claim PDA and
+    ///    the new owner's, which the name moves to
+    /// 9. `[]` (optional) The new owner's wallet settings PDA
+    CompleteAddressUpdate,
+
+    /// Rename a name, charging the config's rename fee
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The current name owner (pays rent for the new
+    ///    name account and the rename fee)
+    /// 1. `[writable]` The old name account
+    /// 2. `[writable]` The new name account PDA `["name", new_name]`
+    /// 3. `[writable]` The reverse record
+    /// 4. `[]` The system program
+    /// 5. `[]` The config account
+    /// 6. `[writable]` The old name's co-signer PDA
+    ///    `["cosigner", old_name_account]`, which may not exist
+    /// 7. `[writable]` The new name's co-signer PDA, which a set co-signer
+    ///    moves to
+    /// 8. `[writable]` The treasury bucket PDA of the new name
+    /// 9. `[]` The blocklist PDA `["blocklist", config]`, if the config has
+    ///    a blocklist; `proof` must then show the new name is not on it
+    /// 10. `[signer]` The name's co-signer, if one is set
+    RenameName {
+        new_name: String,
+        proof: Option<NameProof>,
+    },
+
+    /// Charge `new_fee` for names of every length. Queued for
+    /// ExecuteAdminAction while an admin timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member (writable and
+    ///    paying rent while a timelock is set)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program, if a timelock is set
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetRegistrationFee {
+        new_fee: u64,
+    },
+
+    /// Change program owner. Queued for ExecuteAdminAction while an admin
+    /// timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer]` The current program owner, or a multisig member
+    ///    (writable and paying rent while a timelock is set)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program, if a timelock is set
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    ChangeProgramOwner {
+        new_owner: Pubkey,
+    },
+
+    /// Accept program ownership
+    /// Accounts expected:
+    /// 0. `[signer]` The pending program owner
+    /// 1. `[writable]` The program config account
+    AcceptProgramOwnership,
+
+    /// Resolve address by name. The name account must be the PDA of the name
+    /// it holds, or the call fails with `WrongDerivedAccount`. The address is returned as
+    /// return data, also when invoked through CPI.
+    /// Accounts expected:
+    /// 0. `[]` The name account; passed writable, the resolution is counted
+    ///    in its `resolve_count`
+    /// 1.. `[]` For subnames, every ancestor name account, nearest first
+    ///
+    /// For a top-level name, the tombstone PDA `["tombstone", name_account]`
+    /// may come second: while the name is unregistered and its tombstone
+    /// live, resolving fails with `NameRecentlyReleased` rather than as for a
+    /// name that never existed.
+    ResolveAddress,
+
+    /// Get contract owner
+    /// Accounts expected:
+    /// 0. `[]` The program config account
+    GetContractOwner,
+
+    /// Get the registration fee currently charged for names of five or more
+    /// characters, including any active promo. Shorter names are priced by
+    /// the config's `fee_schedule`. Configs pricing in USD charge their
+    /// `UsdPricing` instead, which this does not convert.
+    /// Accounts expected:
+    /// 0. `[]` The program config account
+    GetRegistrationFee,
+
+    /// Get pending contract owner
+    /// Accounts expected:
+    /// 0. `[]` The program config account
+    GetPendingContractOwner,
+
+    /// Withdraw `amount` lamports of accumulated fees, or all of them when
+    /// `None`, to `destination`. Buckets are drained in order and every one
+    /// is left rent-exempt.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The destination receiving the fees
+    /// 2. `[]` The program config account
+    /// 3. `[writable]` The treasury bucket PDAs, every bucket in order
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    Withdraw {
+        amount: Option<u64>,
+    },
+
+    /// Toggle privacy mode for the signing wallet. Names registered to or
+    /// transferred to a private wallet get no public reverse record.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The wallet (pays rent on first use)
+    /// 1. `[writable]` The wallet settings PDA `["wallet", wallet]`
+    /// 2. `[]` The system program
+    SetPrivacyMode {
+        enabled: bool,
+    },
+
+    /// Set the banner message shown by integrated front-ends (program owner
+    /// only). An empty message clears the banner.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent on first use)
+    /// 1. `[]` The program config account
+    /// 2. `[writable]` The banner PDA `["banner", config]`
+    /// 3. `[]` The system program
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetBannerMessage {
+        message: String,
+    },
+
+    /// Schedule a discount window in which RegisterName charges
+    /// `fee_override_bps` of the registration fee. Replaces any previously
+    /// scheduled promo; `start == end` cancels it. Queued for
+    /// ExecuteAdminAction while an admin timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member (writable and
+    ///    paying rent while a timelock is set)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program, if a timelock is set
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SchedulePromo {
+        start: i64,
+        end: i64,
+        fee_override_bps: u16,
+    },
+
+    /// Set the signing wallet's primary name. The name must resolve to the
+    /// wallet.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The wallet (pays rent on first use)
+    /// 1. `[]` The name account PDA
+    /// 2. `[writable]` The primary name PDA `["reverse", wallet]`
+    /// 3. `[]` The system program
+    SetPrimaryName,
+
+    /// Resolve a wallet's primary name, returned as UTF-8 return data. Fails
+    /// if the name no longer resolves to the wallet.
+    /// Accounts expected:
+    /// 0. `[]` The primary name PDA `["reverse", wallet]`
+    /// 1. `[]` The name account PDA of the recorded name
+    ResolveName,
+
+    /// Toggle the anonymized analytics event emitted by RegisterName
+    /// (program owner only).
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetAnalytics {
+        enabled: bool,
+    },
+
+    /// Register `<label>.<parent>` under a name owned by the signer. The
+    /// subname starts out owned by and resolving to the parent owner.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The parent name owner (pays rent)
+    /// 1. `[]` The parent name account
+    /// 2. `[writable]` The subname account PDA `["name", "<label>.<parent>"]`
+    /// 3. `[]` The system program
+    /// 4. `[]` The config account
+    RegisterSubname {
+        label: String,
+    },
+
+    /// Revoke a subname, closing its account (parent name owner only).
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The parent name owner (receives the rent)
+    /// 1. `[]` The parent name account
+    /// 2. `[writable]` The subname account
+    /// 3. `[writable]` (required if a transfer of the subname is pending) The
+    ///    subname owner's wallet settings PDA
+    RevokeSubname,
+
+    /// Create or overwrite a record of a name (name owner or a `Records`
+    /// operator), or overwrite one as a writer granted by GrantRecordWriter.
+    /// A name holds at most `MAX_RECORDS_PER_NAME` records.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The name owner, operator or record writer
+    ///    (pays rent)
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The record PDA `["record", name_account, kind]`
+    /// 3. `[]` The system program
+    /// 4. `[writable]` The record index PDA `["record_index", name_account]`
+    /// 5. `[]` (required if the signer is an operator) The signer's operator
+    ///    PDA
+    SetRecord {
+        kind: String,
+        value: String,
+    },
+
+    /// Delete a record of a name, refunding its rent to the signer (name
+    /// owner or a `Records` operator).
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The name owner or operator
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The record PDA
+    /// 3. `[writable]` The record index PDA
+    /// 4. `[]` (required if the signer is an operator) The signer's operator
+    ///    PDA
+    DeleteRecord {
+        kind: String,
+    },
+
+    /// Transfer ownership of a name to another wallet. The resolved address
+    /// is left untouched. With `require_acceptance`, `new_owner` only becomes
+    /// pending and must call AcceptNameTransfer; a later TransferName
+    /// replaces the offer. Subject to the name's cooldown. An open offer
+    /// counts as a pending operation of the owner.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The current name owner (pays rent for their
+    ///    wallet settings)
+    /// 1. `[writable]` The name account
+    /// 2. `[]` The config account
+    /// 3. `[writable]` The owner's wallet settings PDA
+    /// 4. `[]` The system program
+    /// 5. `[]` The name's co-signer PDA `["cosigner", name_account]`, which
+    ///    may not exist
+    /// 6. `[writable]` The owner's address claim PDA, without
+    ///    `require_acceptance` if the config has `enforce_unique_address`
+    /// 7. `[writable]` The new owner's address claim PDA, which the name
+    ///    moves to, along with 6
+    /// 8. `[signer]` The name's co-signer, if one is set
+    /// 9. `[writable]` With `wipe_records`: the record index PDA
+    ///    `["record_index", name_account]`, followed by every record PDA in
+    ///    the index
+    ///
+    /// With `wipe_records`, all of the name's records are deleted, refunding
+    /// their rent to the owner, so the next owner starts from a clean slate.
+    TransferName {
+        new_owner: Pubkey,
+        require_acceptance: bool,
+        wipe_records: bool,
+    },
+
+    /// Accept a pending two-step name transfer. With `wipe_records`, the new
+    /// owner deletes all of the name's records as TransferName does, their
+    /// rent going back to the offering owner, who paid it.
+    /// Accounts expected:
+    /// 0. `[signer]` The pending owner (writable and paying rent for their
+    ///    address claim, if the config has `enforce_unique_address`)
+    /// 1. `[writable]` The name account
+    /// 2. `[]` The config account
+    /// 3. `[writable]` The offering owner's wallet settings PDA
+    /// 4. `[writable]` The offering owner, with `wipe_records` or if the
+    ///    config has `enforce_unique_address`
+    /// 5. `[]` The system program, if the config has
+    ///    `enforce_unique_address`, followed by the offering owner's address
+    ///    claim PDA and the pending owner's, which the name moves to
+    /// 6. `[writable]` With `wipe_records`: the record index PDA and every
+    ///    record PDA in the index
+    AcceptNameTransfer {
+        wipe_records: bool,
+    },
+
+    /// Pause name registration, renames, transfers and address updates
+    /// (program owner only). Getters and admin instructions keep working.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    Pause,
+
+    /// Lift a pause (program owner only).
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    Unpause,
+
+    /// Reserve names for a promo giveaway (program owner only). Each name is
+    /// bound to the hash of a secret handed out off-chain and held by its
+    /// claim account until claimed or reclaimed after `expires_at`. At most
+    /// `MAX_PROMO_CLAIMS_PER_INSTRUCTION` claims per instruction. Refused
+    /// while paused, and for names on the blocklist.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent)
+    /// 1. `[]` The config account
+    /// 2. `[]` The system program
+    /// 3. `[]` The blocklist PDA `["blocklist", config]`, if the config has
+    ///    a blocklist
+    /// 4. `[writable]` For each claim, in order: the claim PDA
+    ///    `["promo_claim", config, commitment]`, then the name account PDA
+    /// 5. `[]` The multisig PDA `["multisig", config]`, if one is set, after
+    ///    every other account
+    /// 6. `[signer]` Further multisig members, up to the threshold
+    CreatePromoClaims {
+        expires_at: i64,
+        claims: Vec<PromoClaimEntry>,
+    },
+
+    /// Claim a reserved name fee-free by revealing the preimage of its
+    /// commitment. Each claim can be used once, before it expires. The name
+    /// is counted, and capped by `max_total_names`, like a registration.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The claimer, who becomes the name owner (pays
+    ///    rent for the reverse record)
+    /// 1. `[writable]` The config account
+    /// 2. `[writable]` The program owner, or with a multisig set the name's
+    ///    treasury bucket PDA (receives the claim account's rent)
+    /// 3. `[writable]` The claim PDA
+    /// 4. `[writable]` The name account
+    /// 5. `[writable]` The reverse record PDA `["address", name_account]`
+    /// 6. `[]` The system program
+    /// 7. `[writable]` The claimer's address claim PDA, if the config has
+    ///    `enforce_unique_address`
+    /// 8. `[]` (optional) The claimer's wallet settings PDA
+    ClaimPromoName {
+        preimage: Vec<u8>,
+    },
+
+    /// Take back expired, unclaimed promo names (program owner only).
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member
+    ///    (receives the names and the claim accounts' rent)
+    /// 1. `[]` The config account
+    /// 2. `[writable]` For each claim: the claim PDA, then its name account
+    /// 3. `[]` The multisig PDA `["multisig", config]`, if one is set, after
+    ///    every other account
+    /// 4. `[signer]` Further multisig members, up to the threshold
+    ReclaimPromoNames,
+
+    /// Convert a reverse record still in the legacy `AddressAccount` layout
+    /// to `ReverseRecord`, growing it in place. Permissionless: the new
+    /// record is derived from the name account. Handlers that write a
+    /// reverse record reject legacy accounts with `LegacyAccountLayout`.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer of the extra rent
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The legacy reverse record, either the name's PDA
+    ///    `["address", name_account]` or an account holding the name
+    /// 3. `[]` The system program
+    MigrateAddressAccount,
+
+    /// Set how many address updates and transfer offers a wallet may have
+    /// open at once; zero lifts the cap (program owner only). Wallets
+    /// already above a lowered cap keep their operations but cannot open
+    /// more.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetMaxPendingOperations {
+        max_pending_operations: u16,
+    },
+
+    /// Price names by length (program owner only). Queued for
+    /// ExecuteAdminAction while an admin timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member (writable and
+    ///    paying rent while a timelock is set)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program, if a timelock is set
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetFeeSchedule {
+        schedule: FeeSchedule,
+    },
+
+    /// Put an unregistered premium name up for auction for
+    /// `AUCTION_DURATION` seconds (program owner only). The auction account
+    /// holds the name until SettleAuction. Reserved names and names with a
+    /// live tombstone cannot be auctioned.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent)
+    /// 1. `[]` The config account
+    /// 2. `[writable]` The auction PDA `["auction", name_account]`
+    /// 3. `[writable]` The name account PDA `["name", name]`
+    /// 4. `[writable]` The escrow PDA `["auction_escrow", auction]`
+    /// 5. `[]` The system program
+    /// 6. `[]` The reservation PDA `["reserved", name_account]`
+    /// 7. `[]` The tombstone PDA `["tombstone", name_account]`, if the config
+    ///    has a `tombstone_period`
+    /// 8. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 9. `[signer]` Further multisig members, up to the threshold
+    StartAuction {
+        name: String,
+        min_bid: u64,
+    },
+
+    /// Bid on a running auction. `amount` must reach the minimum bid and
+    /// exceed the highest bid, which is refunded from escrow.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The bidder
+    /// 1. `[]` The config account
+    /// 2. `[writable]` The auction account
+    /// 3. `[writable]` The auction's escrow PDA
+    /// 4. `[writable]` The current highest bidder, if any bid was placed
+    ///    (any account otherwise)
+    /// 5. `[]` The system program
+    PlaceBid {
+        amount: u64,
+    },
+
+    /// Close an ended auction. Permissionless: the name is registered to the
+    /// highest bidder, whose bid goes to the treasury, or left unregistered
+    /// if nobody bid. The auction and escrow rent, and the name account's
+    /// without a bid, go to the program owner, or with a multisig set to the
+    /// name's treasury bucket.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer of the winner's reverse record rent
+    /// 1. `[writable]` The config account
+    /// 2. `[writable]` The program owner, or with a multisig set the name's
+    ///    treasury bucket PDA
+    /// 3. `[writable]` The auction account
+    /// 4. `[writable]` The auction's escrow PDA
+    /// 5. `[writable]` The name account
+    /// 6. `[writable]` The reverse record PDA `["address", name_account]`
+    /// 7. `[writable]` The treasury bucket PDA of the name
+    /// 8. `[]` The system program
+    /// 9. `[writable]` The winner's address claim PDA, if a bid was placed
+    ///    and the config has `enforce_unique_address`
+    /// 10. `[]` (optional) The winner's wallet settings PDA
+    SettleAuction,
+
+    /// Set the share of registration fees paid to referrers, in basis
+    /// points of the fee. Queued for ExecuteAdminAction while an admin
+    /// timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member (writable and
+    ///    paying rent while a timelock is set)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program, if a timelock is set
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetReferralBps {
+        referral_bps: u16,
+    },
+
+    /// Cap how many names RegisterName may register in total; zero lifts
+    /// the cap (program owner only). Lowering the cap below the current
+    /// count only stops new registrations.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetMaxTotalNames {
+        max_total_names: u64,
+    },
+
+    /// Allow or forbid new names using the curated Unicode subset of
+    /// `validate_name_for` (program owner only). Subname labels stay ASCII.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetUnicodeNames {
+        enabled: bool,
+    },
+
+    /// Commit to registering a name without revealing it. `name_hash` is
+    /// `name_commitment(name, salt, committer)`.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The committer (pays rent for the commitment)
+    /// 1. `[writable]` The commitment PDA `["commitment", committer, name_hash]`
+    /// 2. `[]` The system program
+    CommitName {
+        name_hash: [u8; 32],
+    },
+
+    /// Register a name committed to at least `MIN_COMMITMENT_AGE` seconds
+    /// earlier, closing the commitment. Otherwise behaves as RegisterName.
+    /// Accounts expected:
+    /// 0. `[writable]` The commitment PDA, refunded to the registrant
+    /// 1.. The RegisterName accounts; the registrant must be the committer
+    RevealAndRegister {
+        name: String,
+        salt: [u8; 32],
+        proof: Option<NameProof>,
+    },
+
+    /// Require commit-reveal for new registrations, refusing RegisterName
+    /// (program owner only).
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetCommitRevealRequired {
+        required: bool,
+    },
+
+    /// Create or overwrite the signer's private note on a name (name owner
+    /// only). `data` is opaque, encrypted client-side, and at most
+    /// `MAX_NOTE_LENGTH` bytes.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The name owner (pays rent)
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The note PDA `["note", name_account, owner]`
+    /// 3. `[]` The system program
+    SetNote {
+        data: Vec<u8>,
+    },
+
+    /// Delete the signer's note on a name, refunding its rent. Works after
+    /// the name changed hands.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The note's owner
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The note PDA
+    DeleteNote,
+
+    /// Copy a config created before the config PDA into it and retire the
+    /// old account (program owner only). Fees move to the new config's treasury buckets;
+    /// handlers refuse the old config with `ConfigMigrated` afterwards. The
+    /// banner, promo claims, auctions and a queued admin action are keyed to
+    /// the old config, so settle, reclaim or cancel them first and set the
+    /// banner again after. Refused with `MultisigActive` while a multisig is
+    /// set, as its PDA is derived from the old config; remove it first.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner (pays rent)
+    /// 1. `[writable]` The current program config account
+    /// 2. `[writable]` The config PDA `["config"]`
+    /// 3. `[]` The system program
+    /// 4. `[writable]` The current config's treasury bucket PDAs, every
+    ///    bucket in order
+    /// 5. `[writable]` The config PDA's treasury bucket PDAs, every bucket in
+    ///    order
+    /// 6. `[writable]` The config link PDA `["config_link"]`, which must
+    ///    point at the current config if it exists
+    MigrateConfig,
+
+    /// Put every instruction the program owner authorizes under an M-of-N
+    /// multisig, replace its members, or with no signers and a zero
+    /// threshold hand them back to the config owner. Authorized like
+    /// the instructions it covers: by the owner, or by the current multisig.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The multisig PDA `["multisig", config]`
+    /// 3. `[]` The system program
+    /// 4. `[signer]` Further members of the current multisig, up to its
+    ///    threshold
+    SetMultisig {
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    },
+
+    /// Return the addresses of a name's records via return data, encoded by
+    /// `formats::encode_record_keys`
+    /// Accounts expected:
+    /// 0. `[]` The name account
+    /// 1. `[]` The record index PDA `["record_index", name_account]`
+    ListRecordKeys,
+
+    /// Make SetRegistrationFee, SetFeeSchedule, SetRenameFee, SetUpdateFee,
+    /// SchedulePromo, SetReferralBps, ChangeProgramOwner and this
+    /// instruction wait `seconds` (at most
+    /// `MAX_ADMIN_TIMELOCK`) in a PendingAdminAction before they apply, so
+    /// registrants see fee and ownership changes coming; zero applies them
+    /// at once. Itself queued while a timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member (writable and
+    ///    paying rent while a timelock is set)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program, if a timelock is set
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetAdminTimelock {
+        seconds: u32,
+    },
+
+    /// Apply the queued admin action once its `executable_at` has passed,
+    /// closing the admin action PDA.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member
+    ///    (receives the rent)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`
+    /// 3. `[]` The system program, then the buckets as for RotateFeeVault,
+    ///    only when executing a queued RotateFeeVault
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    ExecuteAdminAction,
+
+    /// Drop the queued admin action without applying it. Accounts as for
+    /// ExecuteAdminAction.
+    CancelAdminAction,
+
+    /// Require `cosigner` to sign TransferName, RenameName and
+    /// RequestAddressUpdate on a name besides its owner, or with the default
+    /// pubkey stop requiring one. Replacing or removing a co-signer takes its
+    /// signature as well, and a new co-signer must sign to be set. A
+    /// co-signer set by an earlier owner no longer applies.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The name owner (pays rent)
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The co-signer PDA `["cosigner", name_account]`
+    /// 3. `[]` The system program
+    /// 4. `[signer]` The new co-signer and, if one is set, the current one
+    SetCosigner {
+        cosigner: Pubkey,
+    },
+
+    /// Give up a top-level name, closing its name account, reverse record
+    /// and co-signer PDA and refunding their rent to `destination`. The
+    /// name's records must be deleted first; an open transfer offer is
+    /// withdrawn. Tokenized names cannot be given up. Subnames stay under
+    /// the name's account and pass to whoever registers the name next.
+    /// While the config has a `tombstone_period`, a `NameTombstone` of the
+    /// name is left behind for that long, replacing any earlier one.
+    /// Accounts expected:
+    /// 0. `[signer]` The name owner
+    /// 1. `[writable]` The name account
+    /// 2. `[writable]` The name's reverse record: the PDA
+    ///    `["address", name_account]` or, after a rename, the record created
+    ///    with the original name
+    /// 3. `[writable]` The config account
+    /// 4. `[writable]` The destination of the refunded rent
+    /// 5. `[]` The record index PDA `["record_index", name_account]`, which
+    ///    must not exist
+    /// 6. `[writable]` The owner's wallet settings PDA
+    /// 7. `[writable]` The name's co-signer PDA `["cosigner", name_account]`,
+    ///    which may not exist
+    /// 8. `[signer]` The name's co-signer, if one is set
+    /// 9. `[writable]` While the config has a tombstone period: the tombstone
+    ///    PDA `["tombstone", name_account]`, followed by the system program;
+    ///    the owner pays its rent and must be writable
+    UnregisterName,
+
+    /// Close receipts of the signer, refunding their rent.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer the receipts were issued to
+    /// 1.. `[writable]` The receipt PDAs
+    CloseReceipts,
+
+    /// Get the bounds, in seconds, a cooldown period must fall within.
+    /// Accounts expected: none
+    GetCooldownBounds,
+
+    /// Resolve a name to its address, owner, expiry and whether it is
+    /// frozen, returned as a versioned `cpi::ResolveResult`. Frozen names
+    /// and subnames of frozen names resolve, flagged as frozen; otherwise
+    /// fails like ResolveAddress.
+    /// Accounts expected:
+    /// 0. `[]` The name account
+    /// 1.. `[]` For subnames, every ancestor name account, nearest first
+    ResolveDetails,
+
+    /// Reserve an unregistered name (program owner only), e.g. a trademark
+    /// or an offensive word. The name is held by its reservation PDA and
+    /// cannot be registered until released.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent)
+    /// 1. `[]` The config account
+    /// 2. `[]` The system program
+    /// 3. `[writable]` The name account PDA `["name", name]`
+    /// 4. `[writable]` The reservation PDA `["reserved", name_account]`
+    /// 5. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 6. `[signer]` Further multisig members, up to the threshold
+    ReserveName {
+        name: String,
+    },
+
+    /// Release a reserved name (program owner only), closing its
+    /// reservation. Without an assignee the name account is closed too, so
+    /// anyone can register the name; with one, the name is registered to the
+    /// assignee, fee-free. Reserving and releasing to an assignee in one
+    /// transaction assigns a name directly.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member
+    ///    (receives the rent, and pays for the reverse record of an assigned
+    ///    name)
+    /// 1. `[writable]` The config account, which counts assigned names
+    /// 2. `[writable]` The reservation PDA
+    /// 3. `[writable]` The name account
+    /// 4. `[]` The system program, with an assignee only
+    /// 5. `[writable]` The reverse record PDA `["address", name_account]`,
+    ///    with an assignee only
+    /// 6. `[writable]` The assignee's address claim PDA, with an assignee if
+    ///    the config has `enforce_unique_address`
+    /// 7. `[]` (optional) The assignee's wallet settings PDA
+    /// 8. `[]` The multisig PDA `["multisig", config]`, if one is set, after
+    ///    every other account
+    /// 9. `[signer]` Further multisig members, up to the threshold
+    ReleaseReservedName {
+        assignee: Option<Pubkey>,
+    },
+
+    /// Set the merkle root of the names the config refuses to register
+    /// (program owner only); see `blocklist`. A zero root turns the
+    /// blocklist off, any other replaces the previous list at once.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent for the blocklist)
+    /// 1. `[writable]` The config account
+    /// 2. `[writable]` The blocklist PDA `["blocklist", config]`
+    /// 3. `[]` The system program
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetBlocklistRoot {
+        root: [u8; 32],
+    },
+
+    /// Have subnames of a name that don't exist resolve to the name's
+    /// address through ResolveSubname (name owner only).
+    /// Accounts expected:
+    /// 0. `[signer]` The name owner
+    /// 1. `[writable]` The name account
+    SetWildcardSubdomains {
+        enabled: bool,
+    },
+
+    /// Resolve `<label>.<parent>` to its address like ResolveAddress. A
+    /// subname that doesn't exist resolves to the parent's address if the
+    /// parent has `wildcard_subdomains` set, and fails with `NameNotFound`
+    /// otherwise.
+    /// Accounts expected:
+    /// 0. `[]` The subname account PDA `["name", "<label>.<parent>"]`, which
+    ///    may not exist
+    /// 1. `[]` The parent name account
+    /// 2. `[]` Further ancestor name accounts, nearest first
+    ResolveSubname {
+        label: String,
+    },
+
+    /// Grant `role` to `account`, or update its grant (program owner only).
+    /// A registrar pays `fee_bps` basis points of the registration fee, at
+    /// most 100%.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent)
+    /// 1. `[]` The config account
+    /// 2. `[writable]` The role PDA `["role", config, role, account]`
+    /// 3. `[]` The system program
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    GrantRole {
+        role: Role,
+        account: Pubkey,
+        fee_bps: u16,
+    },
+
+    /// Revoke `role` from `account`, closing its role PDA (program owner
+    /// only).
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member
+    ///    (receives the rent)
+    /// 1. `[]` The config account
+    /// 2. `[writable]` The role PDA `["role", config, role, account]`
+    /// 3. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 4. `[signer]` Further multisig members, up to the threshold
+    RevokeRole {
+        role: Role,
+        account: Pubkey,
+    },
+
+    /// Register a name for `owner` (registrars only). The name is owned by
+    /// and resolves to `owner`, whose wallet settings decide its reverse
+    /// record. The registrar pays rent and the share of the fee set by its
+    /// grant, and skips commit-reveal. Otherwise behaves as RegisterName.
+    /// Accounts expected:
+    /// 0. `[]` The registrar's role PDA `["role", config, Registrar, registrar]`
+    /// 1.. The RegisterName accounts, with the registrar as registrant and
+    ///    `owner`'s wallet settings PDA in place of the registrant's
+    RegisterNameAsRegistrar {
+        name: String,
+        owner: Pubkey,
+        proof: Option<NameProof>,
+    },
+
+    /// Mint the one-token SPL mint of a top-level name to its owner (owner
+    /// only). From then on the name changes hands only with its token:
+    /// anyone can sync the name's owner to the token's holder with
+    /// SyncTokenizedOwner, and transfers, renames and address updates of the
+    /// name are refused. The name must have no open transfer offer. The mint
+    /// is its own authority and gives it up after minting, so the supply
+    /// stays at one. Passing the metadata accounts
+    /// also creates immutable Metaplex metadata naming the token after the
+    /// name, pointing at `uri`, which is at most `MAX_TOKEN_URI_LENGTH`
+    /// bytes.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The name owner (pays rent)
+    /// 1. `[writable]` The name account
+    /// 2. `[writable]` The name's mint PDA `["name_mint", name_account]`
+    /// 3. `[writable]` The owner's associated token account for the mint,
+    ///    created if missing
+    /// 4. `[]` The config account
+    /// 5. `[]` The system program
+    /// 6. `[]` The SPL token program
+    /// 7. `[]` The associated token account program
+    /// 8. `[writable]` Optional: the mint's Metaplex metadata account
+    /// 9. `[]` Optional, with 8: the Metaplex token metadata program
+    TokenizeName {
+        uri: String,
+    },
+
+    /// Set the owner of a tokenized name to the holder of its token, after
+    /// the token changed hands. Permissionless; starts a cooldown like a
+    /// transfer when the owner changes.
+    /// Accounts expected:
+    /// 0. `[writable]` The name account
+    /// 1. `[]` A token account holding the name's token
+    /// 2. `[]` The config account
+    /// 3. `[signer, writable]` When the owner changes, if the config has
+    ///    `enforce_unique_address`: the payer of the holder's address claim,
+    ///    followed by the system program, the previous owner (writable), and
+    ///    the address claim PDAs of the previous owner and the holder
+    SyncTokenizedOwner,
+
+    /// Set how long a name stays locked after it changes hands or address,
+    /// in seconds, between `MIN_COOLDOWN_PERIOD` and `MAX_COOLDOWN_PERIOD`
+    /// (program owner only). Only cooldowns started afterwards use it, and
+    /// on a config from before version 2 only once MigrateConfig moves it.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetCooldown {
+        seconds: u32,
+    },
+
+    /// Upgrade an account still in the version 1 layout, which predates the
+    /// layout version byte, to `LAYOUT_VERSION`, growing it in place. An
+    /// account whose type has since gained trailing fields grows to its
+    /// current size, the new fields reading as zero.
+    /// Permissionless: the content is unchanged. Handlers that write an
+    /// account reject older layouts with `LegacyAccountLayout`. Legacy
+    /// reverse records go through MigrateAddressAccount instead.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The payer of the extra rent
+    /// 1. `[writable]` The account to migrate
+    /// 2. `[]` The system program
+    MigrateAccount,
+
+    /// Register a name and set its first `records`, as `(kind, value)`
+    /// pairs, in one go. Records are checked and written as SetRecord
+    /// would, at most `MAX_RECORDS_PER_NAME` of them; a later pair with the
+    /// same kind overwrites an earlier one. Otherwise behaves as
+    /// RegisterName, the registrant paying the rent of the records too.
+    /// Accounts expected:
+    /// 0. `[writable]` The record index PDA `["record_index", name_account]`
+    /// 1. `[writable]` The record PDAs `["record", name_account, kind]`, one
+    ///    per pair of `records` and in the same order
+    /// 2. The RegisterName accounts, following the record PDAs
+    RegisterNameWithRecords {
+        name: String,
+        records: Vec<(String, String)>,
+        proof: Option<NameProof>,
+    },
+
+    /// Limit wallets to one name at a time through their `AddressClaim`, or
+    /// lift the limit (program owner only). Registrations take the claim of
+    /// the new owner, and transfers and address updates move it from the
+    /// previous owner to the new one. Turning the limit on leaves existing
+    /// names alone.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetEnforceUniqueAddress {
+        enabled: bool,
+    },
+
+    /// Close the address claim of a wallet that no longer owns the name the
+    /// claim holds, because the name was unregistered, renamed or passed on
+    /// while the limit was off, refunding its rent to the wallet.
+    /// Permissionless.
+    /// Accounts expected:
+    /// 0. `[writable]` The address claim PDA `["address_claim", wallet]`
+    /// 1. `[writable]` The wallet
+    /// 2. `[]` The name account the claim holds
+    ReleaseAddressClaim,
+
+    /// Register a name for `owner`, paid for by the registrant, to gift it.
+    /// The name is owned by and resolves to `owner`, whose wallet settings
+    /// decide its reverse record and whose address claim it takes. The
+    /// registrant pays rent and the full fee, and any receipt is theirs.
+    /// Otherwise behaves as RegisterName, including under commit-reveal.
+    /// Accounts expected: the RegisterName accounts, with `owner`'s wallet
+    /// settings PDA in place of the registrant's
+    RegisterNameFor {
+        name: String,
+        owner: Pubkey,
+        proof: Option<NameProof>,
+    },
+
+    /// Abort an address update armed by the current name owner, releasing
+    /// the owner's pending operation. With `close`, the pending update
+    /// account is closed and its rent refunded to the owner; otherwise it
+    /// is cleared for reuse.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The current name owner
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The pending update PDA of the name
+    /// 3. `[writable]` The owner's wallet settings PDA
+    CancelAddressUpdate {
+        close: bool,
+    },
+
+    /// Set how long an address update stays claimable after it is
+    /// requested, in seconds (program owner only). Zero lets updates wait
+    /// until completed or cancelled. Only updates requested afterwards use
+    /// it; CompleteAddressUpdate fails with `PendingUpdateExpired` once an
+    /// update has expired.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetPendingUpdateTtl {
+        seconds: u32,
+    },
+
+    /// Let `operator` act on the name within `scope` (name owner only). The
+    /// approval lapses once the name changes hands.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The name owner (pays rent)
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The operator PDA `["operator", name_account, scope, operator]`
+    /// 3. `[]` The system program
+    ApproveOperator {
+        operator: Pubkey,
+        scope: OperatorScope,
+    },
+
+    /// Withdraw an operator approval, refunding its rent (name owner only).
+    /// Also closes approvals left by an earlier owner.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The name owner
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The operator PDA
+    RevokeOperator {
+        operator: Pubkey,
+        scope: OperatorScope,
+    },
+
+    /// Create the namespace of the names ending in `.<suffix>` (program
+    /// owner only), run by `admin` and charging `fee` per registration in
+    /// place of the config's fees. The namespace holds the suffix's name
+    /// account, which must not be registered or reserved.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent)
+    /// 1. `[]` The config account
+    /// 2. `[]` The system program
+    /// 3. `[writable]` The suffix's name account PDA `["name", suffix]`
+    /// 4. `[writable]` The namespace PDA `["namespace", name_account]`
+    /// 5. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 6. `[signer]` Further multisig members, up to the threshold
+    CreateNamespace {
+        suffix: String,
+        fee: u64,
+        admin: Pubkey,
+    },
+
+    /// Set the registration fee and admin of a namespace, and whether it
+    /// refuses registrations (namespace admin only).
+    /// Accounts expected:
+    /// 0. `[signer]` The namespace admin
+    /// 1. `[writable]` The namespace PDA
+    SetNamespacePolicy {
+        fee: u64,
+        admin: Pubkey,
+        paused: bool,
+    },
+
+    /// Withdraw the fees a namespace collected, leaving it rent-exempt
+    /// (namespace admin only).
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The namespace admin, receiving the fees
+    /// 1. `[writable]` The namespace PDA
+    WithdrawNamespaceFees,
+
+    /// Delegate the registrations of a namespace to `program_id`, another
+    /// on-chain program (namespace admin only). While delegated, names of
+    /// the namespace are only registered with the delegate's authority PDA
+    /// `["namespace_authority", namespace]` as registrant, which the
+    /// delegate program alone signs for, through CPI; it pays the fee and
+    /// rent as any registrant. The default pubkey takes the namespace back.
+    /// Namespaces created before delegation go through MigrateAccount first.
+    /// Accounts expected:
+    /// 0. `[signer]` The namespace admin
+    /// 1. `[writable]` The namespace PDA
+    DelegateNamespaceAuthority {
+        program_id: Pubkey,
+    },
+
+    /// Price names by length in US cents (program owner only), converted to
+    /// lamports at registration at the SOL/USD price of `price_feed`, a Pyth
+    /// price account. Registrations fail while the price is older than
+    /// `max_price_age` seconds or its confidence interval is wider than
+    /// `max_confidence_bps` of it. The default `price_feed` turns USD
+    /// pricing off again, back to the config's fee schedule. Refused while
+    /// an admin timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent for the pricing)
+    /// 1. `[writable]` The config account
+    /// 2. `[writable]` The USD pricing PDA `["usd_pricing", config]`
+    /// 3. `[]` The system program
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetUsdPricing {
+        price_feed: Pubkey,
+        fee_schedule: FeeSchedule,
+        max_price_age: u32,
+        max_confidence_bps: u16,
+    },
+
+    /// Register a top-level name at `discount_bps` off the fee, 10000 for
+    /// free, under a voucher the program owner signed off-chain:
+    /// `signature` over `voucher::voucher_message`, which an Ed25519 program
+    /// instruction right before this one must verify. Refused after
+    /// `expiry` and while the config requires commit-reveal. Otherwise
+    /// behaves as RegisterName.
+    /// Accounts expected:
+    /// 0. `[]` The instructions sysvar
+    /// 1.. The RegisterName accounts; the registrant must be the one the
+    ///    voucher was signed for
+    RegisterWithVoucher {
+        name: String,
+        discount_bps: u16,
+        expiry: i64,
+        signature: [u8; 64],
+        proof: Option<NameProof>,
+    },
+
+    /// Charge `fee` lamports for RenameName. Queued for ExecuteAdminAction
+    /// while an admin timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member (writable and
+    ///    paying rent while a timelock is set)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program, if a timelock is set
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetRenameFee {
+        fee: u64,
+    },
+
+    /// Charge `fee` lamports for CompleteAddressUpdate. Queued for
+    /// ExecuteAdminAction while an admin timelock is set.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member (writable and
+    ///    paying rent while a timelock is set)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program, if a timelock is set
+    /// 4. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 5. `[signer]` Further multisig members, up to the threshold
+    SetUpdateFee {
+        fee: u64,
+    },
+
+    /// Start or stop appending each name RegisterName registers to the
+    /// `RegistryPage` being filled (program owner only). Names registered
+    /// while it is off are not indexed.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetRegistryPages {
+        enabled: bool,
+    },
+
+    /// Take a name down (program owner only), recording who froze it and
+    /// when. A frozen name does not resolve, and cannot be renamed,
+    /// transferred, tokenized or unregistered; nor do its subnames resolve.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[]` The program config account
+    /// 2. `[writable]` The name account
+    /// 3. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 4. `[signer]` Further multisig members, up to the threshold
+    FreezeName,
+
+    /// Lift a FreezeName (program owner only). The name keeps the record of
+    /// its last freeze.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[]` The program config account
+    /// 2. `[writable]` The name account
+    /// 3. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 4. `[signer]` Further multisig members, up to the threshold
+    UnfreezeName,
+
+    /// Resolve a tokenized name to the current holder of its token, through
+    /// program return data like ResolveAddress. Tracks the token as it
+    /// changes hands, without waiting for SyncTokenizedOwner.
+    /// Accounts expected:
+    /// 0. `[]` The name account
+    /// 1. `[]` A token account holding the name's token
+    ResolveTokenHolder,
+
+    /// Set how long UnregisterName keeps a `NameTombstone` of a name, in
+    /// seconds (program owner only). Zero stops leaving them, and stops
+    /// registrations from checking the ones already left.
+    /// Accounts expected:
+    /// 0. `[signer]` The program owner, or a multisig member
+    /// 1. `[writable]` The program config account
+    /// 2. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 3. `[signer]` Further multisig members, up to the threshold
+    SetTombstonePeriod {
+        seconds: u32,
+    },
+
+    /// Close an expired tombstone, refunding its rent to the owner that
+    /// created it. Permissionless.
+    /// Accounts expected:
+    /// 0. `[writable]` The tombstone PDA `["tombstone", name_account]`
+    /// 1. `[writable]` The tombstone's `payer`
+    CloseTombstone,
+
+    /// Hand a tokenized name whose token was burned back to the owner last
+    /// recorded by TokenizeName or SyncTokenizedOwner, who can transfer,
+    /// rename or unregister it again. Permissionless; fails with
+    /// `NameTokenNotBurned` while the mint has a supply. The mint stays, so
+    /// the name cannot be tokenized again.
+    /// Accounts expected:
+    /// 0. `[writable]` The name account
+    /// 1. `[]` The name's mint PDA `["name_mint", name_account]`
+    FinalizeBurnedName,
+
+    /// Move the registry's fees into a new generation of treasury buckets
+    /// and retire the current ones (program owner only), e.g. after a key
+    /// compromise. The current buckets are closed into the new ones, and fee
+    /// payments and withdrawals take only the new ones from then on. Queued
+    /// for ExecuteAdminAction while an admin timelock is set, which then
+    /// takes the buckets instead.
+    /// Accounts expected:
+    /// 0. `[signer, writable]` The program owner, or a multisig member (pays
+    ///    rent for the new buckets, or for the admin action PDA)
+    /// 1. `[writable]` The program config account
+    /// 2. `[writable]` The admin action PDA `["admin_action", config]`, if a
+    ///    timelock is set
+    /// 3. `[]` The system program
+    /// 4. `[writable]` Every current treasury bucket PDA in order, then every
+    ///    bucket PDA of the next generation, if no timelock is set
+    /// 5. `[]` The multisig PDA `["multisig", config]`, if one is set
+    /// 6. `[signer]` Further multisig members, up to the threshold
+    RotateFeeVault,
+
+    /// Let `wallet` overwrite the record `kind` of a name with SetRecord,
+    /// e.g. a game keeping its `game-profile` record (name owner only). A
+    /// record has at most `MAX_RECORD_WRITERS` writers, who cannot create,
+    /// delete or grant records, and whose grants lapse once the name has
+    /// another owner.
+    /// Accounts expected:
+    /// 0. `[signer]` The name owner
+    /// 1. `[]` The name account
+    /// 2. `[writable]` The record PDA `["record", name_account, kind]`
+    GrantRecordWriter {
+        kind: String,
+        wallet: Pubkey,
+    },
+
+    /// Stop letting `wallet` overwrite the record `kind` of a name (name
+    /// owner only). Accounts as for GrantRecordWriter.
+    RevokeRecordWriter {
+        kind: String,
+        wallet: Pubkey,
+    },
+
+    /// Invoke an experimental feature registered under `tag` in the
+    /// extensions dispatcher. `payload` and the expected accounts are defined
+    /// by the extension; `payload` is at most `MAX_EXTENSION_PAYLOAD_LENGTH`
+    /// bytes.
+    Extension {
+        tag: u16,
+        payload: Vec<u8>,
+    },
+}
+
+/// A name reserved by `CreatePromoClaims`, claimable with the preimage of
+/// `commitment` (see `pda::promo_claim_commitment`). `proof` shows the name
+/// is missing from the blocklist, as RegisterName's does.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct PromoClaimEntry {
+    pub commitment: [u8; 32],
+    pub name: String,
+    pub proof: Option<NameProof>,
+}
+
+/// Proof that a name is not on a config's blocklist: the listed keys `low`
+/// and `high` on either side of the name's key, and the merkle path of the
+/// leaf holding them (see `blocklist`).
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct NameProof {
+    pub low: [u8; 32],
+    pub high: [u8; 32],
+    pub path: Vec<[u8; 32]>,
+}
+
+impl NameRegistryInstruction {
+    /// Decodes instruction data, refusing oversized input before decoding
+    /// and collections over their limits right after, so batches that
+    /// could never be processed fail before any account is touched.
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() > MAX_INSTRUCTION_DATA_LENGTH {
+            return Err(NameRegistryError::InstructionTooLarge.into());
+        }
+        let instruction = Self::try_from_slice(input).map_err(|_| ProgramError::InvalidInstructionData)?;
+        instruction.check_bounds()?;
+        Ok(instruction)
+    }
+
+    fn check_bounds(&self) -> Result<(), ProgramError> {
+        match self {
+            Self::CreatePromoClaims { claims, .. } if claims.len() > MAX_PROMO_CLAIMS_PER_INSTRUCTION => {
+                Err(NameRegistryError::BatchTooLarge.into())
+            }
+            Self::CreatePromoClaims { claims, .. } => {
+                claims.iter().filter_map(|entry| entry.proof.as_ref()).try_for_each(validate_name_proof)
+            }
+            Self::ClaimPromoName { preimage } => validate_claim_preimage(preimage),
+            Self::SetNote { data } => validate_note(data),
+            Self::TokenizeName { uri } => validate_token_uri(uri),
+            Self::RegisterNameWithRecords { records, .. } if records.len() > MAX_RECORDS_PER_NAME => {
+                Err(NameRegistryError::TooManyRecords.into())
+            }
+            Self::RegisterName { proof: Some(proof), .. }
+            | Self::RevealAndRegister { proof: Some(proof), .. }
+            | Self::RegisterNameAsRegistrar { proof: Some(proof), .. }
+            | Self::RegisterNameWithRecords { proof: Some(proof), .. }
+            | Self::RegisterNameFor { proof: Some(proof), .. }
+            | Self::RegisterWithVoucher { proof: Some(proof), .. }
+            | Self::RenameName { proof: Some(proof), .. } => validate_name_proof(proof),
+            Self::SetMultisig { signers, .. } if signers.len() > MAX_MULTISIG_SIGNERS => {
+                Err(NameRegistryError::BatchTooLarge.into())
+            }
+            Self::Extension { payload, .. } if payload.len() > MAX_EXTENSION_PAYLOAD_LENGTH => {
+                Err(NameRegistryError::InstructionTooLarge.into())
+            }
+            _ => Ok(()),
+        }
+    }
 } 
\ No newline at end of file
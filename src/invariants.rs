@@ -0,0 +1,107 @@
+//! Program-wide state invariants, checked after every successful
+//! instruction when built with the `invariants` feature.
+//!
+//! The checks only read the accounts the instruction was given, so they add
+//! nothing to production builds and cost nothing outside tests. A failing
+//! check panics: it means a handler committed state that no valid sequence
+//! of instructions should be able to produce.
+
+use solana_program::{
+    account_info::AccountInfo,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    pda::find_name_address,
+    state::{AccountType, NameAccount, ProgramConfig, ReverseRecord, BPS_DENOMINATOR},
+    validation::MAX_NAME_LENGTH,
+};
+
+/// Checks every program-owned account passed to the instruction, telling
+/// state types apart by their discriminator.
+pub fn check(program_id: &Pubkey, accounts: &[AccountInfo]) {
+    for account in accounts {
+        if account.owner != program_id || account.data_is_empty() {
+            continue;
+        }
+        let data = account.data.borrow();
+        match data[0] {
+            ProgramConfig::DISCRIMINATOR => {
+                if let Ok(config) = ProgramConfig::unpack_unchecked(&data) {
+                    check_config(account.key, &config);
+                }
+            }
+            NameAccount::DISCRIMINATOR => {
+                if let Ok(name) = NameAccount::unpack_unchecked(&data) {
+                    check_name(program_id, account.key, &name);
+                }
+            }
+            ReverseRecord::DISCRIMINATOR => {
+                if let Ok(reverse) = ReverseRecord::unpack_unchecked(&data) {
+                    check_reverse_record(account.key, &reverse);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check_config(key: &Pubkey, config: &ProgramConfig) {
+    if !config.is_initialized {
+        return;
+    }
+    assert_ne!(config.owner, Pubkey::default(), "config {} has no owner", key);
+    assert!(
+        config.promo_start <= config.promo_end,
+        "config {} has an inverted promo window",
+        key,
+    );
+    assert!(
+        config.promo_fee_override_bps <= BPS_DENOMINATOR,
+        "config {} promo fee exceeds the registration fee",
+        key,
+    );
+    assert!(
+        config.referral_bps <= BPS_DENOMINATOR,
+        "config {} referral share exceeds the registration fee",
+        key,
+    );
+}
+
+fn check_name(program_id: &Pubkey, key: &Pubkey, name: &NameAccount) {
+    if !name.is_initialized {
+        return;
+    }
+    assert!(
+        !name.name.is_empty() && name.name.len() <= MAX_NAME_LENGTH,
+        "name account {} holds an invalid name",
+        key,
+    );
+    assert_eq!(
+        *key,
+        find_name_address(&name.name, program_id).0,
+        "name account {} is not the PDA of {:?}",
+        key,
+        name.name,
+    );
+    assert_ne!(name.owner, Pubkey::default(), "name {:?} has no owner", name.name);
+    // Top-level names with a dot belong to a namespace
+    assert!(
+        !name.is_subname() || name.name.contains('.'),
+        "name {:?} disagrees with its parent link",
+        name.name,
+    );
+}
+
+fn check_reverse_record(key: &Pubkey, reverse: &ReverseRecord) {
+    if !reverse.is_initialized {
+        return;
+    }
+    assert!(
+        !reverse.name.is_empty() && reverse.name.len() <= MAX_NAME_LENGTH,
+        "reverse record {} holds an invalid name",
+        key,
+    );
+    assert_ne!(reverse.wallet, Pubkey::default(), "reverse record {} has no wallet", key);
+}
@@ -1,26 +1,52 @@
-use solana_program::{
-    account_info::AccountInfo,
-    entrypoint,
-    entrypoint::ProgramResult,
-    pubkey::Pubkey,
-};
-
-pub mod error;
-pub mod instruction;
-pub mod processor;
-pub mod state;
-pub mod validation;
-
-use instruction::NameRegistryInstruction;
-use processor::Processor;
-
-entrypoint!(process_instruction);
-
-pub fn process_instruction(
-    program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    instruction_data: &[u8],
-) -> ProgramResult {
-    let instruction = NameRegistryInstruction::unpack(instruction_data)?;
-    Processor::process(program_id, accounts, instruction)
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::PrintProgramError,
+    pubkey::Pubkey,
+};
+
+pub mod analytics;
+pub mod blocklist;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod compat;
+pub mod cpi;
+pub mod error;
+pub mod extensions;
+pub mod formats;
+pub mod instruction;
+#[cfg(feature = "invariants")]
+pub mod invariants;
+pub mod oracle;
+pub mod pda;
+pub mod processor;
+pub mod state;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod token_metadata;
+pub mod validation;
+pub mod voucher;
+
+use error::NameRegistryError;
+use instruction::NameRegistryInstruction;
+use processor::Processor;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let instruction = NameRegistryInstruction::unpack(instruction_data)?;
+    let result = Processor::process(program_id, accounts, instruction);
+    #[cfg(feature = "invariants")]
+    if result.is_ok() {
+        invariants::check(program_id, accounts);
+    }
+    if let Err(error) = &result {
+        error.print::<NameRegistryError>();
+    }
+    result
 } 
\ No newline at end of file
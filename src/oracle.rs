@@ -0,0 +1,107 @@
+//! The slice of the Pyth price account format USD pricing reads.
+//!
+//! Only the aggregate price of a v2 price account is needed, so its fields
+//! are read at their fixed offsets here rather than pulling in the Pyth SDK
+//! and its SDK pins. The account is little-endian throughout: a header of
+//! `magic`, `version` and account type, the price exponent at byte 20, the
+//! aggregate's publish time at byte 96 and the aggregate price, confidence
+//! and status from byte 208.
+
+use solana_program::program_error::ProgramError;
+
+use crate::{error::NameRegistryError, state::BPS_DENOMINATOR};
+
+pub const MAGIC: u32 = 0xa1b2_c3d4;
+pub const VERSION: u32 = 2;
+/// Account type of price accounts, next to mapping and product accounts.
+pub const PRICE_ACCOUNT_TYPE: u32 = 3;
+/// Aggregate status of a price publishers are currently trading at.
+pub const STATUS_TRADING: u32 = 1;
+
+const EXPONENT_OFFSET: usize = 20;
+const PUBLISH_TIME_OFFSET: usize = 96;
+const AGGREGATE_OFFSET: usize = 208;
+/// Bytes up to the end of the aggregate price, the last field read.
+pub const PRICE_ACCOUNT_MIN_LEN: usize = AGGREGATE_OFFSET + 32;
+
+/// Lamports per SOL over cents per dollar.
+const LAMPORTS_PER_CENT_EXPONENT: i32 = 7;
+
+/// Aggregate SOL/USD price of a price account: `price * 10^exponent` dollars
+/// per SOL, give or take `confidence` in the same units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Price {
+    pub price: i64,
+    pub confidence: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl Price {
+    /// Reads the aggregate price of a Pyth price account, which must be
+    /// trading at a positive price.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < PRICE_ACCOUNT_MIN_LEN
+            || read_u32(data, 0) != MAGIC
+            || read_u32(data, 4) != VERSION
+            || read_u32(data, 8) != PRICE_ACCOUNT_TYPE
+        {
+            return Err(NameRegistryError::InvalidPriceFeed.into());
+        }
+        let price = Self {
+            price: read_u64(data, AGGREGATE_OFFSET) as i64,
+            confidence: read_u64(data, AGGREGATE_OFFSET + 8),
+            exponent: read_u32(data, EXPONENT_OFFSET) as i32,
+            publish_time: read_u64(data, PUBLISH_TIME_OFFSET) as i64,
+        };
+        if read_u32(data, AGGREGATE_OFFSET + 16) != STATUS_TRADING || price.price <= 0 {
+            return Err(NameRegistryError::InvalidPriceFeed.into());
+        }
+        Ok(price)
+    }
+
+    /// Packs the price into a trading price account, for tests and local
+    /// validators standing in for the oracle.
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0; PRICE_ACCOUNT_MIN_LEN];
+        data[..4].copy_from_slice(&MAGIC.to_le_bytes());
+        data[4..8].copy_from_slice(&VERSION.to_le_bytes());
+        data[8..12].copy_from_slice(&PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[12..16].copy_from_slice(&(PRICE_ACCOUNT_MIN_LEN as u32).to_le_bytes());
+        data[EXPONENT_OFFSET..EXPONENT_OFFSET + 4].copy_from_slice(&self.exponent.to_le_bytes());
+        data[PUBLISH_TIME_OFFSET..PUBLISH_TIME_OFFSET + 8].copy_from_slice(&self.publish_time.to_le_bytes());
+        let aggregate = &mut data[AGGREGATE_OFFSET..];
+        aggregate[..8].copy_from_slice(&self.price.to_le_bytes());
+        aggregate[8..16].copy_from_slice(&self.confidence.to_le_bytes());
+        aggregate[16..20].copy_from_slice(&STATUS_TRADING.to_le_bytes());
+        data
+    }
+
+    /// Confidence interval as a share of the price, in basis points.
+    pub fn confidence_bps(&self) -> u64 {
+        let bps = self.confidence as u128 * BPS_DENOMINATOR as u128 / self.price as u128;
+        bps.min(u64::MAX as u128) as u64
+    }
+
+    /// Lamports worth `cents` US cents at this price, rounded down; `None`
+    /// if they overflow.
+    pub fn cents_to_lamports(&self, cents: u64) -> Option<u64> {
+        // lamports = cents * 10^7 / (price * 10^exponent)
+        let shift = LAMPORTS_PER_CENT_EXPONENT.checked_sub(self.exponent)?;
+        let scale = 10u128.checked_pow(shift.unsigned_abs())?;
+        let lamports = if shift >= 0 {
+            (cents as u128).checked_mul(scale)? / self.price as u128
+        } else {
+            cents as u128 / (self.price as u128).checked_mul(scale)?
+        };
+        u64::try_from(lamports).ok()
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
@@ -0,0 +1,143 @@
+//! Program-derived addresses of registry accounts.
+//!
+//! Clients derive these off-chain with the same helpers the program uses to
+//! verify them, so a name maps to exactly one account.
+
+use solana_program::{bpf_loader_upgradeable, hash::hashv, pubkey::Pubkey};
+
+pub const NAME_SEED: &[u8] = b"name";
+pub const ADDRESS_SEED: &[u8] = b"address";
+pub const PRIMARY_NAME_SEED: &[u8] = b"reverse";
+pub const RECORD_SEED: &[u8] = b"record";
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const PROMO_CLAIM_SEED: &[u8] = b"promo_claim";
+pub const AUCTION_SEED: &[u8] = b"auction";
+pub const AUCTION_ESCROW_SEED: &[u8] = b"auction_escrow";
+pub const COMMITMENT_SEED: &[u8] = b"commitment";
+pub const NOTE_SEED: &[u8] = b"note";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const RECORD_INDEX_SEED: &[u8] = b"record_index";
+pub const NAME_MINT_SEED: &[u8] = b"name_mint";
+
+/// Number of treasury buckets fees are sharded across, so registrations of
+/// different names in one slot rarely contend for the same writable account.
+pub const FEE_BUCKETS: u8 = 4;
+
+/// Canonical form of a name, as used in its PDA seeds.
+pub fn normalize_name(name: &str) -> String {
+    name.to_ascii_lowercase()
+}
+
+/// Address of the name account for `name`, at `["name", normalized_name]`.
+///
+/// `name` must be at most `MAX_NAME_LENGTH` bytes, as enforced by
+/// `validate_name`; longer seeds cannot be derived.
+pub fn find_name_address(name: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NAME_SEED, normalize_name(name).as_bytes()], program_id)
+}
+
+/// Address of the reverse record created alongside `name_account`, at
+/// `["address", name_account]`.
+pub fn find_address_account_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ADDRESS_SEED, name_account.as_ref()], program_id)
+}
+
+/// Address of the record holding `wallet`'s primary name, at
+/// `["reverse", wallet]`.
+pub fn find_primary_name_address(wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PRIMARY_NAME_SEED, wallet.as_ref()], program_id)
+}
+
+/// Address of the `kind` record of a name, at `["record", name_account, kind]`.
+pub fn find_record_address(name_account: &Pubkey, kind: &str, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECORD_SEED, name_account.as_ref(), kind.as_bytes()], program_id)
+}
+
+/// Address of the index of a name's records, at
+/// `["record_index", name_account]`.
+pub fn find_record_index_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RECORD_INDEX_SEED, name_account.as_ref()], program_id)
+}
+
+/// Address of `owner`'s note on a name, at `["note", name_account, owner]`.
+pub fn find_note_address(name_account: &Pubkey, owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NOTE_SEED, name_account.as_ref(), owner.as_ref()], program_id)
+}
+
+/// Treasury bucket receiving the registration fee of `name`, taken from the
+/// hash of its normalized form.
+pub fn fee_bucket(name: &str) -> u8 {
+    hashv(&[normalize_name(name).as_bytes()]).to_bytes()[0] % FEE_BUCKETS
+}
+
+/// Address of treasury bucket `bucket` collecting the registration fees of
+/// `config`, at `["treasury", config, bucket]`, until RotateFeeVault first
+/// moves them on.
+pub fn find_treasury_address(config: &Pubkey, bucket: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED, config.as_ref(), &[bucket]], program_id)
+}
+
+/// Address of treasury bucket `bucket` of `config` in fee vault `generation`:
+/// `find_treasury_address` for the first, `["treasury", config, bucket,
+/// generation]` for those RotateFeeVault creates.
+pub fn find_fee_vault_address(config: &Pubkey, generation: u8, bucket: u8, program_id: &Pubkey) -> (Pubkey, u8) {
+    if generation == 0 {
+        return find_treasury_address(config, bucket, program_id);
+    }
+    Pubkey::find_program_address(&[TREASURY_SEED, config.as_ref(), &[bucket], &[generation]], program_id)
+}
+
+/// Address of the program's one config, at `["config"]`. Initialize creates
+/// it there; handlers accept no other config, so configs from before it
+/// have to be moved over by MigrateConfig.
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// Address of the program data account the upgradeable loader keeps for
+/// `program_id`, holding its upgrade authority.
+pub fn find_program_data_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id())
+}
+
+/// Commitment a promo claim is bound to: the hash of the secret preimage
+/// handed out off-chain.
+pub fn promo_claim_commitment(preimage: &[u8]) -> [u8; 32] {
+    hashv(&[preimage]).to_bytes()
+}
+
+/// Address of the promo claim of `config` bound to `commitment`, at
+/// `["promo_claim", config, commitment]`.
+pub fn find_promo_claim_address(config: &Pubkey, commitment: &[u8; 32], program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROMO_CLAIM_SEED, config.as_ref(), commitment], program_id)
+}
+
+/// Address of the auction of `name_account`, at `["auction", name_account]`.
+pub fn find_auction_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_SEED, name_account.as_ref()], program_id)
+}
+
+/// Address of the data-less account escrowing the highest bid of `auction`,
+/// at `["auction_escrow", auction]`.
+pub fn find_auction_escrow_address(auction: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTION_ESCROW_SEED, auction.as_ref()], program_id)
+}
+
+/// Address of the SPL mint TokenizeName creates for `name_account`, at
+/// `["name_mint", name_account]`. The mint is its own mint authority.
+pub fn find_name_mint_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NAME_MINT_SEED, name_account.as_ref()], program_id)
+}
+
+/// Commitment of `committer` to registering `name`: the hash of the
+/// normalized name, a secret `salt` and the committer, so only the committer
+/// can reveal it and the name cannot be guessed from the hash.
+pub fn name_commitment(name: &str, salt: &[u8; 32], committer: &Pubkey) -> [u8; 32] {
+    hashv(&[normalize_name(name).as_bytes(), salt, committer.as_ref()]).to_bytes()
+}
+
+/// Address of the name commitment of `committer`, at
+/// `["commitment", committer, commitment]`.
+pub fn find_commitment_address(committer: &Pubkey, commitment: &[u8; 32], program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COMMITMENT_SEED, committer.as_ref(), commitment], program_id)
+}
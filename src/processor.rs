@@ -1,444 +1,5736 @@
-use solana_program::{
-    account_info::{next_account_info, AccountInfo},
-    clock::Clock,
-    entrypoint::ProgramResult,
-    program::{invoke},
-    program_error::ProgramError,
-    program_pack::Pack,
-    pubkey::Pubkey,
-    system_instruction,
-    sysvar::Sysvar,
-};
-
-use crate::{
-    error::NameRegistryError,
-    instruction::NameRegistryInstruction,
-    state::{AddressAccount, NameAccount, PendingUpdateAccount, ProgramConfig},
-    validation::*,
-};
-
-pub struct Processor;
-
-impl Processor {
-    pub fn process(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        instruction: NameRegistryInstruction,
-    ) -> ProgramResult {
-        match instruction {
-            NameRegistryInstruction::Initialize { registration_fee } => {
-                Self::process_initialize(_program_id, accounts, registration_fee)
-            }
-            NameRegistryInstruction::RegisterName { name } => {
-                Self::process_register_name(_program_id, accounts, name)
-            }
-            NameRegistryInstruction::RequestAddressUpdate { new_address } => {
-                Self::process_request_address_update(_program_id, accounts, new_address)
-            }
-            NameRegistryInstruction::CompleteAddressUpdate => {
-                Self::process_complete_address_update(_program_id, accounts)
-            }
-            NameRegistryInstruction::RenameName { new_name } => {
-                Self::process_rename_name(_program_id, accounts, new_name)
-            }
-            NameRegistryInstruction::SetRegistrationFee { new_fee } => {
-                Self::process_set_registration_fee(_program_id, accounts, new_fee)
-            }
-            NameRegistryInstruction::ChangeProgramOwner { new_owner } => {
-                Self::process_change_program_owner(_program_id, accounts, new_owner)
-            }
-            NameRegistryInstruction::AcceptProgramOwnership => {
-                Self::process_accept_program_ownership(_program_id, accounts)
-            }
-            NameRegistryInstruction::ResolveAddress => {
-                Self::process_resolve_address(_program_id, accounts)
-            }
-            NameRegistryInstruction::GetContractOwner => {
-                Self::process_get_contract_owner(_program_id, accounts)
-            }
-            NameRegistryInstruction::GetRegistrationFee => {
-                Self::process_get_registration_fee(_program_id, accounts)
-            }
-            NameRegistryInstruction::GetPendingContractOwner => {
-                Self::process_get_pending_contract_owner(_program_id, accounts)
-            }
-            NameRegistryInstruction::Withdraw => {
-                Self::process_withdraw(_program_id, accounts)
-            }
-        }
-    }
-
-    fn process_initialize(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        registration_fee: u64,
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let initializer = next_account_info(account_info_iter)?;
-        let config_account = next_account_info(account_info_iter)?;
-        let _system_program = next_account_info(account_info_iter)?;
-
-        if !initializer.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        let mut config = ProgramConfig::unpack_unchecked(&config_account.data.borrow())?;
-        if config.is_initialized {
-            return Err(NameRegistryError::AlreadyInitialized.into());
-        }
-
-        config.is_initialized = true;
-        config.owner = *initializer.key;
-        config.pending_owner = Pubkey::default();
-        config.registration_fee = registration_fee;
-
-        ProgramConfig::pack(config, &mut config_account.data.borrow_mut())?;
-
-        Ok(())
-    }
-
-    fn process_register_name(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        name: String,
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let registrant = next_account_info(account_info_iter)?;
-        let name_account = next_account_info(account_info_iter)?;
-        let address_account = next_account_info(account_info_iter)?;
-        let config_account = next_account_info(account_info_iter)?;
-        let system_program = next_account_info(account_info_iter)?;
-
-        if !registrant.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        // Verify system program
-        if system_program.key != &solana_program::system_program::id() {
-            return Err(ProgramError::IncorrectProgramId);
-        }
-
-        validate_name(&name)?;
-
-        let config = ProgramConfig::unpack(&config_account.data.borrow())?;
-        let registration_fee = config.registration_fee;
-
-        let mut name_data = NameAccount::unpack_unchecked(&name_account.data.borrow())?;
-        if name_data.is_initialized {
-            return Err(NameRegistryError::NameTaken.into());
-        }
-
-        let mut address_data = AddressAccount::unpack_unchecked(&address_account.data.borrow())?;
-        if address_data.is_initialized {
-            return Err(NameRegistryError::NameAlreadyRegistered.into());
-        }
-
-        // Transfer registration fee from registrant to config account
-        invoke(
-            &system_instruction::transfer(
-                registrant.key,
-                config_account.key,
-                registration_fee,
-            ),
-            &[registrant.clone(), config_account.clone()],
-        )?;
-
-        name_data.is_initialized = true;
-        name_data.owner = *registrant.key;
-        name_data.name = name.clone();
-        name_data.address = *registrant.key;
-        name_data.cooldown_until = Clock::get()?.unix_timestamp;
-
-        address_data.is_initialized = true;
-        address_data.name = name;
-
-        NameAccount::pack(name_data, &mut name_account.data.borrow_mut())?;
-        AddressAccount::pack(address_data, &mut address_account.data.borrow_mut())?;
-
-        Ok(())
-    }
-
-    fn process_request_address_update(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        new_address: Pubkey,
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let current_owner = next_account_info(account_info_iter)?;
-        let name_account = next_account_info(account_info_iter)?;
-        let pending_update_account = next_account_info(account_info_iter)?;
-
-        if !current_owner.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        validate_address(&new_address)?;
-
-        let name_data = NameAccount::unpack(&name_account.data.borrow())?;
-        validate_owner(&name_data.owner, current_owner.key)?;
-        validate_cooldown(name_data.cooldown_until)?;
-
-        let mut pending_update = PendingUpdateAccount::unpack_unchecked(&pending_update_account.data.borrow())?;
-        pending_update.is_initialized = true;
-        pending_update.new_address = new_address;
-
-        PendingUpdateAccount::pack(pending_update, &mut pending_update_account.data.borrow_mut())?;
-
-        Ok(())
-    }
-
-    fn process_complete_address_update(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let new_owner = next_account_info(account_info_iter)?;
-        let name_account = next_account_info(account_info_iter)?;
-        let address_account = next_account_info(account_info_iter)?;
-        let pending_update_account = next_account_info(account_info_iter)?;
-
-        if !new_owner.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        let pending_update = PendingUpdateAccount::unpack(&pending_update_account.data.borrow())?;
-        if !pending_update.is_initialized {
-            return Err(NameRegistryError::NoPendingUpdate.into());
-        }
-
-        if pending_update.new_address != *new_owner.key {
-            return Err(NameRegistryError::NotPendingAddress.into());
-        }
-
-        let mut name_data = NameAccount::unpack(&name_account.data.borrow())?;
-        let address_data = AddressAccount::unpack(&address_account.data.borrow())?;
-
-        name_data.address = *new_owner.key;
-        name_data.owner = *new_owner.key;
-        name_data.cooldown_until = Clock::get()?.unix_timestamp;
-
-        NameAccount::pack(name_data, &mut name_account.data.borrow_mut())?;
-        AddressAccount::pack(address_data, &mut address_account.data.borrow_mut())?;
-
-        // Clear pending update
-        let mut pending_update = PendingUpdateAccount::unpack(&pending_update_account.data.borrow())?;
-        pending_update.is_initialized = false;
-        pending_update.new_address = Pubkey::default();
-        PendingUpdateAccount::pack(pending_update, &mut pending_update_account.data.borrow_mut())?;
-
-        Ok(())
-    }
-
-    fn process_rename_name(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        new_name: String,
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let current_owner = next_account_info(account_info_iter)?;
-        let old_name_account = next_account_info(account_info_iter)?;
-        let new_name_account = next_account_info(account_info_iter)?;
-        let address_account = next_account_info(account_info_iter)?;
-
-        if !current_owner.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        validate_name(&new_name)?;
-
-        let old_name_data = NameAccount::unpack(&old_name_account.data.borrow())?;
-        validate_owner(&old_name_data.owner, current_owner.key)?;
-        validate_cooldown(old_name_data.cooldown_until)?;
-
-        let new_name_data = NameAccount::unpack_unchecked(&new_name_account.data.borrow())?;
-        if new_name_data.is_initialized {
-            return Err(NameRegistryError::NameTaken.into());
-        }
-
-        let mut address_data = AddressAccount::unpack(&address_account.data.borrow())?;
-
-        // Update new name account
-        let mut new_name_data = NameAccount::default();
-        new_name_data.is_initialized = true;
-        new_name_data.owner = *current_owner.key;
-        new_name_data.name = new_name.clone();
-        new_name_data.address = old_name_data.address;
-        new_name_data.cooldown_until = Clock::get()?.unix_timestamp;
-
-        // Update address account
-        address_data.name = new_name;
-
-        // Clear old name account
-        let mut old_name_data = NameAccount::unpack(&old_name_account.data.borrow())?;
-        old_name_data.is_initialized = false;
-        old_name_data.owner = Pubkey::default();
-        old_name_data.name = String::new();
-        old_name_data.address = Pubkey::default();
-        old_name_data.cooldown_until = 0;
-
-        NameAccount::pack(new_name_data, &mut new_name_account.data.borrow_mut())?;
-        AddressAccount::pack(address_data, &mut address_account.data.borrow_mut())?;
-        NameAccount::pack(old_name_data, &mut old_name_account.data.borrow_mut())?;
-
-        Ok(())
-    }
-
-    fn process_set_registration_fee(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        new_fee: u64,
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let owner = next_account_info(account_info_iter)?;
-        let config_account = next_account_info(account_info_iter)?;
-
-        if !owner.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        let mut config = ProgramConfig::unpack(&config_account.data.borrow())?;
-        validate_program_owner(&config.owner, owner.key)?;
-
-        config.registration_fee = new_fee;
-        ProgramConfig::pack(config, &mut config_account.data.borrow_mut())?;
-
-        Ok(())
-    }
-
-    fn process_change_program_owner(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-        new_owner: Pubkey,
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let current_owner = next_account_info(account_info_iter)?;
-        let config_account = next_account_info(account_info_iter)?;
-
-        if !current_owner.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        validate_address(&new_owner)?;
-
-        let mut config = ProgramConfig::unpack(&config_account.data.borrow())?;
-        validate_program_owner(&config.owner, current_owner.key)?;
-
-        config.pending_owner = new_owner;
-        ProgramConfig::pack(config, &mut config_account.data.borrow_mut())?;
-
-        Ok(())
-    }
-
-    fn process_accept_program_ownership(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let pending_owner = next_account_info(account_info_iter)?;
-        let config_account = next_account_info(account_info_iter)?;
-
-        if !pending_owner.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        let mut config = ProgramConfig::unpack(&config_account.data.borrow())?;
-        if config.pending_owner != *pending_owner.key {
-            return Err(NameRegistryError::NotPendingContractOwner.into());
-        }
-
-        config.owner = *pending_owner.key;
-        config.pending_owner = Pubkey::default();
-        ProgramConfig::pack(config, &mut config_account.data.borrow_mut())?;
-
-        Ok(())
-    }
-
-    fn process_resolve_address(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let name_account = next_account_info(account_info_iter)?;
-
-        let name_data = NameAccount::unpack(&name_account.data.borrow())?;
-        if !name_data.is_initialized {
-            return Err(NameRegistryError::NameNotFound.into());
-        }
-
-        // Return the address through program return data
-        let return_data = name_data.address.to_bytes();
-        solana_program::program::set_return_data(&return_data);
-
-        Ok(())
-    }
-
-    fn process_get_contract_owner(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let config_account = next_account_info(account_info_iter)?;
-
-        let config = ProgramConfig::unpack(&config_account.data.borrow())?;
-        let return_data = config.owner.to_bytes();
-        solana_program::program::set_return_data(&return_data);
-
-        Ok(())
-    }
-
-    fn process_get_registration_fee(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let config_account = next_account_info(account_info_iter)?;
-
-        let config = ProgramConfig::unpack(&config_account.data.borrow())?;
-        let return_data = config.registration_fee.to_le_bytes();
-        solana_program::program::set_return_data(&return_data);
-
-        Ok(())
-    }
-
-    fn process_get_pending_contract_owner(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let config_account = next_account_info(account_info_iter)?;
-
-        let config = ProgramConfig::unpack(&config_account.data.borrow())?;
-        let return_data = config.pending_owner.to_bytes();
-        solana_program::program::set_return_data(&return_data);
-
-        Ok(())
-    }
-
-    fn process_withdraw(
-        _program_id: &Pubkey,
-        accounts: &[AccountInfo],
-    ) -> ProgramResult {
-        let account_info_iter = &mut accounts.iter();
-        let owner = next_account_info(account_info_iter)?;
-        let config_account = next_account_info(account_info_iter)?;
-
-        if !owner.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        let config = ProgramConfig::unpack(&config_account.data.borrow())?;
-        validate_program_owner(&config.owner, owner.key)?;
-
-        // Transfer all lamports from config account to owner
-        let config_lamports = config_account.lamports();
-        if config_lamports == 0 {
-            return Err(NameRegistryError::NothingToWithdraw.into());
-        }
-
-        **config_account.lamports.borrow_mut() = 0;
-        **owner.lamports.borrow_mut() = owner.lamports().checked_add(config_lamports)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-
-        Ok(())
-    }
-} 
\ No newline at end of file
+use std::cell::Ref;
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::{
+    analytics::log_registration,
+    blocklist,
+    compat,
+    cpi::{ResolveResult, NEVER_EXPIRES, RESOLVE_RESULT_VERSION},
+    error::NameRegistryError,
+    extensions::process_extension,
+    formats,
+    instruction::{NameProof, NameRegistryInstruction, PromoClaimEntry},
+    oracle,
+    pda::{
+        fee_bucket, find_address_account_address, find_auction_address, find_auction_escrow_address,
+        find_commitment_address, find_config_address, find_name_address, find_name_mint_address, find_note_address, find_promo_claim_address,
+        find_fee_vault_address, find_primary_name_address, find_record_address, find_record_index_address,
+        find_treasury_address, name_commitment, normalize_name, promo_claim_commitment, ADDRESS_SEED, AUCTION_ESCROW_SEED, AUCTION_SEED,
+        COMMITMENT_SEED, CONFIG_SEED, FEE_BUCKETS, NAME_MINT_SEED, NAME_SEED, NOTE_SEED, PRIMARY_NAME_SEED, PROMO_CLAIM_SEED,
+        RECORD_INDEX_SEED, RECORD_SEED, TREASURY_SEED,
+    },
+    state::{
+        AddressClaim, AdminAction, AuctionAccount, BannerMessage, Blocklist, ConfigLink, FeeSchedule, MultisigConfig,
+        NameAccount, NameCommitment, NameCosigner, NameOperator, NameTombstone, NamespaceAccount, NoteAccount,
+        OperatorScope, PackChecked, PaidOperation, PendingAdminAction, PendingUpdateAccount, PrimaryNameRecord, ProgramConfig,
+        PromoClaim, ReceiptAccount, RecordAccount, RecordIndex, RegistryPage, ReservedName, ReverseRecord, Role,
+        RoleGrant, UsdPricing, WalletSettings, ADMIN_ACTION_SEED, BANNER_SEED, BLOCKLIST_SEED, BPS_DENOMINATOR,
+        ADDRESS_CLAIM_SEED, CONFIG_LINK_SEED, COSIGNER_SEED, LAYOUT_VERSION, MULTISIG_SEED, NAMESPACE_SEED, OPERATOR_SEED,
+        PENDING_UPDATE_SEED, RECEIPT_SEED, REGISTRY_PAGE_SEED, RESERVED_SEED, ROLE_SEED, TOMBSTONE_SEED,
+        USD_PRICING_SEED, WALLET_SETTINGS_SEED, layout,
+    },
+    token_metadata,
+    validation::*,
+    voucher,
+};
+
+pub struct Processor;
+
+/// How a registration handled by `process_register_name` came about.
+enum Registration<'a, 'b> {
+    /// RegisterName, by the registrant for themselves.
+    Direct,
+    /// RevealAndRegister, whose commitment is already checked.
+    Revealed,
+    /// RegisterNameFor, for `owner` by the registrant.
+    Sponsored { owner: Pubkey },
+    /// RegisterWithVoucher, by the registrant for themselves at
+    /// `discount_bps` off the fee.
+    Voucher { discount_bps: u16 },
+    /// RegisterNameAsRegistrar, for `owner` by the holder of `role_account`.
+    Registrar {
+        owner: Pubkey,
+        role_account: &'a AccountInfo<'b>,
+    },
+}
+
+impl Processor {
+    pub fn process(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        instruction: NameRegistryInstruction,
+    ) -> ProgramResult {
+        match instruction {
+            NameRegistryInstruction::Initialize { registration_fee } => {
+                Self::process_initialize(program_id, accounts, registration_fee)
+            }
+            NameRegistryInstruction::RegisterName { name, proof } => {
+                Self::process_register_name(program_id, accounts, name, proof, Registration::Direct)
+            }
+            NameRegistryInstruction::RequestAddressUpdate { new_address } => {
+                Self::process_request_address_update(program_id, accounts, new_address)
+            }
+            NameRegistryInstruction::CompleteAddressUpdate => {
+                Self::process_complete_address_update(program_id, accounts)
+            }
+            NameRegistryInstruction::RenameName { new_name, proof } => {
+                Self::process_rename_name(program_id, accounts, new_name, proof)
+            }
+            NameRegistryInstruction::SetRegistrationFee { new_fee } => {
+                Self::process_set_registration_fee(program_id, accounts, new_fee)
+            }
+            NameRegistryInstruction::ChangeProgramOwner { new_owner } => {
+                Self::process_change_program_owner(program_id, accounts, new_owner)
+            }
+            NameRegistryInstruction::AcceptProgramOwnership => {
+                Self::process_accept_program_ownership(program_id, accounts)
+            }
+            NameRegistryInstruction::ResolveAddress => {
+                Self::process_resolve_address(program_id, accounts)
+            }
+            NameRegistryInstruction::GetContractOwner => {
+                Self::process_get_contract_owner(program_id, accounts)
+            }
+            NameRegistryInstruction::GetRegistrationFee => {
+                Self::process_get_registration_fee(program_id, accounts)
+            }
+            NameRegistryInstruction::GetPendingContractOwner => {
+                Self::process_get_pending_contract_owner(program_id, accounts)
+            }
+            NameRegistryInstruction::Withdraw { amount } => {
+                Self::process_withdraw(program_id, accounts, amount)
+            }
+            NameRegistryInstruction::SetPrivacyMode { enabled } => {
+                Self::process_set_privacy_mode(program_id, accounts, enabled)
+            }
+            NameRegistryInstruction::SetBannerMessage { message } => {
+                Self::process_set_banner_message(program_id, accounts, message)
+            }
+            NameRegistryInstruction::SchedulePromo { start, end, fee_override_bps } => {
+                Self::process_schedule_promo(program_id, accounts, start, end, fee_override_bps)
+            }
+            NameRegistryInstruction::SetPrimaryName => {
+                Self::process_set_primary_name(program_id, accounts)
+            }
+            NameRegistryInstruction::ResolveName => {
+                Self::process_resolve_name(program_id, accounts)
+            }
+            NameRegistryInstruction::SetAnalytics { enabled } => {
+                Self::process_set_analytics(program_id, accounts, enabled)
+            }
+            NameRegistryInstruction::RegisterSubname { label } => {
+                Self::process_register_subname(program_id, accounts, label)
+            }
+            NameRegistryInstruction::RevokeSubname => {
+                Self::process_revoke_subname(program_id, accounts)
+            }
+            NameRegistryInstruction::SetRecord { kind, value } => {
+                Self::process_set_record(program_id, accounts, kind, value)
+            }
+            NameRegistryInstruction::DeleteRecord { kind } => {
+                Self::process_delete_record(program_id, accounts, kind)
+            }
+            NameRegistryInstruction::TransferName { new_owner, require_acceptance, wipe_records } => {
+                Self::process_transfer_name(program_id, accounts, new_owner, require_acceptance, wipe_records)
+            }
+            NameRegistryInstruction::AcceptNameTransfer { wipe_records } => {
+                Self::process_accept_name_transfer(program_id, accounts, wipe_records)
+            }
+            NameRegistryInstruction::Pause => {
+                Self::process_set_paused(program_id, accounts, true)
+            }
+            NameRegistryInstruction::Unpause => {
+                Self::process_set_paused(program_id, accounts, false)
+            }
+            NameRegistryInstruction::CreatePromoClaims { expires_at, claims } => {
+                Self::process_create_promo_claims(program_id, accounts, expires_at, claims)
+            }
+            NameRegistryInstruction::ClaimPromoName { preimage } => {
+                Self::process_claim_promo_name(program_id, accounts, preimage)
+            }
+            NameRegistryInstruction::ReclaimPromoNames => {
+                Self::process_reclaim_promo_names(program_id, accounts)
+            }
+            NameRegistryInstruction::MigrateAddressAccount => {
+                Self::process_migrate_address_account(program_id, accounts)
+            }
+            NameRegistryInstruction::SetMaxPendingOperations { max_pending_operations } => {
+                Self::process_set_max_pending_operations(program_id, accounts, max_pending_operations)
+            }
+            NameRegistryInstruction::SetFeeSchedule { schedule } => {
+                Self::process_set_fee_schedule(program_id, accounts, schedule)
+            }
+            NameRegistryInstruction::StartAuction { name, min_bid } => {
+                Self::process_start_auction(program_id, accounts, name, min_bid)
+            }
+            NameRegistryInstruction::PlaceBid { amount } => {
+                Self::process_place_bid(program_id, accounts, amount)
+            }
+            NameRegistryInstruction::SettleAuction => {
+                Self::process_settle_auction(program_id, accounts)
+            }
+            NameRegistryInstruction::SetReferralBps { referral_bps } => {
+                Self::process_set_referral_bps(program_id, accounts, referral_bps)
+            }
+            NameRegistryInstruction::SetMaxTotalNames { max_total_names } => {
+                Self::process_set_max_total_names(program_id, accounts, max_total_names)
+            }
+            NameRegistryInstruction::SetUnicodeNames { enabled } => {
+                Self::process_set_unicode_names(program_id, accounts, enabled)
+            }
+            NameRegistryInstruction::CommitName { name_hash } => {
+                Self::process_commit_name(program_id, accounts, name_hash)
+            }
+            NameRegistryInstruction::RevealAndRegister { name, salt, proof } => {
+                Self::process_reveal_and_register(program_id, accounts, name, salt, proof)
+            }
+            NameRegistryInstruction::SetCommitRevealRequired { required } => {
+                Self::process_set_commit_reveal_required(program_id, accounts, required)
+            }
+            NameRegistryInstruction::SetNote { data } => {
+                Self::process_set_note(program_id, accounts, data)
+            }
+            NameRegistryInstruction::DeleteNote => {
+                Self::process_delete_note(program_id, accounts)
+            }
+            NameRegistryInstruction::MigrateConfig => {
+                Self::process_migrate_config(program_id, accounts)
+            }
+            NameRegistryInstruction::SetMultisig { signers, threshold } => {
+                Self::process_set_multisig(program_id, accounts, signers, threshold)
+            }
+            NameRegistryInstruction::ListRecordKeys => {
+                Self::process_list_record_keys(program_id, accounts)
+            }
+            NameRegistryInstruction::SetAdminTimelock { seconds } => {
+                Self::process_set_admin_timelock(program_id, accounts, seconds)
+            }
+            NameRegistryInstruction::ExecuteAdminAction => {
+                Self::process_close_admin_action(program_id, accounts, true)
+            }
+            NameRegistryInstruction::CancelAdminAction => {
+                Self::process_close_admin_action(program_id, accounts, false)
+            }
+            NameRegistryInstruction::SetCosigner { cosigner } => {
+                Self::process_set_cosigner(program_id, accounts, cosigner)
+            }
+            NameRegistryInstruction::UnregisterName => {
+                Self::process_unregister_name(program_id, accounts)
+            }
+            NameRegistryInstruction::CloseReceipts => {
+                Self::process_close_receipts(program_id, accounts)
+            }
+            NameRegistryInstruction::GetCooldownBounds => {
+                Self::process_get_cooldown_bounds()
+            }
+            NameRegistryInstruction::ResolveDetails => {
+                Self::process_resolve_details(program_id, accounts)
+            }
+            NameRegistryInstruction::ReserveName { name } => {
+                Self::process_reserve_name(program_id, accounts, name)
+            }
+            NameRegistryInstruction::ReleaseReservedName { assignee } => {
+                Self::process_release_reserved_name(program_id, accounts, assignee)
+            }
+            NameRegistryInstruction::SetBlocklistRoot { root } => {
+                Self::process_set_blocklist_root(program_id, accounts, root)
+            }
+            NameRegistryInstruction::SetWildcardSubdomains { enabled } => {
+                Self::process_set_wildcard_subdomains(program_id, accounts, enabled)
+            }
+            NameRegistryInstruction::ResolveSubname { label } => {
+                Self::process_resolve_subname(program_id, accounts, label)
+            }
+            NameRegistryInstruction::GrantRole { role, account, fee_bps } => {
+                Self::process_grant_role(program_id, accounts, role, account, fee_bps)
+            }
+            NameRegistryInstruction::RevokeRole { role, account } => {
+                Self::process_revoke_role(program_id, accounts, role, account)
+            }
+            NameRegistryInstruction::RegisterNameAsRegistrar { name, owner, proof } => {
+                Self::process_register_name_as_registrar(program_id, accounts, name, owner, proof)
+            }
+            NameRegistryInstruction::TokenizeName { uri } => {
+                Self::process_tokenize_name(program_id, accounts, uri)
+            }
+            NameRegistryInstruction::SyncTokenizedOwner => {
+                Self::process_sync_tokenized_owner(program_id, accounts)
+            }
+            NameRegistryInstruction::SetCooldown { seconds } => {
+                Self::process_set_cooldown(program_id, accounts, seconds)
+            }
+            NameRegistryInstruction::MigrateAccount => {
+                Self::process_migrate_account(program_id, accounts)
+            }
+            NameRegistryInstruction::RegisterNameWithRecords { name, records, proof } => {
+                Self::process_register_name_with_records(program_id, accounts, name, records, proof)
+            }
+            NameRegistryInstruction::SetEnforceUniqueAddress { enabled } => {
+                Self::process_set_enforce_unique_address(program_id, accounts, enabled)
+            }
+            NameRegistryInstruction::ReleaseAddressClaim => {
+                Self::process_release_address_claim(program_id, accounts)
+            }
+            NameRegistryInstruction::RegisterNameFor { name, owner, proof } => {
+                let registration = Registration::Sponsored { owner };
+                Self::process_register_name(program_id, accounts, name, proof, registration)
+            }
+            NameRegistryInstruction::CancelAddressUpdate { close } => {
+                Self::process_cancel_address_update(program_id, accounts, close)
+            }
+            NameRegistryInstruction::SetPendingUpdateTtl { seconds } => {
+                Self::process_set_pending_update_ttl(program_id, accounts, seconds)
+            }
+            NameRegistryInstruction::ApproveOperator { operator, scope } => {
+                Self::process_approve_operator(program_id, accounts, operator, scope)
+            }
+            NameRegistryInstruction::RevokeOperator { operator, scope } => {
+                Self::process_revoke_operator(program_id, accounts, operator, scope)
+            }
+            NameRegistryInstruction::CreateNamespace { suffix, fee, admin } => {
+                Self::process_create_namespace(program_id, accounts, suffix, fee, admin)
+            }
+            NameRegistryInstruction::SetNamespacePolicy { fee, admin, paused } => {
+                Self::process_set_namespace_policy(program_id, accounts, fee, admin, paused)
+            }
+            NameRegistryInstruction::WithdrawNamespaceFees => {
+                Self::process_withdraw_namespace_fees(program_id, accounts)
+            }
+            NameRegistryInstruction::DelegateNamespaceAuthority { program_id: delegate_program } => {
+                Self::process_delegate_namespace_authority(program_id, accounts, delegate_program)
+            }
+            NameRegistryInstruction::RegisterWithVoucher { name, discount_bps, expiry, signature, proof } => {
+                Self::process_register_with_voucher(program_id, accounts, name, discount_bps, expiry, signature, proof)
+            }
+            NameRegistryInstruction::SetUsdPricing { price_feed, fee_schedule, max_price_age, max_confidence_bps } => {
+                Self::process_set_usd_pricing(
+                    program_id,
+                    accounts,
+                    price_feed,
+                    fee_schedule,
+                    max_price_age,
+                    max_confidence_bps,
+                )
+            }
+            NameRegistryInstruction::SetRenameFee { fee } => {
+                Self::process_set_rename_fee(program_id, accounts, fee)
+            }
+            NameRegistryInstruction::SetUpdateFee { fee } => {
+                Self::process_set_update_fee(program_id, accounts, fee)
+            }
+            NameRegistryInstruction::SetRegistryPages { enabled } => {
+                Self::process_set_registry_pages(program_id, accounts, enabled)
+            }
+            NameRegistryInstruction::FreezeName => {
+                Self::process_set_name_frozen(program_id, accounts, true)
+            }
+            NameRegistryInstruction::UnfreezeName => {
+                Self::process_set_name_frozen(program_id, accounts, false)
+            }
+            NameRegistryInstruction::ResolveTokenHolder => {
+                Self::process_resolve_token_holder(program_id, accounts)
+            }
+            NameRegistryInstruction::SetTombstonePeriod { seconds } => {
+                Self::process_set_tombstone_period(program_id, accounts, seconds)
+            }
+            NameRegistryInstruction::CloseTombstone => {
+                Self::process_close_tombstone(program_id, accounts)
+            }
+            NameRegistryInstruction::FinalizeBurnedName => {
+                Self::process_finalize_burned_name(program_id, accounts)
+            }
+            NameRegistryInstruction::RotateFeeVault => {
+                Self::process_rotate_fee_vault(program_id, accounts)
+            }
+            NameRegistryInstruction::GrantRecordWriter { kind, wallet } => {
+                Self::process_set_record_writer(program_id, accounts, kind, wallet, true)
+            }
+            NameRegistryInstruction::RevokeRecordWriter { kind, wallet } => {
+                Self::process_set_record_writer(program_id, accounts, kind, wallet, false)
+            }
+            NameRegistryInstruction::Extension { tag, payload } => {
+                process_extension(program_id, accounts, tag, &payload)
+            }
+        }
+    }
+
+    fn process_initialize(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        registration_fee: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let initializer = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let program_data = next_account_info(account_info_iter)?;
+        let config_link = next_account_info(account_info_iter)?;
+
+        if !initializer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Otherwise anyone could set themselves up as owner of a config of
+        // their own
+        validate_upgrade_authority(program_id, program_data, initializer.key)?;
+
+        let (expected_config, config_bump) = find_config_address(program_id);
+        if config_account.key != &expected_config {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(config_account)?;
+        if config_account.owner != program_id {
+            create_program_account(
+                program_id,
+                initializer,
+                config_account,
+                system_program,
+                ProgramConfig::LEN,
+                &[CONFIG_SEED, &[config_bump]],
+            )?;
+        }
+
+        let mut config = ProgramConfig::unpack_unchecked(&config_account.data.borrow())?;
+        if config.is_initialized {
+            return Err(NameRegistryError::AlreadyInitialized.into());
+        }
+        validate_rent_exempt(config_account)?;
+
+        // One config per program: the link exists once the first is set up
+        let (expected_link, link_bump) = ConfigLink::find_address(program_id);
+        if config_link.key != &expected_link {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if config_link.owner == program_id {
+            return Err(NameRegistryError::AlreadyInitialized.into());
+        }
+        assert_writable(config_link)?;
+        create_program_account(
+            program_id,
+            initializer,
+            config_link,
+            system_program,
+            ConfigLink::LEN,
+            &[CONFIG_LINK_SEED, &[link_bump]],
+        )?;
+        let link = ConfigLink { is_initialized: true, config: *config_account.key };
+        ConfigLink::pack_checked(link, &mut config_link.data.borrow_mut())?;
+
+        // Fees accrue in data-less PDAs so sweeping them never touches the
+        // lamports backing config state
+        for bucket in 0..FEE_BUCKETS {
+            let treasury_account = next_account_info(account_info_iter)?;
+            let (expected_treasury, bump) = find_treasury_address(config_account.key, bucket, program_id);
+            if treasury_account.key != &expected_treasury {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            assert_writable(treasury_account)?;
+            if treasury_account.owner != program_id {
+                create_program_account(
+                    program_id,
+                    initializer,
+                    treasury_account,
+                    system_program,
+                    0,
+                    &[TREASURY_SEED, config_account.key.as_ref(), &[bucket], &[bump]],
+                )?;
+            }
+        }
+
+        config.is_initialized = true;
+        config.owner = *initializer.key;
+        config.pending_owner = Pubkey::default();
+        config.fee_schedule = FeeSchedule::flat(registration_fee);
+        config.max_pending_operations = DEFAULT_MAX_PENDING_OPERATIONS;
+        config.cooldown_seconds = DEFAULT_COOLDOWN_PERIOD;
+        config.pending_update_ttl = DEFAULT_PENDING_UPDATE_TTL;
+        config.version = ProgramConfig::VERSION;
+
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_register_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        proof: Option<NameProof>,
+        registration: Registration,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let registrant = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let address_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
+
+        if !registrant.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // Verify system program
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        let unrevealed = matches!(
+            registration,
+            Registration::Direct | Registration::Sponsored { .. } | Registration::Voucher { .. }
+        );
+        if config.commit_reveal_required && unrevealed {
+            return Err(NameRegistryError::CommitRevealRequired.into());
+        }
+        let (owner, grant) = match registration {
+            Registration::Registrar { owner, role_account } => {
+                let grant = Self::unpack_role(program_id, config_account, role_account, Role::Registrar, registrant.key)?;
+                (owner, Some(grant))
+            }
+            Registration::Sponsored { owner } => (owner, None),
+            _ => (*registrant.key, None),
+        };
+        let discount_bps = match registration {
+            Registration::Voucher { discount_bps } => discount_bps,
+            _ => 0,
+        };
+        validate_registered_name(&config, &name)?;
+        Self::assert_unlisted(program_id, config_account, &config, account_info_iter, &name, proof.as_ref())?;
+        let claim_account = if config.enforce_unique_address {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+        let pricing_accounts = if config.usd_pricing_enabled {
+            Some((next_account_info(account_info_iter)?, next_account_info(account_info_iter)?))
+        } else {
+            None
+        };
+        let page_account = if config.registry_pages_enabled {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+        let tombstone_account = if config.tombstone_period > 0 {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+        let wallet_settings = next_account_info(account_info_iter).ok();
+        let referrer = next_account_info(account_info_iter).ok().filter(|referrer| referrer.key != program_id);
+        let receipt_account = next_account_info(account_info_iter).ok();
+
+        let (expected_name_account, bump) = find_name_address(&name, program_id);
+        if name_account.key != &expected_name_account {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if let Some(tombstone_account) = tombstone_account {
+            Self::assert_not_released(program_id, name_account, tombstone_account)?;
+        }
+        assert_writable(name_account)?;
+        if name_account.data_is_empty() {
+            let normalized = normalize_name(&name);
+            create_program_account(
+                program_id,
+                registrant,
+                name_account,
+                system_program,
+                NameAccount::LEN,
+                &[NAME_SEED, normalized.as_bytes(), &[bump]],
+            )?;
+        }
+        assert_owned_by(name_account, program_id)?;
+
+        // A name registered earlier in the same transaction is already
+        // initialized here, so a duplicate fails before touching any other
+        // account or counter
+        let mut name_data = NameAccount::unpack_unchecked(&name_account.data.borrow())?;
+        if name_data.is_initialized {
+            return Err(NameRegistryError::NameTaken.into());
+        }
+
+        if let Some(claim_account) = claim_account {
+            Self::take_address_claim(program_id, registrant, &owner, name_account, claim_account, system_program)?;
+        }
+
+        Self::create_address_account(program_id, registrant, name_account, address_account, system_program)?;
+
+        assert_owned_by(address_account, program_id)?;
+        assert_writable(address_account)?;
+        assert_writable(config_account)?;
+        let namespace = Self::unpack_fee_recipient(program_id, config_account, &config, &name, treasury_account)?;
+        if let Some(namespace) = &namespace {
+            Self::assert_namespace_authority(treasury_account, namespace, registrant)?;
+        }
+
+        validate_not_paused(&config)?;
+        Self::count_registration(&mut config)?;
+        let mut registration_fee = match &namespace {
+            Some(namespace) => namespace.fee,
+            None => Self::registration_fee(program_id, config_account, &config, &name, pricing_accounts)?,
+        };
+        if let Some(grant) = &grant {
+            registration_fee = grant.fee_share(registration_fee);
+        }
+        registration_fee -= (registration_fee as u128 * discount_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+
+        let address_data = Self::unpack_reverse_record(address_account)?;
+        if address_data.is_initialized {
+            return Err(NameRegistryError::NameAlreadyRegistered.into());
+        }
+        validate_rent_exempt(name_account)?;
+        validate_rent_exempt(address_account)?;
+        let private = Self::is_private_wallet(program_id, &owner, wallet_settings)?;
+
+        // The referrer's share comes out of the fee, not on top of it
+        let mut treasury_share = registration_fee;
+        if let Some(referrer) = referrer {
+            if referrer.key == registrant.key {
+                return Err(NameRegistryError::InvalidReferral.into());
+            }
+            assert_writable(referrer)?;
+            let payout = config.referral_share(registration_fee);
+            if payout > 0 {
+                invoke(
+                    &system_instruction::transfer(registrant.key, referrer.key, payout),
+                    &[registrant.clone(), referrer.clone()],
+                )?;
+                msg!("{}", formats::referral_event(&name, referrer.key, payout));
+            }
+            treasury_share -= payout;
+        }
+
+        // Transfer registration fee from registrant to the treasury
+        invoke(
+            &system_instruction::transfer(
+                registrant.key,
+                treasury_account.key,
+                treasury_share,
+            ),
+            &[registrant.clone(), treasury_account.clone()],
+        )?;
+        validate_rent_exempt(treasury_account)?;
+        log_event(formats::fee_event(&name, registration_fee));
+        if let Some(receipt_account) = receipt_account {
+            let receipt = ReceiptAccount {
+                is_initialized: true,
+                payer: *registrant.key,
+                name_account: *name_account.key,
+                operation: PaidOperation::Registration,
+                amount: registration_fee,
+                slot: compat::clock()?.slot,
+            };
+            Self::issue_receipt(program_id, registrant, receipt_account, system_program, receipt)?;
+        }
+
+        name_data.is_initialized = true;
+        name_data.owner = owner;
+        name_data.name = name.clone();
+        name_data.address = owner;
+        name_data.cooldown_until = compat::unix_timestamp()?;
+        name_data.registered_at = name_data.cooldown_until;
+
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        if config.analytics_enabled {
+            log_registration(&name);
+        }
+        if let Some(page_account) = page_account {
+            Self::append_to_registry_page(
+                program_id,
+                registrant,
+                config_account,
+                &mut config,
+                name_account.key,
+                page_account,
+                system_program,
+            )?;
+        }
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        // Private wallets own names without a public reverse record
+        let address_data = if private {
+            ReverseRecord::cleared()
+        } else {
+            ReverseRecord::new(owner, name)
+        };
+        ReverseRecord::pack_checked(address_data, &mut address_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_commit_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name_hash: [u8; 32],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let committer = next_account_info(account_info_iter)?;
+        let commitment_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !committer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let (expected_commitment, bump) = find_commitment_address(committer.key, &name_hash, program_id);
+        if commitment_account.key != &expected_commitment {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !commitment_account.data_is_empty() {
+            return Err(NameRegistryError::AlreadyInitialized.into());
+        }
+        create_program_account(
+            program_id,
+            committer,
+            commitment_account,
+            system_program,
+            NameCommitment::LEN,
+            &[COMMITMENT_SEED, committer.key.as_ref(), &name_hash, &[bump]],
+        )?;
+
+        let commitment = NameCommitment {
+            is_initialized: true,
+            committer: *committer.key,
+            committed_at: compat::unix_timestamp()?,
+        };
+        NameCommitment::pack_checked(commitment, &mut commitment_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_reveal_and_register(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        salt: [u8; 32],
+        proof: Option<NameProof>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let commitment_account = next_account_info(account_info_iter)?;
+        let registrant = next_account_info(account_info_iter)?;
+
+        if !registrant.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // The PDA is derived from the registrant, so only the committer's
+        // own commitment can match
+        let name_hash = name_commitment(&name, &salt, registrant.key);
+        if commitment_account.key != &find_commitment_address(registrant.key, &name_hash, program_id).0
+            || commitment_account.data_is_empty()
+        {
+            return Err(NameRegistryError::InvalidCommitment.into());
+        }
+        assert_owned_by(commitment_account, program_id)?;
+        assert_writable(commitment_account)?;
+        let commitment = NameCommitment::unpack(&commitment_account.data.borrow())?;
+        if compat::unix_timestamp()? < commitment.committed_at.saturating_add(MIN_COMMITMENT_AGE) {
+            return Err(NameRegistryError::CommitmentTooNew.into());
+        }
+
+        // Closed last: moving lamports before the registration's CPIs would
+        // leave them unbalanced
+        Self::process_register_name(program_id, &accounts[1..], name, proof, Registration::Revealed)?;
+        close_program_account(commitment_account, registrant)
+    }
+
+    fn process_set_commit_reveal_required(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        required: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.commit_reveal_required = required;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_request_address_update(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_address: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let current_owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let pending_update_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let wallet_settings = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let cosigner_account = next_account_info(account_info_iter)?;
+
+        if !current_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        validate_address(&new_address)?;
+        let config = Self::unpack_config(program_id, config_account)?;
+        validate_not_paused(&config)?;
+
+        assert_owned_by(name_account, program_id)?;
+        let (expected_pending_update, bump) = PendingUpdateAccount::find_address(name_account.key, program_id);
+        if pending_update_account.key != &expected_pending_update {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(pending_update_account)?;
+
+        let name_data = NameAccount::unpack(&name_account.data.borrow())?;
+        validate_owner(&name_data.owner, current_owner.key)?;
+        assert_not_tokenized(&name_data)?;
+        assert_not_frozen(&name_data)?;
+        Self::assert_cosigned(program_id, name_account, &name_data, cosigner_account, account_info_iter.as_slice())?;
+        // The first address assignment is part of setup and is not rate limited
+        if name_data.target_set {
+            assert_cooldown_over(&name_data)?;
+        }
+
+        if pending_update_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                current_owner,
+                pending_update_account,
+                system_program,
+                PendingUpdateAccount::LEN,
+                &[PENDING_UPDATE_SEED, name_account.key.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(pending_update_account, program_id)?;
+
+        let mut pending_update = PendingUpdateAccount::unpack_unchecked(&pending_update_account.data.borrow())?;
+        // Re-arming one's own update retargets it without opening another.
+        // An update armed by an earlier owner died with their ownership and
+        // is replaced
+        if !pending_update.is_initialized || pending_update.requester != *current_owner.key {
+            validate_rent_exempt(pending_update_account)?;
+            Self::open_pending_operation(program_id, &config, current_owner, wallet_settings, system_program)?;
+        }
+        pending_update.is_initialized = true;
+        pending_update.new_address = new_address;
+        pending_update.requester = *current_owner.key;
+        pending_update.name_account = *name_account.key;
+        pending_update.requested_at = compat::unix_timestamp()?;
+        pending_update.expires_at = match config.pending_update_ttl {
+            0 => 0,
+            ttl => pending_update.requested_at + ttl as i64,
+        };
+
+        PendingUpdateAccount::pack_checked(pending_update, &mut pending_update_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_complete_address_update(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let new_owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let address_account = next_account_info(account_info_iter)?;
+        let pending_update_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let requester_settings = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !new_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        validate_not_paused(&config)?;
+        let previous_owner = if config.enforce_unique_address {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+
+        assert_owned_by(name_account, program_id)?;
+        assert_owned_by(address_account, program_id)?;
+        assert_owned_by(pending_update_account, program_id)?;
+        if pending_update_account.key != &PendingUpdateAccount::find_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(name_account)?;
+        assert_writable(address_account)?;
+        assert_writable(pending_update_account)?;
+
+        let pending_update = PendingUpdateAccount::unpack(&pending_update_account.data.borrow())?;
+        if !pending_update.is_initialized || pending_update.name_account != *name_account.key {
+            return Err(NameRegistryError::NoPendingUpdate.into());
+        }
+
+        if pending_update.new_address != *new_owner.key {
+            return Err(NameRegistryError::NotPendingAddress.into());
+        }
+        if pending_update.expires_at != 0 && compat::unix_timestamp()? >= pending_update.expires_at {
+            return Err(NameRegistryError::PendingUpdateExpired.into());
+        }
+
+        let mut name_data = NameAccount::unpack(&name_account.data.borrow())?;
+        assert_not_tokenized(&name_data)?;
+        assert_not_frozen(&name_data)?;
+        // An update armed by an earlier owner died with their ownership
+        if pending_update.requester != name_data.owner {
+            return Err(NameRegistryError::NoPendingUpdate.into());
+        }
+        Self::unpack_reverse_record(address_account)?;
+        if let Some(previous_owner) = previous_owner {
+            validate_owner(&name_data.owner, previous_owner.key)?;
+            Self::move_address_claim(
+                program_id,
+                account_info_iter,
+                new_owner,
+                previous_owner,
+                new_owner.key,
+                name_account,
+                system_program,
+            )?;
+        }
+        let wallet_settings = next_account_info(account_info_iter).ok();
+
+        Self::assert_treasury(program_id, config_account, &config, fee_bucket(&name_data.name), treasury_account)?;
+        if config.update_fee > 0 {
+            invoke(
+                &system_instruction::transfer(new_owner.key, treasury_account.key, config.update_fee),
+                &[new_owner.clone(), treasury_account.clone()],
+            )?;
+            validate_rent_exempt(treasury_account)?;
+            log_event(formats::fee_event(&name_data.name, config.update_fee));
+        }
+
+        // The update and any transfer offer of the outgoing owner close here
+        Self::close_pending_operation(program_id, &name_data.owner, requester_settings)?;
+        if name_data.pending_owner != Pubkey::default() {
+            Self::close_pending_operation(program_id, &name_data.owner, requester_settings)?;
+            name_data.pending_owner = Pubkey::default();
+        }
+
+        // The reverse record follows the new owner's privacy preference
+        let address_data = if Self::is_private_wallet(program_id, new_owner.key, wallet_settings)? {
+            ReverseRecord::cleared()
+        } else {
+            ReverseRecord::new(*new_owner.key, name_data.name.clone())
+        };
+
+        name_data.address = *new_owner.key;
+        name_data.transfer_to(*new_owner.key);
+        if name_data.target_set {
+            name_data.cooldown_until = get_cooldown_until(&config)?;
+        }
+        name_data.target_set = true;
+
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+        ReverseRecord::pack_checked(address_data, &mut address_account.data.borrow_mut())?;
+
+        // Clear pending update
+        let cleared = PendingUpdateAccount::default();
+        PendingUpdateAccount::pack_checked(cleared, &mut pending_update_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_cancel_address_update(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        close: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let pending_update_account = next_account_info(account_info_iter)?;
+        let wallet_settings = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_owned_by(name_account, program_id)?;
+        assert_owned_by(pending_update_account, program_id)?;
+        if pending_update_account.key != &PendingUpdateAccount::find_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(pending_update_account)?;
+
+        let name_data = NameAccount::unpack(&name_account.data.borrow())?;
+        validate_owner(&name_data.owner, owner.key)?;
+
+        let pending_update = PendingUpdateAccount::unpack_unchecked(&pending_update_account.data.borrow())?;
+        if !pending_update.is_initialized || pending_update.requester != *owner.key {
+            return Err(NameRegistryError::NoPendingUpdate.into());
+        }
+        Self::close_pending_operation(program_id, owner.key, wallet_settings)?;
+
+        if close {
+            assert_writable(owner)?;
+            close_program_account(pending_update_account, owner)
+        } else {
+            let cleared = PendingUpdateAccount::default();
+            PendingUpdateAccount::pack_checked(cleared, &mut pending_update_account.data.borrow_mut())
+        }
+    }
+
+    fn process_rename_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_name: String,
+        proof: Option<NameProof>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let current_owner = next_account_info(account_info_iter)?;
+        let old_name_account = next_account_info(account_info_iter)?;
+        let new_name_account = next_account_info(account_info_iter)?;
+        let address_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let old_cosigner_account = next_account_info(account_info_iter)?;
+        let new_cosigner_account = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
+
+        if !current_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        validate_name_for(&config, &new_name)?;
+        validate_not_paused(&config)?;
+        Self::assert_unlisted(program_id, config_account, &config, account_info_iter, &new_name, proof.as_ref())?;
+
+        let (expected_name_account, bump) = find_name_address(&new_name, program_id);
+        if new_name_account.key != &expected_name_account {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(new_name_account)?;
+        if new_name_account.data_is_empty() {
+            let normalized = normalize_name(&new_name);
+            create_program_account(
+                program_id,
+                current_owner,
+                new_name_account,
+                system_program,
+                NameAccount::LEN,
+                &[NAME_SEED, normalized.as_bytes(), &[bump]],
+            )?;
+        }
+
+        assert_owned_by(old_name_account, program_id)?;
+        assert_owned_by(new_name_account, program_id)?;
+        assert_owned_by(address_account, program_id)?;
+        assert_writable(old_name_account)?;
+        assert_writable(new_name_account)?;
+        assert_writable(address_account)?;
+
+        let old_name_data = NameAccount::unpack(&old_name_account.data.borrow())?;
+        validate_owner(&old_name_data.owner, current_owner.key)?;
+        assert_not_tokenized(&old_name_data)?;
+        assert_not_frozen(&old_name_data)?;
+        // A namespaced name would otherwise leave its namespace for free
+        if old_name_data.is_subname() || old_name_data.is_namespaced() {
+            return Err(NameRegistryError::NotTopLevelName.into());
+        }
+        let old_cosigner = Self::assert_cosigned(
+            program_id,
+            old_name_account,
+            &old_name_data,
+            old_cosigner_account,
+            account_info_iter.as_slice(),
+        )?;
+        let cosigned = old_cosigner.as_ref().is_some_and(|cosigner| cosigner.guards(&old_name_data));
+        assert_cooldown_over(&old_name_data)?;
+
+        let new_name_data = NameAccount::unpack_unchecked(&new_name_account.data.borrow())?;
+        if new_name_data.is_initialized {
+            return Err(NameRegistryError::NameTaken.into());
+        }
+
+        Self::assert_treasury(program_id, config_account, &config, fee_bucket(&new_name), treasury_account)?;
+        if config.rename_fee > 0 {
+            invoke(
+                &system_instruction::transfer(current_owner.key, treasury_account.key, config.rename_fee),
+                &[current_owner.clone(), treasury_account.clone()],
+            )?;
+            validate_rent_exempt(treasury_account)?;
+            log_event(formats::fee_event(&new_name, config.rename_fee));
+        }
+
+        let mut address_data = Self::unpack_reverse_record(address_account)?;
+
+        // Update new name account
+        let new_name_data = NameAccount {
+            is_initialized: true,
+            owner: *current_owner.key,
+            name: new_name.clone(),
+            address: old_name_data.address,
+            cooldown_until: compat::unix_timestamp()?,
+            target_set: old_name_data.target_set,
+            parent: Pubkey::default(),
+            // An open transfer offer moves with the name
+            pending_owner: old_name_data.pending_owner,
+            wildcard_subdomains: old_name_data.wildcard_subdomains,
+            tokenized: false,
+            registered_at: old_name_data.registered_at,
+            transfer_count: old_name_data.transfer_count,
+            resolve_count: old_name_data.resolve_count,
+            // So does the record of its last freeze
+            frozen: false,
+            frozen_by: old_name_data.frozen_by,
+            frozen_at: old_name_data.frozen_at,
+        };
+
+        // Update the reverse record, unless the owner keeps none public
+        if address_data.is_initialized {
+            address_data.name = new_name;
+        }
+
+        // Clear old name account
+        let mut old_name_data = NameAccount::unpack(&old_name_account.data.borrow())?;
+        old_name_data.is_initialized = false;
+        old_name_data.owner = Pubkey::default();
+        old_name_data.name = String::new();
+        old_name_data.address = Pubkey::default();
+        old_name_data.cooldown_until = 0;
+        old_name_data.target_set = false;
+        old_name_data.pending_owner = Pubkey::default();
+        old_name_data.registered_at = 0;
+        old_name_data.transfer_count = 0;
+        old_name_data.resolve_count = 0;
+        old_name_data.frozen_by = Pubkey::default();
+        old_name_data.frozen_at = 0;
+
+        NameAccount::pack_checked(new_name_data, &mut new_name_account.data.borrow_mut())?;
+        ReverseRecord::pack_checked(address_data, &mut address_account.data.borrow_mut())?;
+        NameAccount::pack_checked(old_name_data, &mut old_name_account.data.borrow_mut())?;
+
+        // A co-signer moves with the name; one set by an earlier owner is
+        // dropped rather than revived should they own the old name again
+        let (expected_new_cosigner, cosigner_bump) = NameCosigner::find_address(new_name_account.key, program_id);
+        if new_cosigner_account.key != &expected_new_cosigner {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let Some(mut cosigner) = old_cosigner else {
+            return Ok(());
+        };
+        if cosigned {
+            assert_writable(new_cosigner_account)?;
+            if new_cosigner_account.owner != program_id {
+                create_program_account(
+                    program_id,
+                    current_owner,
+                    new_cosigner_account,
+                    system_program,
+                    NameCosigner::LEN,
+                    &[COSIGNER_SEED, new_name_account.key.as_ref(), &[cosigner_bump]],
+                )?;
+            }
+            cosigner.name_account = *new_name_account.key;
+            NameCosigner::pack_checked(cosigner, &mut new_cosigner_account.data.borrow_mut())?;
+        }
+        assert_writable(old_cosigner_account)?;
+        close_program_account(old_cosigner_account, current_owner)
+    }
+
+    fn process_set_registration_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_fee: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+
+        let action = AdminAction::SetFeeSchedule(FeeSchedule::flat(new_fee));
+        Self::queue_or_apply_admin_action(program_id, config_account, config, owner, timelock_accounts, action)
+    }
+
+    fn process_set_rename_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        fee: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+
+        let action = AdminAction::SetRenameFee(fee);
+        Self::queue_or_apply_admin_action(program_id, config_account, config, owner, timelock_accounts, action)
+    }
+
+    fn process_set_update_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        fee: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+
+        let action = AdminAction::SetUpdateFee(fee);
+        Self::queue_or_apply_admin_action(program_id, config_account, config, owner, timelock_accounts, action)
+    }
+
+    fn process_schedule_promo(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        start: i64,
+        end: i64,
+        fee_override_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+        validate_promo(start, end, fee_override_bps)?;
+
+        let action = AdminAction::SchedulePromo { start, end, fee_override_bps };
+        Self::queue_or_apply_admin_action(program_id, config_account, config, owner, timelock_accounts, action)
+    }
+
+    fn process_set_analytics(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.analytics_enabled = enabled;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_unicode_names(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.allow_unicode_names = enabled;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_max_pending_operations(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        max_pending_operations: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.max_pending_operations = max_pending_operations;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_referral_bps(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        referral_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+        validate_referral_bps(referral_bps)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+
+        let action = AdminAction::SetReferralBps(referral_bps);
+        Self::queue_or_apply_admin_action(program_id, config_account, config, owner, timelock_accounts, action)
+    }
+
+    fn process_set_max_total_names(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        max_total_names: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.max_total_names = max_total_names;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_cooldown(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        seconds: u32,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+        validate_cooldown_period(seconds)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.cooldown_seconds = seconds;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_enforce_unique_address(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.enforce_unique_address = enabled;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_registry_pages(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.registry_pages_enabled = enabled;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_name_frozen(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        frozen: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        let mut name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        match (name_data.frozen, frozen) {
+            (true, true) => return Err(NameRegistryError::NameFrozen.into()),
+            (false, false) => return Err(NameRegistryError::NameNotFrozen.into()),
+            _ => {}
+        }
+
+        // The last freeze stays on record after it is lifted
+        name_data.frozen = frozen;
+        if frozen {
+            name_data.frozen_by = *owner.key;
+            name_data.frozen_at = compat::unix_timestamp()?;
+        }
+        log_event(formats::freeze_event(&name_data.name, frozen, owner.key));
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_pending_update_ttl(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        seconds: u32,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.pending_update_ttl = seconds;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_tombstone_period(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        seconds: u32,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.tombstone_period = seconds;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_release_address_claim(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let claim_account = next_account_info(account_info_iter)?;
+        let wallet = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+
+        if claim_account.key != &AddressClaim::find_address(wallet.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(claim_account, program_id)?;
+        assert_writable(claim_account)?;
+        assert_writable(wallet)?;
+        let claim = AddressClaim::unpack(&claim_account.data.borrow())?;
+        if name_account.key != &claim.name_account {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // The claim stands while the name exists and the wallet owns it
+        let held = name_account.owner == program_id
+            && NameAccount::unpack_unchecked(&name_account.data.borrow())
+                .is_ok_and(|name_data| name_data.is_initialized && name_data.owner == *wallet.key);
+        if held {
+            return Err(NameRegistryError::NameAlreadyRegistered.into());
+        }
+
+        close_program_account(claim_account, wallet)
+    }
+
+    fn process_approve_operator(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        operator: Pubkey,
+        scope: OperatorScope,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let operator_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        validate_address(&operator)?;
+        if operator == *owner.key {
+            return Err(NameRegistryError::InvalidAddress.into());
+        }
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        validate_owner(&name_data.owner, owner.key)?;
+
+        let (expected_operator, bump) = NameOperator::find_address(name_account.key, scope, &operator, program_id);
+        if operator_account.key != &expected_operator {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(operator_account)?;
+        if operator_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                owner,
+                operator_account,
+                system_program,
+                NameOperator::LEN,
+                &[OPERATOR_SEED, name_account.key.as_ref(), &[scope as u8], operator.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(operator_account, program_id)?;
+
+        // Re-approving after a change of hands renews the approval for the
+        // new owner
+        let approval = NameOperator {
+            is_initialized: true,
+            name_account: *name_account.key,
+            owner: *owner.key,
+            operator,
+            scope,
+        };
+        NameOperator::pack_checked(approval, &mut operator_account.data.borrow_mut())
+    }
+
+    fn process_revoke_operator(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        operator: Pubkey,
+        scope: OperatorScope,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let operator_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        validate_owner(&name_data.owner, owner.key)?;
+
+        if operator_account.key != &NameOperator::find_address(name_account.key, scope, &operator, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(operator_account, program_id)?;
+        assert_writable(operator_account)?;
+        assert_writable(owner)?;
+        NameOperator::unpack(&operator_account.data.borrow())?;
+        close_program_account(operator_account, owner)
+    }
+
+    fn process_create_namespace(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        suffix: String,
+        fee: u64,
+        admin: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let namespace_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+        validate_name_for(&config, &suffix)?;
+        validate_address(&admin)?;
+
+        let (expected_name_account, name_bump) = find_name_address(&suffix, program_id);
+        if name_account.key != &expected_name_account {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let (expected_namespace, namespace_bump) = NamespaceAccount::find_address(name_account.key, program_id);
+        if namespace_account.key != &expected_namespace {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(name_account)?;
+        assert_writable(namespace_account)?;
+
+        if !namespace_account.data_is_empty() {
+            return Err(NameRegistryError::AlreadyInitialized.into());
+        }
+        create_program_account(
+            program_id,
+            owner,
+            namespace_account,
+            system_program,
+            NamespaceAccount::LEN,
+            &[NAMESPACE_SEED, name_account.key.as_ref(), &[namespace_bump]],
+        )?;
+        if name_account.data_is_empty() {
+            let normalized = normalize_name(&suffix);
+            create_program_account(
+                program_id,
+                owner,
+                name_account,
+                system_program,
+                NameAccount::LEN,
+                &[NAME_SEED, normalized.as_bytes(), &[name_bump]],
+            )?;
+        }
+        assert_owned_by(name_account, program_id)?;
+
+        let mut name_data = NameAccount::unpack_unchecked(&name_account.data.borrow())?;
+        if name_data.is_initialized {
+            return Err(NameRegistryError::NameTaken.into());
+        }
+
+        // The namespace holds its suffix, as a reservation does, so nobody
+        // can register it or hang subnames off it that shadow its names
+        name_data.is_initialized = true;
+        name_data.owner = *namespace_account.key;
+        name_data.name = suffix;
+        name_data.address = *namespace_account.key;
+        name_data.cooldown_until = compat::unix_timestamp()?;
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        let namespace = NamespaceAccount {
+            is_initialized: true,
+            config: *config_account.key,
+            name_account: *name_account.key,
+            admin,
+            fee,
+            paused: false,
+            delegate_program: Pubkey::default(),
+        };
+        NamespaceAccount::pack_checked(namespace, &mut namespace_account.data.borrow_mut())
+    }
+
+    fn process_set_namespace_policy(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        fee: u64,
+        admin: Pubkey,
+        paused: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let current_admin = next_account_info(account_info_iter)?;
+        let namespace_account = next_account_info(account_info_iter)?;
+
+        let mut namespace = Self::unpack_namespace(program_id, namespace_account, current_admin)?;
+        validate_address(&admin)?;
+
+        namespace.fee = fee;
+        namespace.admin = admin;
+        namespace.paused = paused;
+        NamespaceAccount::pack_checked(namespace, &mut namespace_account.data.borrow_mut())
+    }
+
+    fn process_withdraw_namespace_fees(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin = next_account_info(account_info_iter)?;
+        let namespace_account = next_account_info(account_info_iter)?;
+
+        Self::unpack_namespace(program_id, namespace_account, admin)?;
+        assert_writable(admin)?;
+
+        let rent_exempt_minimum = compat::rent()?.minimum_balance(namespace_account.data_len());
+        let withdrawable = namespace_account.lamports().saturating_sub(rent_exempt_minimum);
+        if withdrawable == 0 {
+            return Err(NameRegistryError::NothingToWithdraw.into());
+        }
+        **namespace_account.lamports.borrow_mut() -= withdrawable;
+        **admin.lamports.borrow_mut() = admin.lamports().checked_add(withdrawable)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    fn process_delegate_namespace_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        delegate_program: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let admin = next_account_info(account_info_iter)?;
+        let namespace_account = next_account_info(account_info_iter)?;
+
+        let mut namespace = Self::unpack_namespace(program_id, namespace_account, admin)?;
+        // The registry never signs for a PDA of its own
+        if delegate_program == *program_id {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        namespace.delegate_program = delegate_program;
+        NamespaceAccount::pack_checked(namespace, &mut namespace_account.data.borrow_mut())
+    }
+
+    fn process_set_usd_pricing(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        price_feed: Pubkey,
+        fee_schedule: FeeSchedule,
+        max_price_age: u32,
+        max_confidence_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let pricing_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        assert_writable(config_account)?;
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+        // Fee changes wait out the timelock as queued admin actions, which
+        // have no room for a pricing
+        if config.admin_timelock != 0 {
+            return Err(NameRegistryError::TimelockActive.into());
+        }
+
+        let (expected_pricing, bump) = UsdPricing::find_address(config_account.key, program_id);
+        if pricing_account.key != &expected_pricing {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(pricing_account)?;
+        if pricing_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                owner,
+                pricing_account,
+                system_program,
+                UsdPricing::LEN,
+                &[USD_PRICING_SEED, config_account.key.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(pricing_account, program_id)?;
+
+        let pricing = UsdPricing {
+            is_initialized: true,
+            config: *config_account.key,
+            price_feed,
+            fee_schedule,
+            max_price_age,
+            max_confidence_bps,
+        };
+        UsdPricing::pack_checked(pricing, &mut pricing_account.data.borrow_mut())?;
+
+        config.usd_pricing_enabled = price_feed != Pubkey::default();
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())
+    }
+
+    fn process_register_with_voucher(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        discount_bps: u16,
+        expiry: i64,
+        signature: [u8; 64],
+        proof: Option<NameProof>,
+    ) -> ProgramResult {
+        let [instructions_sysvar, registrant, _, _, config_account, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Namespaced names pay their namespace, whose fee is not the
+        // program owner's to waive
+        if discount_bps > BPS_DENOMINATOR || name.contains('.') {
+            return Err(NameRegistryError::InvalidVoucher.into());
+        }
+        if compat::unix_timestamp()? > expiry {
+            return Err(NameRegistryError::VoucherExpired.into());
+        }
+        let config = Self::unpack_config(program_id, config_account)?;
+        let message = voucher::voucher_message(config_account.key, registrant.key, &name, discount_bps, expiry);
+        voucher::check_verified(instructions_sysvar, &config.owner, &message, &signature)?;
+
+        Self::process_register_name(program_id, &accounts[1..], name, proof, Registration::Voucher { discount_bps })
+    }
+
+    fn process_set_paused(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        paused: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        config.paused = paused;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_fee_schedule(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        schedule: FeeSchedule,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+
+        let action = AdminAction::SetFeeSchedule(schedule);
+        Self::queue_or_apply_admin_action(program_id, config_account, config, owner, timelock_accounts, action)
+    }
+
+    fn process_change_program_owner(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_owner: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let current_owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !current_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        validate_address(&new_owner)?;
+
+        assert_writable(config_account)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, current_owner, remaining)?;
+
+        let action = AdminAction::ChangeProgramOwner(new_owner);
+        Self::queue_or_apply_admin_action(program_id, config_account, config, current_owner, timelock_accounts, action)
+    }
+
+    fn process_accept_program_ownership(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let pending_owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !pending_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        if config.pending_owner != *pending_owner.key {
+            return Err(NameRegistryError::NotPendingContractOwner.into());
+        }
+
+        config.owner = *pending_owner.key;
+        config.pending_owner = Pubkey::default();
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_resolve_address(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let name_data = Self::resolve_name(program_id, accounts)?;
+
+        // Return the address through program return data. Return data is
+        // set by the innermost program, so CPI callers read it back with
+        // `get_return_data` right after the invoke.
+        let return_data = formats::encode_address(&NameAccount::address_from_slice(&name_data)?);
+        compat::set_return_data(&return_data);
+        drop(name_data);
+
+        // Passing the name writable opts into counting the resolution
+        let name_account = &accounts[0];
+        if name_account.is_writable {
+            let mut name_data = NameAccount::unpack(&name_account.data.borrow())?;
+            name_data.resolve_count = name_data.resolve_count.saturating_add(1);
+            NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+        }
+
+        Ok(())
+    }
+
+    fn process_resolve_details(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        // Frozen names answer too, flagged, so callers refuse them in one call
+        let (name_data, frozen) = Self::borrow_name_chain_state(program_id, accounts)?;
+        let address = NameAccount::address_from_slice(&name_data)?;
+        log_resolved(NameAccount::name_from_slice(&name_data)?, &address);
+
+        let return_data = formats::encode_resolve_result(&ResolveResult {
+            version: RESOLVE_RESULT_VERSION,
+            address,
+            owner: NameAccount::owner_from_slice(&name_data)?,
+            expires_at: NEVER_EXPIRES,
+            frozen,
+        });
+        compat::set_return_data(&return_data);
+
+        Ok(())
+    }
+
+    /// Borrows the name a resolve instruction asks for, checking the chain
+    /// of parents of a subname, and logs the resolution unless invoked
+    /// through CPI. Resolving is what CPI callers pay for, so its handlers
+    /// read the few fields they need in place instead of unpacking.
+    fn resolve_name<'a, 'b>(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Ref<'a, &'b mut [u8]>, ProgramError> {
+        let name_data = Self::borrow_name_chain(program_id, accounts)?;
+        log_resolved(NameAccount::name_from_slice(&name_data)?, &NameAccount::address_from_slice(&name_data)?);
+        Ok(name_data)
+    }
+
+    /// Borrows the name in the first of `accounts`, requiring the rest to
+    /// hold its whole chain of parents, nearest first, and none of them to be
+    /// frozen.
+    fn borrow_name_chain<'a, 'b>(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<Ref<'a, &'b mut [u8]>, ProgramError> {
+        let (name_data, frozen) = Self::borrow_name_chain_state(program_id, accounts)?;
+        if frozen {
+            return Err(NameRegistryError::NameFrozen.into());
+        }
+        Ok(name_data)
+    }
+
+    /// Like `borrow_name_chain`, but returns whether the name or one of its
+    /// parents is frozen instead of failing.
+    fn borrow_name_chain_state<'a, 'b>(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<(Ref<'a, &'b mut [u8]>, bool), ProgramError> {
+        let account_info_iter = &mut accounts.iter();
+        let name_account = next_account_info(account_info_iter)?;
+
+        // A name unregistered lately may have left a tombstone, passed second
+        if name_account.data_is_empty() {
+            let tombstone = NameTombstone::find_address(name_account.key, program_id).0;
+            if let Some(tombstone_account) = accounts.get(1).filter(|account| account.key == &tombstone) {
+                Self::assert_not_released(program_id, name_account, tombstone_account)?;
+            }
+        }
+
+        // Only the PDA of the name it claims may answer for that name
+        let name_data = Self::borrow_derived_name(program_id, name_account)?;
+        let mut frozen = NameAccount::frozen_from_slice(&name_data)?;
+
+        // A subname only resolves while its whole chain of parents exists,
+        // and is frozen along with any of them
+        let mut parent = NameAccount::parent_from_slice(&name_data)?;
+        while parent != Pubkey::default() {
+            let parent_account = next_account_info(account_info_iter)?;
+            if parent_account.key != &parent {
+                return Err(NameRegistryError::InvalidParent.into());
+            }
+            let parent_data = Self::borrow_derived_name(program_id, parent_account)?;
+            frozen |= NameAccount::frozen_from_slice(&parent_data)?;
+            parent = NameAccount::parent_from_slice(&parent_data)?;
+        }
+
+        Ok((name_data, frozen))
+    }
+
+    /// Fails with `NameRecentlyReleased` while the tombstone PDA of
+    /// `name_account`, `tombstone_account`, holds a live tombstone.
+    fn assert_not_released(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+        tombstone_account: &AccountInfo,
+    ) -> ProgramResult {
+        if tombstone_account.key != &NameTombstone::find_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if tombstone_account.owner != program_id {
+            return Ok(());
+        }
+        let tombstone = NameTombstone::unpack_unchecked(&tombstone_account.data.borrow())?;
+        if tombstone.is_initialized && compat::unix_timestamp()? < tombstone.expires_at {
+            return Err(NameRegistryError::NameRecentlyReleased.into());
+        }
+        Ok(())
+    }
+
+    fn process_resolve_subname(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        label: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let subname_account = next_account_info(account_info_iter)?;
+        let parent_account = next_account_info(account_info_iter)?;
+
+        let parent_data = Self::unpack_derived_name(program_id, parent_account)?;
+        let full_name = subname(&label, &parent_data.name)?;
+        if subname_account.key != &find_name_address(&full_name, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let exists = subname_account.owner == program_id
+            && NameAccount::is_initialized_from_slice(&subname_account.data.borrow()).unwrap_or(false);
+        let address = if exists {
+            NameAccount::address_from_slice(&Self::borrow_name_chain(program_id, accounts)?)?
+        } else if parent_data.wildcard_subdomains {
+            // The parent itself must still resolve
+            NameAccount::address_from_slice(&Self::borrow_name_chain(program_id, &accounts[1..])?)?
+        } else {
+            return Err(NameRegistryError::NameNotFound.into());
+        };
+        log_resolved(&full_name, &address);
+
+        let return_data = formats::encode_address(&address);
+        compat::set_return_data(&return_data);
+
+        Ok(())
+    }
+
+    fn process_get_contract_owner(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let config_account = next_account_info(account_info_iter)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let return_data = formats::encode_address(&config.owner);
+        compat::set_return_data(&return_data);
+
+        Ok(())
+    }
+
+    fn process_get_registration_fee(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let config_account = next_account_info(account_info_iter)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let return_data = formats::encode_fee(
+            config.apply_promo(config.fee_schedule.five_plus_char, compat::unix_timestamp()?),
+        );
+        compat::set_return_data(&return_data);
+
+        Ok(())
+    }
+
+    fn process_get_pending_contract_owner(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let config_account = next_account_info(account_info_iter)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let return_data = formats::encode_address(&config.pending_owner);
+        compat::set_return_data(&return_data);
+
+        Ok(())
+    }
+
+    fn process_get_cooldown_bounds() -> ProgramResult {
+        let return_data = formats::encode_cooldown_bounds(MIN_COOLDOWN_PERIOD, MAX_COOLDOWN_PERIOD);
+        compat::set_return_data(&return_data);
+
+        Ok(())
+    }
+
+    fn process_withdraw(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: Option<u64>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let destination = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(destination)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let treasuries = (0..FEE_BUCKETS)
+            .map(|_| next_account_info(account_info_iter))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        // Drain buckets in order until `amount` is met, leaving each rent-exempt
+        let rent = compat::rent()?;
+        let mut remaining = amount.unwrap_or(u64::MAX);
+        let mut withdrawn: u64 = 0;
+        for (bucket, treasury_account) in (0..FEE_BUCKETS).zip(treasuries) {
+            Self::assert_treasury(program_id, config_account, &config, bucket, treasury_account)?;
+
+            let rent_exempt_minimum = rent.minimum_balance(treasury_account.data_len());
+            let withdrawable = treasury_account.lamports()
+                .saturating_sub(rent_exempt_minimum)
+                .min(remaining);
+            **treasury_account.lamports.borrow_mut() -= withdrawable;
+            remaining -= withdrawable;
+            withdrawn = withdrawn.checked_add(withdrawable)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            validate_rent_exempt(treasury_account)?;
+        }
+        if withdrawn == 0 {
+            return Err(NameRegistryError::NothingToWithdraw.into());
+        }
+        if amount.is_some() && remaining > 0 {
+            return Err(NameRegistryError::WithdrawExceedsBalance.into());
+        }
+
+        **destination.lamports.borrow_mut() = destination.lamports().checked_add(withdrawn)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        Ok(())
+    }
+
+    fn process_set_privacy_mode(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let wallet = next_account_info(account_info_iter)?;
+        let settings_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut settings = Self::load_wallet_settings(program_id, wallet, settings_account, system_program)?;
+        settings.privacy_mode = enabled;
+        WalletSettings::pack_checked(settings, &mut settings_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_banner_message(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        message: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let banner_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+        validate_banner_message(&message)?;
+
+        assert_writable(banner_account)?;
+
+        let (expected_banner, bump) = BannerMessage::find_address(config_account.key, program_id);
+        if banner_account.key != &expected_banner {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if banner_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                owner,
+                banner_account,
+                system_program,
+                BannerMessage::LEN,
+                &[BANNER_SEED, config_account.key.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(banner_account, program_id)?;
+
+        let banner = BannerMessage {
+            is_initialized: true,
+            message,
+        };
+        BannerMessage::pack_checked(banner, &mut banner_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_register_subname(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        label: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let parent_owner = next_account_info(account_info_iter)?;
+        let parent_account = next_account_info(account_info_iter)?;
+        let subname_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !parent_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Self::assert_not_paused(program_id, config_account)?;
+
+        let parent_data = Self::unpack_derived_name(program_id, parent_account)?;
+        validate_owner(&parent_data.owner, parent_owner.key)?;
+        let full_name = subname(&label, &parent_data.name)?;
+
+        let (expected_subname, bump) = find_name_address(&full_name, program_id);
+        if subname_account.key != &expected_subname {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(subname_account)?;
+        if subname_account.data_is_empty() {
+            let normalized = normalize_name(&full_name);
+            create_program_account(
+                program_id,
+                parent_owner,
+                subname_account,
+                system_program,
+                NameAccount::LEN,
+                &[NAME_SEED, normalized.as_bytes(), &[bump]],
+            )?;
+        }
+        assert_owned_by(subname_account, program_id)?;
+
+        let subname_data = NameAccount::unpack_unchecked(&subname_account.data.borrow())?;
+        if subname_data.is_initialized {
+            return Err(NameRegistryError::NameTaken.into());
+        }
+
+        let now = compat::unix_timestamp()?;
+        let subname_data = NameAccount {
+            is_initialized: true,
+            owner: *parent_owner.key,
+            name: full_name,
+            address: *parent_owner.key,
+            cooldown_until: now,
+            target_set: false,
+            parent: *parent_account.key,
+            pending_owner: Pubkey::default(),
+            wildcard_subdomains: false,
+            tokenized: false,
+            registered_at: now,
+            transfer_count: 0,
+            resolve_count: 0,
+            frozen: false,
+            frozen_by: Pubkey::default(),
+            frozen_at: 0,
+        };
+        NameAccount::pack_checked(subname_data, &mut subname_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_revoke_subname(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let parent_owner = next_account_info(account_info_iter)?;
+        let parent_account = next_account_info(account_info_iter)?;
+        let subname_account = next_account_info(account_info_iter)?;
+        let owner_settings = next_account_info(account_info_iter).ok();
+
+        if !parent_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let parent_data = Self::unpack_derived_name(program_id, parent_account)?;
+        validate_owner(&parent_data.owner, parent_owner.key)?;
+
+        assert_writable(subname_account)?;
+        assert_writable(parent_owner)?;
+        let subname_data = Self::unpack_derived_name(program_id, subname_account)?;
+        if subname_data.parent != *parent_account.key {
+            return Err(NameRegistryError::InvalidParent.into());
+        }
+        if subname_data.pending_owner != Pubkey::default() {
+            let owner_settings = owner_settings.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            Self::close_pending_operation(program_id, &subname_data.owner, owner_settings)?;
+        }
+
+        // Refund the subname's rent to the parent owner
+        close_program_account(subname_account, parent_owner)?;
+
+        Ok(())
+    }
+
+    fn process_set_record(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: String,
+        value: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let signer = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let record_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let index_account = next_account_info(account_info_iter)?;
+        let operator_account = next_account_info(account_info_iter).ok();
+
+        if !signer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        validate_record(&kind, &value)?;
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        // Writers the owner granted pass no operator PDA
+        if name_data.owner != *signer.key && operator_account.is_none() {
+            Self::assert_record_writer(program_id, name_account, &name_data, record_account, &kind, signer.key)?;
+        } else {
+            Self::assert_owner_or_operator(
+                program_id,
+                name_account,
+                &name_data,
+                signer.key,
+                operator_account,
+                OperatorScope::Records,
+            )?;
+        }
+
+        let record = RecordAccount {
+            is_initialized: true,
+            name_account: *name_account.key,
+            kind,
+            value,
+            ..RecordAccount::default()
+        };
+        Self::write_record(program_id, signer, name_account, record_account, index_account, system_program, record)
+    }
+
+    fn process_set_record_writer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: String,
+        wallet: Pubkey,
+        granted: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let record_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        validate_owner(&name_data.owner, owner.key)?;
+        let mut record = Self::unpack_record(program_id, name_account, record_account, &kind)?;
+        assert_writable(record_account)?;
+
+        // Grants of an earlier owner lapsed with the name
+        if record.writers_granted_by != name_data.owner {
+            record.writers_granted_by = name_data.owner;
+            record.writers.clear();
+        }
+        record.writers.retain(|writer| writer != &wallet);
+        if granted {
+            if record.writers.len() >= MAX_RECORD_WRITERS {
+                return Err(NameRegistryError::TooManyRecordWriters.into());
+            }
+            record.writers.push(wallet);
+        }
+        RecordAccount::pack_checked(record, &mut record_account.data.borrow_mut())
+    }
+
+    fn process_delete_record(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        kind: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let signer = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let record_account = next_account_info(account_info_iter)?;
+        let index_account = next_account_info(account_info_iter)?;
+        let operator_account = next_account_info(account_info_iter).ok();
+
+        if !signer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        Self::assert_owner_or_operator(
+            program_id,
+            name_account,
+            &name_data,
+            signer.key,
+            operator_account,
+            OperatorScope::Records,
+        )?;
+
+        Self::unpack_record(program_id, name_account, record_account, &kind)?;
+        assert_writable(record_account)?;
+        assert_writable(signer)?;
+
+        if index_account.key != &find_record_index_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(index_account, program_id)?;
+        assert_writable(index_account)?;
+        let mut index = RecordIndex::unpack(&index_account.data.borrow())?;
+        index.records.retain(|record| record != record_account.key);
+
+        close_program_account(record_account, signer)?;
+        if index.records.is_empty() {
+            close_program_account(index_account, signer)
+        } else {
+            RecordIndex::pack_checked(index, &mut index_account.data.borrow_mut())
+        }
+    }
+
+    /// Deletes every record of the name in `name_account`, refunding their
+    /// rent to `destination`. `accounts` are its record index PDA followed by
+    /// all the records in the index; a name without records has no index.
+    fn wipe_records(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+        accounts: &[AccountInfo],
+        destination: &AccountInfo,
+    ) -> ProgramResult {
+        let (index_account, record_accounts) = accounts.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if index_account.key != &find_record_index_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if index_account.data_is_empty() {
+            return Ok(());
+        }
+        assert_owned_by(index_account, program_id)?;
+        assert_writable(index_account)?;
+        let index = RecordIndex::unpack(&index_account.data.borrow())?;
+
+        for record in &index.records {
+            let record_account = record_accounts
+                .iter()
+                .find(|account| account.key == record)
+                .ok_or(NameRegistryError::NameHasRecords)?;
+            assert_owned_by(record_account, program_id)?;
+            assert_writable(record_account)?;
+            if RecordAccount::unpack(&record_account.data.borrow())?.name_account != *name_account.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            close_program_account(record_account, destination)?;
+        }
+        close_program_account(index_account, destination)
+    }
+
+    fn process_list_record_keys(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let name_account = next_account_info(account_info_iter)?;
+        let index_account = next_account_info(account_info_iter)?;
+
+        Self::unpack_derived_name(program_id, name_account)?;
+        if index_account.key != &find_record_index_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        // A name without records has no index
+        let records = if index_account.data_is_empty() {
+            Vec::new()
+        } else {
+            assert_owned_by(index_account, program_id)?;
+            RecordIndex::unpack(&index_account.data.borrow())?.records
+        };
+        compat::set_return_data(&formats::encode_record_keys(&records));
+
+        Ok(())
+    }
+
+    fn process_set_note(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        data: Vec<u8>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let note_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        validate_note(&data)?;
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        validate_owner(&name_data.owner, owner.key)?;
+
+        assert_writable(note_account)?;
+        let (expected_note, bump) = find_note_address(name_account.key, owner.key, program_id);
+        if note_account.key != &expected_note {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if note_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                owner,
+                note_account,
+                system_program,
+                NoteAccount::LEN,
+                &[NOTE_SEED, name_account.key.as_ref(), owner.key.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(note_account, program_id)?;
+
+        let note = NoteAccount {
+            is_initialized: true,
+            name_account: *name_account.key,
+            owner: *owner.key,
+            data,
+        };
+        NoteAccount::pack_checked(note, &mut note_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_delete_note(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let note_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // The PDA is derived from the signer, so nobody else's note matches
+        if note_account.key != &find_note_address(name_account.key, owner.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(note_account, program_id)?;
+        assert_writable(note_account)?;
+        assert_writable(owner)?;
+        NoteAccount::unpack(&note_account.data.borrow())?;
+
+        close_program_account(note_account, owner)
+    }
+
+    fn process_transfer_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_owner: Pubkey,
+        require_acceptance: bool,
+        wipe_records: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let current_owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let wallet_settings = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let cosigner_account = next_account_info(account_info_iter)?;
+
+        if !current_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        validate_address(&new_owner)?;
+        let config = Self::unpack_config(program_id, config_account)?;
+        validate_not_paused(&config)?;
+
+        let mut name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        validate_owner(&name_data.owner, current_owner.key)?;
+        assert_not_tokenized(&name_data)?;
+        assert_not_frozen(&name_data)?;
+        if config.enforce_unique_address && !require_acceptance {
+            Self::move_address_claim(
+                program_id,
+                account_info_iter,
+                current_owner,
+                current_owner,
+                &new_owner,
+                name_account,
+                system_program,
+            )?;
+        }
+        let remaining = account_info_iter.as_slice();
+        Self::assert_cosigned(program_id, name_account, &name_data, cosigner_account, remaining)?;
+        assert_cooldown_over(&name_data)?;
+        if wipe_records {
+            // The co-signer, if any, comes before the records
+            let index = find_record_index_address(name_account.key, program_id).0;
+            let start = remaining
+                .iter()
+                .position(|account| account.key == &index)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            Self::wipe_records(program_id, name_account, &remaining[start..], current_owner)?;
+        }
+
+        let offer_open = name_data.pending_owner != Pubkey::default();
+        if require_acceptance {
+            // Replacing an open offer keeps the same pending operation
+            if !offer_open {
+                Self::open_pending_operation(program_id, &config, current_owner, wallet_settings, system_program)?;
+            }
+            name_data.pending_owner = new_owner;
+        } else {
+            if offer_open {
+                Self::close_pending_operation(program_id, current_owner.key, wallet_settings)?;
+            }
+            name_data.transfer_to(new_owner);
+            name_data.pending_owner = Pubkey::default();
+            name_data.cooldown_until = get_cooldown_until(&config)?;
+            log_event(formats::cooldown_event(&name_data.name, name_data.cooldown_until));
+        }
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_accept_name_transfer(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        wipe_records: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let new_owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let owner_settings = next_account_info(account_info_iter)?;
+
+        if !new_owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        validate_not_paused(&config)?;
+        let mut name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        if name_data.pending_owner == Pubkey::default() || name_data.pending_owner != *new_owner.key {
+            return Err(NameRegistryError::NotPendingNameOwner.into());
+        }
+        assert_not_frozen(&name_data)?;
+        Self::close_pending_operation(program_id, &name_data.owner, owner_settings)?;
+        if wipe_records || config.enforce_unique_address {
+            let previous_owner = next_account_info(account_info_iter)?;
+            validate_owner(&name_data.owner, previous_owner.key)?;
+            if config.enforce_unique_address {
+                let system_program = next_account_info(account_info_iter)?;
+                if system_program.key != &solana_program::system_program::id() {
+                    return Err(ProgramError::IncorrectProgramId);
+                }
+                Self::move_address_claim(
+                    program_id,
+                    account_info_iter,
+                    new_owner,
+                    previous_owner,
+                    new_owner.key,
+                    name_account,
+                    system_program,
+                )?;
+            }
+            if wipe_records {
+                assert_writable(previous_owner)?;
+                Self::wipe_records(program_id, name_account, account_info_iter.as_slice(), previous_owner)?;
+            }
+        }
+
+        name_data.transfer_to(*new_owner.key);
+        name_data.pending_owner = Pubkey::default();
+        name_data.cooldown_until = get_cooldown_until(&config)?;
+        log_event(formats::cooldown_event(&name_data.name, name_data.cooldown_until));
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_create_promo_claims(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        expires_at: i64,
+        claims: Vec<PromoClaimEntry>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (accounts, multisig_accounts) =
+            Self::split_multisig_accounts(program_id, config_account, &config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, multisig_accounts)?;
+        let account_info_iter = &mut accounts.iter();
+        validate_not_paused(&config)?;
+        let blocklist_account = if config.blocklist_enabled {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+
+        let now = compat::unix_timestamp()?;
+        if expires_at <= now {
+            return Err(NameRegistryError::PromoClaimExpired.into());
+        }
+
+        for entry in claims {
+            let claim_account = next_account_info(account_info_iter)?;
+            let name_account = next_account_info(account_info_iter)?;
+
+            validate_name_for(&config, &entry.name)?;
+            let blocklist = &mut blocklist_account.into_iter();
+            Self::assert_unlisted(program_id, config_account, &config, blocklist, &entry.name, entry.proof.as_ref())?;
+
+            let (expected_claim, claim_bump) =
+                find_promo_claim_address(config_account.key, &entry.commitment, program_id);
+            if claim_account.key != &expected_claim {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            let (expected_name_account, name_bump) = find_name_address(&entry.name, program_id);
+            if name_account.key != &expected_name_account {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            assert_writable(claim_account)?;
+            assert_writable(name_account)?;
+
+            if !claim_account.data_is_empty() {
+                return Err(NameRegistryError::AlreadyInitialized.into());
+            }
+            create_program_account(
+                program_id,
+                owner,
+                claim_account,
+                system_program,
+                PromoClaim::LEN,
+                &[PROMO_CLAIM_SEED, config_account.key.as_ref(), &entry.commitment, &[claim_bump]],
+            )?;
+            if name_account.data_is_empty() {
+                let normalized = normalize_name(&entry.name);
+                create_program_account(
+                    program_id,
+                    owner,
+                    name_account,
+                    system_program,
+                    NameAccount::LEN,
+                    &[NAME_SEED, normalized.as_bytes(), &[name_bump]],
+                )?;
+            }
+            assert_owned_by(name_account, program_id)?;
+
+            let mut name_data = NameAccount::unpack_unchecked(&name_account.data.borrow())?;
+            if name_data.is_initialized {
+                return Err(NameRegistryError::NameTaken.into());
+            }
+
+            // The claim account holds the name until someone opens it
+            name_data.is_initialized = true;
+            name_data.owner = *claim_account.key;
+            name_data.name = entry.name;
+            name_data.address = *claim_account.key;
+            name_data.cooldown_until = now;
+            log_event(formats::expiry_event(&name_data.name, expires_at));
+            NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+            let claim = PromoClaim {
+                is_initialized: true,
+                config: *config_account.key,
+                name_account: *name_account.key,
+                expires_at,
+            };
+            PromoClaim::pack_checked(claim, &mut claim_account.data.borrow_mut())?;
+        }
+
+        Ok(())
+    }
+
+    fn process_claim_promo_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        preimage: Vec<u8>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let claimer = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let program_owner = next_account_info(account_info_iter)?;
+        let claim_account = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let address_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !claimer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        validate_not_paused(&config)?;
+
+        // Only the preimage leads to the claim, and a used claim is closed
+        validate_claim_preimage(&preimage)?;
+        let commitment = promo_claim_commitment(&preimage);
+        if claim_account.key != &find_promo_claim_address(config_account.key, &commitment, program_id).0
+            || claim_account.data_is_empty()
+        {
+            return Err(NameRegistryError::InvalidPromoClaim.into());
+        }
+        let claim = Self::unpack_promo_claim(program_id, config_account, claim_account)?;
+        let now = compat::unix_timestamp()?;
+        if now >= claim.expires_at {
+            return Err(NameRegistryError::PromoClaimExpired.into());
+        }
+
+        let mut name_data = Self::unpack_claimed_name(program_id, claim_account, &claim, name_account)?;
+        Self::assert_rent_recipient(program_id, config_account, &config, &name_data.name, program_owner)?;
+        if config.enforce_unique_address {
+            let address_claim = next_account_info(account_info_iter)?;
+            Self::take_address_claim(program_id, claimer, claimer.key, name_account, address_claim, system_program)?;
+        }
+        let wallet_settings = next_account_info(account_info_iter).ok();
+
+        Self::create_address_account(program_id, claimer, name_account, address_account, system_program)?;
+        assert_owned_by(address_account, program_id)?;
+        assert_writable(address_account)?;
+        let address_data = Self::unpack_reverse_record(address_account)?;
+        if address_data.is_initialized {
+            return Err(NameRegistryError::NameAlreadyRegistered.into());
+        }
+        let private = Self::is_private_wallet(program_id, claimer.key, wallet_settings)?;
+
+        assert_writable(config_account)?;
+        Self::count_registration(&mut config)?;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        name_data.owner = *claimer.key;
+        name_data.address = *claimer.key;
+        name_data.cooldown_until = now;
+        name_data.registered_at = now;
+        let name = name_data.name.clone();
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        let address_data = if private {
+            ReverseRecord::cleared()
+        } else {
+            ReverseRecord::new(*claimer.key, name)
+        };
+        ReverseRecord::pack_checked(address_data, &mut address_account.data.borrow_mut())?;
+
+        close_program_account(claim_account, program_owner)
+    }
+
+    fn process_reclaim_promo_names(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(owner)?;
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (accounts, multisig_accounts) =
+            Self::split_multisig_accounts(program_id, config_account, &config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, multisig_accounts)?;
+        let account_info_iter = &mut accounts.iter();
+
+        let now = compat::unix_timestamp()?;
+        while let Ok(claim_account) = next_account_info(account_info_iter) {
+            let name_account = next_account_info(account_info_iter)?;
+
+            let claim = Self::unpack_promo_claim(program_id, config_account, claim_account)?;
+            if now < claim.expires_at {
+                return Err(NameRegistryError::PromoClaimNotExpired.into());
+            }
+
+            let mut name_data = Self::unpack_claimed_name(program_id, claim_account, &claim, name_account)?;
+            name_data.owner = *owner.key;
+            name_data.address = *owner.key;
+            name_data.cooldown_until = now;
+            NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+            close_program_account(claim_account, owner)?;
+        }
+
+        Ok(())
+    }
+
+    #[allow(deprecated)]
+    fn process_migrate_address_account(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        use crate::state::AddressAccount;
+
+        let account_info_iter = &mut accounts.iter();
+        let payer = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let address_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_owned_by(address_account, program_id)?;
+        assert_writable(address_account)?;
+        match address_account.data_len() {
+            AddressAccount::LEN => {}
+            ReverseRecord::LEN => return Err(NameRegistryError::AlreadyMigrated.into()),
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+
+        let legacy = AddressAccount::unpack_unchecked(&address_account.data.borrow())?;
+        let derived = address_account.key == &find_address_account_address(name_account.key, program_id).0;
+        let holds_name = legacy.is_initialized && legacy.name == name_data.name;
+        if !(derived || holds_name) {
+            return Err(NameRegistryError::WrongDerivedAccount.into());
+        }
+        let record = if legacy.is_initialized {
+            ReverseRecord::new(name_data.address, legacy.name)
+        } else {
+            ReverseRecord::cleared()
+        };
+
+        let rent_due = compat::rent()?
+            .minimum_balance(ReverseRecord::LEN)
+            .saturating_sub(address_account.lamports());
+        if rent_due > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, address_account.key, rent_due),
+                &[payer.clone(), address_account.clone(), system_program.clone()],
+            )?;
+        }
+        compat::resize_account(address_account, ReverseRecord::LEN)?;
+        ReverseRecord::pack_checked(record, &mut address_account.data.borrow_mut())
+    }
+
+    fn process_migrate_account(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer = next_account_info(account_info_iter)?;
+        let account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        assert_owned_by(account, program_id)?;
+        assert_writable(account)?;
+        let (tag, version_byte) = match **account.data.borrow() {
+            [tag, version, ..] => (tag, version),
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let layout = layout(tag).ok_or(NameRegistryError::InvalidAccountType)?;
+        let (data_len, len) = (account.data_len(), layout.len);
+        if data_len == len {
+            return Err(NameRegistryError::AlreadyMigrated.into());
+        }
+        let version = layout.version(data_len).ok_or(ProgramError::InvalidAccountData)?;
+        if version == LAYOUT_VERSION && version_byte != LAYOUT_VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let rent_due = compat::rent()?
+            .minimum_balance(len)
+            .saturating_sub(account.lamports());
+        if rent_due > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, account.key, rent_due),
+                &[payer.clone(), account.clone(), system_program.clone()],
+            )?;
+        }
+        // Fields the earlier layout lacked read as zero once grown
+        compat::resize_account(account, len)?;
+        if version == LAYOUT_VERSION {
+            return Ok(());
+        }
+        // Make room for the version byte after the discriminator
+        let mut data = account.data.borrow_mut();
+        data.copy_within(1..data_len, 2);
+        data[1] = LAYOUT_VERSION;
+        Ok(())
+    }
+
+    fn process_migrate_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let new_config_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        assert_writable(config_account)?;
+        let mut config = Self::unpack_any_config(program_id, config_account)?;
+        validate_program_owner(&config.owner, owner.key)?;
+        // The multisig PDA is derived from the config key and would not follow
+        if config.multisig_enabled {
+            return Err(NameRegistryError::MultisigActive.into());
+        }
+
+        let (expected_config, config_bump) = find_config_address(program_id);
+        if new_config_account.key != &expected_config {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if config_account.key == new_config_account.key {
+            return Err(NameRegistryError::AlreadyMigrated.into());
+        }
+        assert_writable(new_config_account)?;
+        create_program_account(
+            program_id,
+            owner,
+            new_config_account,
+            system_program,
+            ProgramConfig::LEN,
+            &[CONFIG_SEED, &[config_bump]],
+        )?;
+
+        let mut old_treasuries = Vec::with_capacity(FEE_BUCKETS as usize);
+        for bucket in 0..FEE_BUCKETS {
+            let treasury_account = next_account_info(account_info_iter)?;
+            Self::assert_treasury(program_id, config_account, &config, bucket, treasury_account)?;
+            old_treasuries.push(treasury_account);
+        }
+        let mut new_treasuries = Vec::with_capacity(FEE_BUCKETS as usize);
+        for bucket in 0..FEE_BUCKETS {
+            let treasury_account = next_account_info(account_info_iter)?;
+            let (expected_treasury, bump) = find_treasury_address(new_config_account.key, bucket, program_id);
+            if treasury_account.key != &expected_treasury {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            assert_writable(treasury_account)?;
+            create_program_account(
+                program_id,
+                owner,
+                treasury_account,
+                system_program,
+                0,
+                &[TREASURY_SEED, new_config_account.key.as_ref(), &[bucket], &[bump]],
+            )?;
+            new_treasuries.push(treasury_account);
+        }
+
+        // Registries set up before the link existed get one now
+        let config_link = next_account_info(account_info_iter)?;
+        let (expected_link, link_bump) = ConfigLink::find_address(program_id);
+        if config_link.key != &expected_link {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(config_link)?;
+        if config_link.owner == program_id {
+            Self::assert_linked_config(program_id, config_link, config_account)?;
+        } else {
+            create_program_account(
+                program_id,
+                owner,
+                config_link,
+                system_program,
+                ConfigLink::LEN,
+                &[CONFIG_LINK_SEED, &[link_bump]],
+            )?;
+        }
+
+        // Lamports move only after the CPIs above, which check balances
+        for (old_treasury, new_treasury) in old_treasuries.into_iter().zip(new_treasuries) {
+            close_program_account(old_treasury, new_treasury)?;
+        }
+        let link = ConfigLink { is_initialized: true, config: *new_config_account.key };
+        ConfigLink::pack_checked(link, &mut config_link.data.borrow_mut())?;
+
+        config.cooldown_seconds = config.cooldown_period();
+        config.version = ProgramConfig::VERSION;
+        // The buckets created above are the new config's first generation
+        config.fee_vault_generation = 0;
+        ProgramConfig::pack_checked(config.clone(), &mut new_config_account.data.borrow_mut())?;
+        config.successor = *new_config_account.key;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())
+    }
+
+    fn process_set_multisig(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let multisig_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        assert_writable(config_account)?;
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        if config.multisig_enabled {
+            let multisig = Self::unpack_multisig(program_id, config_account, multisig_account)?;
+            Self::assert_multisig_approval(&multisig, owner, account_info_iter.as_slice())?;
+        } else {
+            validate_program_owner(&config.owner, owner.key)?;
+        }
+        validate_multisig(&signers, threshold)?;
+
+        let (expected_multisig, bump) = MultisigConfig::find_address(config_account.key, program_id);
+        if multisig_account.key != &expected_multisig {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(multisig_account)?;
+
+        config.multisig_enabled = !signers.is_empty();
+        if config.multisig_enabled {
+            if multisig_account.owner != program_id {
+                create_program_account(
+                    program_id,
+                    owner,
+                    multisig_account,
+                    system_program,
+                    MultisigConfig::LEN,
+                    &[MULTISIG_SEED, config_account.key.as_ref(), &[bump]],
+                )?;
+            }
+            let multisig = MultisigConfig {
+                is_initialized: true,
+                config: *config_account.key,
+                threshold,
+                signers,
+            };
+            MultisigConfig::pack_checked(multisig, &mut multisig_account.data.borrow_mut())?;
+        } else if multisig_account.owner == program_id {
+            assert_writable(owner)?;
+            close_program_account(multisig_account, owner)?;
+        }
+
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())
+    }
+
+    fn process_set_admin_timelock(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        seconds: u32,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        validate_admin_timelock(seconds)?;
+        assert_writable(config_account)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+
+        let action = AdminAction::SetAdminTimelock(seconds);
+        Self::queue_or_apply_admin_action(program_id, config_account, config, owner, timelock_accounts, action)
+    }
+
+    /// Applies the queued admin action once its timelock has run out, or
+    /// with `execute` unset drops it. Either way the rent goes to the signer.
+    fn process_close_admin_action(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        execute: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let action_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(owner)?;
+        assert_writable(config_account)?;
+        assert_writable(action_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        if action_account.key != &PendingAdminAction::find_address(config_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(action_account, program_id)?;
+        let pending = PendingAdminAction::unpack(&action_account.data.borrow())?;
+
+        // A queued rotation takes the buckets it moves the fees through
+        let (vault_accounts, remaining) = match pending.action {
+            AdminAction::RotateFeeVault if execute => {
+                let (vault_accounts, remaining) = Self::split_fee_vault_accounts(account_info_iter.as_slice())?;
+                (Some(vault_accounts), remaining)
+            }
+            _ => (None, account_info_iter.as_slice()),
+        };
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+
+        if execute {
+            if compat::unix_timestamp()? < pending.executable_at {
+                return Err(NameRegistryError::AdminActionNotReady.into());
+            }
+            match vault_accounts {
+                Some(vault_accounts) => {
+                    Self::rotate_fee_vault(program_id, config_account, &mut config, owner, vault_accounts)?
+                }
+                None => pending.action.apply(&mut config),
+            }
+            ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+        }
+
+        close_program_account(action_account, owner)
+    }
+
+    fn process_set_cosigner(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        cosigner: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let cosigner_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let signers = account_info_iter.as_slice();
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if cosigner == *owner.key {
+            return Err(NameRegistryError::InvalidAddress.into());
+        }
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        validate_owner(&name_data.owner, owner.key)?;
+        // Replacing or removing a co-signer takes its signature, so the
+        // owner key alone cannot strip the protection
+        let current = Self::assert_cosigned(program_id, name_account, &name_data, cosigner_account, signers)?;
+        assert_writable(cosigner_account)?;
+
+        if cosigner == Pubkey::default() {
+            if current.is_some() {
+                assert_writable(owner)?;
+                close_program_account(cosigner_account, owner)?;
+            }
+            return Ok(());
+        }
+
+        // The new co-signer signs too, so a mistyped key cannot lock the name
+        assert_signed_by(&cosigner, signers)?;
+        if cosigner_account.owner != program_id {
+            let bump = NameCosigner::find_address(name_account.key, program_id).1;
+            create_program_account(
+                program_id,
+                owner,
+                cosigner_account,
+                system_program,
+                NameCosigner::LEN,
+                &[COSIGNER_SEED, name_account.key.as_ref(), &[bump]],
+            )?;
+        }
+        let state = NameCosigner {
+            is_initialized: true,
+            name_account: *name_account.key,
+            owner: *owner.key,
+            cosigner,
+        };
+        NameCosigner::pack_checked(state, &mut cosigner_account.data.borrow_mut())
+    }
+
+    fn process_unregister_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let address_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let destination = next_account_info(account_info_iter)?;
+        let index_account = next_account_info(account_info_iter)?;
+        let wallet_settings = next_account_info(account_info_iter)?;
+        let cosigner_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        validate_not_paused(&config)?;
+        assert_writable(config_account)?;
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        validate_owner(&name_data.owner, owner.key)?;
+        assert_not_tokenized(&name_data)?;
+        assert_not_frozen(&name_data)?;
+        if name_data.is_subname() {
+            return Err(NameRegistryError::NotTopLevelName.into());
+        }
+        let cosigner =
+            Self::assert_cosigned(program_id, name_account, &name_data, cosigner_account, account_info_iter.as_slice())?;
+
+        // The reverse record stays where the name was first registered
+        // across renames, so it is recognised by its contents there
+        assert_owned_by(address_account, program_id)?;
+        assert_writable(address_account)?;
+        let address_data = Self::unpack_reverse_record(address_account)?;
+        let derived = address_account.key == &find_address_account_address(name_account.key, program_id).0;
+        if !derived && (!address_data.is_initialized || address_data.name != name_data.name) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // Records would otherwise outlive the name and greet its next owner
+        if index_account.key != &find_record_index_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !index_account.data_is_empty() {
+            return Err(NameRegistryError::NameHasRecords.into());
+        }
+
+        if name_data.pending_owner != Pubkey::default() {
+            Self::close_pending_operation(program_id, owner.key, wallet_settings)?;
+        }
+
+        let tombstone_period = config.tombstone_period;
+        config.total_names = config.total_names.saturating_sub(1);
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        if tombstone_period > 0 {
+            let remaining = account_info_iter.as_slice();
+            Self::leave_tombstone(program_id, owner, name_account, &name_data, remaining, tombstone_period)?;
+        }
+
+        assert_writable(destination)?;
+        close_program_account(name_account, destination)?;
+        close_program_account(address_account, destination)?;
+        if cosigner.is_some() {
+            assert_writable(cosigner_account)?;
+            close_program_account(cosigner_account, destination)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the tombstone of the name being unregistered, found by its key
+    /// among `accounts` and followed by the system program, creating it at
+    /// the owner's expense the first time.
+    fn leave_tombstone<'a>(
+        program_id: &Pubkey,
+        owner: &AccountInfo<'a>,
+        name_account: &AccountInfo<'a>,
+        name_data: &NameAccount,
+        accounts: &[AccountInfo<'a>],
+        period: u32,
+    ) -> ProgramResult {
+        let (expected_tombstone, bump) = NameTombstone::find_address(name_account.key, program_id);
+        let start = accounts
+            .iter()
+            .position(|account| account.key == &expected_tombstone)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let [tombstone_account, system_program, ..] = &accounts[start..] else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        assert_writable(tombstone_account)?;
+
+        let payer = if tombstone_account.data_is_empty() {
+            assert_writable(owner)?;
+            create_program_account(
+                program_id,
+                owner,
+                tombstone_account,
+                system_program,
+                NameTombstone::LEN,
+                &[TOMBSTONE_SEED, name_account.key.as_ref(), &[bump]],
+            )?;
+            *owner.key
+        } else {
+            assert_owned_by(tombstone_account, program_id)?;
+            NameTombstone::unpack(&tombstone_account.data.borrow())?.payer
+        };
+
+        let now = compat::unix_timestamp()?;
+        let tombstone = NameTombstone {
+            is_initialized: true,
+            name_account: *name_account.key,
+            previous_owner: name_data.owner,
+            previous_address: name_data.address,
+            released_at: now,
+            expires_at: now.saturating_add(i64::from(period)),
+            payer,
+        };
+        NameTombstone::pack_checked(tombstone, &mut tombstone_account.data.borrow_mut())
+    }
+
+    fn process_close_tombstone(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let tombstone_account = next_account_info(account_info_iter)?;
+        let payer = next_account_info(account_info_iter)?;
+
+        assert_owned_by(tombstone_account, program_id)?;
+        assert_writable(tombstone_account)?;
+        assert_writable(payer)?;
+        let tombstone = NameTombstone::unpack(&tombstone_account.data.borrow())?;
+        if tombstone_account.key != &NameTombstone::find_address(&tombstone.name_account, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if payer.key != &tombstone.payer {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if compat::unix_timestamp()? < tombstone.expires_at {
+            return Err(NameRegistryError::TombstoneNotExpired.into());
+        }
+
+        close_program_account(tombstone_account, payer)
+    }
+
+    fn process_close_receipts(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer = next_account_info(account_info_iter)?;
+
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        assert_writable(payer)?;
+
+        for receipt_account in account_info_iter {
+            assert_owned_by(receipt_account, program_id)?;
+            assert_writable(receipt_account)?;
+            let receipt = ReceiptAccount::unpack(&receipt_account.data.borrow())?;
+            if receipt.payer != *payer.key {
+                return Err(NameRegistryError::NotReceiptPayer.into());
+            }
+            close_program_account(receipt_account, payer)?;
+        }
+
+        Ok(())
+    }
+
+    fn process_reserve_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let reservation_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+        validate_name_for(&config, &name)?;
+
+        let (expected_name_account, name_bump) = find_name_address(&name, program_id);
+        if name_account.key != &expected_name_account {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let (expected_reservation, reservation_bump) = ReservedName::find_address(name_account.key, program_id);
+        if reservation_account.key != &expected_reservation {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(name_account)?;
+        assert_writable(reservation_account)?;
+
+        if !reservation_account.data_is_empty() {
+            return Err(NameRegistryError::AlreadyInitialized.into());
+        }
+        create_program_account(
+            program_id,
+            owner,
+            reservation_account,
+            system_program,
+            ReservedName::LEN,
+            &[RESERVED_SEED, name_account.key.as_ref(), &[reservation_bump]],
+        )?;
+        if name_account.data_is_empty() {
+            let normalized = normalize_name(&name);
+            create_program_account(
+                program_id,
+                owner,
+                name_account,
+                system_program,
+                NameAccount::LEN,
+                &[NAME_SEED, normalized.as_bytes(), &[name_bump]],
+            )?;
+        }
+        assert_owned_by(name_account, program_id)?;
+
+        let mut name_data = NameAccount::unpack_unchecked(&name_account.data.borrow())?;
+        if name_data.is_initialized {
+            return Err(NameRegistryError::NameTaken.into());
+        }
+
+        // The reservation holds the name, as a promo claim does
+        name_data.is_initialized = true;
+        name_data.owner = *reservation_account.key;
+        name_data.name = name;
+        name_data.address = *reservation_account.key;
+        name_data.cooldown_until = compat::unix_timestamp()?;
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        let reservation = ReservedName {
+            is_initialized: true,
+            config: *config_account.key,
+            name_account: *name_account.key,
+        };
+        ReservedName::pack_checked(reservation, &mut reservation_account.data.borrow_mut())
+    }
+
+    fn process_release_reserved_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        assignee: Option<Pubkey>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let reservation_account = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        assert_writable(owner)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        let (accounts, multisig_accounts) =
+            Self::split_multisig_accounts(program_id, config_account, &config, account_info_iter.as_slice())?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, multisig_accounts)?;
+        let account_info_iter = &mut accounts.iter();
+
+        assert_owned_by(reservation_account, program_id)?;
+        assert_writable(reservation_account)?;
+        let reservation = ReservedName::unpack(&reservation_account.data.borrow())
+            .map_err(|_| NameRegistryError::NameNotReserved)?;
+        if reservation.config != *config_account.key || reservation.name_account != *name_account.key {
+            return Err(NameRegistryError::NameNotReserved.into());
+        }
+        assert_owned_by(name_account, program_id)?;
+        assert_writable(name_account)?;
+        let mut name_data = NameAccount::unpack(&name_account.data.borrow())?;
+        if name_data.owner != *reservation_account.key {
+            return Err(NameRegistryError::NameNotReserved.into());
+        }
+
+        let assignee = match assignee {
+            Some(assignee) => assignee,
+            None => {
+                close_program_account(name_account, owner)?;
+                return close_program_account(reservation_account, owner);
+            }
+        };
+
+        // Registered like any other name, minus the fee
+        let system_program = next_account_info(account_info_iter)?;
+        let address_account = next_account_info(account_info_iter)?;
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        validate_address(&assignee)?;
+        if config.enforce_unique_address {
+            let claim_account = next_account_info(account_info_iter)?;
+            Self::take_address_claim(program_id, owner, &assignee, name_account, claim_account, system_program)?;
+        }
+        let wallet_settings = next_account_info(account_info_iter).ok();
+
+        Self::create_address_account(program_id, owner, name_account, address_account, system_program)?;
+        assert_owned_by(address_account, program_id)?;
+        let address_data = Self::unpack_reverse_record(address_account)?;
+        if address_data.is_initialized {
+            return Err(NameRegistryError::NameAlreadyRegistered.into());
+        }
+        let private = Self::is_private_wallet(program_id, &assignee, wallet_settings)?;
+
+        assert_writable(config_account)?;
+        Self::count_registration(&mut config)?;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        name_data.owner = assignee;
+        name_data.address = assignee;
+        name_data.cooldown_until = compat::unix_timestamp()?;
+        name_data.registered_at = name_data.cooldown_until;
+        let address_data = if private {
+            ReverseRecord::cleared()
+        } else {
+            ReverseRecord::new(assignee, name_data.name.clone())
+        };
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+        ReverseRecord::pack_checked(address_data, &mut address_account.data.borrow_mut())?;
+
+        close_program_account(reservation_account, owner)
+    }
+
+    fn process_set_blocklist_root(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        root: [u8; 32],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let blocklist_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        assert_writable(config_account)?;
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        let (expected_blocklist, bump) = Blocklist::find_address(config_account.key, program_id);
+        if blocklist_account.key != &expected_blocklist {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(blocklist_account)?;
+        if blocklist_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                owner,
+                blocklist_account,
+                system_program,
+                Blocklist::LEN,
+                &[BLOCKLIST_SEED, config_account.key.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(blocklist_account, program_id)?;
+
+        let blocklist = Blocklist {
+            is_initialized: true,
+            config: *config_account.key,
+            root,
+        };
+        Blocklist::pack_checked(blocklist, &mut blocklist_account.data.borrow_mut())?;
+
+        config.blocklist_enabled = root != [0; 32];
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_set_wildcard_subdomains(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        enabled: bool,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(name_account)?;
+        let mut name_data = Self::unpack_derived_name(program_id, name_account)?;
+        validate_owner(&name_data.owner, owner.key)?;
+
+        name_data.wildcard_subdomains = enabled;
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_grant_role(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        role: Role,
+        account: Pubkey,
+        fee_bps: u16,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let role_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+        if fee_bps > BPS_DENOMINATOR {
+            return Err(NameRegistryError::InvalidRoleGrant.into());
+        }
+
+        let (expected_role, bump) = RoleGrant::find_address(config_account.key, role, &account, program_id);
+        if role_account.key != &expected_role {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(role_account)?;
+        if role_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                owner,
+                role_account,
+                system_program,
+                RoleGrant::LEN,
+                &[ROLE_SEED, config_account.key.as_ref(), &[role as u8], account.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(role_account, program_id)?;
+
+        let grant = RoleGrant {
+            is_initialized: true,
+            config: *config_account.key,
+            account,
+            role,
+            fee_bps,
+        };
+        RoleGrant::pack_checked(grant, &mut role_account.data.borrow_mut())
+    }
+
+    fn process_revoke_role(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        role: Role,
+        account: Pubkey,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let role_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+
+        assert_writable(role_account)?;
+        Self::unpack_role(program_id, config_account, role_account, role, &account)?;
+        close_program_account(role_account, owner)
+    }
+
+    fn process_register_name_as_registrar(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        owner: Pubkey,
+        proof: Option<NameProof>,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let role_account = next_account_info(account_info_iter)?;
+
+        let registration = Registration::Registrar { owner, role_account };
+        Self::process_register_name(program_id, &accounts[1..], name, proof, registration)
+    }
+
+    fn process_register_name_with_records(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        records: Vec<(String, String)>,
+        proof: Option<NameProof>,
+    ) -> ProgramResult {
+        if accounts.len() < 1 + records.len() {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (record_accounts, register_accounts) = accounts.split_at(1 + records.len());
+        let (index_account, record_accounts) = record_accounts.split_first().unwrap();
+        for (kind, value) in &records {
+            validate_record(kind, value)?;
+        }
+
+        Self::process_register_name(program_id, register_accounts, name, proof, Registration::Direct)?;
+
+        // RegisterName checked these: the registrant, the name they now own
+        // and the system program
+        let registrant = &register_accounts[0];
+        let name_account = &register_accounts[1];
+        let system_program = &register_accounts[4];
+        for (record_account, (kind, value)) in record_accounts.iter().zip(records) {
+            let record = RecordAccount {
+                is_initialized: true,
+                name_account: *name_account.key,
+                kind,
+                value,
+                ..RecordAccount::default()
+            };
+            Self::write_record(program_id, registrant, name_account, record_account, index_account, system_program, record)?;
+        }
+        Ok(())
+    }
+
+    fn process_tokenize_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        uri: String,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+        let associated_token_program = next_account_info(account_info_iter)?;
+        let metadata_account = next_account_info(account_info_iter).ok();
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if system_program.key != &solana_program::system_program::id()
+            || token_program.key != &spl_token::id()
+            || associated_token_program.key != &spl_associated_token_account::id()
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Self::assert_not_paused(program_id, config_account)?;
+
+        let mut name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        validate_owner(&name_data.owner, owner.key)?;
+        // A parent can revoke its subnames, which would strand their tokens
+        if name_data.is_subname() {
+            return Err(NameRegistryError::NotTopLevelName.into());
+        }
+        assert_not_tokenized(&name_data)?;
+        assert_not_frozen(&name_data)?;
+        if name_data.pending_owner != Pubkey::default() {
+            return Err(NameRegistryError::TransferOfferOpen.into());
+        }
+
+        let (expected_mint, mint_bump) = find_name_mint_address(name_account.key, program_id);
+        if mint_account.key != &expected_mint {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(mint_account)?;
+        let mint_seeds: &[&[u8]] = &[NAME_MINT_SEED, name_account.key.as_ref(), &[mint_bump]];
+        create_program_account(
+            &spl_token::id(),
+            owner,
+            mint_account,
+            system_program,
+            spl_token::state::Mint::LEN,
+            mint_seeds,
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_mint2(&spl_token::id(), mint_account.key, mint_account.key, None, 0)?,
+            &[mint_account.clone(), token_program.clone()],
+        )?;
+
+        invoke(
+            &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                owner.key,
+                owner.key,
+                mint_account.key,
+                &spl_token::id(),
+            ),
+            &[
+                owner.clone(),
+                token_account.clone(),
+                mint_account.clone(),
+                system_program.clone(),
+                token_program.clone(),
+                associated_token_program.clone(),
+            ],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::mint_to(&spl_token::id(), mint_account.key, token_account.key, mint_account.key, &[], 1)?,
+            &[mint_account.clone(), token_account.clone(), token_program.clone()],
+            &[mint_seeds],
+        )?;
+
+        if let Some(metadata_account) = metadata_account {
+            let metadata_program = next_account_info(account_info_iter)?;
+            if metadata_program.key != &token_metadata::ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            invoke_signed(
+                &token_metadata::create_metadata_accounts_v3(
+                    metadata_account.key,
+                    mint_account.key,
+                    mint_account.key,
+                    owner.key,
+                    &name_data.name,
+                    &uri,
+                ),
+                &[
+                    metadata_account.clone(),
+                    mint_account.clone(),
+                    owner.clone(),
+                    system_program.clone(),
+                    metadata_program.clone(),
+                ],
+                &[mint_seeds],
+            )?;
+        }
+
+        // With no mint authority left the supply stays at one
+        invoke_signed(
+            &spl_token::instruction::set_authority(
+                &spl_token::id(),
+                mint_account.key,
+                None,
+                spl_token::instruction::AuthorityType::MintTokens,
+                mint_account.key,
+                &[],
+            )?,
+            &[mint_account.clone(), token_program.clone()],
+            &[mint_seeds],
+        )?;
+
+        name_data.tokenized = true;
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_sync_tokenized_owner(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let name_account = next_account_info(account_info_iter)?;
+        let token_account = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let mut name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        if !name_data.tokenized {
+            return Err(NameRegistryError::InvalidNameToken.into());
+        }
+        assert_not_frozen(&name_data)?;
+
+        let token = Self::unpack_name_token(program_id, name_account, token_account)?;
+        if token.owner != name_data.owner {
+            if config.enforce_unique_address {
+                let payer = next_account_info(account_info_iter)?;
+                let system_program = next_account_info(account_info_iter)?;
+                let previous_owner = next_account_info(account_info_iter)?;
+                if !payer.is_signer {
+                    return Err(ProgramError::MissingRequiredSignature);
+                }
+                if system_program.key != &solana_program::system_program::id() {
+                    return Err(ProgramError::IncorrectProgramId);
+                }
+                validate_owner(&name_data.owner, previous_owner.key)?;
+                Self::move_address_claim(
+                    program_id,
+                    account_info_iter,
+                    payer,
+                    previous_owner,
+                    &token.owner,
+                    name_account,
+                    system_program,
+                )?;
+            }
+            name_data.transfer_to(token.owner);
+            name_data.cooldown_until = get_cooldown_until(&config)?;
+            log_event(formats::cooldown_event(&name_data.name, name_data.cooldown_until));
+            NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+        }
+
+        Ok(())
+    }
+
+    fn process_resolve_token_holder(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let name_account = next_account_info(account_info_iter)?;
+        let token_account = next_account_info(account_info_iter)?;
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        if !name_data.tokenized {
+            return Err(NameRegistryError::InvalidNameToken.into());
+        }
+        assert_not_frozen(&name_data)?;
+
+        let holder = Self::unpack_name_token(program_id, name_account, token_account)?.owner;
+        log_resolved(&name_data.name, &holder);
+
+        let return_data = formats::encode_address(&holder);
+        compat::set_return_data(&return_data);
+
+        Ok(())
+    }
+
+    fn process_finalize_burned_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let name_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+
+        let mut name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        if !name_data.tokenized {
+            return Err(NameRegistryError::InvalidNameToken.into());
+        }
+        if mint_account.key != &find_name_mint_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(mint_account, &spl_token::id())?;
+        let mint = spl_token::state::Mint::unpack(&mint_account.data.borrow())?;
+        if mint.supply != 0 {
+            return Err(NameRegistryError::NameTokenNotBurned.into());
+        }
+
+        // Nobody can hold the token any more; the name stays with the owner
+        // it was last synced to
+        name_data.tokenized = false;
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())
+    }
+
+    fn process_rotate_fee_vault(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        assert_writable(config_account)?;
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        let (timelock_accounts, remaining) = Self::split_timelock_accounts(&config, account_info_iter.as_slice())?;
+        if timelock_accounts.is_some() {
+            // ExecuteAdminAction takes the buckets once the timelock runs out
+            Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+            let (action, payer) = (AdminAction::RotateFeeVault, owner);
+            return Self::queue_or_apply_admin_action(program_id, config_account, config, payer, timelock_accounts, action);
+        }
+
+        let (vault_accounts, remaining) = Self::split_fee_vault_accounts(remaining)?;
+        Self::assert_program_authority(program_id, config_account, &config, owner, remaining)?;
+        Self::rotate_fee_vault(program_id, config_account, &mut config, owner, vault_accounts)?;
+        ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())
+    }
+
+    /// Unpacks `token_account`, which must hold the one token of the name
+    /// in `name_account`.
+    fn unpack_name_token(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+        token_account: &AccountInfo,
+    ) -> Result<spl_token::state::Account, ProgramError> {
+        assert_owned_by(token_account, &spl_token::id())?;
+        let token = spl_token::state::Account::unpack(&token_account.data.borrow())?;
+        if token.mint != find_name_mint_address(name_account.key, program_id).0 || token.amount != 1 {
+            return Err(NameRegistryError::InvalidNameToken.into());
+        }
+        Ok(token)
+    }
+
+    fn process_start_auction(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        name: String,
+        min_bid: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let owner = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let auction_account = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let escrow_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+        let reservation_account = next_account_info(account_info_iter)?;
+
+        if !owner.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let config = Self::unpack_config(program_id, config_account)?;
+        let tombstone_account = if config.tombstone_period > 0 {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+        Self::assert_program_authority(program_id, config_account, &config, owner, account_info_iter.as_slice())?;
+        validate_name_for(&config, &name)?;
+
+        let (expected_name_account, name_bump) = find_name_address(&name, program_id);
+        if name_account.key != &expected_name_account {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        // Reserved and recently released names are held back from auctions
+        // as they are from RegisterName
+        if reservation_account.key != &ReservedName::find_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if reservation_account.owner == program_id {
+            return Err(NameRegistryError::NameTaken.into());
+        }
+        if let Some(tombstone_account) = tombstone_account {
+            Self::assert_not_released(program_id, name_account, tombstone_account)?;
+        }
+        let (expected_auction, auction_bump) = find_auction_address(name_account.key, program_id);
+        if auction_account.key != &expected_auction {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let (expected_escrow, escrow_bump) = find_auction_escrow_address(auction_account.key, program_id);
+        if escrow_account.key != &expected_escrow {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(auction_account)?;
+        assert_writable(name_account)?;
+        assert_writable(escrow_account)?;
+
+        if !auction_account.data_is_empty() {
+            return Err(NameRegistryError::AlreadyInitialized.into());
+        }
+        create_program_account(
+            program_id,
+            owner,
+            auction_account,
+            system_program,
+            AuctionAccount::LEN,
+            &[AUCTION_SEED, name_account.key.as_ref(), &[auction_bump]],
+        )?;
+        // Bids are escrowed apart from the auction state, like fees in the
+        // treasury buckets
+        if escrow_account.owner != program_id {
+            create_program_account(
+                program_id,
+                owner,
+                escrow_account,
+                system_program,
+                0,
+                &[AUCTION_ESCROW_SEED, auction_account.key.as_ref(), &[escrow_bump]],
+            )?;
+        }
+        if name_account.data_is_empty() {
+            let normalized = normalize_name(&name);
+            create_program_account(
+                program_id,
+                owner,
+                name_account,
+                system_program,
+                NameAccount::LEN,
+                &[NAME_SEED, normalized.as_bytes(), &[name_bump]],
+            )?;
+        }
+        assert_owned_by(name_account, program_id)?;
+
+        let mut name_data = NameAccount::unpack_unchecked(&name_account.data.borrow())?;
+        if name_data.is_initialized {
+            return Err(NameRegistryError::NameTaken.into());
+        }
+
+        // The auction account holds the name until it is settled
+        let now = compat::unix_timestamp()?;
+        name_data.is_initialized = true;
+        name_data.owner = *auction_account.key;
+        name_data.name = name;
+        name_data.address = *auction_account.key;
+        name_data.cooldown_until = now;
+        NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+        let auction = AuctionAccount {
+            is_initialized: true,
+            config: *config_account.key,
+            name_account: *name_account.key,
+            min_bid,
+            ends_at: now.checked_add(AUCTION_DURATION).ok_or(ProgramError::ArithmeticOverflow)?,
+            highest_bidder: Pubkey::default(),
+            highest_bid: 0,
+        };
+        AuctionAccount::pack_checked(auction, &mut auction_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_place_bid(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let bidder = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let auction_account = next_account_info(account_info_iter)?;
+        let escrow_account = next_account_info(account_info_iter)?;
+        let previous_bidder = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !bidder.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Self::assert_not_paused(program_id, config_account)?;
+        let mut auction = Self::unpack_auction(program_id, config_account, auction_account)?;
+        Self::assert_auction_escrow(program_id, auction_account, escrow_account)?;
+        if compat::unix_timestamp()? >= auction.ends_at {
+            return Err(NameRegistryError::AuctionEnded.into());
+        }
+        if amount < auction.min_bid || amount <= auction.highest_bid {
+            return Err(NameRegistryError::BidTooLow.into());
+        }
+
+        invoke(
+            &system_instruction::transfer(bidder.key, escrow_account.key, amount),
+            &[bidder.clone(), escrow_account.clone()],
+        )?;
+
+        // Outbid funds go straight back to their bidder
+        if auction.highest_bid > 0 {
+            if previous_bidder.key != &auction.highest_bidder {
+                return Err(NameRegistryError::InvalidAuction.into());
+            }
+            assert_writable(previous_bidder)?;
+            **escrow_account.lamports.borrow_mut() -= auction.highest_bid;
+            **previous_bidder.lamports.borrow_mut() = previous_bidder.lamports()
+                .checked_add(auction.highest_bid)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+
+        auction.highest_bidder = *bidder.key;
+        auction.highest_bid = amount;
+        AuctionAccount::pack_checked(auction, &mut auction_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_settle_auction(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let payer = next_account_info(account_info_iter)?;
+        let config_account = next_account_info(account_info_iter)?;
+        let program_owner = next_account_info(account_info_iter)?;
+        let auction_account = next_account_info(account_info_iter)?;
+        let escrow_account = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let address_account = next_account_info(account_info_iter)?;
+        let treasury_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !payer.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut config = Self::unpack_config(program_id, config_account)?;
+        validate_not_paused(&config)?;
+
+        let auction = Self::unpack_auction(program_id, config_account, auction_account)?;
+        Self::assert_auction_escrow(program_id, auction_account, escrow_account)?;
+        let now = compat::unix_timestamp()?;
+        if now < auction.ends_at {
+            return Err(NameRegistryError::AuctionNotEnded.into());
+        }
+
+        if name_account.key != &auction.name_account {
+            return Err(NameRegistryError::InvalidAuction.into());
+        }
+        let mut name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        if name_data.owner != *auction_account.key {
+            return Err(NameRegistryError::InvalidAuction.into());
+        }
+        Self::assert_rent_recipient(program_id, config_account, &config, &name_data.name, program_owner)?;
+
+        if auction.highest_bid == 0 {
+            // Nobody bid: the name is left unregistered, and its rent goes
+            // back to the program owner who paid it
+            close_program_account(name_account, program_owner)?;
+        } else {
+            let winner = auction.highest_bidder;
+            if config.enforce_unique_address {
+                let claim_account = next_account_info(account_info_iter)?;
+                Self::take_address_claim(program_id, payer, &winner, name_account, claim_account, system_program)?;
+            }
+            let wallet_settings = next_account_info(account_info_iter).ok();
+
+            Self::assert_treasury(program_id, config_account, &config, fee_bucket(&name_data.name), treasury_account)?;
+            assert_writable(config_account)?;
+            Self::count_registration(&mut config)?;
+            ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut())?;
+
+            Self::create_address_account(program_id, payer, name_account, address_account, system_program)?;
+            assert_owned_by(address_account, program_id)?;
+            assert_writable(address_account)?;
+            let address_data = Self::unpack_reverse_record(address_account)?;
+            if address_data.is_initialized {
+                return Err(NameRegistryError::NameAlreadyRegistered.into());
+            }
+            let private = Self::is_private_wallet(program_id, &winner, wallet_settings)?;
+
+            // The winning bid is paid into the treasury like a registration fee
+            **escrow_account.lamports.borrow_mut() -= auction.highest_bid;
+            **treasury_account.lamports.borrow_mut() = treasury_account.lamports()
+                .checked_add(auction.highest_bid)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            log_event(formats::fee_event(&name_data.name, auction.highest_bid));
+
+            name_data.owner = winner;
+            name_data.address = winner;
+            name_data.cooldown_until = now;
+            name_data.registered_at = now;
+            let name = name_data.name.clone();
+            NameAccount::pack_checked(name_data, &mut name_account.data.borrow_mut())?;
+
+            let address_data = if private {
+                ReverseRecord::cleared()
+            } else {
+                ReverseRecord::new(winner, name)
+            };
+            ReverseRecord::pack_checked(address_data, &mut address_account.data.borrow_mut())?;
+        }
+
+        close_program_account(escrow_account, program_owner)?;
+        close_program_account(auction_account, program_owner)
+    }
+
+    fn process_set_primary_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let wallet = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+        let primary_name_account = next_account_info(account_info_iter)?;
+        let system_program = next_account_info(account_info_iter)?;
+
+        if !wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        if name_data.address != *wallet.key {
+            return Err(NameRegistryError::PrimaryNameMismatch.into());
+        }
+
+        assert_writable(primary_name_account)?;
+        let (expected_record, bump) = find_primary_name_address(wallet.key, program_id);
+        if primary_name_account.key != &expected_record {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if primary_name_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                wallet,
+                primary_name_account,
+                system_program,
+                PrimaryNameRecord::LEN,
+                &[PRIMARY_NAME_SEED, wallet.key.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(primary_name_account, program_id)?;
+
+        let record = PrimaryNameRecord {
+            is_initialized: true,
+            wallet: *wallet.key,
+            name: name_data.name,
+        };
+        PrimaryNameRecord::pack_checked(record, &mut primary_name_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    fn process_resolve_name(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let account_info_iter = &mut accounts.iter();
+        let primary_name_account = next_account_info(account_info_iter)?;
+        let name_account = next_account_info(account_info_iter)?;
+
+        assert_owned_by(primary_name_account, program_id)?;
+        let record = PrimaryNameRecord::unpack(&primary_name_account.data.borrow())?;
+        if primary_name_account.key != &find_primary_name_address(&record.wallet, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // The name may have moved on since the record was written
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        if name_data.name != record.name || name_data.address != record.wallet {
+            return Err(NameRegistryError::PrimaryNameMismatch.into());
+        }
+        assert_not_frozen(&name_data)?;
+
+        compat::set_return_data(formats::encode_primary_name(&record.name));
+
+        Ok(())
+    }
+
+    /// Checks that `treasury_account` is the writable treasury PDA of
+    /// `config_account` for `bucket`, in the config's fee vault generation.
+    fn assert_treasury(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        config: &ProgramConfig,
+        bucket: u8,
+        treasury_account: &AccountInfo,
+    ) -> ProgramResult {
+        let generation = config.fee_vault_generation;
+        if treasury_account.key != &find_fee_vault_address(config_account.key, generation, bucket, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(treasury_account, program_id)?;
+        assert_writable(treasury_account)
+    }
+
+    /// Checks the account receiving the registration fee of `name`: its
+    /// treasury bucket, or for a namespaced name the namespace, which is
+    /// returned.
+    fn unpack_fee_recipient(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        config: &ProgramConfig,
+        name: &str,
+        fee_account: &AccountInfo,
+    ) -> Result<Option<NamespaceAccount>, ProgramError> {
+        let Some((_, suffix)) = name.split_once('.') else {
+            Self::assert_treasury(program_id, config_account, config, fee_bucket(name), fee_account)?;
+            return Ok(None);
+        };
+        let suffix_account = find_name_address(suffix, program_id).0;
+        if fee_account.key != &NamespaceAccount::find_address(&suffix_account, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if fee_account.owner != program_id || fee_account.data_is_empty() {
+            return Err(NameRegistryError::NamespaceNotFound.into());
+        }
+        assert_writable(fee_account)?;
+        let namespace = NamespaceAccount::unpack(&fee_account.data.borrow())?;
+        if namespace.config != *config_account.key {
+            return Err(NameRegistryError::NamespaceNotFound.into());
+        }
+        if namespace.paused {
+            return Err(NameRegistryError::NamespacePaused.into());
+        }
+        Ok(Some(namespace))
+    }
+
+    /// Requires a name of a delegated namespace to be registered by the
+    /// delegate program's authority PDA, which it signs for through CPI.
+    fn assert_namespace_authority(
+        namespace_account: &AccountInfo,
+        namespace: &NamespaceAccount,
+        registrant: &AccountInfo,
+    ) -> ProgramResult {
+        if namespace.delegate_program == Pubkey::default() {
+            return Ok(());
+        }
+        let authority = NamespaceAccount::find_authority_address(namespace_account.key, &namespace.delegate_program).0;
+        if registrant.key != &authority {
+            return Err(NameRegistryError::NotNamespaceAuthority.into());
+        }
+        Ok(())
+    }
+
+    /// Fee of registering `name` under `config`: its fee schedule, or while
+    /// the config prices in USD, its `UsdPricing` converted at the price in
+    /// `pricing_accounts`. Promos apply to either.
+    fn registration_fee(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        config: &ProgramConfig,
+        name: &str,
+        pricing_accounts: Option<(&AccountInfo, &AccountInfo)>,
+    ) -> Result<u64, ProgramError> {
+        let now = compat::unix_timestamp()?;
+        let Some((pricing_account, price_feed)) = pricing_accounts else {
+            return Ok(config.effective_registration_fee(name, now));
+        };
+        if pricing_account.key != &UsdPricing::find_address(config_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(pricing_account, program_id)?;
+        let pricing = UsdPricing::unpack(&pricing_account.data.borrow())?;
+        if price_feed.key != &pricing.price_feed {
+            return Err(NameRegistryError::InvalidPriceFeed.into());
+        }
+
+        let price = oracle::Price::unpack(&price_feed.data.borrow())?;
+        if now.saturating_sub(price.publish_time) > pricing.max_price_age as i64 {
+            return Err(NameRegistryError::StalePrice.into());
+        }
+        if price.confidence_bps() > pricing.max_confidence_bps as u64 {
+            return Err(NameRegistryError::PriceTooUncertain.into());
+        }
+        let fee = price
+            .cents_to_lamports(pricing.fee_schedule.fee_for(name))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(config.apply_promo(fee, now))
+    }
+
+    /// Unpacks the namespace in `namespace_account`, requiring `admin` to
+    /// run it.
+    fn unpack_namespace(
+        program_id: &Pubkey,
+        namespace_account: &AccountInfo,
+        admin: &AccountInfo,
+    ) -> Result<NamespaceAccount, ProgramError> {
+        if !admin.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        assert_owned_by(namespace_account, program_id)?;
+        assert_writable(namespace_account)?;
+        let namespace = NamespaceAccount::unpack(&namespace_account.data.borrow())?;
+        if namespace_account.key != &NamespaceAccount::find_address(&namespace.name_account, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if namespace.admin != *admin.key {
+            return Err(NameRegistryError::NotNamespaceAdmin.into());
+        }
+        Ok(namespace)
+    }
+
+    /// With a timelock set, the instructions it covers take the
+    /// PendingAdminAction PDA and the system program right after the
+    /// config. Splits those off the accounts that follow the config.
+    fn split_timelock_accounts<'b, 'a>(
+        config: &ProgramConfig,
+        accounts: &'b [AccountInfo<'a>],
+    ) -> Result<(Option<&'b [AccountInfo<'a>]>, &'b [AccountInfo<'a>]), ProgramError> {
+        if config.admin_timelock == 0 {
+            return Ok((None, accounts));
+        }
+        if accounts.len() < 2 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (timelock_accounts, remaining) = accounts.split_at(2);
+        Ok((Some(timelock_accounts), remaining))
+    }
+
+    /// Splits the accounts RotateFeeVault moves the fees through off the
+    /// front of `accounts`: the system program, then every current and
+    /// every next treasury bucket.
+    fn split_fee_vault_accounts<'b, 'a>(
+        accounts: &'b [AccountInfo<'a>],
+    ) -> Result<(&'b [AccountInfo<'a>], &'b [AccountInfo<'a>]), ProgramError> {
+        let len = 1 + 2 * FEE_BUCKETS as usize;
+        if accounts.len() < len {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        Ok(accounts.split_at(len))
+    }
+
+    /// Creates the next generation of treasury buckets, funded by `payer`,
+    /// and closes the current ones into them, moving every fee collected.
+    fn rotate_fee_vault<'a>(
+        program_id: &Pubkey,
+        config_account: &AccountInfo<'a>,
+        config: &mut ProgramConfig,
+        payer: &AccountInfo<'a>,
+        vault_accounts: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let (system_program, treasury_accounts) = vault_accounts.split_first()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let (old_treasuries, new_treasuries) = treasury_accounts.split_at(FEE_BUCKETS as usize);
+
+        let generation = config.fee_vault_generation.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        for (bucket, (old_treasury, new_treasury)) in (0..FEE_BUCKETS).zip(old_treasuries.iter().zip(new_treasuries)) {
+            Self::assert_treasury(program_id, config_account, config, bucket, old_treasury)?;
+            let (expected_treasury, bump) = find_fee_vault_address(config_account.key, generation, bucket, program_id);
+            if new_treasury.key != &expected_treasury {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            assert_writable(new_treasury)?;
+            create_program_account(
+                program_id,
+                payer,
+                new_treasury,
+                system_program,
+                0,
+                &[TREASURY_SEED, config_account.key.as_ref(), &[bucket], &[generation], &[bump]],
+            )?;
+        }
+
+        // Lamports move only after the CPIs above, which check balances
+        for (old_treasury, new_treasury) in old_treasuries.iter().zip(new_treasuries) {
+            close_program_account(old_treasury, new_treasury)?;
+        }
+        AdminAction::RotateFeeVault.apply(config);
+        Ok(())
+    }
+
+    /// Applies `action` to the config, or with a timelock set queues it in
+    /// the PendingAdminAction PDA, funded by `payer`.
+    fn queue_or_apply_admin_action<'a>(
+        program_id: &Pubkey,
+        config_account: &AccountInfo<'a>,
+        mut config: ProgramConfig,
+        payer: &AccountInfo<'a>,
+        timelock_accounts: Option<&[AccountInfo<'a>]>,
+        action: AdminAction,
+    ) -> ProgramResult {
+        let Some([action_account, system_program]) = timelock_accounts else {
+            action.apply(&mut config);
+            return ProgramConfig::pack_checked(config, &mut config_account.data.borrow_mut());
+        };
+
+        if system_program.key != &solana_program::system_program::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let (expected_action_account, bump) = PendingAdminAction::find_address(config_account.key, program_id);
+        if action_account.key != &expected_action_account {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(action_account)?;
+        if !action_account.data_is_empty() {
+            return Err(NameRegistryError::AdminActionPending.into());
+        }
+        create_program_account(
+            program_id,
+            payer,
+            action_account,
+            system_program,
+            PendingAdminAction::LEN,
+            &[ADMIN_ACTION_SEED, config_account.key.as_ref(), &[bump]],
+        )?;
+
+        let pending = PendingAdminAction {
+            is_initialized: true,
+            config: *config_account.key,
+            action,
+            executable_at: compat::unix_timestamp()? + config.admin_timelock as i64,
+        };
+        PendingAdminAction::pack_checked(pending, &mut action_account.data.borrow_mut())
+    }
+
+    /// Requires `signer` to own the name or to hold a `scope` approval its
+    /// owner gave, passed as `operator_account`.
+    fn assert_owner_or_operator(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+        name_data: &NameAccount,
+        signer: &Pubkey,
+        operator_account: Option<&AccountInfo>,
+        scope: OperatorScope,
+    ) -> ProgramResult {
+        if name_data.owner == *signer {
+            return Ok(());
+        }
+        let Some(operator_account) = operator_account else {
+            return Err(NameRegistryError::NotNameOwner.into());
+        };
+        if operator_account.key != &NameOperator::find_address(name_account.key, scope, signer, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if operator_account.owner != program_id {
+            return Err(NameRegistryError::OperatorNotApproved.into());
+        }
+        let approval = NameOperator::unpack_unchecked(&operator_account.data.borrow())?;
+        if !approval.approves(name_data) {
+            return Err(NameRegistryError::OperatorNotApproved.into());
+        }
+        Ok(())
+    }
+
+    /// Requires the co-signer guarding the name, if any, among `signers`.
+    /// Returns the state of the name's co-signer PDA, guarding or not.
+    fn assert_cosigned(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+        name_data: &NameAccount,
+        cosigner_account: &AccountInfo,
+        signers: &[AccountInfo],
+    ) -> Result<Option<NameCosigner>, ProgramError> {
+        let cosigner = Self::unpack_cosigner(program_id, name_account, cosigner_account)?;
+        if let Some(cosigner) = &cosigner {
+            if cosigner.guards(name_data) {
+                assert_signed_by(&cosigner.cosigner, signers)?;
+            }
+        }
+        Ok(cosigner)
+    }
+
+    /// While `config` has a blocklist, requires `proof` to show `name` is
+    /// missing from it, taking the blocklist PDA as the next account.
+    fn assert_unlisted<'a, 'b: 'a>(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        config: &ProgramConfig,
+        account_info_iter: &mut impl Iterator<Item = &'a AccountInfo<'b>>,
+        name: &str,
+        proof: Option<&NameProof>,
+    ) -> ProgramResult {
+        if !config.blocklist_enabled {
+            return Ok(());
+        }
+        let proof = proof.ok_or(NameRegistryError::NameProofRequired)?;
+        let blocklist_account = next_account_info(account_info_iter)?;
+        if blocklist_account.key != &Blocklist::find_address(config_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(blocklist_account, program_id)?;
+        let blocklist = Blocklist::unpack(&blocklist_account.data.borrow())?;
+        if !blocklist::proves_unlisted(&blocklist.root, name, proof) {
+            return Err(NameRegistryError::InvalidNameProof.into());
+        }
+        Ok(())
+    }
+
+    /// Counts a new name in `config`, which must have room for it under
+    /// `max_total_names`. Every path that registers a name goes through it.
+    fn count_registration(config: &mut ProgramConfig) -> ProgramResult {
+        validate_name_cap(config)?;
+        config.total_names = config.total_names.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        config.total_registrations = config.total_registrations.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Records `name_account` in the address claim of `owner`, created with
+    /// `payer`'s lamports, failing with `NameAlreadyRegistered` while the
+    /// claim holds another name.
+    fn take_address_claim<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        owner: &Pubkey,
+        name_account: &AccountInfo<'a>,
+        claim_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (expected_claim, bump) = AddressClaim::find_address(owner, program_id);
+        if claim_account.key != &expected_claim {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(claim_account)?;
+        if claim_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                payer,
+                claim_account,
+                system_program,
+                AddressClaim::LEN,
+                &[ADDRESS_CLAIM_SEED, owner.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(claim_account, program_id)?;
+        if AddressClaim::unpack_unchecked(&claim_account.data.borrow())?.is_initialized {
+            return Err(NameRegistryError::NameAlreadyRegistered.into());
+        }
+        let claim = AddressClaim {
+            is_initialized: true,
+            wallet: *owner,
+            name_account: *name_account.key,
+        };
+        AddressClaim::pack_checked(claim, &mut claim_account.data.borrow_mut())
+    }
+
+    /// Closes the address claim of `wallet`, `claim_account`, refunding
+    /// them, if it holds `name_account`. A wallet that got the name before
+    /// its config had `enforce_unique_address` holds no claim on it.
+    fn release_address_claim<'a>(
+        program_id: &Pubkey,
+        wallet: &AccountInfo<'a>,
+        name_account: &AccountInfo<'a>,
+        claim_account: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if claim_account.key != &AddressClaim::find_address(wallet.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if claim_account.owner != program_id {
+            return Ok(());
+        }
+        let claim = AddressClaim::unpack_unchecked(&claim_account.data.borrow())?;
+        if !claim.is_initialized || claim.name_account != *name_account.key {
+            return Ok(());
+        }
+        assert_writable(claim_account)?;
+        assert_writable(wallet)?;
+        close_program_account(claim_account, wallet)
+    }
+
+    /// Moves the address claim on `name_account` from `previous_owner` to
+    /// `new_owner`, whose claim is created with `payer`'s lamports. The
+    /// claims come next in `account_info_iter`: the previous owner's, then
+    /// the new owner's.
+    fn move_address_claim<'a, 'b: 'a>(
+        program_id: &Pubkey,
+        account_info_iter: &mut impl Iterator<Item = &'a AccountInfo<'b>>,
+        payer: &AccountInfo<'b>,
+        previous_owner: &AccountInfo<'b>,
+        new_owner: &Pubkey,
+        name_account: &AccountInfo<'b>,
+        system_program: &AccountInfo<'b>,
+    ) -> ProgramResult {
+        let previous_claim = next_account_info(account_info_iter)?;
+        let new_claim = next_account_info(account_info_iter)?;
+        if previous_owner.key == new_owner {
+            return Ok(());
+        }
+        Self::take_address_claim(program_id, payer, new_owner, name_account, new_claim, system_program)?;
+        Self::release_address_claim(program_id, previous_owner, name_account, previous_claim)
+    }
+
+    /// Appends `name_account` to the registry page `config` is filling,
+    /// creating the page when the last one is full.
+    fn append_to_registry_page<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        config_account: &AccountInfo,
+        config: &mut ProgramConfig,
+        name_account: &Pubkey,
+        page_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let index = config.registry_page();
+        let (expected_page, bump) = RegistryPage::find_address(config_account.key, index, program_id);
+        if page_account.key != &expected_page {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(page_account)?;
+        if page_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                payer,
+                page_account,
+                system_program,
+                RegistryPage::LEN,
+                &[REGISTRY_PAGE_SEED, config_account.key.as_ref(), &index.to_le_bytes(), &[bump]],
+            )?;
+        }
+        assert_owned_by(page_account, program_id)?;
+
+        let mut page = RegistryPage::unpack_unchecked(&page_account.data.borrow())?;
+        if !page.is_initialized {
+            page = RegistryPage {
+                is_initialized: true,
+                config: *config_account.key,
+                index,
+                names: Vec::new(),
+            };
+        }
+        page.names.push(*name_account);
+        RegistryPage::pack_checked(page, &mut page_account.data.borrow_mut())?;
+
+        config.indexed_names = config.indexed_names.checked_add(1).ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Unpacks the grant of `role` to `account` under the config, failing
+    /// with `RoleNotGranted` if there is none.
+    fn unpack_role(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        role_account: &AccountInfo,
+        role: Role,
+        account: &Pubkey,
+    ) -> Result<RoleGrant, ProgramError> {
+        if role_account.key != &RoleGrant::find_address(config_account.key, role, account, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if role_account.owner != program_id {
+            return Err(NameRegistryError::RoleNotGranted.into());
+        }
+        let grant = RoleGrant::unpack_unchecked(&role_account.data.borrow())?;
+        if !grant.is_initialized {
+            return Err(NameRegistryError::RoleNotGranted.into());
+        }
+        Ok(grant)
+    }
+
+    /// Creates `receipt_account` for `receipt`, funded by `payer`.
+    fn issue_receipt<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        receipt_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        receipt: ReceiptAccount,
+    ) -> ProgramResult {
+        let (expected_receipt, bump) =
+            ReceiptAccount::find_address(payer.key, &receipt.name_account, receipt.operation, program_id);
+        if receipt_account.key != &expected_receipt {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(receipt_account)?;
+        // An unclosed receipt for the same name and operation is never
+        // overwritten, so no paid fee drops out of the payer's records
+        if !receipt_account.data_is_empty() {
+            return Err(NameRegistryError::AlreadyInitialized.into());
+        }
+        create_program_account(
+            program_id,
+            payer,
+            receipt_account,
+            system_program,
+            ReceiptAccount::LEN,
+            &[
+                RECEIPT_SEED,
+                payer.key.as_ref(),
+                receipt.name_account.as_ref(),
+                &[receipt.operation as u8],
+                &[bump],
+            ],
+        )?;
+        ReceiptAccount::pack_checked(receipt, &mut receipt_account.data.borrow_mut())
+    }
+
+    /// Unpacks the co-signer PDA of `name_account`; `None` while no
+    /// co-signer is set.
+    fn unpack_cosigner(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+        cosigner_account: &AccountInfo,
+    ) -> Result<Option<NameCosigner>, ProgramError> {
+        if cosigner_account.key != &NameCosigner::find_address(name_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if cosigner_account.owner != program_id {
+            return Ok(None);
+        }
+        let cosigner = NameCosigner::unpack_unchecked(&cosigner_account.data.borrow())?;
+        Ok(Some(cosigner).filter(|cosigner| cosigner.is_initialized))
+    }
+
+    /// Checks that `owner` acts for the program: as the config owner or,
+    /// while a multisig is set, as one of enough members signing. `remaining`
+    /// then starts with the multisig PDA, followed by the other members.
+    fn assert_program_authority<'a>(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        config: &ProgramConfig,
+        owner: &AccountInfo<'a>,
+        remaining: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        if !config.multisig_enabled {
+            return validate_program_owner(&config.owner, owner.key);
+        }
+        let (multisig_account, cosigners) = remaining.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let multisig = Self::unpack_multisig(program_id, config_account, multisig_account)?;
+        Self::assert_multisig_approval(&multisig, owner, cosigners)
+    }
+
+    /// Splits the multisig PDA and the members signing with it, while a
+    /// multisig is set, off the end of `accounts`, for instructions taking a
+    /// variable number of accounts before them.
+    fn split_multisig_accounts<'b, 'a>(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        config: &ProgramConfig,
+        accounts: &'b [AccountInfo<'a>],
+    ) -> Result<(&'b [AccountInfo<'a>], &'b [AccountInfo<'a>]), ProgramError> {
+        if !config.multisig_enabled {
+            return Ok((accounts, &[]));
+        }
+        let multisig_account = MultisigConfig::find_address(config_account.key, program_id).0;
+        let position = accounts
+            .iter()
+            .rposition(|account| account.key == &multisig_account)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        Ok(accounts.split_at(position))
+    }
+
+    /// Checks the account receiving rent the program owner paid: the config
+    /// owner or, while a multisig is set, the treasury bucket of `name`,
+    /// which the members withdraw from together.
+    fn assert_rent_recipient(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        config: &ProgramConfig,
+        name: &str,
+        recipient: &AccountInfo,
+    ) -> ProgramResult {
+        if config.multisig_enabled {
+            return Self::assert_treasury(program_id, config_account, config, fee_bucket(name), recipient);
+        }
+        validate_program_owner(&config.owner, recipient.key)?;
+        assert_writable(recipient)
+    }
+
+    fn assert_multisig_approval<'a>(
+        multisig: &MultisigConfig,
+        owner: &AccountInfo<'a>,
+        cosigners: &[AccountInfo<'a>],
+    ) -> ProgramResult {
+        let signers = std::iter::once(owner)
+            .chain(cosigners)
+            .filter(|account| account.is_signer)
+            .map(|account| account.key);
+        if multisig.approvals(signers) < multisig.threshold as usize {
+            return Err(NameRegistryError::MultisigThresholdNotMet.into());
+        }
+        Ok(())
+    }
+
+    fn unpack_multisig(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        multisig_account: &AccountInfo,
+    ) -> Result<MultisigConfig, ProgramError> {
+        if multisig_account.key != &MultisigConfig::find_address(config_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(multisig_account, program_id)?;
+        MultisigConfig::unpack(&multisig_account.data.borrow())
+    }
+
+    /// Unpacks the program config, refusing one retired by MigrateConfig and
+    /// any account but the config PDA `["config"]`.
+    fn unpack_config(program_id: &Pubkey, config_account: &AccountInfo) -> Result<ProgramConfig, ProgramError> {
+        let config = Self::unpack_any_config(program_id, config_account)?;
+        if config_account.key != &find_config_address(program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(config)
+    }
+
+    /// Like `unpack_config`, but also takes configs created before the
+    /// config PDA, which only MigrateConfig still accepts.
+    fn unpack_any_config(program_id: &Pubkey, config_account: &AccountInfo) -> Result<ProgramConfig, ProgramError> {
+        assert_owned_by(config_account, program_id)?;
+        let config = ProgramConfig::unpack(&config_account.data.borrow())?;
+        if config.successor != Pubkey::default() {
+            return Err(NameRegistryError::ConfigMigrated.into());
+        }
+        Ok(config)
+    }
+
+    /// Fails with `ConfigNotLinked` unless `config_account` is the config the
+    /// program's config link points at.
+    fn assert_linked_config(
+        program_id: &Pubkey,
+        config_link: &AccountInfo,
+        config_account: &AccountInfo,
+    ) -> ProgramResult {
+        if config_link.key != &ConfigLink::find_address(program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(config_link, program_id)?;
+        let link = ConfigLink::unpack(&config_link.data.borrow())?;
+        if &link.config != config_account.key {
+            return Err(NameRegistryError::ConfigNotLinked.into());
+        }
+        Ok(())
+    }
+
+    /// Fails with `ProgramPaused` while the registry's circuit breaker is on.
+    fn assert_not_paused(program_id: &Pubkey, config_account: &AccountInfo) -> ProgramResult {
+        let config = Self::unpack_config(program_id, config_account)?;
+        validate_not_paused(&config)
+    }
+
+    /// Unpacks a registered name, checking that the account is the PDA of the
+    /// name it holds. Anything else is a forgery and fails with
+    /// `WrongDerivedAccount`.
+    fn unpack_derived_name(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+    ) -> Result<NameAccount, ProgramError> {
+        assert_owned_by(name_account, program_id)?;
+        let name_data = NameAccount::unpack_unchecked(&name_account.data.borrow())?;
+        if !name_data.is_initialized {
+            return Err(NameRegistryError::NameNotFound.into());
+        }
+        if name_account.key != &find_name_address(&name_data.name, program_id).0 {
+            return Err(NameRegistryError::WrongDerivedAccount.into());
+        }
+        Ok(name_data)
+    }
+
+    /// Zero-copy counterpart of `unpack_derived_name` for read-only handlers,
+    /// handing back the account data to read fields from in place.
+    fn borrow_derived_name<'a, 'b>(
+        program_id: &Pubkey,
+        name_account: &'a AccountInfo<'b>,
+    ) -> Result<Ref<'a, &'b mut [u8]>, ProgramError> {
+        assert_owned_by(name_account, program_id)?;
+        let name_data = name_account.data.borrow();
+        if !NameAccount::is_initialized_from_slice(&name_data)? {
+            return Err(NameRegistryError::NameNotFound.into());
+        }
+        if name_account.key != &find_name_address(NameAccount::name_from_slice(&name_data)?, program_id).0 {
+            return Err(NameRegistryError::WrongDerivedAccount.into());
+        }
+        Ok(name_data)
+    }
+
+    /// Unpacks a reverse record, rejecting accounts still in the legacy
+    /// `AddressAccount` layout.
+    #[allow(deprecated)]
+    fn unpack_reverse_record(address_account: &AccountInfo) -> Result<ReverseRecord, ProgramError> {
+        if address_account.data_len() == crate::state::AddressAccount::LEN {
+            return Err(NameRegistryError::LegacyAccountLayout.into());
+        }
+        ReverseRecord::unpack_unchecked(&address_account.data.borrow())
+    }
+
+    /// Creates the reverse record PDA of `name_account`, funded by `payer`,
+    /// unless it already exists.
+    fn create_address_account<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        name_account: &AccountInfo<'a>,
+        address_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (expected_address_account, bump) = find_address_account_address(name_account.key, program_id);
+        if address_account.key != &expected_address_account {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_writable(address_account)?;
+        if address_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                payer,
+                address_account,
+                system_program,
+                ReverseRecord::LEN,
+                &[ADDRESS_SEED, name_account.key.as_ref(), &[bump]],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Unpacks a live promo claim of `config_account`.
+    fn unpack_promo_claim(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        claim_account: &AccountInfo,
+    ) -> Result<PromoClaim, ProgramError> {
+        assert_owned_by(claim_account, program_id)?;
+        assert_writable(claim_account)?;
+        let claim = PromoClaim::unpack(&claim_account.data.borrow())?;
+        if claim.config != *config_account.key {
+            return Err(NameRegistryError::InvalidPromoClaim.into());
+        }
+        Ok(claim)
+    }
+
+    /// Unpacks the name reserved by `claim`, which must still be held by the
+    /// claim account.
+    fn unpack_claimed_name(
+        program_id: &Pubkey,
+        claim_account: &AccountInfo,
+        claim: &PromoClaim,
+        name_account: &AccountInfo,
+    ) -> Result<NameAccount, ProgramError> {
+        if name_account.key != &claim.name_account {
+            return Err(NameRegistryError::InvalidPromoClaim.into());
+        }
+        let name_data = Self::unpack_derived_name(program_id, name_account)?;
+        assert_writable(name_account)?;
+        if name_data.owner != *claim_account.key {
+            return Err(NameRegistryError::InvalidPromoClaim.into());
+        }
+        Ok(name_data)
+    }
+
+    /// Unpacks an auction of the registry behind `config_account`.
+    fn unpack_auction(
+        program_id: &Pubkey,
+        config_account: &AccountInfo,
+        auction_account: &AccountInfo,
+    ) -> Result<AuctionAccount, ProgramError> {
+        assert_owned_by(auction_account, program_id)?;
+        assert_writable(auction_account)?;
+        let auction = AuctionAccount::unpack(&auction_account.data.borrow())?;
+        if auction.config != *config_account.key {
+            return Err(NameRegistryError::InvalidAuction.into());
+        }
+        Ok(auction)
+    }
+
+    fn assert_auction_escrow(
+        program_id: &Pubkey,
+        auction_account: &AccountInfo,
+        escrow_account: &AccountInfo,
+    ) -> ProgramResult {
+        if escrow_account.key != &find_auction_escrow_address(auction_account.key, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(escrow_account, program_id)?;
+        assert_writable(escrow_account)
+    }
+
+    /// Reads the privacy preference of `wallet` from its optional settings PDA.
+    /// Wallets that never opted in have no settings account and are public.
+    fn is_private_wallet(
+        program_id: &Pubkey,
+        wallet: &Pubkey,
+        settings_account: Option<&AccountInfo>,
+    ) -> Result<bool, ProgramError> {
+        let settings_account = match settings_account {
+            Some(account) => account,
+            None => return Ok(false),
+        };
+
+        let (expected_settings, _) = WalletSettings::find_address(wallet, program_id);
+        if settings_account.key != &expected_settings {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if settings_account.data_is_empty() {
+            return Ok(false);
+        }
+        assert_owned_by(settings_account, program_id)?;
+
+        let settings = WalletSettings::unpack(&settings_account.data.borrow())?;
+        Ok(settings.privacy_mode)
+    }
+
+    /// Loads the settings PDA of `wallet`, creating it at the wallet's
+    /// expense on first use.
+    fn load_wallet_settings<'a>(
+        program_id: &Pubkey,
+        wallet: &AccountInfo<'a>,
+        settings_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+    ) -> Result<WalletSettings, ProgramError> {
+        assert_writable(settings_account)?;
+
+        let (expected_settings, bump) = WalletSettings::find_address(wallet.key, program_id);
+        if settings_account.key != &expected_settings {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if settings_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                wallet,
+                settings_account,
+                system_program,
+                WalletSettings::LEN,
+                &[WALLET_SETTINGS_SEED, wallet.key.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(settings_account, program_id)?;
+
+        let mut settings = WalletSettings::unpack_unchecked(&settings_account.data.borrow())?;
+        settings.is_initialized = true;
+        settings.wallet = *wallet.key;
+        Ok(settings)
+    }
+
+    /// Unpacks the record `kind` of the name in `name_account`.
+    fn unpack_record(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+        record_account: &AccountInfo,
+        kind: &str,
+    ) -> Result<RecordAccount, ProgramError> {
+        if record_account.key != &find_record_address(name_account.key, kind, program_id).0 {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        assert_owned_by(record_account, program_id)?;
+        RecordAccount::unpack(&record_account.data.borrow())
+    }
+
+    /// Requires `writer` to be granted the existing record `kind` by the
+    /// name's current owner; writers cannot create records.
+    fn assert_record_writer(
+        program_id: &Pubkey,
+        name_account: &AccountInfo,
+        name_data: &NameAccount,
+        record_account: &AccountInfo,
+        kind: &str,
+        writer: &Pubkey,
+    ) -> ProgramResult {
+        if record_account.data_is_empty() {
+            return Err(NameRegistryError::NotNameOwner.into());
+        }
+        let record = Self::unpack_record(program_id, name_account, record_account, kind)?;
+        if !record.allows_writer(name_data, writer) {
+            return Err(NameRegistryError::NotNameOwner.into());
+        }
+        Ok(())
+    }
+
+    /// Writes `record` to its PDA, creating it and adding it to the name's
+    /// record index if it is new. `owner` pays the rent. An existing record
+    /// keeps the writers granted on it.
+    fn write_record<'a>(
+        program_id: &Pubkey,
+        owner: &AccountInfo<'a>,
+        name_account: &AccountInfo<'a>,
+        record_account: &AccountInfo<'a>,
+        index_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        mut record: RecordAccount,
+    ) -> ProgramResult {
+        assert_writable(record_account)?;
+        let (expected_record, bump) = find_record_address(name_account.key, &record.kind, program_id);
+        if record_account.key != &expected_record {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if record_account.data_is_empty() {
+            let mut index = Self::load_record_index(program_id, owner, name_account, index_account, system_program)?;
+            if index.records.len() >= MAX_RECORDS_PER_NAME {
+                return Err(NameRegistryError::TooManyRecords.into());
+            }
+            create_program_account(
+                program_id,
+                owner,
+                record_account,
+                system_program,
+                RecordAccount::LEN,
+                &[RECORD_SEED, name_account.key.as_ref(), record.kind.as_bytes(), &[bump]],
+            )?;
+            index.records.push(*record_account.key);
+            RecordIndex::pack_checked(index, &mut index_account.data.borrow_mut())?;
+        } else {
+            let existing = Self::unpack_record(program_id, name_account, record_account, &record.kind)?;
+            record.writers_granted_by = existing.writers_granted_by;
+            record.writers = existing.writers;
+        }
+        assert_owned_by(record_account, program_id)?;
+        RecordAccount::pack_checked(record, &mut record_account.data.borrow_mut())
+    }
+
+    /// Loads the record index of a name, creating it on the first record.
+    fn load_record_index<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        name_account: &AccountInfo<'a>,
+        index_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+    ) -> Result<RecordIndex, ProgramError> {
+        assert_writable(index_account)?;
+
+        let (expected_index, bump) = find_record_index_address(name_account.key, program_id);
+        if index_account.key != &expected_index {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if index_account.data_is_empty() {
+            create_program_account(
+                program_id,
+                payer,
+                index_account,
+                system_program,
+                RecordIndex::LEN,
+                &[RECORD_INDEX_SEED, name_account.key.as_ref(), &[bump]],
+            )?;
+        }
+        assert_owned_by(index_account, program_id)?;
+
+        let mut index = RecordIndex::unpack_unchecked(&index_account.data.borrow())?;
+        index.is_initialized = true;
+        index.name_account = *name_account.key;
+        Ok(index)
+    }
+
+    /// Counts an address update or transfer offer opened by `wallet`,
+    /// failing with `TooManyPendingOperations` once the config cap is
+    /// exceeded.
+    fn open_pending_operation<'a>(
+        program_id: &Pubkey,
+        config: &ProgramConfig,
+        wallet: &AccountInfo<'a>,
+        settings_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let mut settings = Self::load_wallet_settings(program_id, wallet, settings_account, system_program)?;
+        settings.pending_operations = settings.pending_operations.saturating_add(1);
+        validate_pending_operations(config, settings.pending_operations)?;
+        WalletSettings::pack_checked(settings, &mut settings_account.data.borrow_mut())
+    }
+
+    /// Releases a pending operation of `wallet`. Operations opened before
+    /// they were counted have nothing to release, so a missing settings
+    /// account or a zero count is not an error.
+    fn close_pending_operation(
+        program_id: &Pubkey,
+        wallet: &Pubkey,
+        settings_account: &AccountInfo,
+    ) -> ProgramResult {
+        let (expected_settings, _) = WalletSettings::find_address(wallet, program_id);
+        if settings_account.key != &expected_settings {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if settings_account.data_is_empty() {
+            return Ok(());
+        }
+        assert_owned_by(settings_account, program_id)?;
+        assert_writable(settings_account)?;
+
+        let mut settings = WalletSettings::unpack(&settings_account.data.borrow())?;
+        settings.pending_operations = settings.pending_operations.saturating_sub(1);
+        WalletSettings::pack_checked(settings, &mut settings_account.data.borrow_mut())
+    }
+}
+
+/// Creates the rent-exempt PDA `account` owned by the program, funded by
+/// `payer`. `signer_seeds` must include the bump. PDA addresses are known
+/// in advance, and the system program refuses to create an account that
+/// already holds lamports, so lamports sent there beforehand count towards
+/// the rent instead.
+fn create_program_account<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent = compat::rent()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(payer.key, account.key, rent, space as u64, program_id),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[signer_seeds],
+        );
+    }
+
+    let rent_due = rent.saturating_sub(account.lamports());
+    if rent_due > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, rent_due),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    let accounts = [account.clone(), system_program.clone()];
+    invoke_signed(&system_instruction::allocate(account.key, space as u64), &accounts, &[signer_seeds])?;
+    invoke_signed(&system_instruction::assign(account.key, program_id), &accounts, &[signer_seeds])
+}
+
+/// Closes `account`, moving all its lamports to `destination` and zeroing
+/// its data so it can no longer be read as initialized state.
+fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let refund = account.lamports();
+    **account.lamports.borrow_mut() = 0;
+    **destination.lamports.borrow_mut() = destination.lamports().checked_add(refund)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    account.data.borrow_mut().fill(0);
+    Ok(())
+}
+
+/// Logs the resolution of `name`; callers resolving through CPI don't pay
+/// for the log line.
+fn log_resolved(name: &str, address: &Pubkey) {
+    if !compat::is_cpi() {
+        msg!("{}", formats::resolved_event(name, address));
+    }
+}
+
+/// Logs a structured `registry:` event from `formats`, unless the build
+/// opts out of program logs with `no-log-ix-name`.
+fn log_event(event: String) {
+    if cfg!(not(feature = "no-log-ix-name")) {
+        msg!("{}", event);
+    }
+}
+
+/// Requires `key` to have signed as one of `signers`.
+fn assert_signed_by(key: &Pubkey, signers: &[AccountInfo]) -> ProgramResult {
+    if !signers.iter().any(|account| account.is_signer && account.key == key) {
+        return Err(NameRegistryError::CosignerRequired.into());
+    }
+    Ok(())
+}
+
+/// Rejects owner-driven ownership changes of a tokenized name, which follows
+/// its token instead.
+fn assert_not_tokenized(name_data: &NameAccount) -> ProgramResult {
+    if name_data.tokenized {
+        return Err(NameRegistryError::NameTokenized.into());
+    }
+    Ok(())
+}
+
+/// Rejects changes to a name taken down by FreezeName.
+fn assert_not_frozen(name_data: &NameAccount) -> ProgramResult {
+    if name_data.frozen {
+        return Err(NameRegistryError::NameFrozen.into());
+    }
+    Ok(())
+}
+
+/// Rejects changes to a name still in its cooldown, logging when it ends.
+fn assert_cooldown_over(name_data: &NameAccount) -> ProgramResult {
+    validate_cooldown(name_data.cooldown_until).inspect_err(|_| {
+        log_event(formats::cooldown_event(&name_data.name, name_data.cooldown_until));
+    })
+}
@@ -1,116 +1,1852 @@
-use solana_program::{
-    program_error::ProgramError,
-    program_pack::{Pack, IsInitialized, Sealed},
-    pubkey::Pubkey,
-};
-use borsh::{BorshDeserialize, BorshSerialize};
-
-#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
-pub struct NameAccount {
-    pub is_initialized: bool,
-    pub owner: Pubkey,
-    pub name: String,
-    pub address: Pubkey,
-    pub cooldown_until: i64,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
-pub struct AddressAccount {
-    pub is_initialized: bool,
-    pub name: String,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
-pub struct PendingUpdateAccount {
-    pub is_initialized: bool,
-    pub new_address: Pubkey,
-}
-
-#[derive(BorshSerialize, BorshDeserialize, Debug, Default)]
-pub struct ProgramConfig {
-    pub is_initialized: bool,
-    pub owner: Pubkey,
-    pub pending_owner: Pubkey,
-    pub registration_fee: u64,
-}
-
-impl Sealed for NameAccount {}
-impl Sealed for AddressAccount {}
-impl Sealed for PendingUpdateAccount {}
-impl Sealed for ProgramConfig {}
-
-impl IsInitialized for NameAccount {
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
-    }
-}
-
-impl IsInitialized for AddressAccount {
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
-    }
-}
-
-impl IsInitialized for PendingUpdateAccount {
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
-    }
-}
-
-impl IsInitialized for ProgramConfig {
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
-    }
-}
-
-impl Pack for NameAccount {
-    const LEN: usize = 1 + 32 + 32 + 32 + 8 + 4; // is_initialized + owner + name (max 32) + address + cooldown + name length prefix
-
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let data = self.try_to_vec().unwrap();
-        dst[..data.len()].copy_from_slice(&data);
-    }
-
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        Self::try_from_slice(src).map_err(|_| ProgramError::InvalidAccountData)
-    }
-}
-
-impl Pack for AddressAccount {
-    const LEN: usize = 1 + 4 + 32; // is_initialized + name length prefix + name (max 32)
-
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let data = self.try_to_vec().unwrap();
-        dst[..data.len()].copy_from_slice(&data);
-    }
-
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        Self::try_from_slice(src).map_err(|_| ProgramError::InvalidAccountData)
-    }
-}
-
-impl Pack for PendingUpdateAccount {
-    const LEN: usize = 1 + 32; // is_initialized + new_address
-
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let data = self.try_to_vec().unwrap();
-        dst[..data.len()].copy_from_slice(&data);
-    }
-
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        Self::try_from_slice(src).map_err(|_| ProgramError::InvalidAccountData)
-    }
-}
-
-impl Pack for ProgramConfig {
-    const LEN: usize = 1 + 32 + 32 + 8; // is_initialized + owner + pending_owner + fee
-
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let data = self.try_to_vec().unwrap();
-        dst[..data.len()].copy_from_slice(&data);
-    }
-
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        Self::try_from_slice(src).map_err(|_| ProgramError::InvalidAccountData)
-    }
-} 
\ No newline at end of file
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{Pack, IsInitialized, Sealed},
+    pubkey::Pubkey,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{error::NameRegistryError, validation::DEFAULT_COOLDOWN_PERIOD};
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct NameAccount {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub name: String,
+    pub address: Pubkey,
+    pub cooldown_until: i64,
+    pub target_set: bool,
+    /// Name account this subname hangs off; default for top-level names.
+    pub parent: Pubkey,
+    /// Wallet offered the name by a two-step TransferName; default if none.
+    pub pending_owner: Pubkey,
+    /// Whether ResolveSubname answers for subnames of this name that don't
+    /// exist with this name's address.
+    pub wildcard_subdomains: bool,
+    /// Whether TokenizeName minted the name's token, whose holder owns it.
+    pub tokenized: bool,
+    /// Unix timestamp at which the name was registered to its first owner;
+    /// kept across renames.
+    pub registered_at: i64,
+    /// Times the name changed hands after registration.
+    pub transfer_count: u32,
+    /// ResolveAddress calls that passed the name account writable.
+    pub resolve_count: u64,
+    /// Whether FreezeName took the name down: a frozen name neither resolves
+    /// nor changes hands or name until UnfreezeName.
+    pub frozen: bool,
+    /// Program owner that last froze the name, kept after UnfreezeName;
+    /// default if never frozen.
+    pub frozen_by: Pubkey,
+    /// Unix timestamp of the last FreezeName; zero if never frozen.
+    pub frozen_at: i64,
+}
+
+/// Public reverse record of a name: the wallet the name resolves to and the
+/// name itself, stored at the PDA `["address", name_account]`. Left
+/// uninitialized for names owned by private wallets.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ReverseRecord {
+    pub is_initialized: bool,
+    pub wallet: Pubkey,
+    pub name: String,
+}
+
+#[allow(deprecated)]
+pub use legacy::AddressAccount;
+
+/// Address update armed on a name, stored at the PDA
+/// `["pending_update", name_account]`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct PendingUpdateAccount {
+    pub is_initialized: bool,
+    pub new_address: Pubkey,
+    /// Wallet that armed the update and is charged a pending operation for
+    /// it.
+    pub requester: Pubkey,
+    /// Name the update applies to.
+    pub name_account: Pubkey,
+    /// Unix timestamp of the last RequestAddressUpdate.
+    pub requested_at: i64,
+    /// Unix timestamp from which the update can no longer be completed, or
+    /// zero if it does not expire.
+    pub expires_at: i64,
+}
+
+/// Per-wallet preferences, stored at the PDA `["wallet", wallet]`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct WalletSettings {
+    pub is_initialized: bool,
+    pub wallet: Pubkey,
+    /// When set, names owned by the wallet get no public reverse record.
+    pub privacy_mode: bool,
+    /// Address updates and transfer offers the wallet has open.
+    pub pending_operations: u16,
+}
+
+/// Primary (display) name chosen by a wallet, stored at the PDA
+/// `["reverse", wallet]`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct PrimaryNameRecord {
+    pub is_initialized: bool,
+    pub wallet: Pubkey,
+    pub name: String,
+}
+
+/// Free-form record attached to a name (avatar URL, social handle, IPFS CID,
+/// ...), stored at the PDA `["record", name_account, kind]`. Besides the
+/// name owner and its `Records` operators, the `writers` granted by
+/// `writers_granted_by` may overwrite it while that wallet owns the name.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct RecordAccount {
+    pub is_initialized: bool,
+    pub name_account: Pubkey,
+    pub kind: String,
+    pub value: String,
+    pub writers_granted_by: Pubkey,
+    pub writers: Vec<Pubkey>,
+}
+
+impl RecordAccount {
+    /// Whether `writer` may overwrite the record of `name`, i.e. the name's
+    /// current owner granted it.
+    pub fn allows_writer(&self, name: &NameAccount, writer: &Pubkey) -> bool {
+        self.writers_granted_by == name.owner && self.writers.contains(writer)
+    }
+}
+
+/// Addresses of the records a name holds, in creation order, stored at the
+/// PDA `["record_index", name_account]` and kept by SetRecord and
+/// DeleteRecord. Lets clients list records without knowing their kinds.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct RecordIndex {
+    pub is_initialized: bool,
+    pub name_account: Pubkey,
+    pub records: Vec<Pubkey>,
+}
+
+/// Second signer a name owner requires for TransferName, RenameName and
+/// RequestAddressUpdate on the name, stored at the PDA
+/// `["cosigner", name_account]`. Set by `owner` and void once the name has
+/// another owner.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct NameCosigner {
+    pub is_initialized: bool,
+    pub name_account: Pubkey,
+    pub owner: Pubkey,
+    pub cosigner: Pubkey,
+}
+
+/// Owner-managed notice for integrated front-ends, stored at the PDA
+/// `["banner", config]`. An empty message means no notice is active.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct BannerMessage {
+    pub is_initialized: bool,
+    pub message: String,
+}
+
+/// Name reserved for whoever reveals the preimage of a commitment, stored at
+/// the PDA `["promo_claim", config, commitment]`. The name account is owned
+/// by the claim until it is claimed or, after `expires_at`, reclaimed.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct PromoClaim {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub name_account: Pubkey,
+    pub expires_at: i64,
+}
+
+/// Running auction of a premium name, stored at the PDA
+/// `["auction", name_account]`. The name account is owned by the auction
+/// until it is settled, and the highest bid sits in the escrow PDA
+/// `["auction_escrow", auction]`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct AuctionAccount {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub name_account: Pubkey,
+    pub min_bid: u64,
+    pub ends_at: i64,
+    /// Default until the first bid.
+    pub highest_bidder: Pubkey,
+    pub highest_bid: u64,
+}
+
+/// Private note of a name owner, stored at the PDA
+/// `["note", name_account, owner]`. `data` is encrypted client-side (e.g.
+/// with a key derived from an owner signature); the program never reads it.
+/// A new owner gets a fresh slot, and the old owner keeps the right to
+/// delete theirs.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct NoteAccount {
+    pub is_initialized: bool,
+    pub name_account: Pubkey,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// M-of-N multisig acting as the program owner for every instruction the
+/// program owner authorizes, stored at the PDA `["multisig", config]`. In
+/// force while `ProgramConfig::multisig_enabled`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct MultisigConfig {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    /// Distinct member signatures required, at least one.
+    pub threshold: u8,
+    pub signers: Vec<Pubkey>,
+}
+
+/// Admin change held back by `ProgramConfig::admin_timelock`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AdminAction {
+    SetFeeSchedule(FeeSchedule),
+    ChangeProgramOwner(Pubkey),
+    SetAdminTimelock(u32),
+    SetRenameFee(u64),
+    SetUpdateFee(u64),
+    SchedulePromo { start: i64, end: i64, fee_override_bps: u16 },
+    SetReferralBps(u16),
+    /// ExecuteAdminAction also moves the fees into the new buckets.
+    RotateFeeVault,
+}
+
+/// Admin action queued while a timelock is set, stored at the PDA
+/// `["admin_action", config]` until ExecuteAdminAction applies it at
+/// `executable_at` or CancelAdminAction drops it. One action is queued at a
+/// time.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PendingAdminAction {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub action: AdminAction,
+    pub executable_at: i64,
+}
+
+/// Fee-paying operation a receipt is issued for.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum PaidOperation {
+    /// RegisterName or RevealAndRegister.
+    #[default]
+    Registration,
+}
+
+/// Proof of a fee paid, issued on request to the payer at the PDA
+/// `["receipt", payer, name_account, operation]` for on-chain expense
+/// accounting. Kept until the payer closes it with CloseReceipts.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ReceiptAccount {
+    pub is_initialized: bool,
+    pub payer: Pubkey,
+    pub name_account: Pubkey,
+    pub operation: PaidOperation,
+    /// Lamports paid, including any referrer's share.
+    pub amount: u64,
+    pub slot: u64,
+}
+
+/// Reservation of a name by the program owner, stored at the PDA
+/// `["reserved", name_account]`. The reservation holds the name account, so
+/// the name cannot be registered until ReleaseReservedName.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ReservedName {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub name_account: Pubkey,
+}
+
+/// Role the program owner grants a wallet with GrantRole.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Role {
+    /// Registers names for other wallets with RegisterNameAsRegistrar.
+    #[default]
+    Registrar,
+}
+
+/// Role granted to `account` by the program owner, stored at the PDA
+/// `["role", config, role, account]` until RevokeRole.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct RoleGrant {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub account: Pubkey,
+    pub role: Role,
+    /// Share of the registration fee a registrar pays, in basis points;
+    /// zero registers for free.
+    pub fee_bps: u16,
+}
+
+/// Name a wallet got while its config had `enforce_unique_address`, stored at
+/// the PDA `["address_claim", wallet]`. The wallet cannot get another name
+/// while the claim exists. Passing the name on moves the claim along;
+/// ReleaseAddressClaim closes it once the wallet no longer owns
+/// `name_account`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct AddressClaim {
+    pub is_initialized: bool,
+    pub wallet: Pubkey,
+    pub name_account: Pubkey,
+}
+
+/// What an operator approved with ApproveOperator may do on a name.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum OperatorScope {
+    /// SetRecord and DeleteRecord.
+    #[default]
+    Records,
+}
+
+/// Key `owner` lets act on their name within `scope`, stored at the PDA
+/// `["operator", name_account, scope, operator]` until RevokeOperator. Void
+/// once the name has another owner.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct NameOperator {
+    pub is_initialized: bool,
+    pub name_account: Pubkey,
+    pub owner: Pubkey,
+    pub operator: Pubkey,
+    pub scope: OperatorScope,
+}
+
+/// Registry of the names ending in `.<suffix>`, stored at the PDA
+/// `["namespace", name_account]` of the suffix's name account, which it
+/// holds so the suffix can be neither registered nor subnamed. Registering
+/// a name of the namespace pays `fee` into this account for `admin` to
+/// withdraw.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct NamespaceAccount {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub name_account: Pubkey,
+    pub admin: Pubkey,
+    pub fee: u64,
+    /// Whether registrations in the namespace are refused.
+    pub paused: bool,
+    /// Program the admin delegated the namespace's registrations to, see
+    /// `find_authority_address`; default while anyone may register.
+    pub delegate_program: Pubkey,
+}
+
+/// Merkle root of the names a config refuses to register, stored at the PDA
+/// `["blocklist", config]` and rotated by SetBlocklistRoot. Registrations
+/// prove their name is not on the list, see `blocklist`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct Blocklist {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub root: [u8; 32],
+}
+
+/// Registration fees of a config in US cents, stored at the PDA
+/// `["usd_pricing", config]` and set by SetUsdPricing. Registrations convert
+/// them to lamports at the SOL/USD price of `price_feed`, a Pyth price
+/// account, refusing prices older than `max_price_age` seconds or less
+/// certain than `max_confidence_bps`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct UsdPricing {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub price_feed: Pubkey,
+    pub fee_schedule: FeeSchedule,
+    pub max_price_age: u32,
+    pub max_confidence_bps: u16,
+}
+
+/// Address of the program's one config, stored at the PDA `["config_link"]`
+/// by Initialize and moved along by MigrateConfig. New configs live at the
+/// config PDA anyway; for registries from before it, the link is what tells
+/// their config from config-shaped accounts someone else created.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ConfigLink {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+}
+
+/// Names registered while `ProgramConfig::registry_pages_enabled`, in
+/// registration order and `REGISTRY_PAGE_CAPACITY` to a page, stored at the
+/// PDA `["registry_page", config, index]`. Lets indexers and other programs
+/// enumerate names without scanning program accounts. Entries are never
+/// removed: a name renamed or unregistered later leaves behind an account
+/// that no longer holds it.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct RegistryPage {
+    pub is_initialized: bool,
+    pub config: Pubkey,
+    pub index: u32,
+    pub names: Vec<Pubkey>,
+}
+
+/// What UnregisterName leaves of a top-level name while the config has a
+/// `tombstone_period`, stored at the PDA `["tombstone", name_account]`:
+/// until `expires_at`, resolving the name while it is unregistered fails
+/// with `NameRecentlyReleased`, so that payers holding a cached address
+/// find out the name is gone. CloseTombstone refunds its rent afterwards.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct NameTombstone {
+    pub is_initialized: bool,
+    pub name_account: Pubkey,
+    /// Owner that unregistered the name.
+    pub previous_owner: Pubkey,
+    /// Address the name resolved to when it was unregistered.
+    pub previous_address: Pubkey,
+    /// Unix timestamp of the UnregisterName.
+    pub released_at: i64,
+    pub expires_at: i64,
+    /// Owner that created the tombstone and gets its rent back from
+    /// CloseTombstone; kept when the name is unregistered again.
+    pub payer: Pubkey,
+}
+
+/// Commitment to a name a wallet intends to register, stored at the PDA
+/// `["commitment", committer, commitment]` until RevealAndRegister.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct NameCommitment {
+    pub is_initialized: bool,
+    pub committer: Pubkey,
+    pub committed_at: i64,
+}
+
+/// Registration fees in lamports by name length, in characters. Short names
+/// are scarce and priced higher to discourage squatting.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeSchedule {
+    pub one_char: u64,
+    pub two_char: u64,
+    pub three_to_four_char: u64,
+    pub five_plus_char: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ProgramConfig {
+    pub is_initialized: bool,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub fee_schedule: FeeSchedule,
+    /// Discount window `[promo_start, promo_end)` in unix time; empty when
+    /// no promo is scheduled.
+    pub promo_start: i64,
+    pub promo_end: i64,
+    /// Fee charged during the promo, in basis points of the scheduled fee.
+    pub promo_fee_override_bps: u16,
+    /// Whether RegisterName logs an anonymized analytics event.
+    pub analytics_enabled: bool,
+    /// Circuit breaker: while set, names cannot be registered, renamed,
+    /// transferred or repointed.
+    pub paused: bool,
+    /// Address updates and transfer offers a wallet may have open at once;
+    /// zero lifts the cap.
+    pub max_pending_operations: u16,
+    /// Share of the registration fee paid to a referrer, in basis points.
+    pub referral_bps: u16,
+    /// Names registered through RegisterName.
+    pub total_names: u64,
+    /// Cap on `total_names`, e.g. during a beta; zero lifts the cap.
+    pub max_total_names: u64,
+    /// Whether new names may use the curated Unicode subset of
+    /// `validate_name_for` besides ASCII.
+    pub allow_unicode_names: bool,
+    /// Whether names must be registered through CommitName and
+    /// RevealAndRegister, so pending registrations cannot be front-run.
+    pub commit_reveal_required: bool,
+    /// Whether the MultisigConfig of this config, rather than `owner`,
+    /// authorizes the instructions it covers.
+    pub multisig_enabled: bool,
+    /// Version of the config's settings, `ProgramConfig::VERSION` when
+    /// written by Initialize or MigrateConfig. Unrelated to the
+    /// `LAYOUT_VERSION` of the account header; see `ProgramConfig::VERSION`.
+    pub version: u8,
+    /// Config PDA this one was migrated to by MigrateConfig; default while
+    /// live. Handlers refuse a config that has a successor.
+    pub successor: Pubkey,
+    /// Seconds SetRegistrationFee, SetFeeSchedule, SetRenameFee,
+    /// SetUpdateFee, SchedulePromo, SetReferralBps, RotateFeeVault,
+    /// ChangeProgramOwner and SetAdminTimelock wait in a PendingAdminAction
+    /// before they apply; zero applies them at once.
+    pub admin_timelock: u32,
+    /// Whether registrations must prove their name is missing from the
+    /// `Blocklist` of this config.
+    pub blocklist_enabled: bool,
+    /// Seconds a name stays locked after it changes hands or address; read
+    /// it through `cooldown_period`.
+    pub cooldown_seconds: u32,
+    /// Whether a name takes the `AddressClaim` of its owner, so that a wallet
+    /// holds one name at a time.
+    pub enforce_unique_address: bool,
+    /// Seconds an address update stays claimable after it is requested;
+    /// zero lets updates wait until completed or cancelled.
+    pub pending_update_ttl: u32,
+    /// Whether registration fees are set in US cents by the `UsdPricing` of
+    /// this config rather than in lamports by `fee_schedule`.
+    pub usd_pricing_enabled: bool,
+    /// Lamports RenameName charges into the treasury bucket of the new
+    /// name.
+    pub rename_fee: u64,
+    /// Lamports CompleteAddressUpdate charges the new owner into the
+    /// treasury bucket of the name.
+    pub update_fee: u64,
+    /// Names ever registered, never decremented by UnregisterName.
+    pub total_registrations: u64,
+    /// Whether RegisterName appends each new name to a `RegistryPage`.
+    pub registry_pages_enabled: bool,
+    /// Names appended to registry pages so far.
+    pub indexed_names: u64,
+    /// Seconds UnregisterName keeps a `NameTombstone` of the name; zero
+    /// leaves none.
+    pub tombstone_period: u32,
+    /// Generation of the treasury buckets collecting fees, moved on by
+    /// RotateFeeVault; see `pda::find_fee_vault_address`.
+    pub fee_vault_generation: u8,
+    /// Room for fields added later, which took their bytes from here. Now
+    /// used up: later fields grow the config through MigrateAccount.
+    pub reserved: [u8; 0],
+}
+
+pub const WALLET_SETTINGS_SEED: &[u8] = b"wallet";
+
+impl WalletSettings {
+    pub fn find_address(wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[WALLET_SETTINGS_SEED, wallet.as_ref()], program_id)
+    }
+}
+
+pub const MULTISIG_SEED: &[u8] = b"multisig";
+
+impl MultisigConfig {
+    pub fn find_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[MULTISIG_SEED, config.as_ref()], program_id)
+    }
+
+    /// Number of distinct members among `signers`.
+    pub fn approvals<'a>(&self, signers: impl IntoIterator<Item = &'a Pubkey>) -> usize {
+        let mut approved: Vec<&Pubkey> = signers.into_iter().filter(|key| self.signers.contains(key)).collect();
+        approved.sort();
+        approved.dedup();
+        approved.len()
+    }
+}
+
+pub const COSIGNER_SEED: &[u8] = b"cosigner";
+
+impl NameCosigner {
+    pub fn find_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[COSIGNER_SEED, name_account.as_ref()], program_id)
+    }
+
+    /// Whether the co-signer still guards the name, i.e. it was set by the
+    /// name's current owner.
+    pub fn guards(&self, name: &NameAccount) -> bool {
+        self.is_initialized && self.owner == name.owner
+    }
+}
+
+pub const PENDING_UPDATE_SEED: &[u8] = b"pending_update";
+
+impl PendingUpdateAccount {
+    pub fn find_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[PENDING_UPDATE_SEED, name_account.as_ref()], program_id)
+    }
+}
+
+pub const RECEIPT_SEED: &[u8] = b"receipt";
+
+impl ReceiptAccount {
+    pub fn find_address(
+        payer: &Pubkey,
+        name_account: &Pubkey,
+        operation: PaidOperation,
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[RECEIPT_SEED, payer.as_ref(), name_account.as_ref(), &[operation as u8]],
+            program_id,
+        )
+    }
+}
+
+pub const RESERVED_SEED: &[u8] = b"reserved";
+
+impl ReservedName {
+    pub fn find_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[RESERVED_SEED, name_account.as_ref()], program_id)
+    }
+}
+
+pub const BLOCKLIST_SEED: &[u8] = b"blocklist";
+
+impl Blocklist {
+    pub fn find_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[BLOCKLIST_SEED, config.as_ref()], program_id)
+    }
+}
+
+pub const ADDRESS_CLAIM_SEED: &[u8] = b"address_claim";
+
+impl AddressClaim {
+    pub fn find_address(wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ADDRESS_CLAIM_SEED, wallet.as_ref()], program_id)
+    }
+}
+
+pub const OPERATOR_SEED: &[u8] = b"operator";
+
+impl NameOperator {
+    pub fn find_address(
+        name_account: &Pubkey,
+        scope: OperatorScope,
+        operator: &Pubkey,
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[OPERATOR_SEED, name_account.as_ref(), &[scope as u8], operator.as_ref()],
+            program_id,
+        )
+    }
+
+    /// Whether the approval still stands for `name`, i.e. it was given by
+    /// the name's current owner.
+    pub fn approves(&self, name: &NameAccount) -> bool {
+        self.is_initialized && self.owner == name.owner
+    }
+}
+
+pub const NAMESPACE_SEED: &[u8] = b"namespace";
+pub const NAMESPACE_AUTHORITY_SEED: &[u8] = b"namespace_authority";
+pub const USD_PRICING_SEED: &[u8] = b"usd_pricing";
+pub const CONFIG_LINK_SEED: &[u8] = b"config_link";
+pub const REGISTRY_PAGE_SEED: &[u8] = b"registry_page";
+
+/// Names a `RegistryPage` holds.
+pub const REGISTRY_PAGE_CAPACITY: usize = 64;
+
+impl ConfigLink {
+    pub fn find_address(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[CONFIG_LINK_SEED], program_id)
+    }
+}
+
+impl RegistryPage {
+    pub fn find_address(config: &Pubkey, index: u32, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[REGISTRY_PAGE_SEED, config.as_ref(), &index.to_le_bytes()], program_id)
+    }
+}
+
+pub const TOMBSTONE_SEED: &[u8] = b"tombstone";
+
+impl NameTombstone {
+    pub fn find_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[TOMBSTONE_SEED, name_account.as_ref()], program_id)
+    }
+}
+
+impl UsdPricing {
+    pub fn find_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[USD_PRICING_SEED, config.as_ref()], program_id)
+    }
+}
+
+impl NamespaceAccount {
+    /// `name_account` is the name account of the namespace's suffix.
+    pub fn find_address(name_account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[NAMESPACE_SEED, name_account.as_ref()], program_id)
+    }
+
+    /// Registrant of every name of a delegated namespace: the PDA
+    /// `["namespace_authority", namespace]` of its `delegate_program`, which
+    /// only that program can sign for.
+    pub fn find_authority_address(namespace: &Pubkey, delegate_program: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[NAMESPACE_AUTHORITY_SEED, namespace.as_ref()], delegate_program)
+    }
+}
+
+pub const ROLE_SEED: &[u8] = b"role";
+
+impl RoleGrant {
+    pub fn find_address(config: &Pubkey, role: Role, account: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ROLE_SEED, config.as_ref(), &[role as u8], account.as_ref()], program_id)
+    }
+
+    /// Part of `fee` the holder of the grant pays.
+    pub fn fee_share(&self, fee: u64) -> u64 {
+        (fee as u128 * self.fee_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+}
+
+pub const ADMIN_ACTION_SEED: &[u8] = b"admin_action";
+
+impl PendingAdminAction {
+    pub fn find_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ADMIN_ACTION_SEED, config.as_ref()], program_id)
+    }
+}
+
+impl AdminAction {
+    pub fn apply(&self, config: &mut ProgramConfig) {
+        match *self {
+            AdminAction::SetFeeSchedule(schedule) => config.fee_schedule = schedule,
+            AdminAction::ChangeProgramOwner(new_owner) => config.pending_owner = new_owner,
+            AdminAction::SetAdminTimelock(seconds) => config.admin_timelock = seconds,
+            AdminAction::SetRenameFee(fee) => config.rename_fee = fee,
+            AdminAction::SetUpdateFee(fee) => config.update_fee = fee,
+            AdminAction::SchedulePromo { start, end, fee_override_bps } => {
+                config.promo_start = start;
+                config.promo_end = end;
+                config.promo_fee_override_bps = fee_override_bps;
+            }
+            AdminAction::SetReferralBps(referral_bps) => config.referral_bps = referral_bps,
+            AdminAction::RotateFeeVault => config.fee_vault_generation += 1,
+        }
+    }
+}
+
+pub const BANNER_SEED: &[u8] = b"banner";
+
+impl BannerMessage {
+    pub fn find_address(config: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[BANNER_SEED, config.as_ref()], program_id)
+    }
+}
+
+pub const BPS_DENOMINATOR: u16 = 10_000;
+
+impl FeeSchedule {
+    /// Schedule charging `fee` for names of every length.
+    pub fn flat(fee: u64) -> Self {
+        Self {
+            one_char: fee,
+            two_char: fee,
+            three_to_four_char: fee,
+            five_plus_char: fee,
+        }
+    }
+
+    /// Fee of registering `name`.
+    pub fn fee_for(&self, name: &str) -> u64 {
+        match name.chars().count() {
+            0 | 1 => self.one_char,
+            2 => self.two_char,
+            3 | 4 => self.three_to_four_char,
+            _ => self.five_plus_char,
+        }
+    }
+}
+
+impl ProgramConfig {
+    /// Version 2 added `cooldown_seconds`.
+    ///
+    /// This versions what the settings mean, while `LAYOUT_VERSION` versions
+    /// how accounts are laid out, and the two move independently:
+    /// `cooldown_seconds` took bytes that were reserved, and so zero, in
+    /// configs of either layout version, and MigrateAccount only touches the
+    /// layout. A config migrated to layout version 2 stays at version 1, on
+    /// the fixed cooldown, until MigrateConfig writes it.
+    pub const VERSION: u8 = 2;
+
+    /// Seconds a name stays locked after it changes hands or address.
+    /// Configs last written before version 2 keep the fixed day they were
+    /// deployed with.
+    pub fn cooldown_period(&self) -> u32 {
+        if self.version < 2 {
+            DEFAULT_COOLDOWN_PERIOD
+        } else {
+            self.cooldown_seconds
+        }
+    }
+
+    /// Fee charged for registering `name` at `now`, with any active promo
+    /// applied.
+    pub fn effective_registration_fee(&self, name: &str, now: i64) -> u64 {
+        self.apply_promo(self.fee_schedule.fee_for(name), now)
+    }
+
+    /// Part of `fee` owed to the referrer of a registration.
+    pub fn referral_share(&self, fee: u64) -> u64 {
+        (fee as u128 * self.referral_bps as u128 / BPS_DENOMINATOR as u128) as u64
+    }
+
+    /// Index of the registry page the next registered name goes into.
+    pub fn registry_page(&self) -> u32 {
+        (self.indexed_names / REGISTRY_PAGE_CAPACITY as u64) as u32
+    }
+
+    /// `fee` as charged at `now`, discounted while a promo is active.
+    pub fn apply_promo(&self, fee: u64, now: i64) -> u64 {
+        if self.promo_start <= now && now < self.promo_end {
+            (fee as u128 * self.promo_fee_override_bps as u128 / BPS_DENOMINATOR as u128) as u64
+        } else {
+            fee
+        }
+    }
+}
+
+impl ReverseRecord {
+    pub fn new(wallet: Pubkey, name: String) -> Self {
+        Self {
+            is_initialized: true,
+            wallet,
+            name,
+        }
+    }
+
+    /// A record that publishes nothing, for names held by private wallets.
+    pub fn cleared() -> Self {
+        Self::default()
+    }
+
+    /// Read-compat shim: decodes either layout. Legacy accounts carry no
+    /// wallet, so it is left default; read it from the name account instead.
+    /// Will be removed once all accounts are migrated.
+    #[allow(deprecated)]
+    pub fn unpack_compat(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() == AddressAccount::LEN {
+            let legacy = AddressAccount::unpack_unchecked(src)?;
+            return Ok(Self {
+                is_initialized: legacy.is_initialized,
+                wallet: Pubkey::default(),
+                name: legacy.name,
+            });
+        }
+        Self::unpack_unchecked(src)
+    }
+}
+
+impl NameAccount {
+    /// Byte offset of `owner`, for `getProgramAccounts` memcmp filters.
+    pub const OWNER_OFFSET: usize = HEADER_LEN + 1;
+    /// Offset of the length prefix of `name` past the header. The fields
+    /// after the name sit that many bytes further along.
+    const NAME_SHIFT: usize = 1 + 32;
+    /// Offset of `parent` past the end of the name, after `address`,
+    /// `cooldown_until` and `target_set`.
+    const PARENT_SHIFT: usize = 32 + 8 + 1;
+    /// Offset of `frozen` past the end of the name, after `parent`,
+    /// `pending_owner`, `wildcard_subdomains`, `tokenized` and the
+    /// statistics.
+    const FROZEN_SHIFT: usize = Self::PARENT_SHIFT + 32 + 32 + 1 + 1 + 8 + 4 + 8;
+
+    pub fn is_subname(&self) -> bool {
+        self.parent != Pubkey::default()
+    }
+
+    /// Whether the name was registered in a namespace, as `<label>.<suffix>`.
+    pub fn is_namespaced(&self) -> bool {
+        !self.is_subname() && self.name.contains('.')
+    }
+
+    /// Hands the name to `new_owner`, counting the transfer.
+    pub fn transfer_to(&mut self, new_owner: Pubkey) {
+        self.owner = new_owner;
+        self.transfer_count = self.transfer_count.saturating_add(1);
+    }
+
+    // Zero-copy reads of packed name accounts, for read-only handlers that
+    // need a few fields without decoding the whole account. Each checks the
+    // discriminator as `unpack` does and reads either layout version.
+
+    /// Whether the packed account holds a registered name.
+    pub fn is_initialized_from_slice(src: &[u8]) -> Result<bool, ProgramError> {
+        let tag = *src.first().ok_or(ProgramError::InvalidAccountData)?;
+        let initialized = *src.get(Self::header_len(src)).ok_or(ProgramError::InvalidAccountData)? != 0;
+        if tag != Self::DISCRIMINATOR && (tag != 0 || initialized) {
+            return Err(NameRegistryError::InvalidAccountType.into());
+        }
+        Ok(initialized)
+    }
+
+    pub fn owner_from_slice(src: &[u8]) -> Result<Pubkey, ProgramError> {
+        Self::is_initialized_from_slice(src)?;
+        pubkey_at(src, Self::header_len(src) + 1)
+    }
+
+    pub fn name_from_slice(src: &[u8]) -> Result<&str, ProgramError> {
+        let start = Self::header_len(src) + Self::NAME_SHIFT + 4;
+        let name = src.get(start..Self::name_end(src)?).ok_or(ProgramError::InvalidAccountData)?;
+        std::str::from_utf8(name).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    pub fn address_from_slice(src: &[u8]) -> Result<Pubkey, ProgramError> {
+        pubkey_at(src, Self::name_end(src)?)
+    }
+
+    pub fn parent_from_slice(src: &[u8]) -> Result<Pubkey, ProgramError> {
+        pubkey_at(src, Self::name_end(src)? + Self::PARENT_SHIFT)
+    }
+
+    /// Accounts not yet grown to hold `frozen` were never frozen.
+    pub fn frozen_from_slice(src: &[u8]) -> Result<bool, ProgramError> {
+        let offset = Self::name_end(src)? + Self::FROZEN_SHIFT;
+        Ok(src.get(offset).is_some_and(|frozen| *frozen != 0))
+    }
+
+    /// Header bytes before `is_initialized`: just the discriminator in the
+    /// version 1 layout.
+    fn header_len(src: &[u8]) -> usize {
+        if Layout::of::<Self>().version(src.len()) == Some(1) {
+            1
+        } else {
+            HEADER_LEN
+        }
+    }
+
+    /// Offset just past the name, where `address` starts.
+    fn name_end(src: &[u8]) -> Result<usize, ProgramError> {
+        Self::is_initialized_from_slice(src)?;
+        let offset = Self::header_len(src) + Self::NAME_SHIFT;
+        let prefix = src.get(offset..offset + 4).ok_or(ProgramError::InvalidAccountData)?;
+        let len = u32::from_le_bytes(prefix.try_into().unwrap()) as usize;
+        Ok(offset + 4 + len)
+    }
+}
+
+fn pubkey_at(src: &[u8], offset: usize) -> Result<Pubkey, ProgramError> {
+    let bytes = src.get(offset..offset + 32).ok_or(ProgramError::InvalidAccountData)?;
+    Ok(Pubkey::new_from_array(bytes.try_into().unwrap()))
+}
+
+/// Leading byte of every registry account, naming its state type so that an
+/// account of one type cannot be passed where another is expected. A freshly
+/// allocated account reads zero until it is first written.
+///
+/// ReverseRecord took 2 because the legacy `AddressAccount` layout, which
+/// starts with its `is_initialized` flag, reads as 1.
+///
+/// The discriminator is followed by `LAYOUT_VERSION`, then the Borsh
+/// encoding of the state.
+pub trait AccountType {
+    const DISCRIMINATOR: u8;
+    /// `(layout version, length)` of every size the type had since it was
+    /// tagged, before its current `Pack::LEN`. Types that never grew other
+    /// than by the version byte leave this empty: their version 1 accounts
+    /// are one byte short of `LEN`.
+    const EARLIER_LAYOUTS: &'static [(u8, usize)] = &[];
+}
+
+/// Layout version written after the discriminator of every account. Version
+/// 1 accounts predate the version byte. Accounts in any earlier layout stay
+/// readable, with the fields they are too short for reading as zero, but
+/// handlers refuse to write them with `LegacyAccountLayout` until
+/// MigrateAccount upgrades them.
+///
+/// Appending fields to a state type keeps the version: record the old
+/// length in its `EARLIER_LAYOUTS` (along with the version 1 length, on its
+/// first growth), which is how readers and MigrateAccount tell the layouts
+/// apart. Bump this, and teach MigrateAccount the conversion, whenever a
+/// state type changes layout other than by appending fields.
+pub const LAYOUT_VERSION: u8 = 2;
+
+/// Discriminator and layout version.
+const HEADER_LEN: usize = 2;
+
+/// Current and earlier sizes of a state type, from which the layout version
+/// of an account follows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Layout {
+    /// Current `Pack::LEN`.
+    pub len: usize,
+    earlier: &'static [(u8, usize)],
+}
+
+impl Layout {
+    pub fn of<T: Pack + AccountType>() -> Self {
+        Layout { len: T::LEN, earlier: T::EARLIER_LAYOUTS }
+    }
+
+    /// Layout version of an account `len` bytes long, or `None` if the type
+    /// never had that size.
+    pub fn version(&self, len: usize) -> Option<u8> {
+        if len == self.len {
+            Some(LAYOUT_VERSION)
+        } else if self.earlier.is_empty() {
+            (len + 1 == self.len).then_some(1)
+        } else {
+            self.earlier.iter().find(|(_, earlier)| *earlier == len).map(|(version, _)| *version)
+        }
+    }
+}
+
+/// Layout of the state type `discriminator` tags, or `None` if it names none.
+pub fn layout(discriminator: u8) -> Option<Layout> {
+    Some(match discriminator {
+        ReverseRecord::DISCRIMINATOR => Layout::of::<ReverseRecord>(),
+        NameAccount::DISCRIMINATOR => Layout::of::<NameAccount>(),
+        PendingUpdateAccount::DISCRIMINATOR => Layout::of::<PendingUpdateAccount>(),
+        ProgramConfig::DISCRIMINATOR => Layout::of::<ProgramConfig>(),
+        WalletSettings::DISCRIMINATOR => Layout::of::<WalletSettings>(),
+        BannerMessage::DISCRIMINATOR => Layout::of::<BannerMessage>(),
+        RecordAccount::DISCRIMINATOR => Layout::of::<RecordAccount>(),
+        PrimaryNameRecord::DISCRIMINATOR => Layout::of::<PrimaryNameRecord>(),
+        PromoClaim::DISCRIMINATOR => Layout::of::<PromoClaim>(),
+        AuctionAccount::DISCRIMINATOR => Layout::of::<AuctionAccount>(),
+        NameCommitment::DISCRIMINATOR => Layout::of::<NameCommitment>(),
+        NoteAccount::DISCRIMINATOR => Layout::of::<NoteAccount>(),
+        MultisigConfig::DISCRIMINATOR => Layout::of::<MultisigConfig>(),
+        RecordIndex::DISCRIMINATOR => Layout::of::<RecordIndex>(),
+        PendingAdminAction::DISCRIMINATOR => Layout::of::<PendingAdminAction>(),
+        NameCosigner::DISCRIMINATOR => Layout::of::<NameCosigner>(),
+        ReceiptAccount::DISCRIMINATOR => Layout::of::<ReceiptAccount>(),
+        ReservedName::DISCRIMINATOR => Layout::of::<ReservedName>(),
+        Blocklist::DISCRIMINATOR => Layout::of::<Blocklist>(),
+        RoleGrant::DISCRIMINATOR => Layout::of::<RoleGrant>(),
+        AddressClaim::DISCRIMINATOR => Layout::of::<AddressClaim>(),
+        NameOperator::DISCRIMINATOR => Layout::of::<NameOperator>(),
+        NamespaceAccount::DISCRIMINATOR => Layout::of::<NamespaceAccount>(),
+        UsdPricing::DISCRIMINATOR => Layout::of::<UsdPricing>(),
+        ConfigLink::DISCRIMINATOR => Layout::of::<ConfigLink>(),
+        RegistryPage::DISCRIMINATOR => Layout::of::<RegistryPage>(),
+        NameTombstone::DISCRIMINATOR => Layout::of::<NameTombstone>(),
+        _ => return None,
+    })
+}
+
+impl AccountType for ReverseRecord {
+    const DISCRIMINATOR: u8 = 2;
+}
+
+impl AccountType for NameAccount {
+    const DISCRIMINATOR: u8 = 3;
+    /// Before and after `wildcard_subdomains` and `tokenized` in version 1,
+    /// then before the statistics and before the freeze fields.
+    const EARLIER_LAYOUTS: &'static [(u8, usize)] = &[(1, 175), (1, 176), (1, 177), (2, 178), (2, 198)];
+}
+
+impl AccountType for PendingUpdateAccount {
+    const DISCRIMINATOR: u8 = 4;
+    /// Before `expires_at`.
+    const EARLIER_LAYOUTS: &'static [(u8, usize)] = &[(2, 107)];
+}
+
+impl AccountType for ProgramConfig {
+    const DISCRIMINATOR: u8 = 5;
+    /// Before `total_registrations`, in both versions, then before the
+    /// registry page fields, before `tombstone_period` and before
+    /// `fee_vault_generation`.
+    const EARLIER_LAYOUTS: &'static [(u8, usize)] = &[(1, 205), (2, 206), (2, 214), (2, 223), (2, 227)];
+}
+
+impl AccountType for WalletSettings {
+    const DISCRIMINATOR: u8 = 6;
+}
+
+impl AccountType for BannerMessage {
+    const DISCRIMINATOR: u8 = 7;
+}
+
+impl AccountType for RecordAccount {
+    const DISCRIMINATOR: u8 = 8;
+    /// Before the writer fields, in both versions.
+    const EARLIER_LAYOUTS: &'static [(u8, usize)] = &[(1, 330), (2, 331)];
+}
+
+impl AccountType for PrimaryNameRecord {
+    const DISCRIMINATOR: u8 = 9;
+}
+
+impl AccountType for PromoClaim {
+    const DISCRIMINATOR: u8 = 10;
+}
+
+impl AccountType for AuctionAccount {
+    const DISCRIMINATOR: u8 = 11;
+}
+
+impl AccountType for NameCommitment {
+    const DISCRIMINATOR: u8 = 12;
+}
+
+impl AccountType for NoteAccount {
+    const DISCRIMINATOR: u8 = 13;
+}
+
+impl AccountType for MultisigConfig {
+    const DISCRIMINATOR: u8 = 14;
+}
+
+impl AccountType for RecordIndex {
+    const DISCRIMINATOR: u8 = 15;
+}
+
+impl AccountType for PendingAdminAction {
+    const DISCRIMINATOR: u8 = 16;
+}
+
+impl AccountType for NameCosigner {
+    const DISCRIMINATOR: u8 = 17;
+}
+
+impl AccountType for ReceiptAccount {
+    const DISCRIMINATOR: u8 = 18;
+}
+
+impl AccountType for ReservedName {
+    const DISCRIMINATOR: u8 = 19;
+}
+
+impl AccountType for Blocklist {
+    const DISCRIMINATOR: u8 = 20;
+}
+
+impl AccountType for RoleGrant {
+    const DISCRIMINATOR: u8 = 21;
+}
+
+impl AccountType for AddressClaim {
+    const DISCRIMINATOR: u8 = 22;
+}
+
+impl AccountType for NameOperator {
+    const DISCRIMINATOR: u8 = 23;
+}
+
+impl AccountType for NamespaceAccount {
+    const DISCRIMINATOR: u8 = 24;
+    /// Before `delegate_program`.
+    const EARLIER_LAYOUTS: &'static [(u8, usize)] = &[(2, 108)];
+}
+
+impl AccountType for UsdPricing {
+    const DISCRIMINATOR: u8 = 25;
+}
+
+impl AccountType for ConfigLink {
+    const DISCRIMINATOR: u8 = 26;
+}
+
+impl AccountType for RegistryPage {
+    const DISCRIMINATOR: u8 = 27;
+}
+
+impl AccountType for NameTombstone {
+    const DISCRIMINATOR: u8 = 28;
+}
+
+fn pack_tagged<T: AccountType + BorshSerialize>(state: &T, dst: &mut [u8]) {
+    let data = state.try_to_vec().unwrap();
+    dst[..HEADER_LEN].copy_from_slice(&[T::DISCRIMINATOR, LAYOUT_VERSION]);
+    dst[HEADER_LEN..HEADER_LEN + data.len()].copy_from_slice(&data);
+}
+
+/// Unpacks any layout the type had, zero-filling the fields an earlier one
+/// lacks; `Pack::unpack_unchecked` is overridden to come here directly, as
+/// its length check would turn earlier layouts away.
+fn unpack_tagged<T: Pack + AccountType + BorshDeserialize + IsInitialized>(src: &[u8]) -> Result<T, ProgramError> {
+    let (&tag, data) = src.split_first().ok_or(ProgramError::InvalidAccountData)?;
+    let data = match Layout::of::<T>().version(src.len()) {
+        Some(1) => data,
+        Some(_) => {
+            let (&version, data) = data.split_first().ok_or(ProgramError::InvalidAccountData)?;
+            if tag != 0 && version != LAYOUT_VERSION {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            data
+        }
+        None => return Err(ProgramError::InvalidAccountData),
+    };
+    let mut padded;
+    let data = if data.len() < T::LEN - HEADER_LEN {
+        padded = data.to_vec();
+        padded.resize(T::LEN - HEADER_LEN, 0);
+        &padded[..]
+    } else {
+        data
+    };
+    let state = T::deserialize(&mut &data[..]).map_err(|_| ProgramError::InvalidAccountData)?;
+    if tag != T::DISCRIMINATOR && (tag != 0 || state.is_initialized()) {
+        return Err(NameRegistryError::InvalidAccountType.into());
+    }
+    Ok(state)
+}
+
+/// Fallible counterpart of `Pack::pack` for Borsh-encoded state.
+///
+/// `Pack::pack_into_slice` cannot report errors, so state whose serialized
+/// form outgrows the account (e.g. an over-long name) would panic and abort
+/// the transaction with an opaque error. Handlers pack through `pack_checked`
+/// instead, which returns `AccountDataTooSmall` and zeroes any trailing bytes
+/// left over from a longer previous value.
+pub trait PackChecked: Pack + AccountType + BorshSerialize {
+    /// Bytes `self` takes once packed: the header and its Borsh encoding.
+    /// State fits its account while this is at most `LEN`, which is sized
+    /// for the longest values validation lets through.
+    fn packed_len(&self) -> usize {
+        self.try_to_vec().map_or(usize::MAX, |data| HEADER_LEN + data.len())
+    }
+
+    /// Also refuses accounts still in an earlier layout, with
+    /// `LegacyAccountLayout`.
+    fn pack_checked(src: Self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        let earlier = dst.len() != Self::LEN && Layout::of::<Self>().version(dst.len()).is_some();
+        if earlier && dst.first() == Some(&Self::DISCRIMINATOR) {
+            return Err(NameRegistryError::LegacyAccountLayout.into());
+        }
+        if dst.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let data = src.try_to_vec().map_err(|_| ProgramError::InvalidAccountData)?;
+        if HEADER_LEN + data.len() > dst.len() {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        dst[..HEADER_LEN].copy_from_slice(&[Self::DISCRIMINATOR, LAYOUT_VERSION]);
+        dst[HEADER_LEN..HEADER_LEN + data.len()].copy_from_slice(&data);
+        dst[HEADER_LEN + data.len()..].fill(0);
+        Ok(())
+    }
+}
+
+impl<T: Pack + AccountType + BorshSerialize> PackChecked for T {}
+
+impl Sealed for NameAccount {}
+impl Sealed for ReverseRecord {}
+impl Sealed for PendingUpdateAccount {}
+impl Sealed for ProgramConfig {}
+impl Sealed for WalletSettings {}
+impl Sealed for BannerMessage {}
+impl Sealed for PrimaryNameRecord {}
+impl Sealed for RecordAccount {}
+impl Sealed for PromoClaim {}
+impl Sealed for AuctionAccount {}
+impl Sealed for NameCommitment {}
+impl Sealed for NoteAccount {}
+impl Sealed for MultisigConfig {}
+impl Sealed for RecordIndex {}
+impl Sealed for PendingAdminAction {}
+impl Sealed for NameCosigner {}
+impl Sealed for ReceiptAccount {}
+impl Sealed for ReservedName {}
+impl Sealed for Blocklist {}
+impl Sealed for RoleGrant {}
+impl Sealed for AddressClaim {}
+impl Sealed for NameOperator {}
+impl Sealed for NamespaceAccount {}
+impl Sealed for UsdPricing {}
+impl Sealed for ConfigLink {}
+impl Sealed for RegistryPage {}
+impl Sealed for NameTombstone {}
+
+impl IsInitialized for NameAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for ReverseRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for PendingUpdateAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for ProgramConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for WalletSettings {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for PrimaryNameRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for RecordAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for PromoClaim {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for AuctionAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for NameCommitment {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for NoteAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for MultisigConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for RecordIndex {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for PendingAdminAction {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for NameCosigner {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for ReceiptAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for ReservedName {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for Blocklist {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for RoleGrant {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for AddressClaim {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for NameOperator {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for NamespaceAccount {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for UsdPricing {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for ConfigLink {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for RegistryPage {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for NameTombstone {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl IsInitialized for BannerMessage {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for NameAccount {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 32 + 8 + 4 + 1 + 32 + 32 + 1 + 1 + 8 + 4 + 8 + 1 + 32 + 8; // discriminator + layout version + is_initialized + owner + name (max 32) + address + cooldown + name length prefix + target_set + parent + pending_owner + wildcard_subdomains + tokenized + registered_at + transfer_count + resolve_count + frozen + frozen_by + frozen_at
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for ReverseRecord {
+    const LEN: usize = 1 + 1 + 1 + 32 + 4 + 32; // discriminator + layout version + is_initialized + wallet + name length prefix + name (max 32)
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for PendingUpdateAccount {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 32 + 8 + 8; // discriminator + layout version + is_initialized + new_address + requester + name_account + requested_at + expires_at
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for ProgramConfig {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 4 * 8 + 8 + 8 + 2 + 1 + 1 + 2 + 2 + 8 + 8 + 1 + 1 + 1 + 1 + 32 + 4 + 1 + 4 + 1 + 4 + 1 + 8 + 8 + 8 + 1 + 8 + 4 + 1; // discriminator + layout version + is_initialized + owner + pending_owner + fee_schedule + promo_start + promo_end + promo_fee_override_bps + analytics_enabled + paused + max_pending_operations + referral_bps + total_names + max_total_names + allow_unicode_names + commit_reveal_required + multisig_enabled + version + successor + admin_timelock + blocklist_enabled + cooldown_seconds + enforce_unique_address + pending_update_ttl + usd_pricing_enabled + rename_fee + update_fee + total_registrations + registry_pages_enabled + indexed_names + tombstone_period + fee_vault_generation + reserved
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for WalletSettings {
+    const LEN: usize = 1 + 1 + 1 + 32 + 1 + 2; // discriminator + layout version + is_initialized + wallet + privacy_mode + pending_operations
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for BannerMessage {
+    const LEN: usize = 1 + 1 + 1 + 4 + 200; // discriminator + layout version + is_initialized + message length prefix + message (max 200)
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for RecordAccount {
+    const LEN: usize = 1 + 1 + 1 + 32 + 4 + 32 + 4 + 256 + 32 + 4 + 32 * 4; // discriminator + layout version + is_initialized + name_account + kind length prefix + kind (max 32) + value length prefix + value (max 256) + writers_granted_by + writers length prefix + writers (max 4)
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for PrimaryNameRecord {
+    const LEN: usize = 1 + 1 + 1 + 32 + 4 + 32; // discriminator + layout version + is_initialized + wallet + name length prefix + name (max 32)
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for PromoClaim {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 8; // discriminator + layout version + is_initialized + config + name_account + expires_at
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for AuctionAccount {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 8 + 8 + 32 + 8; // discriminator + layout version + is_initialized + config + name_account + min_bid + ends_at + highest_bidder + highest_bid
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for NameCommitment {
+    const LEN: usize = 1 + 1 + 1 + 32 + 8; // discriminator + layout version + is_initialized + committer + committed_at
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for NoteAccount {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 4 + 512; // discriminator + layout version + is_initialized + name_account + owner + data length prefix + data (max 512)
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for MultisigConfig {
+    const LEN: usize = 1 + 1 + 1 + 32 + 1 + 4 + 32 * 11; // discriminator + layout version + is_initialized + config + threshold + signers length prefix + signers (max 11)
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for RecordIndex {
+    const LEN: usize = 1 + 1 + 1 + 32 + 4 + 32 * 16; // discriminator + layout version + is_initialized + name_account + records length prefix + records (max 16)
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for PendingAdminAction {
+    const LEN: usize = 1 + 1 + 1 + 32 + 1 + 32 + 8; // discriminator + layout version + is_initialized + config + action variant + action (max 32) + executable_at
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for NameCosigner {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 32; // discriminator + layout version + is_initialized + name_account + owner + cosigner
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for ReceiptAccount {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 1 + 8 + 8; // discriminator + layout version + is_initialized + payer + name_account + operation + amount + slot
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for ReservedName {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32; // discriminator + layout version + is_initialized + config + name_account
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for Blocklist {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32; // discriminator + layout version + is_initialized + config + root
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for RoleGrant {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 1 + 2; // discriminator + layout version + is_initialized + config + account + role + fee_bps
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for AddressClaim {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32; // discriminator + layout version + is_initialized + wallet + name_account
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for NameOperator {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 32 + 1; // discriminator + layout version + is_initialized + name_account + owner + operator + scope
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for NamespaceAccount {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 32 + 8 + 1 + 32; // discriminator + layout version + is_initialized + config + name_account + admin + fee + paused + delegate_program
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for UsdPricing {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 4 * 8 + 4 + 2; // discriminator + layout version + is_initialized + config + price_feed + fee_schedule + max_price_age + max_confidence_bps
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for ConfigLink {
+    const LEN: usize = 1 + 1 + 1 + 32; // discriminator + layout version + is_initialized + config
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for RegistryPage {
+    const LEN: usize = 1 + 1 + 1 + 32 + 4 + 4 + 32 * REGISTRY_PAGE_CAPACITY; // discriminator + layout version + is_initialized + config + index + names length prefix + names
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+impl Pack for NameTombstone {
+    const LEN: usize = 1 + 1 + 1 + 32 + 32 + 32 + 8 + 8 + 32; // discriminator + layout version + is_initialized + name_account + previous_owner + previous_address + released_at + expires_at + payer
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        pack_tagged(self, dst);
+    }
+
+    fn unpack_unchecked(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        unpack_tagged(src)
+    }
+}
+
+/// Layouts kept readable for one release after a rename.
+#[allow(deprecated)]
+mod legacy {
+    use super::*;
+
+    /// Pre-migration layout of `ReverseRecord`, which held the name but not
+    /// the wallet. Existing accounts are converted in place by
+    /// MigrateAddressAccount.
+    #[deprecated(note = "renamed to ReverseRecord; convert accounts with MigrateAddressAccount")]
+    #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
+    pub struct AddressAccount {
+        pub is_initialized: bool,
+        pub name: String,
+    }
+
+    impl Sealed for AddressAccount {}
+
+    impl IsInitialized for AddressAccount {
+        fn is_initialized(&self) -> bool {
+            self.is_initialized
+        }
+    }
+
+    impl Pack for AddressAccount {
+        const LEN: usize = 1 + 4 + 32; // is_initialized + name length prefix + name (max 32)
+
+        fn pack_into_slice(&self, dst: &mut [u8]) {
+            let data = self.try_to_vec().unwrap();
+            dst[..data.len()].copy_from_slice(&data);
+        }
+
+        fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+            Self::deserialize(&mut &src[..]).map_err(|_| ProgramError::InvalidAccountData)
+        }
+    }
+}
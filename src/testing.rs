@@ -0,0 +1,303 @@
+//! Typed snapshots of registry state for banks-client tests, built with the
+//! `testing` feature.
+//!
+//! `Snapshot::capture` decodes every account a test cares about into its
+//! state type, so a flow can be checked by comparing two snapshots instead of
+//! fetching and unpacking each account by hand:
+//!
+//! ```ignore
+//! let before = Snapshot::capture(&mut context.banks_client, &pdas).await;
+//! process(&mut context, ix, &payer, &[&payer]).await.unwrap();
+//! let after = Snapshot::capture(&mut context.banks_client, &pdas).await;
+//! assert_diff(&before, &after, &[name_account, address_account, treasury]);
+//! assert_eq!(after.name(&name_account).owner, payer.pubkey());
+//! ```
+
+use std::collections::BTreeMap;
+
+use solana_banks_client::BanksClient;
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::account::Account;
+
+#[allow(deprecated)]
+use crate::state::AddressAccount;
+use crate::state::{
+    AccountType, AddressClaim, AuctionAccount, BannerMessage, Blocklist, ConfigLink, MultisigConfig, NameAccount, NameCommitment, NoteAccount,
+    NameCosigner, NameOperator, NamespaceAccount, PendingAdminAction, PendingUpdateAccount, PrimaryNameRecord, ProgramConfig,
+    PromoClaim, ReceiptAccount, RecordAccount, RecordIndex, ReservedName, ReverseRecord, RoleGrant, UsdPricing,
+    WalletSettings,
+};
+
+/// Decoded contents of a single account.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccountState {
+    /// No account exists at the address.
+    Missing,
+    /// An account with no data, such as a treasury bucket or a wallet.
+    Empty,
+    Config(ProgramConfig),
+    Name(NameAccount),
+    ReverseRecord(ReverseRecord),
+    /// A reverse record still in the pre-migration `AddressAccount` layout.
+    #[allow(deprecated)]
+    LegacyAddress(AddressAccount),
+    PendingUpdate(PendingUpdateAccount),
+    WalletSettings(WalletSettings),
+    Banner(BannerMessage),
+    Record(RecordAccount),
+    PrimaryName(PrimaryNameRecord),
+    PromoClaim(PromoClaim),
+    Auction(AuctionAccount),
+    Commitment(NameCommitment),
+    Note(NoteAccount),
+    Multisig(MultisigConfig),
+    RecordIndex(RecordIndex),
+    AdminAction(PendingAdminAction),
+    Cosigner(NameCosigner),
+    Receipt(ReceiptAccount),
+    Reserved(ReservedName),
+    Blocklist(Blocklist),
+    Role(RoleGrant),
+    AddressClaim(AddressClaim),
+    Operator(NameOperator),
+    Namespace(NamespaceAccount),
+    UsdPricing(UsdPricing),
+    ConfigLink(ConfigLink),
+    /// Data that matches no registry state type.
+    Unknown(Vec<u8>),
+}
+
+impl AccountState {
+    /// Decodes an account, telling state types apart by their
+    /// discriminator. Blank accounts carry none yet and are told apart by
+    /// size where it is unambiguous.
+    #[allow(deprecated)]
+    pub fn decode(account: Option<&Account>) -> Self {
+        let Some(account) = account else {
+            return AccountState::Missing;
+        };
+        let data = account.data.as_slice();
+        let Some(&tag) = data.first() else {
+            return AccountState::Empty;
+        };
+        let decoded = match tag {
+            0 => Self::decode_blank(data),
+            1 if data.len() == AddressAccount::LEN => {
+                AddressAccount::unpack_unchecked(data).map(AccountState::LegacyAddress)
+            }
+            ProgramConfig::DISCRIMINATOR => ProgramConfig::unpack_unchecked(data).map(AccountState::Config),
+            NameAccount::DISCRIMINATOR => NameAccount::unpack_unchecked(data).map(AccountState::Name),
+            ReverseRecord::DISCRIMINATOR => ReverseRecord::unpack_unchecked(data).map(AccountState::ReverseRecord),
+            PendingUpdateAccount::DISCRIMINATOR => {
+                PendingUpdateAccount::unpack_unchecked(data).map(AccountState::PendingUpdate)
+            }
+            WalletSettings::DISCRIMINATOR => WalletSettings::unpack_unchecked(data).map(AccountState::WalletSettings),
+            BannerMessage::DISCRIMINATOR => BannerMessage::unpack_unchecked(data).map(AccountState::Banner),
+            RecordAccount::DISCRIMINATOR => RecordAccount::unpack_unchecked(data).map(AccountState::Record),
+            PrimaryNameRecord::DISCRIMINATOR => {
+                PrimaryNameRecord::unpack_unchecked(data).map(AccountState::PrimaryName)
+            }
+            PromoClaim::DISCRIMINATOR => PromoClaim::unpack_unchecked(data).map(AccountState::PromoClaim),
+            AuctionAccount::DISCRIMINATOR => AuctionAccount::unpack_unchecked(data).map(AccountState::Auction),
+            NameCommitment::DISCRIMINATOR => NameCommitment::unpack_unchecked(data).map(AccountState::Commitment),
+            NoteAccount::DISCRIMINATOR => NoteAccount::unpack_unchecked(data).map(AccountState::Note),
+            MultisigConfig::DISCRIMINATOR => MultisigConfig::unpack_unchecked(data).map(AccountState::Multisig),
+            RecordIndex::DISCRIMINATOR => RecordIndex::unpack_unchecked(data).map(AccountState::RecordIndex),
+            PendingAdminAction::DISCRIMINATOR => {
+                PendingAdminAction::unpack_unchecked(data).map(AccountState::AdminAction)
+            }
+            NameCosigner::DISCRIMINATOR => NameCosigner::unpack_unchecked(data).map(AccountState::Cosigner),
+            ReceiptAccount::DISCRIMINATOR => ReceiptAccount::unpack_unchecked(data).map(AccountState::Receipt),
+            ReservedName::DISCRIMINATOR => ReservedName::unpack_unchecked(data).map(AccountState::Reserved),
+            Blocklist::DISCRIMINATOR => Blocklist::unpack_unchecked(data).map(AccountState::Blocklist),
+            RoleGrant::DISCRIMINATOR => RoleGrant::unpack_unchecked(data).map(AccountState::Role),
+            AddressClaim::DISCRIMINATOR => AddressClaim::unpack_unchecked(data).map(AccountState::AddressClaim),
+            NameOperator::DISCRIMINATOR => NameOperator::unpack_unchecked(data).map(AccountState::Operator),
+            NamespaceAccount::DISCRIMINATOR => NamespaceAccount::unpack_unchecked(data).map(AccountState::Namespace),
+            UsdPricing::DISCRIMINATOR => UsdPricing::unpack_unchecked(data).map(AccountState::UsdPricing),
+            ConfigLink::DISCRIMINATOR => ConfigLink::unpack_unchecked(data).map(AccountState::ConfigLink),
+            _ => return AccountState::Unknown(data.to_vec()),
+        };
+        decoded.unwrap_or_else(|_| AccountState::Unknown(data.to_vec()))
+    }
+
+    /// Reverse records share their size with primary name records, and name
+    /// reservations, blocklists and address claims share theirs, so blank
+    /// accounts of those sizes stay `Unknown`.
+    fn decode_blank(data: &[u8]) -> Result<Self, ProgramError> {
+        match data.len() {
+            ProgramConfig::LEN => ProgramConfig::unpack_unchecked(data).map(AccountState::Config),
+            NameAccount::LEN => NameAccount::unpack_unchecked(data).map(AccountState::Name),
+            PendingUpdateAccount::LEN => {
+                PendingUpdateAccount::unpack_unchecked(data).map(AccountState::PendingUpdate)
+            }
+            WalletSettings::LEN => WalletSettings::unpack_unchecked(data).map(AccountState::WalletSettings),
+            BannerMessage::LEN => BannerMessage::unpack_unchecked(data).map(AccountState::Banner),
+            RecordAccount::LEN => RecordAccount::unpack_unchecked(data).map(AccountState::Record),
+            PromoClaim::LEN => PromoClaim::unpack_unchecked(data).map(AccountState::PromoClaim),
+            AuctionAccount::LEN => AuctionAccount::unpack_unchecked(data).map(AccountState::Auction),
+            NameCommitment::LEN => NameCommitment::unpack_unchecked(data).map(AccountState::Commitment),
+            NoteAccount::LEN => NoteAccount::unpack_unchecked(data).map(AccountState::Note),
+            MultisigConfig::LEN => MultisigConfig::unpack_unchecked(data).map(AccountState::Multisig),
+            RecordIndex::LEN => RecordIndex::unpack_unchecked(data).map(AccountState::RecordIndex),
+            PendingAdminAction::LEN => PendingAdminAction::unpack_unchecked(data).map(AccountState::AdminAction),
+            NameCosigner::LEN => NameCosigner::unpack_unchecked(data).map(AccountState::Cosigner),
+            ReceiptAccount::LEN => ReceiptAccount::unpack_unchecked(data).map(AccountState::Receipt),
+            RoleGrant::LEN => RoleGrant::unpack_unchecked(data).map(AccountState::Role),
+            NameOperator::LEN => NameOperator::unpack_unchecked(data).map(AccountState::Operator),
+            NamespaceAccount::LEN => NamespaceAccount::unpack_unchecked(data).map(AccountState::Namespace),
+            UsdPricing::LEN => UsdPricing::unpack_unchecked(data).map(AccountState::UsdPricing),
+            ConfigLink::LEN => ConfigLink::unpack_unchecked(data).map(AccountState::ConfigLink),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// State and balance of one account at capture time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountSnapshot {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub state: AccountState,
+}
+
+/// Registry state of a fixed set of accounts at one point in a test.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    pub accounts: BTreeMap<Pubkey, AccountSnapshot>,
+}
+
+impl Snapshot {
+    /// Fetches and decodes every account in `pdas`. Missing accounts are
+    /// recorded as `AccountState::Missing` so their later creation shows up
+    /// in a diff.
+    pub async fn capture(banks: &mut BanksClient, pdas: &[Pubkey]) -> Self {
+        let mut accounts = BTreeMap::new();
+        for key in pdas {
+            let account = banks
+                .get_account(*key)
+                .await
+                .unwrap_or_else(|err| panic!("failed to fetch {}: {}", key, err));
+            accounts.insert(
+                *key,
+                AccountSnapshot {
+                    lamports: account.as_ref().map_or(0, |account| account.lamports),
+                    owner: account.as_ref().map_or_else(Pubkey::default, |account| account.owner),
+                    state: AccountState::decode(account.as_ref()),
+                },
+            );
+        }
+        Snapshot { accounts }
+    }
+
+    pub fn get(&self, key: &Pubkey) -> &AccountSnapshot {
+        self.accounts
+            .get(key)
+            .unwrap_or_else(|| panic!("{} was not captured", key))
+    }
+
+    pub fn state(&self, key: &Pubkey) -> &AccountState {
+        &self.get(key).state
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.get(key).lamports
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.state(key) != &AccountState::Missing
+    }
+
+    pub fn config(&self, key: &Pubkey) -> &ProgramConfig {
+        match self.state(key) {
+            AccountState::Config(config) => config,
+            other => panic!("{} is not a config account: {:?}", key, other),
+        }
+    }
+
+    pub fn name(&self, key: &Pubkey) -> &NameAccount {
+        match self.state(key) {
+            AccountState::Name(name) => name,
+            other => panic!("{} is not a name account: {:?}", key, other),
+        }
+    }
+
+    pub fn reverse_record(&self, key: &Pubkey) -> &ReverseRecord {
+        match self.state(key) {
+            AccountState::ReverseRecord(reverse) => reverse,
+            other => panic!("{} is not a reverse record: {:?}", key, other),
+        }
+    }
+
+    pub fn pending_update(&self, key: &Pubkey) -> &PendingUpdateAccount {
+        match self.state(key) {
+            AccountState::PendingUpdate(pending) => pending,
+            other => panic!("{} is not a pending update account: {:?}", key, other),
+        }
+    }
+
+    pub fn record(&self, key: &Pubkey) -> &RecordAccount {
+        match self.state(key) {
+            AccountState::Record(record) => record,
+            other => panic!("{} is not a record account: {:?}", key, other),
+        }
+    }
+
+    pub fn primary_name(&self, key: &Pubkey) -> &PrimaryNameRecord {
+        match self.state(key) {
+            AccountState::PrimaryName(primary) => primary,
+            other => panic!("{} is not a primary name record: {:?}", key, other),
+        }
+    }
+
+    pub fn auction(&self, key: &Pubkey) -> &AuctionAccount {
+        match self.state(key) {
+            AccountState::Auction(auction) => auction,
+            other => panic!("{} is not an auction account: {:?}", key, other),
+        }
+    }
+
+    /// Accounts whose balance, owner or state differ in `after`, in key
+    /// order. Both snapshots must cover the same accounts.
+    pub fn diff(&self, after: &Snapshot) -> Vec<Pubkey> {
+        assert_eq!(
+            self.accounts.keys().collect::<Vec<_>>(),
+            after.accounts.keys().collect::<Vec<_>>(),
+            "snapshots cover different accounts",
+        );
+        self.accounts
+            .iter()
+            .filter(|(key, account)| after.accounts[*key] != **account)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+}
+
+/// Asserts that exactly the accounts in `changed` differ between the two
+/// snapshots, printing the before and after state of any surprise.
+pub fn assert_diff(before: &Snapshot, after: &Snapshot, changed: &[Pubkey]) {
+    let actual = before.diff(after);
+    let mut message = String::new();
+    for key in &actual {
+        if !changed.contains(key) {
+            message.push_str(&format!(
+                "\n  unexpected change to {}:\n    before: {:?}\n    after:  {:?}",
+                key,
+                before.get(key),
+                after.get(key),
+            ));
+        }
+    }
+    for key in changed {
+        if !actual.contains(key) {
+            message.push_str(&format!("\n  expected {} to change, but it did not: {:?}", key, before.get(key)));
+        }
+    }
+    assert!(message.is_empty(), "snapshot diff mismatch:{}", message);
+}
+
+/// Asserts that nothing in the snapshots changed, e.g. after a rejected
+/// instruction.
+pub fn assert_unchanged(before: &Snapshot, after: &Snapshot) {
+    assert_diff(before, after, &[]);
+}
@@ -0,0 +1,80 @@
+//! The slice of the Metaplex token metadata program TokenizeName calls.
+//!
+//! Only `CreateMetadataAccountV3` is needed, so the instruction is encoded
+//! here rather than pulling in the Metaplex crate and its SDK pins. The
+//! layout follows the program's Borsh encoding: a one-byte instruction tag,
+//! then the `DataV2` fields, `is_mutable` and the collection details.
+
+use borsh::BorshSerialize;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    system_program,
+};
+
+/// The Metaplex token metadata program.
+pub const ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const METADATA_SEED: &[u8] = b"metadata";
+/// Symbol every name token carries.
+pub const NAME_TOKEN_SYMBOL: &str = "FOLIO";
+
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+
+/// Address of the metadata account of `mint`, at
+/// `["metadata", metadata_program, mint]` under the metadata program.
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[METADATA_SEED, ID.as_ref(), mint.as_ref()], &ID)
+}
+
+#[derive(BorshSerialize)]
+struct CreateMetadataAccountArgsV3<'a> {
+    name: &'a str,
+    symbol: &'a str,
+    uri: &'a str,
+    seller_fee_basis_points: u16,
+    /// Creators, collection and uses, none of which name tokens set.
+    creators: Option<()>,
+    collection: Option<()>,
+    uses: Option<()>,
+    is_mutable: bool,
+    collection_details: Option<()>,
+}
+
+/// Creates immutable metadata for a name token, with `authority` as both
+/// mint and update authority.
+pub fn create_metadata_accounts_v3(
+    metadata: &Pubkey,
+    mint: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    name: &str,
+    uri: &str,
+) -> Instruction {
+    let args = CreateMetadataAccountArgsV3 {
+        name,
+        symbol: NAME_TOKEN_SYMBOL,
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+        is_mutable: false,
+        collection_details: None,
+    };
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+    args.serialize(&mut data).unwrap();
+    Instruction {
+        program_id: ID,
+        accounts: vec![
+            AccountMeta::new(*metadata, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
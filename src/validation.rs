@@ -1,55 +1,324 @@
-use solana_program::{
-    program_error::ProgramError,
-    sysvar::Sysvar,
-    clock::Clock,
-};
-use crate::error::NameRegistryError;
-
-pub const MAX_NAME_LENGTH: usize = 32;
-
-pub fn validate_name(name: &str) -> Result<(), ProgramError> {
-    if name.is_empty() {
-        return Err(NameRegistryError::InvalidNameFormat.into());
-    }
-    if name.len() > MAX_NAME_LENGTH {
-        return Err(NameRegistryError::InvalidNameFormat.into());
-    }
-    if !name.chars().all(|c| c.is_alphanumeric() || c == '-') {
-        return Err(NameRegistryError::InvalidNameFormat.into());
-    }
-    Ok(())
-}
-
-pub fn validate_address(address: &solana_program::pubkey::Pubkey) -> Result<(), ProgramError> {
-    if address == &solana_program::pubkey::Pubkey::default() {
-        return Err(NameRegistryError::InvalidAddress.into());
-    }
-    Ok(())
-}
-
-pub fn validate_cooldown(cooldown_until: i64) -> Result<(), ProgramError> {
-    let clock = Clock::get()?;
-    if clock.unix_timestamp < cooldown_until {
-        return Err(NameRegistryError::CooldownNotOver.into());
-    }
-    Ok(())
-}
-
-pub fn get_cooldown_until() -> Result<i64, ProgramError> {
-    let current_time = Clock::get()?.unix_timestamp;
-    Ok(current_time + 86400) // 1 day in seconds
-}
-
-pub fn validate_owner(owner: &solana_program::pubkey::Pubkey, signer: &solana_program::pubkey::Pubkey) -> Result<(), ProgramError> {
-    if owner != signer {
-        return Err(NameRegistryError::NotNameOwner.into());
-    }
-    Ok(())
-}
-
-pub fn validate_program_owner(owner: &solana_program::pubkey::Pubkey, signer: &solana_program::pubkey::Pubkey) -> Result<(), ProgramError> {
-    if owner != signer {
-        return Err(NameRegistryError::NotContractOwner.into());
-    }
-    Ok(())
-} 
\ No newline at end of file
+use solana_program::{
+    account_info::AccountInfo,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use crate::{
+    compat,
+    error::NameRegistryError,
+    instruction::NameProof,
+    pda::find_program_data_address,
+    state::{ProgramConfig, BPS_DENOMINATOR},
+};
+
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_BANNER_LENGTH: usize = 200;
+pub const MAX_RECORD_KIND_LENGTH: usize = 32;
+pub const MAX_RECORD_VALUE_LENGTH: usize = 256;
+pub const MAX_NOTE_LENGTH: usize = 512;
+pub const MAX_RECORDS_PER_NAME: usize = 16;
+pub const MAX_RECORD_WRITERS: usize = 4;
+/// Largest instruction data accepted, the size of a transaction packet.
+/// Only CPI callers can send more.
+pub const MAX_INSTRUCTION_DATA_LENGTH: usize = 1232;
+pub const MAX_PROMO_CLAIMS_PER_INSTRUCTION: usize = 16;
+pub const MAX_EXTENSION_PAYLOAD_LENGTH: usize = 1024;
+pub const MAX_MULTISIG_SIGNERS: usize = 11;
+/// Longest URI the token metadata program accepts.
+pub const MAX_TOKEN_URI_LENGTH: usize = 200;
+/// Promo claim preimages must carry enough entropy that commitments cannot
+/// be brute-forced from the public claim accounts.
+pub const MIN_CLAIM_PREIMAGE_LENGTH: usize = 16;
+pub const MAX_CLAIM_PREIMAGE_LENGTH: usize = 64;
+/// Cap on open pending operations per wallet for newly initialized configs.
+pub const DEFAULT_MAX_PENDING_OPERATIONS: u16 = 8;
+/// How long an auction takes bids after StartAuction, in seconds.
+pub const AUCTION_DURATION: i64 = 3 * 24 * 60 * 60;
+/// How long a name commitment must age before it can be revealed, in
+/// seconds, so the commit lands in an earlier block than the reveal.
+pub const MIN_COMMITMENT_AGE: i64 = 60;
+/// Longest admin timelock, in seconds, so a typo cannot freeze the config's
+/// fees and ownership for good. 48 hours is the suggested setting.
+pub const MAX_ADMIN_TIMELOCK: u32 = 30 * 24 * 60 * 60;
+/// Bounds on the cooldown period a config may set, in seconds. Zero turns
+/// the cooldown off; 30 days is the longest a name can be locked after a
+/// change. Reported by GetCooldownBounds.
+pub const MIN_COOLDOWN_PERIOD: u32 = 0;
+pub const MAX_COOLDOWN_PERIOD: u32 = 30 * 24 * 60 * 60;
+/// Cooldown period of newly initialized configs.
+pub const DEFAULT_COOLDOWN_PERIOD: u32 = 24 * 60 * 60;
+/// How long an address update stays claimable in newly initialized
+/// configs, in seconds.
+pub const DEFAULT_PENDING_UPDATE_TTL: u32 = 7 * 24 * 60 * 60;
+/// Longest merkle path a blocklist proof may carry, enough for a list of
+/// four billion names.
+pub const MAX_NAME_PROOF_DEPTH: usize = 32;
+
+/// Names are ASCII lowercase letters, digits and single hyphens between
+/// them, e.g. `alice` or `pay-day`.
+pub fn validate_name(name: &str) -> Result<(), ProgramError> {
+    validate_name_with(name, false)
+}
+
+/// Validates `name` under the character policy of `config`.
+pub fn validate_name_for(config: &ProgramConfig, name: &str) -> Result<(), ProgramError> {
+    validate_name_with(name, config.allow_unicode_names)
+}
+
+/// Validates a name to register under the character policy of `config`:
+/// a top-level name, or `<label>.<suffix>` for a name of the namespace of
+/// `suffix`.
+pub fn validate_registered_name(config: &ProgramConfig, name: &str) -> Result<(), ProgramError> {
+    let Some((label, suffix)) = name.split_once('.') else {
+        return validate_name_for(config, name);
+    };
+    validate_name_for(config, label)?;
+    validate_name_for(config, suffix)?;
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(NameRegistryError::InvalidNameFormat.into());
+    }
+    Ok(())
+}
+
+fn validate_name_with(name: &str, allow_unicode: bool) -> Result<(), ProgramError> {
+    if name.is_empty() {
+        return Err(NameRegistryError::InvalidNameFormat.into());
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(NameRegistryError::InvalidNameFormat.into());
+    }
+    let allowed = |c: char| {
+        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || (allow_unicode && is_curated_letter(c))
+    };
+    if !name.chars().all(allowed) {
+        return Err(NameRegistryError::InvalidNameFormat.into());
+    }
+    if name.starts_with('-') || name.ends_with('-') || name.contains("--") {
+        return Err(NameRegistryError::InvalidNameFormat.into());
+    }
+    Ok(())
+}
+
+/// Curated Unicode subset: the precomposed lowercase Latin letters of
+/// Latin-1 (`ß`, `à`..`ÿ` without `÷`). Each is its own NFKC form, so a name
+/// made of them has exactly one encoding and one PDA; decomposed and
+/// compatibility forms are rejected rather than normalized on chain. Other
+/// scripts stay out, which keeps Cyrillic and Greek homoglyphs of Latin
+/// letters (`аlice`) unregistrable.
+fn is_curated_letter(c: char) -> bool {
+    matches!(c, '\u{df}' | '\u{e0}'..='\u{ff}') && c != '\u{f7}'
+}
+
+/// Full name of `label` under `parent`, e.g. `pay.alice`. Labels follow the
+/// ASCII name rules and the full name must still fit a name account.
+pub fn subname(label: &str, parent: &str) -> Result<String, ProgramError> {
+    validate_name(label)?;
+    let full_name = format!("{}.{}", label, parent);
+    if full_name.len() > MAX_NAME_LENGTH {
+        return Err(NameRegistryError::InvalidNameFormat.into());
+    }
+    Ok(full_name)
+}
+
+pub fn validate_banner_message(message: &str) -> Result<(), ProgramError> {
+    if message.len() > MAX_BANNER_LENGTH {
+        return Err(NameRegistryError::BannerMessageTooLong.into());
+    }
+    Ok(())
+}
+
+pub fn validate_token_uri(uri: &str) -> Result<(), ProgramError> {
+    if uri.len() > MAX_TOKEN_URI_LENGTH {
+        return Err(NameRegistryError::TokenUriTooLong.into());
+    }
+    Ok(())
+}
+
+/// Record kinds are short identifiers like `avatar` or `twitter`, using the
+/// same charset as names; values are free-form UTF-8.
+pub fn validate_record(kind: &str, value: &str) -> Result<(), ProgramError> {
+    if kind.is_empty() || kind.len() > MAX_RECORD_KIND_LENGTH {
+        return Err(NameRegistryError::InvalidRecord.into());
+    }
+    if !kind.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return Err(NameRegistryError::InvalidRecord.into());
+    }
+    if value.len() > MAX_RECORD_VALUE_LENGTH {
+        return Err(NameRegistryError::InvalidRecord.into());
+    }
+    Ok(())
+}
+
+pub fn validate_note(data: &[u8]) -> Result<(), ProgramError> {
+    if data.len() > MAX_NOTE_LENGTH {
+        return Err(NameRegistryError::NoteTooLong.into());
+    }
+    Ok(())
+}
+
+pub fn validate_name_proof(proof: &NameProof) -> Result<(), ProgramError> {
+    if proof.path.len() > MAX_NAME_PROOF_DEPTH {
+        return Err(NameRegistryError::InvalidNameProof.into());
+    }
+    Ok(())
+}
+
+pub fn validate_claim_preimage(preimage: &[u8]) -> Result<(), ProgramError> {
+    if preimage.len() < MIN_CLAIM_PREIMAGE_LENGTH || preimage.len() > MAX_CLAIM_PREIMAGE_LENGTH {
+        return Err(NameRegistryError::InvalidPromoClaim.into());
+    }
+    Ok(())
+}
+
+/// A promo needs `start <= end` and a fee of at most 100%. `start == end`
+/// describes an empty window, which cancels any scheduled promo.
+pub fn validate_promo(start: i64, end: i64, fee_override_bps: u16) -> Result<(), ProgramError> {
+    if start > end || fee_override_bps > BPS_DENOMINATOR {
+        return Err(NameRegistryError::InvalidPromo.into());
+    }
+    Ok(())
+}
+
+pub fn validate_name_cap(config: &ProgramConfig) -> Result<(), ProgramError> {
+    if config.max_total_names != 0 && config.total_names >= config.max_total_names {
+        return Err(NameRegistryError::RegistryFull.into());
+    }
+    Ok(())
+}
+
+pub fn validate_referral_bps(referral_bps: u16) -> Result<(), ProgramError> {
+    if referral_bps > BPS_DENOMINATOR {
+        return Err(NameRegistryError::InvalidReferral.into());
+    }
+    Ok(())
+}
+
+/// A multisig has 1 to `MAX_MULTISIG_SIGNERS` distinct signers and a
+/// threshold of at least one and at most their number. No signers with a
+/// zero threshold turns the multisig off.
+pub fn validate_multisig(signers: &[solana_program::pubkey::Pubkey], threshold: u8) -> Result<(), ProgramError> {
+    if signers.is_empty() && threshold == 0 {
+        return Ok(());
+    }
+    let distinct = signers.iter().enumerate().all(|(i, signer)| !signers[..i].contains(signer));
+    if signers.len() > MAX_MULTISIG_SIGNERS || !distinct || threshold == 0 || threshold as usize > signers.len() {
+        return Err(NameRegistryError::InvalidMultisig.into());
+    }
+    Ok(())
+}
+
+pub fn validate_admin_timelock(seconds: u32) -> Result<(), ProgramError> {
+    if seconds > MAX_ADMIN_TIMELOCK {
+        return Err(NameRegistryError::InvalidTimelock.into());
+    }
+    Ok(())
+}
+
+pub fn validate_cooldown_period(seconds: u32) -> Result<(), ProgramError> {
+    if !(MIN_COOLDOWN_PERIOD..=MAX_COOLDOWN_PERIOD).contains(&seconds) {
+        return Err(NameRegistryError::InvalidCooldownPeriod.into());
+    }
+    Ok(())
+}
+
+pub fn validate_address(address: &solana_program::pubkey::Pubkey) -> Result<(), ProgramError> {
+    if address == &solana_program::pubkey::Pubkey::default() {
+        return Err(NameRegistryError::InvalidAddress.into());
+    }
+    Ok(())
+}
+
+pub fn validate_cooldown(cooldown_until: i64) -> Result<(), ProgramError> {
+    let clock = compat::clock()?;
+    if clock.unix_timestamp < cooldown_until {
+        return Err(NameRegistryError::CooldownNotOver.into());
+    }
+    Ok(())
+}
+
+/// End of the cooldown a change made now starts under `config`.
+pub fn get_cooldown_until(config: &ProgramConfig) -> Result<i64, ProgramError> {
+    let current_time = compat::unix_timestamp()?;
+    Ok(current_time + config.cooldown_period() as i64)
+}
+
+pub fn validate_owner(owner: &solana_program::pubkey::Pubkey, signer: &solana_program::pubkey::Pubkey) -> Result<(), ProgramError> {
+    if owner != signer {
+        return Err(NameRegistryError::NotNameOwner.into());
+    }
+    Ok(())
+}
+
+pub fn validate_program_owner(owner: &solana_program::pubkey::Pubkey, signer: &solana_program::pubkey::Pubkey) -> Result<(), ProgramError> {
+    if owner != signer {
+        return Err(NameRegistryError::NotContractOwner.into());
+    }
+    Ok(())
+}
+
+/// Checks that `program_data` is the upgradeable loader's program data
+/// account of `program_id` and names `signer` its upgrade authority.
+/// Programs deployed immutable have none.
+pub fn validate_upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
+    signer: &Pubkey,
+) -> Result<(), ProgramError> {
+    if program_data.key != &find_program_data_address(program_id).0 {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    assert_owned_by(program_data, &bpf_loader_upgradeable::id())?;
+
+    // The loader's bincode layout: the `ProgramData` variant tag (3), the
+    // deployment slot, then the optional upgrade authority
+    let data = program_data.data.borrow();
+    let metadata = data
+        .get(..UpgradeableLoaderState::size_of_programdata_metadata())
+        .ok_or(ProgramError::InvalidAccountData)?;
+    if metadata[..4] != 3u32.to_le_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if metadata[12] != 1 || &metadata[13..] != signer.as_ref() {
+        return Err(NameRegistryError::NotUpgradeAuthority.into());
+    }
+    Ok(())
+}
+
+pub fn assert_owned_by(account: &AccountInfo, program_id: &Pubkey) -> Result<(), ProgramError> {
+    if account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+pub fn assert_writable(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_writable {
+        return Err(NameRegistryError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+/// Program state must never drop below the rent-exempt minimum, or the runtime
+/// may reap it. Checked after every lamport movement touching state accounts,
+/// and before state is first written to an account the caller funded.
+pub fn validate_rent_exempt(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !compat::rent()?.is_exempt(account.lamports(), account.data_len()) {
+        return Err(NameRegistryError::NotRentExempt.into());
+    }
+    Ok(())
+}
+
+pub fn validate_not_paused(config: &ProgramConfig) -> Result<(), ProgramError> {
+    if config.paused {
+        return Err(NameRegistryError::ProgramPaused.into());
+    }
+    Ok(())
+}
+
+pub fn validate_pending_operations(config: &ProgramConfig, open: u16) -> Result<(), ProgramError> {
+    if config.max_pending_operations != 0 && open > config.max_pending_operations {
+        return Err(NameRegistryError::TooManyPendingOperations.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Registration vouchers: discounts the program owner signs off-chain.
+//!
+//! A voucher is the owner's ed25519 signature over `voucher_message`, which
+//! binds it to one config, registrant and name until its expiry. Programs
+//! cannot check signatures themselves, so the transaction carries an
+//! Ed25519 program instruction verifying the signature right before
+//! RegisterWithVoucher, and the handler reads that instruction back through
+//! the instructions sysvar to make sure it covered the expected key and
+//! message.
+//!
+//! The verify instruction holds everything inline, in the layout of the
+//! Ed25519 program: a signature count and a padding byte, one set of seven
+//! little-endian `u16` offsets, then the public key, signature and message.
+
+use solana_program::{
+    account_info::AccountInfo,
+    ed25519_program,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+
+use crate::{error::NameRegistryError, pda::normalize_name};
+
+const MESSAGE_PREFIX: &[u8] = b"instantfolio_voucher";
+
+/// Offsets of the one signature a verify instruction checks.
+const OFFSETS_START: usize = 2;
+const OFFSETS_LEN: usize = 14;
+const DATA_START: usize = OFFSETS_START + OFFSETS_LEN;
+const PUBLIC_KEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+/// Instruction index meaning "this instruction" in the offsets.
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Message the program owner signs to grant `registrant` `discount_bps` off
+/// the fee of registering `name` under `config` until `expiry`.
+pub fn voucher_message(config: &Pubkey, registrant: &Pubkey, name: &str, discount_bps: u16, expiry: i64) -> Vec<u8> {
+    let name = normalize_name(name);
+    let mut message = Vec::with_capacity(MESSAGE_PREFIX.len() + 64 + 4 + name.len() + 2 + 8);
+    message.extend_from_slice(MESSAGE_PREFIX);
+    message.extend_from_slice(config.as_ref());
+    message.extend_from_slice(registrant.as_ref());
+    message.extend_from_slice(&(name.len() as u32).to_le_bytes());
+    message.extend_from_slice(name.as_bytes());
+    message.extend_from_slice(&discount_bps.to_le_bytes());
+    message.extend_from_slice(&expiry.to_le_bytes());
+    message
+}
+
+/// Ed25519 program instruction verifying that `signer` signed `message`.
+pub fn verify_instruction(signer: &Pubkey, message: &[u8], signature: &[u8; 64]) -> Instruction {
+    let public_key_offset = DATA_START;
+    let signature_offset = public_key_offset + PUBLIC_KEY_LEN;
+    let message_offset = signature_offset + SIGNATURE_LEN;
+    let offsets = [
+        signature_offset as u16,
+        CURRENT_INSTRUCTION,
+        public_key_offset as u16,
+        CURRENT_INSTRUCTION,
+        message_offset as u16,
+        message.len() as u16,
+        CURRENT_INSTRUCTION,
+    ];
+
+    let mut data = vec![1, 0];
+    data.extend(offsets.iter().flat_map(|offset| offset.to_le_bytes()));
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    Instruction { program_id: ed25519_program::id(), accounts: vec![], data }
+}
+
+/// Checks that the instruction before the current one verified `signature`
+/// by `signer` over `message`.
+pub fn check_verified(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> ProgramResult {
+    let current = load_current_index_checked(instructions_sysvar)?;
+    let previous = current.checked_sub(1).ok_or(NameRegistryError::InvalidVoucher)?;
+    let verify = load_instruction_at_checked(previous as usize, instructions_sysvar)?;
+    if verify.program_id != ed25519_program::id() {
+        return Err(NameRegistryError::InvalidVoucher.into());
+    }
+
+    let data = &verify.data;
+    if data.len() < DATA_START || data[0] != 1 {
+        return Err(NameRegistryError::InvalidVoucher.into());
+    }
+    let offset = |index: usize| {
+        let start = OFFSETS_START + 2 * index;
+        u16::from_le_bytes([data[start], data[start + 1]])
+    };
+    // Data pulled from other instructions would not be what this one shows
+    if [1, 3, 6].iter().any(|&index| offset(index) != CURRENT_INSTRUCTION) {
+        return Err(NameRegistryError::InvalidVoucher.into());
+    }
+    let field = |start: u16, len: usize| data.get(start as usize..start as usize + len);
+    let signed = field(offset(2), PUBLIC_KEY_LEN) == Some(signer.as_ref())
+        && field(offset(0), SIGNATURE_LEN) == Some(signature.as_slice())
+        && offset(5) as usize == message.len()
+        && field(offset(4), message.len()) == Some(message);
+    if !signed {
+        return Err(NameRegistryError::InvalidVoucher.into());
+    }
+    Ok(())
+}
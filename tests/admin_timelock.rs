@@ -0,0 +1,109 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    state::{AdminAction, FeeSchedule, PendingAdminAction, ProgramConfig},
+    validation::MAX_ADMIN_TIMELOCK,
+};
+
+use common::*;
+
+const TIMELOCK: u32 = 48 * 60 * 60;
+
+async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_timelocked_admin_actions_wait_before_applying() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let owner = initializer.pubkey();
+    let action_account = PendingAdminAction::find_address(&config, &program_id).0;
+
+    let ix = instructions::set_admin_timelock(&program_id, &owner, &config, MAX_ADMIN_TIMELOCK + 1);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidTimelock);
+    let ix = instructions::set_admin_timelock(&program_id, &owner, &config, TIMELOCK);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    // Fee changes now go through the queue
+    let ix = instructions::set_registration_fee(&program_id, &owner, &config, HIGH_FEE);
+    let result = process(&mut context, ix.clone(), &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    process(&mut context, instructions::with_timelock(ix, &program_id, &config), &initializer, &[&initializer])
+        .await
+        .unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.fee_schedule, FeeSchedule::flat(REGISTRATION_FEE));
+    let pending: PendingAdminAction = get_account_data(&mut context, &action_account).await;
+    assert_eq!(pending.action, AdminAction::SetFeeSchedule(FeeSchedule::flat(HIGH_FEE)));
+    assert!(pending.executable_at >= now + TIMELOCK as i64);
+
+    // One action at a time, executed only by the owner and only when due
+    let ix = instructions::with_timelock(
+        instructions::change_program_owner(&program_id, &owner, &config, Pubkey::new_unique()),
+        &program_id,
+        &config,
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AdminActionPending);
+
+    let ix = instructions::execute_admin_action(&program_id, &owner, &config);
+    let result = process(&mut context, ix.clone(), &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AdminActionNotReady);
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    warp_to(&mut context, pending.executable_at).await;
+    let stranger_ix = instructions::execute_admin_action(&program_id, &stranger.pubkey(), &config);
+    let result = process(&mut context, stranger_ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.fee_schedule, FeeSchedule::flat(HIGH_FEE));
+    assert!(context.banks_client.get_account(action_account).await.unwrap().is_none());
+
+    // A cancelled action never applies
+    let ix = instructions::with_timelock(
+        instructions::change_program_owner(&program_id, &owner, &config, stranger.pubkey()),
+        &program_id,
+        &config,
+    );
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::cancel_admin_action(&program_id, &owner, &config);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    assert!(context.banks_client.get_account(action_account).await.unwrap().is_none());
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.pending_owner, Pubkey::default());
+
+    // Promos and referral shares change what registrants pay, so they queue
+    // like fees
+    let ix = instructions::schedule_promo(&program_id, &owner, &config, 0, 1, 5_000);
+    let result = process(&mut context, ix.clone(), &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    process(&mut context, instructions::with_timelock(ix, &program_id, &config), &initializer, &[&initializer])
+        .await
+        .unwrap();
+    let pending: PendingAdminAction = get_account_data(&mut context, &action_account).await;
+    assert_eq!(pending.action, AdminAction::SchedulePromo { start: 0, end: 1, fee_override_bps: 5_000 });
+    let ix = instructions::cancel_admin_action(&program_id, &owner, &config);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::with_timelock(
+        instructions::set_referral_bps(&program_id, &owner, &config, 500),
+        &program_id,
+        &config,
+    );
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let pending: PendingAdminAction = get_account_data(&mut context, &action_account).await;
+    assert_eq!(pending.action, AdminAction::SetReferralBps(500));
+}
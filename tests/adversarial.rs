@@ -0,0 +1,702 @@
+mod common;
+
+use solana_program::{
+    instruction::{AccountMeta, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    instruction::Instruction,
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    instruction::NameRegistryInstruction,
+    pda::{fee_bucket, find_address_account_address, find_config_address, find_name_address, find_treasury_address},
+    state::{
+        FeeSchedule, NameAccount, NameCosigner, PendingUpdateAccount, PrimaryNameRecord, ProgramConfig, WalletSettings,
+    },
+};
+
+use common::*;
+
+fn instruction(program_id: &Pubkey, ix: NameRegistryInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ix.try_to_vec().unwrap(),
+    }
+}
+
+fn short_account(owner: &Pubkey) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(8),
+        data: vec![0; 8],
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+fn register_ix(
+    program_id: &Pubkey,
+    registrant: &Pubkey,
+    name_account: AccountMeta,
+    config_account: &Pubkey,
+    name: &str,
+) -> Instruction {
+    let address_account = find_address_account_address(&name_account.pubkey, program_id).0;
+    instruction(
+        program_id,
+        NameRegistryInstruction::RegisterName { name: name.to_string(), proof: None },
+        vec![
+            AccountMeta::new(*registrant, true),
+            name_account,
+            AccountMeta::new(address_account, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new(find_treasury_address(config_account, fee_bucket(name), program_id).0, false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_register_with_fake_config_rejected() {
+    let program_id = Pubkey::new_unique();
+    let attacker_program = Pubkey::new_unique();
+    let attacker = Keypair::new();
+    let fake_config = Pubkey::new_unique();
+    let forged_config = ProgramConfig {
+        is_initialized: true,
+        owner: attacker.pubkey(),
+        pending_owner: Pubkey::default(),
+        fee_schedule: FeeSchedule::flat(0),
+        ..ProgramConfig::default()
+    };
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![(fake_config, packed_account(forged_config, &attacker_program))],
+    ).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    fund_account(&mut context, &attacker.pubkey(), 1_000_000_000).await;
+
+    let name_account = find_name_address("free-name", &program_id).0;
+
+    // Registering against a zero-fee config owned by another program must fail
+    let ix = register_ix(
+        &program_id,
+        &attacker.pubkey(),
+        AccountMeta::new(name_account, false),
+        &fake_config,
+        "free-name",
+    );
+    let result = process(&mut context, ix, &attacker, &[&attacker]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+
+    // The name account created by the failed transaction was rolled back
+    assert!(context.banks_client.get_account(name_account).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_admin_with_fake_config_rejected() {
+    let program_id = Pubkey::new_unique();
+    let attacker_program = Pubkey::new_unique();
+    let attacker = Keypair::new();
+    let fake_config = Pubkey::new_unique();
+    let forged_config = ProgramConfig {
+        is_initialized: true,
+        owner: attacker.pubkey(),
+        pending_owner: Pubkey::default(),
+        fee_schedule: FeeSchedule::flat(REGISTRATION_FEE),
+        ..ProgramConfig::default()
+    };
+
+    let (mut context, _initializer, _config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![(fake_config, packed_account(forged_config, &attacker_program))],
+    ).await;
+    fund_account(&mut context, &attacker.pubkey(), 1_000_000_000).await;
+
+    let ix = instruction(
+        &program_id,
+        NameRegistryInstruction::SetRegistrationFee { new_fee: 0 },
+        vec![
+            AccountMeta::new(attacker.pubkey(), true),
+            AccountMeta::new(fake_config, false),
+        ],
+    );
+    let result = process(&mut context, ix, &attacker, &[&attacker]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+
+    let ix = withdraw_ix(&program_id, &attacker.pubkey(), &fake_config);
+    let result = process(&mut context, ix, &attacker, &[&attacker]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+
+    // Read-only getters would otherwise report the forged owner and fee
+    for ix in [
+        instructions::get_contract_owner(&program_id, &fake_config),
+        instructions::get_registration_fee(&program_id, &fake_config),
+        instructions::get_pending_contract_owner(&program_id, &fake_config),
+    ] {
+        let result = process(&mut context, ix, &attacker, &[&attacker]).await;
+        assert_instruction_error(result, InstructionError::IncorrectProgramId);
+    }
+}
+
+#[tokio::test]
+async fn test_foreign_name_account_rejected() {
+    let program_id = Pubkey::new_unique();
+    let attacker_program = Pubkey::new_unique();
+    let attacker = Keypair::new();
+    let fake_name = Pubkey::new_unique();
+    let forged_name = NameAccount {
+        is_initialized: true,
+        owner: attacker.pubkey(),
+        name: "victim".to_string(),
+        address: attacker.pubkey(),
+        cooldown_until: 0,
+        target_set: false,
+        parent: Pubkey::default(),
+        pending_owner: Pubkey::default(),
+        wildcard_subdomains: false,
+        tokenized: false,
+        registered_at: 0,
+        transfer_count: 0,
+        resolve_count: 0,
+        frozen: false,
+        frozen_by: Pubkey::default(),
+        frozen_at: 0,
+    };
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![(fake_name, packed_account(forged_name, &attacker_program))],
+    ).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    fund_account(&mut context, &attacker.pubkey(), 1_000_000_000).await;
+
+    let ix = instruction(
+        &program_id,
+        NameRegistryInstruction::RequestAddressUpdate { new_address: attacker.pubkey() },
+        vec![
+            AccountMeta::new(attacker.pubkey(), true),
+            AccountMeta::new(fake_name, false),
+            AccountMeta::new(PendingUpdateAccount::find_address(&fake_name, &program_id).0, false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&attacker.pubkey(), &program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(&fake_name, &program_id).0, false),
+        ],
+    );
+    let result = process(&mut context, ix, &attacker, &[&attacker]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
+
+#[tokio::test]
+async fn test_wrong_owner_name_account_rejected() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "test-name".to_string(),
+    ).await;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+
+    let attacker = Keypair::new();
+    fund_account(&mut context, &attacker.pubkey(), 1_000_000_000).await;
+    let pending_update_account = PendingUpdateAccount::find_address(&name_account, &program_id).0;
+
+    let ix = instruction(
+        &program_id,
+        NameRegistryInstruction::RequestAddressUpdate { new_address: attacker.pubkey() },
+        vec![
+            AccountMeta::new(attacker.pubkey(), true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(pending_update_account, false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&attacker.pubkey(), &program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(&name_account, &program_id).0, false),
+        ],
+    );
+    let result = process(&mut context, ix, &attacker, &[&attacker]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    let ix = instruction(
+        &program_id,
+        NameRegistryInstruction::RenameName { new_name: "stolen".to_string(), proof: None },
+        vec![
+            AccountMeta::new(attacker.pubkey(), true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(pending_update_account, false),
+            AccountMeta::new(address_account, false),
+        ],
+    );
+    let result = process(&mut context, ix, &attacker, &[&attacker]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_mismatched_pending_accounts_rejected() {
+    let program_id = Pubkey::new_unique();
+    let attacker_program = Pubkey::new_unique();
+    let attacker = Keypair::new();
+    let fake_pending = Pubkey::new_unique();
+    let forged_pending = PendingUpdateAccount {
+        is_initialized: true,
+        new_address: attacker.pubkey(),
+        requester: attacker.pubkey(),
+        ..PendingUpdateAccount::default()
+    };
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![(fake_pending, packed_account(forged_pending, &attacker_program))],
+    ).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    fund_account(&mut context, &attacker.pubkey(), 1_000_000_000).await;
+
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "test-name".to_string(),
+    ).await;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+
+    let complete_ix = |pending: Pubkey| instruction(
+        &program_id,
+        NameRegistryInstruction::CompleteAddressUpdate,
+        vec![
+            AccountMeta::new(attacker.pubkey(), true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(address_account, false),
+            AccountMeta::new(pending, false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&initializer.pubkey(), &program_id).0, false),
+            AccountMeta::new(find_treasury_address(&config_account, fee_bucket("test-name"), &program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+
+    // Pending update forged by another program
+    let result = process(&mut context, complete_ix(fake_pending), &attacker, &[&attacker]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+
+    // Pending update that was never requested
+    let pending_update_account = PendingUpdateAccount::find_address(&name_account, &program_id).0;
+    let result = process(&mut context, complete_ix(pending_update_account), &attacker, &[&attacker]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+
+    // Program account that is not the name's pending update
+    let stray_pending = Keypair::new();
+    add_account(&mut context, &stray_pending, &program_id, 0, "pending_update").await;
+    let result = process(&mut context, complete_ix(stray_pending.pubkey()), &attacker, &[&attacker]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    // Pending update requested for somebody else
+    let ix = instruction(
+        &program_id,
+        NameRegistryInstruction::RequestAddressUpdate { new_address: Pubkey::new_unique() },
+        vec![
+            AccountMeta::new(initializer.pubkey(), true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(pending_update_account, false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&initializer.pubkey(), &program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(&name_account, &program_id).0, false),
+        ],
+    );
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let result = process(&mut context, complete_ix(pending_update_account), &attacker, &[&attacker]).await;
+    assert_registry_error(result, NameRegistryError::NotPendingAddress);
+
+    // Pending update armed on another name
+    let attacker_name = register_name(&mut context, &program_id, &attacker, &config_account, "attacker".to_string()).await;
+    let ix = instructions::request_address_update(
+        &program_id, &attacker.pubkey(), &attacker_name, &config_account, attacker.pubkey(),
+    );
+    process(&mut context, ix, &attacker, &[&attacker]).await.unwrap();
+    let attacker_pending = PendingUpdateAccount::find_address(&attacker_name, &program_id).0;
+    let result = process(&mut context, complete_ix(attacker_pending), &attacker, &[&attacker]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    let name_data: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name_data.owner, initializer.pubkey());
+}
+
+#[tokio::test]
+async fn test_short_accounts_rejected() {
+    let program_id = Pubkey::new_unique();
+    let short_config = find_config_address(&program_id).0;
+    let short_name = find_name_address("test-name", &program_id).0;
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![
+            (short_config, short_account(&program_id)),
+            (short_name, short_account(&program_id)),
+        ],
+    ).await;
+
+    let ix = instructions::initialize(&program_id, &initializer.pubkey(), &short_config, 0);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+
+    // Clear the config PDA for Initialize to create it properly
+    context.set_account(&short_config, &Account::default().into());
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let ix = register_ix(
+        &program_id,
+        &initializer.pubkey(),
+        AccountMeta::new(short_name, false),
+        &config_account,
+        "test-name",
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+}
+
+#[tokio::test]
+async fn test_read_only_writable_slots_rejected() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let name_account = find_name_address("test-name", &program_id).0;
+
+    let ix = register_ix(
+        &program_id,
+        &initializer.pubkey(),
+        AccountMeta::new_readonly(name_account, false),
+        &config_account,
+        "test-name",
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AccountNotWritable);
+
+    let ix = instruction(
+        &program_id,
+        NameRegistryInstruction::SetRegistrationFee { new_fee: HIGH_FEE },
+        vec![
+            AccountMeta::new(initializer.pubkey(), true),
+            AccountMeta::new_readonly(config_account, false),
+        ],
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AccountNotWritable);
+
+    let config: ProgramConfig = get_account_data(&mut context, &config_account).await;
+    assert_eq!(config.fee_schedule, FeeSchedule::flat(REGISTRATION_FEE));
+}
+
+#[tokio::test]
+async fn test_name_account_must_be_derived() {
+    let program_id = Pubkey::new_unique();
+    let attacker = Keypair::new();
+    let forged_name = Pubkey::new_unique();
+    let forged_state = NameAccount {
+        is_initialized: true,
+        owner: attacker.pubkey(),
+        name: "victim".to_string(),
+        address: attacker.pubkey(),
+        cooldown_until: 0,
+        target_set: false,
+        parent: Pubkey::default(),
+        pending_owner: Pubkey::default(),
+        wildcard_subdomains: false,
+        tokenized: false,
+        registered_at: 0,
+        transfer_count: 0,
+        resolve_count: 0,
+        frozen: false,
+        frozen_by: Pubkey::default(),
+        frozen_at: 0,
+    };
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![(forged_name, packed_account(forged_state, &program_id))],
+    ).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    // Registering into an arbitrary account instead of the name's PDA
+    let ix = register_ix(
+        &program_id,
+        &initializer.pubkey(),
+        AccountMeta::new(Pubkey::new_unique(), false),
+        &config_account,
+        "test-name",
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    // Same for the address account, which is derived from the name account
+    let mut ix = register_ix(
+        &program_id,
+        &initializer.pubkey(),
+        AccountMeta::new(find_name_address("test-name", &program_id).0, false),
+        &config_account,
+        "test-name",
+    );
+    ix.accounts[2] = AccountMeta::new(Pubkey::new_unique(), false);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    // Program-owned state claiming a name it is not derived from
+    let ix = instruction(
+        &program_id,
+        NameRegistryInstruction::ResolveAddress,
+        vec![AccountMeta::new_readonly(forged_name, false)],
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::WrongDerivedAccount);
+
+    // The derived account holds each name at most once
+    register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "test-name".to_string(),
+    ).await;
+    let ix = register_ix(
+        &program_id,
+        &initializer.pubkey(),
+        AccountMeta::new(find_name_address("test-name", &program_id).0, false),
+        &config_account,
+        "test-name",
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+}
+
+#[tokio::test]
+async fn test_forged_parent_breaks_subname_resolution() {
+    let program_id = Pubkey::new_unique();
+    let attacker = Keypair::new();
+    let forged_parent = Pubkey::new_unique();
+    let name_state = |name: &str, parent: Pubkey| NameAccount {
+        is_initialized: true,
+        owner: attacker.pubkey(),
+        name: name.to_string(),
+        address: attacker.pubkey(),
+        cooldown_until: 0,
+        target_set: false,
+        parent,
+        pending_owner: Pubkey::default(),
+        wildcard_subdomains: false,
+        tokenized: false,
+        registered_at: 0,
+        transfer_count: 0,
+        resolve_count: 0,
+        frozen: false,
+        frozen_by: Pubkey::default(),
+        frozen_at: 0,
+    };
+    let subname = find_name_address("pay.victim", &program_id).0;
+
+    let (mut context, initializer, _config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![
+            (forged_parent, packed_account(name_state("victim", Pubkey::default()), &program_id)),
+            (subname, packed_account(name_state("pay.victim", forged_parent), &program_id)),
+        ],
+    ).await;
+
+    // The subname itself is derived correctly, its parent is not
+    let ix = instruction(
+        &program_id,
+        NameRegistryInstruction::ResolveAddress,
+        vec![
+            AccountMeta::new_readonly(subname, false),
+            AccountMeta::new_readonly(forged_parent, false),
+        ],
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::WrongDerivedAccount);
+}
+
+#[tokio::test]
+async fn test_withdraw_cannot_reap_treasury() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "test-name".to_string(),
+    ).await;
+    let ix = withdraw_ix(&program_id, &initializer.pubkey(), &config_account);
+    process(&mut context, ix.clone(), &initializer, &[&initializer]).await.unwrap();
+
+    // Repeated withdraws never touch the rent reserve
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NothingToWithdraw);
+
+    for treasury in treasury_buckets(&config_account, &program_id) {
+        let treasury = context.banks_client.get_account(treasury).await.unwrap().unwrap();
+        assert_eq!(treasury.lamports, Rent::default().minimum_balance(0));
+        assert_eq!(treasury.owner, program_id);
+    }
+
+    // Config state and its rent are never part of the sweep
+    let config = context.banks_client.get_account(config_account).await.unwrap().unwrap();
+    assert_eq!(config.lamports, Rent::default().minimum_balance(ProgramConfig::LEN));
+    let config: ProgramConfig = get_account_data(&mut context, &config_account).await;
+    assert!(config.is_initialized);
+}
+
+#[tokio::test]
+async fn test_underfunded_treasury_rejected() {
+    let program_id = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let config = find_config_address(&program_id).0;
+    let config_state = packed_account(
+        ProgramConfig {
+            is_initialized: true,
+            owner: owner.pubkey(),
+            ..ProgramConfig::default()
+        },
+        &program_id,
+    );
+    let underfunded_treasury = Account {
+        lamports: Rent::default().minimum_balance(0) / 2,
+        owner: program_id,
+        ..Account::default()
+    };
+
+    let (mut context, initializer, _config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![
+            (config, config_state),
+            (find_treasury_address(&config, fee_bucket("test-name"), &program_id).0, underfunded_treasury),
+        ],
+    ).await;
+
+    let ix = register_ix(
+        &program_id,
+        &initializer.pubkey(),
+        AccountMeta::new(find_name_address("test-name", &program_id).0, false),
+        &config,
+        "test-name",
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NotRentExempt);
+}
+
+#[tokio::test]
+async fn test_underfunded_state_accounts_rejected() {
+    let program_id = Pubkey::new_unique();
+    let underfunded = |space: usize| Account {
+        lamports: Rent::default().minimum_balance(space) / 2,
+        data: vec![0; space],
+        owner: program_id,
+        ..Account::default()
+    };
+    let config = find_config_address(&program_id).0;
+    let pending_update = PendingUpdateAccount::find_address(&find_name_address("alice", &program_id).0, &program_id).0;
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![
+            (config, underfunded(ProgramConfig::LEN)),
+            (pending_update, underfunded(PendingUpdateAccount::LEN)),
+        ],
+    ).await;
+
+    let ix = instructions::initialize(&program_id, &initializer.pubkey(), &config, 0);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NotRentExempt);
+
+    fund_account(&mut context, &config, Rent::default().minimum_balance(ProgramConfig::LEN)).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let name_account =
+        register_name(&mut context, &program_id, &initializer, &config_account, "alice".to_string()).await;
+    let ix = instructions::request_address_update(
+        &program_id,
+        &initializer.pubkey(),
+        &name_account,
+        &config_account,
+        Pubkey::new_unique(),
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NotRentExempt);
+}
+
+#[tokio::test]
+async fn test_wrong_account_type_rejected() {
+    let program_id = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let name_account = find_name_address("alice", &program_id).0;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    let name = NameAccount {
+        is_initialized: true,
+        owner: owner.pubkey(),
+        name: "alice".to_string(),
+        address: owner.pubkey(),
+        cooldown_until: 0,
+        target_set: false,
+        parent: Pubkey::default(),
+        pending_owner: Pubkey::default(),
+        wildcard_subdomains: false,
+        tokenized: false,
+        registered_at: 0,
+        transfer_count: 0,
+        resolve_count: 0,
+        frozen: false,
+        frozen_by: Pubkey::default(),
+        frozen_at: 0,
+    };
+    // Same size as a reverse record, so only the discriminator tells them apart
+    let primary = PrimaryNameRecord { is_initialized: true, wallet: owner.pubkey(), name: "alice".to_string() };
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![
+            (name_account, packed_account(name, &program_id)),
+            (address_account, packed_account(primary, &program_id)),
+        ],
+    ).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    fund_account(&mut context, &owner.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::rename_name(&program_id, &owner.pubkey(), "alice", &config_account, "alicia");
+    let result = process(&mut context, ix, &owner, &[&owner]).await;
+    assert_registry_error(result, NameRegistryError::InvalidAccountType);
+}
+
+#[tokio::test]
+async fn test_prefunded_pdas_still_get_created() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    let name_account = find_name_address("alice", &program_id).0;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+
+    // Anyone can send lamports to an address known in advance
+    fund_account(&mut context, &config_account, Rent::default().minimum_balance(0)).await;
+    fund_account(&mut context, &name_account, Rent::default().minimum_balance(0)).await;
+    fund_account(&mut context, &address_account, 2 * Rent::default().minimum_balance(NameAccount::LEN)).await;
+
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    register_name(&mut context, &program_id, &initializer, &config_account, "alice".to_string()).await;
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, initializer.pubkey());
+    for account in [config_account, name_account, address_account] {
+        let account = context.banks_client.get_account(account).await.unwrap().unwrap();
+        assert_eq!(account.owner, program_id);
+        assert!(account.lamports >= Rent::default().minimum_balance(account.data.len()));
+    }
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use solana_program::instruction::AccountMeta;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    analytics::{charset_class, CharsetClass},
+    client::instructions,
+    instruction::NameRegistryInstruction,
+    pda::find_name_address,
+    state::{NameAccount, ProgramConfig},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_registration_analytics_follow_config_flag() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let mut register_logs = Vec::new();
+    for (enabled, name) in [(false, "quiet-name"), (true, "loud-name7")] {
+        let ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(initializer.pubkey(), true),
+                AccountMeta::new(config_account, false),
+            ],
+            data: NameRegistryInstruction::SetAnalytics { enabled }.try_to_vec().unwrap(),
+        };
+        process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+        let ix = register_name_ix(
+            &program_id,
+            &initializer.pubkey(),
+            &find_name_address(name, &program_id).0,
+            &config_account,
+            name.to_string(),
+        );
+        register_logs.push(process_with_logs(&mut context, ix, &initializer).await);
+    }
+
+    assert!(!register_logs[0].iter().any(|log| log.contains("analytics:")));
+    let events: Vec<_> = register_logs[1].iter().filter(|log| log.contains("analytics:")).collect();
+    assert_eq!(events.len(), 1);
+    assert!(events[0].ends_with("analytics: event=register name_length=10 charset=alphanumeric hyphen=true"));
+    assert!(!events[0].contains("loud-name7"));
+}
+
+#[tokio::test]
+async fn test_name_statistics_are_counted_on_chain() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name_account = find_name_address("alice", &program_id).0;
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(name.registered_at > 0);
+    assert_eq!(name.registered_at, name.cooldown_until);
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.total_registrations, 1);
+
+    // Only resolutions passing the name writable are counted
+    let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    for _ in 0..2 {
+        let ix = instructions::resolve_address_counted(&program_id, &name_account, &[]);
+        process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    }
+
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob.pubkey(), false);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.resolve_count, 2);
+    assert_eq!(name.transfer_count, 1);
+    assert_eq!(name.owner, bob.pubkey());
+}
+
+#[test]
+fn test_charset_class() {
+    assert_eq!(charset_class("alice"), CharsetClass::Alphabetic);
+    assert_eq!(charset_class("2024"), CharsetClass::Numeric);
+    assert_eq!(charset_class("web3-dev"), CharsetClass::Alphanumeric);
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{fee_bucket, find_address_account_address, find_auction_address, find_auction_escrow_address, find_name_address, find_treasury_address},
+    state::{AuctionAccount, NameAccount, NameTombstone, ProgramConfig, ReverseRecord},
+    validation::AUCTION_DURATION,
+};
+
+use common::*;
+
+const MIN_BID: u64 = 50_000_000;
+
+async fn warp_past(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp + 1;
+    context.set_sysvar(&clock);
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context.banks_client.get_account(*address).await.unwrap().map_or(0, |account| account.lamports)
+}
+
+#[tokio::test]
+async fn test_auction_registers_name_to_highest_bidder() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = find_name_address("sol", &program_id).0;
+    let auction_account = find_auction_address(&name_account, &program_id).0;
+    let escrow = find_auction_escrow_address(&auction_account, &program_id).0;
+    let treasury = find_treasury_address(&config, fee_bucket("sol"), &program_id).0;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+
+    // Only the program owner auctions names
+    let ix = instructions::start_auction(&program_id, &alice.pubkey(), &config, "sol", MIN_BID);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::start_auction(&program_id, &initializer.pubkey(), &config, "sol", MIN_BID);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let auction: AuctionAccount = get_account_data(&mut context, &auction_account).await;
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, auction_account);
+
+    // The auctioned name cannot be registered the normal way
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "sol");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+
+    let ix = instructions::place_bid(&program_id, &alice.pubkey(), &config, "sol", None, MIN_BID - 1);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::BidTooLow);
+    let ix = instructions::place_bid(&program_id, &alice.pubkey(), &config, "sol", None, MIN_BID);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+
+    let ix = instructions::place_bid(&program_id, &bob.pubkey(), &config, "sol", Some(&alice.pubkey()), MIN_BID);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::BidTooLow);
+    let ix = instructions::place_bid(&program_id, &bob.pubkey(), &config, "sol", Some(&bob.pubkey()), 2 * MIN_BID);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::InvalidAuction);
+
+    // Outbidding refunds the previous bid from escrow. Alice pays no fee
+    // here, so her balance shows the exact refund
+    let alice_before = lamports(&mut context, &alice.pubkey()).await;
+    let ix = instructions::place_bid(&program_id, &bob.pubkey(), &config, "sol", Some(&alice.pubkey()), 2 * MIN_BID);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    assert_eq!(lamports(&mut context, &alice.pubkey()).await, alice_before + MIN_BID);
+    assert_eq!(lamports(&mut context, &escrow).await, Rent::default().minimum_balance(0) + 2 * MIN_BID);
+
+    let ix = instructions::settle_auction(&program_id, &alice.pubkey(), &config, &initializer.pubkey(), "sol");
+    let result = process(&mut context, ix.clone(), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::AuctionNotEnded);
+
+    warp_past(&mut context, auction.ends_at).await;
+    let late_bid = instructions::place_bid(&program_id, &alice.pubkey(), &config, "sol", Some(&bob.pubkey()), 3 * MIN_BID);
+    let result = process(&mut context, late_bid, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::AuctionEnded);
+
+    // Anyone can settle; the winner gets the name and the bid goes to the
+    // treasury
+    let treasury_before = lamports(&mut context, &treasury).await;
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, bob.pubkey());
+    assert_eq!(name.address, bob.pubkey());
+    assert!(name.registered_at > auction.ends_at);
+    let config_data: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!((config_data.total_names, config_data.total_registrations), (1, 1));
+    let reverse: ReverseRecord =
+        get_account_data(&mut context, &find_address_account_address(&name_account, &program_id).0).await;
+    assert_eq!(reverse, ReverseRecord::new(bob.pubkey(), "sol".to_string()));
+    assert_eq!(lamports(&mut context, &treasury).await, treasury_before + 2 * MIN_BID);
+    assert_eq!(lamports(&mut context, &auction_account).await, 0);
+    assert_eq!(lamports(&mut context, &escrow).await, 0);
+}
+
+#[tokio::test]
+async fn test_auction_without_bids_leaves_name_unregistered() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = find_name_address("gm", &program_id).0;
+    let auction_account = find_auction_address(&name_account, &program_id).0;
+
+    let ix = instructions::start_auction(&program_id, &initializer.pubkey(), &config, "gm", MIN_BID);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let auction: AuctionAccount = get_account_data(&mut context, &auction_account).await;
+    assert!(auction.ends_at >= AUCTION_DURATION);
+    assert_eq!(auction.highest_bidder, Pubkey::default());
+
+    // A name can only be auctioned while unregistered
+    let ix = instructions::start_auction(&program_id, &initializer.pubkey(), &config, "gm", MIN_BID);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AlreadyInitialized);
+    register_name(&mut context, &program_id, &initializer, &config_account, "taken".to_string()).await;
+    let ix = instructions::start_auction(&program_id, &initializer.pubkey(), &config, "taken", MIN_BID);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+
+    // Nobody bid: the name account is closed, its rent going back to the
+    // program owner, and nothing is counted besides "taken"
+    warp_past(&mut context, auction.ends_at).await;
+    let rent = lamports(&mut context, &name_account).await;
+    let escrow = find_auction_escrow_address(&auction_account, &program_id).0;
+    let refund = rent + lamports(&mut context, &auction_account).await + lamports(&mut context, &escrow).await;
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    let before = lamports(&mut context, &initializer.pubkey()).await;
+    let ix = instructions::settle_auction(&program_id, &alice.pubkey(), &config, &initializer.pubkey(), "gm");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    assert_eq!(lamports(&mut context, &name_account).await, 0);
+    assert_eq!(lamports(&mut context, &auction_account).await, 0);
+    assert_eq!(lamports(&mut context, &initializer.pubkey()).await, before + refund);
+    let config_data: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!((config_data.total_names, config_data.total_registrations), (1, 1));
+
+    // So it can be registered the normal way
+    register_name(&mut context, &program_id, &alice, &config_account, "gm".to_string()).await;
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, alice.pubkey());
+}
+
+#[tokio::test]
+async fn test_reserved_and_released_names_cannot_be_auctioned() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::reserve_name(&program_id, &initializer.pubkey(), &config, "brand");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::start_auction(&program_id, &initializer.pubkey(), &config, "brand", MIN_BID);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+
+    let name_account = register_name(&mut context, &program_id, &alice, &config, "alice".to_string()).await;
+    let ix = instructions::set_tombstone_period(&program_id, &initializer.pubkey(), &config, 3600);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    let owner = alice.pubkey();
+    let ix = instructions::unregister_name(&program_id, &owner, &name_account, &address_account, &config, &owner);
+    process(&mut context, instructions::with_tombstone(ix, &program_id), &alice, &[&alice]).await.unwrap();
+
+    let auction = || instructions::start_auction(&program_id, &initializer.pubkey(), &config, "alice", MIN_BID);
+    let result = process(&mut context, auction(), &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::with_tombstone(auction(), &program_id);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameRecentlyReleased);
+    let tombstone: NameTombstone =
+        get_account_data(&mut context, &NameTombstone::find_address(&name_account, &program_id).0).await;
+    warp_past(&mut context, tombstone.expires_at).await;
+    let ix = instructions::with_tombstone(auction(), &program_id);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    error::NameRegistryError,
+    instruction::NameRegistryInstruction,
+    state::BannerMessage,
+    validation::MAX_BANNER_LENGTH,
+};
+
+use common::*;
+
+fn set_banner_ix(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, message: &str) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*config, false),
+            AccountMeta::new(BannerMessage::find_address(config, program_id).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: NameRegistryInstruction::SetBannerMessage { message: message.to_string() }
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn test_set_and_clear_banner_message() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let banner_address = BannerMessage::find_address(&config_account, &program_id).0;
+
+    let ix = set_banner_ix(&program_id, &initializer.pubkey(), &config_account, "maintenance at 12:00 UTC");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let banner: BannerMessage = get_account_data(&mut context, &banner_address).await;
+    assert_eq!(banner.message, "maintenance at 12:00 UTC");
+
+    let ix = set_banner_ix(&program_id, &initializer.pubkey(), &config_account, "");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let banner: BannerMessage = get_account_data(&mut context, &banner_address).await;
+    assert!(banner.message.is_empty());
+}
+
+#[tokio::test]
+async fn test_banner_message_rejections() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = set_banner_ix(&program_id, &stranger.pubkey(), &config_account, "hijacked");
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    let too_long = "x".repeat(MAX_BANNER_LENGTH + 1);
+    let ix = set_banner_ix(&program_id, &initializer.pubkey(), &config_account, &too_long);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::BannerMessageTooLong);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    blocklist::proves_unlisted,
+    client::{blocklist::BlocklistTree, instructions},
+    error::NameRegistryError,
+    pda::find_name_address,
+    state::{Blocklist, NameAccount, PaidOperation, ProgramConfig, ReceiptAccount},
+};
+
+use common::*;
+
+#[test]
+fn test_tree_proves_exactly_the_unlisted_names() {
+    let names = ["scam", "phish", "fake-bank", "drainer", "airdrop", "free-sol", "rug", "official", "support"];
+    for count in 0..=names.len() {
+        let tree = BlocklistTree::new(names[..count].iter().copied());
+        for (i, name) in names.iter().enumerate() {
+            assert_eq!(tree.contains(name), i < count);
+            assert_eq!(tree.prove_unlisted(name).is_none(), i < count);
+        }
+        for name in ["alice", "bob", "scam-2", "SUPPORT-desk"] {
+            let proof = tree.prove_unlisted(name).unwrap();
+            assert!(proves_unlisted(&tree.root(), name, &proof));
+        }
+
+        // A proof only covers names between its bounds
+        if count > 0 {
+            let proof = tree.prove_unlisted("alice").unwrap();
+            assert!(!names[..count].iter().any(|name| proves_unlisted(&tree.root(), name, &proof)));
+        }
+    }
+
+    // Case does not dodge the list, and proofs do not carry across lists
+    let tree = BlocklistTree::new(["scam"]);
+    assert!(tree.contains("SCAM"));
+    let proof = BlocklistTree::new(["phish"]).prove_unlisted("alice").unwrap();
+    assert!(!proves_unlisted(&tree.root(), "alice", &proof));
+}
+
+#[tokio::test]
+async fn test_blocklist_gates_registrations_and_renames() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+
+    let tree = BlocklistTree::new(["scam", "phish"]);
+    let ix = instructions::set_blocklist_root(&program_id, &alice.pubkey(), &config, tree.root());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_blocklist_root(&program_id, &initializer.pubkey(), &config, tree.root());
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let blocklist: Blocklist = get_account_data(&mut context, &Blocklist::find_address(&config, &program_id).0).await;
+    assert_eq!((blocklist.config, blocklist.root), (config, tree.root()));
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameProofRequired);
+
+    // A proof for one name does not clear another
+    let proof = tree.prove_unlisted("alice").unwrap();
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "scam");
+    let ix = instructions::with_name_proof(ix, &program_id, &config, proof.clone());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameProof);
+
+    // Optional accounts follow the blocklist PDA
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let ix = instructions::with_receipt(ix, &program_id, &alice.pubkey(), "alice");
+    let ix = instructions::with_name_proof(ix, &program_id, &config, proof);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let alice_account = find_name_address("alice", &program_id).0;
+    let receipt = ReceiptAccount::find_address(&alice.pubkey(), &alice_account, PaidOperation::Registration, &program_id);
+    let receipt: ReceiptAccount = get_account_data(&mut context, &receipt.0).await;
+    assert_eq!(receipt.amount, REGISTRATION_FEE);
+
+    // Renaming onto a listed name is refused just the same
+    let ix = instructions::rename_name(&program_id, &alice.pubkey(), "alice", &config, "phish");
+    let ix = instructions::with_name_proof(ix, &program_id, &config, tree.prove_unlisted("alice-2").unwrap());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameProof);
+    let ix = instructions::rename_name(&program_id, &alice.pubkey(), "alice", &config, "alice-2");
+    let ix = instructions::with_name_proof(ix, &program_id, &config, tree.prove_unlisted("alice-2").unwrap());
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("alice-2", &program_id).0).await;
+    assert_eq!(name.owner, alice.pubkey());
+
+    // A zero root turns the blocklist off
+    let ix = instructions::set_blocklist_root(&program_id, &initializer.pubkey(), &config, [0; 32]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_data: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert!(!config_data.blocklist_enabled);
+    register_name(&mut context, &program_id, &alice, &config_account, "scam".to_string()).await;
+}
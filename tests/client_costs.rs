@@ -0,0 +1,81 @@
+mod common;
+
+use solana_program::program_error::ProgramError;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use instant_folio::{
+    client::{estimate_costs, CostOptions},
+    error::NameRegistryError,
+    pda::find_name_address,
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_estimate_matches_registration_spend() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let registrant = Keypair::new();
+    fund_account(&mut context, &registrant.pubkey(), 1_000_000_000).await;
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instruction = register_name_ix(
+        &program_id,
+        &registrant.pubkey(),
+        &find_name_address("alice", &program_id).0,
+        &config_account,
+        "alice".to_string(),
+    );
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&registrant.pubkey()));
+    transaction.sign(&[&registrant], blockhash);
+
+    let message_fee = context
+        .banks_client
+        .get_fee_for_message(transaction.message().clone())
+        .await
+        .unwrap()
+        .unwrap();
+    let options = CostOptions {
+        rent,
+        registration_fee: REGISTRATION_FEE,
+        lamports_per_signature: message_fee / transaction.signatures.len() as u64,
+        ..CostOptions::default()
+    };
+    let estimate = estimate_costs("alice", &options).unwrap();
+    assert_eq!(estimate.transaction_fees, message_fee);
+
+    let before = context.banks_client.get_balance(registrant.pubkey()).await.unwrap();
+    context.banks_client.process_transaction(transaction).await.unwrap();
+    let after = context.banks_client.get_balance(registrant.pubkey()).await.unwrap();
+    assert_eq!(before - after, estimate.total);
+}
+
+#[test]
+fn test_estimate_options() {
+    let base = estimate_costs("alice", &CostOptions::default()).unwrap();
+    assert_eq!(base.total, base.rent + base.transaction_fees);
+
+    let private = estimate_costs(
+        "alice",
+        &CostOptions {
+            create_wallet_settings: true,
+            registration_fee: REGISTRATION_FEE,
+            ..CostOptions::default()
+        },
+    )
+    .unwrap();
+    assert!(private.rent > base.rent);
+    assert_eq!(private.total, private.rent + REGISTRATION_FEE + private.transaction_fees);
+
+    let receipted = estimate_costs("alice", &CostOptions { create_receipt: true, ..CostOptions::default() }).unwrap();
+    assert!(receipted.rent > base.rent);
+
+    assert_eq!(
+        estimate_costs("Not Valid", &CostOptions::default()),
+        Err(ProgramError::from(NameRegistryError::InvalidNameFormat)),
+    );
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::client::export::{name_entries, to_csv, to_json, NameEntry};
+
+use common::*;
+
+#[tokio::test]
+async fn test_export_lists_registered_names_sorted() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let bob = Keypair::new();
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+
+    let zed = register_name(&mut context, &program_id, &bob, &config, "zed".to_string()).await;
+    let alice = register_name(&mut context, &program_id, &initializer, &config, "alice".to_string()).await;
+    let mut accounts = Vec::new();
+    for address in [zed, config, alice] {
+        accounts.push((address, context.banks_client.get_account(address).await.unwrap().unwrap()));
+    }
+
+    // The config decodes as no name and is skipped
+    let entries = name_entries(&program_id, accounts);
+    let owner = initializer.pubkey();
+    assert_eq!(
+        entries,
+        vec![
+            NameEntry { name: "alice".to_string(), name_account: alice, owner, address: owner },
+            NameEntry { name: "zed".to_string(), name_account: zed, owner: bob.pubkey(), address: bob.pubkey() },
+        ]
+    );
+
+    assert_eq!(
+        to_csv(&entries),
+        format!(
+            "name,name_account,owner,address\nalice,{alice},{owner},{owner}\nzed,{zed},{bob},{bob}\n",
+            bob = bob.pubkey(),
+        )
+    );
+    assert_eq!(
+        to_json(&entries[..1]),
+        format!(
+            "[\n  {{\"name\": \"alice\", \"name_account\": \"{alice}\", \"owner\": \"{owner}\", \"address\": \"{owner}\"}}\n]\n"
+        )
+    );
+    assert_eq!(to_json(&[]), "[]\n");
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use solana_program::pubkey::Pubkey;
+use solana_rpc_client_api::filter::RpcFilterType;
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::{Keypair, Signer},
+};
+use instant_folio::{client::filters, pda::find_address_account_address};
+
+use common::*;
+
+fn matches(filters: &[RpcFilterType], account: &Account) -> bool {
+    let account = AccountSharedData::from(account.clone());
+    filters.iter().all(|filter| filter.allows(&account))
+}
+
+#[tokio::test]
+async fn test_filters_select_registry_accounts() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let other = Keypair::new();
+    fund_account(&mut context, &other.pubkey(), 1_000_000_000).await;
+
+    let mut registered = Vec::new();
+    for (registrant, name) in [(&initializer, "alice"), (&other, "bob")] {
+        let name_account = register_name(
+            &mut context,
+            &program_id,
+            registrant,
+            &config_account,
+            name.to_string(),
+        ).await;
+        registered.push((name_account, find_address_account_address(&name_account, &program_id).0));
+    }
+    let unused_name = Keypair::new();
+    add_account(&mut context, &unused_name, &program_id, 0, "name").await;
+
+    let mut fetch = Vec::new();
+    for address in [
+        registered[0].0,
+        registered[0].1,
+        registered[1].0,
+        registered[1].1,
+        unused_name.pubkey(),
+        config_account,
+    ] {
+        let account = context.banks_client.get_account(address).await.unwrap().unwrap();
+        fetch.push((address, account));
+    }
+    let select = |filters: &[RpcFilterType]| -> Vec<(Pubkey, Account)> {
+        fetch.iter().filter(|(_, account)| matches(filters, account)).cloned().collect()
+    };
+
+    let names = filters::decode_name_accounts(select(&filters::name_accounts()));
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[0].1.name, "alice");
+    assert_eq!(names[1].1.name, "bob");
+
+    let owned = filters::decode_name_accounts(select(&filters::names_by_owner(&other.pubkey())));
+    assert_eq!(owned.len(), 1);
+    assert_eq!(owned[0].0, registered[1].0);
+    assert_eq!(owned[0].1.owner, other.pubkey());
+
+    let reverse = filters::decode_reverse_records(select(&filters::reverse_records()));
+    assert_eq!(
+        reverse.iter().map(|(address, _)| *address).collect::<Vec<_>>(),
+        vec![registered[0].1, registered[1].1],
+    );
+
+    assert!(select(&filters::pending_update_accounts()).is_empty());
+}
+
+#[test]
+fn test_decode_skips_foreign_accounts() {
+    let garbage = Account {
+        data: vec![7; 3],
+        ..Account::default()
+    };
+    let decoded = filters::decode_name_accounts(vec![(Pubkey::new_unique(), garbage)]);
+    assert!(decoded.is_empty());
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    formats,
+    pda::{find_address_account_address, find_name_address, find_record_address},
+    state::{FeeSchedule, NameAccount, ProgramConfig, RecordAccount, ReverseRecord},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_builders_drive_a_full_flow() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    let owner = initializer.pubkey();
+    let config = config_account;
+
+    let ix = instructions::initialize(&program_id, &owner, &config, REGISTRATION_FEE);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name_account = find_name_address("alice", &program_id).0;
+    let address: ReverseRecord =
+        get_account_data(&mut context, &find_address_account_address(&name_account, &program_id).0).await;
+    assert_eq!(address.name, "alice");
+
+    let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+    let logs = process_with_logs(&mut context, ix, &alice).await;
+    let event = formats::resolved_event("alice", &alice.pubkey());
+    assert!(logs.iter().any(|log| log.ends_with(&event)));
+
+    let ix = instructions::set_primary_name(&program_id, &alice.pubkey(), "alice");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::resolve_name(&program_id, &alice.pubkey(), "alice");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+
+    let ix = instructions::set_record(&program_id, &alice.pubkey(), &name_account, "url", "https://alice.example");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let record: RecordAccount =
+        get_account_data(&mut context, &find_record_address(&name_account, "url", &program_id).0).await;
+    assert_eq!(record.value, "https://alice.example");
+
+    let ix = instructions::register_subname(&program_id, &alice.pubkey(), "alice", &config, "pay");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let subname: NameAccount = get_account_data(&mut context, &find_name_address("pay.alice", &program_id).0).await;
+    assert_eq!(subname.parent, name_account);
+
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob.pubkey(), true);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::accept_name_transfer(&program_id, &bob.pubkey(), &alice.pubkey(), &name_account, &config);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, bob.pubkey());
+
+    let ix = instructions::set_registration_fee(&program_id, &owner, &config, HIGH_FEE);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::pause(&program_id, &owner, &config);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::unpause(&program_id, &owner, &config);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::withdraw(&program_id, &owner, &config, &owner, None);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let ix = instructions::change_program_owner(&program_id, &owner, &config, bob.pubkey());
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::accept_program_ownership(&program_id, &bob.pubkey(), &config);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.owner, bob.pubkey());
+    assert_eq!(config_state.fee_schedule, FeeSchedule::flat(HIGH_FEE));
+}
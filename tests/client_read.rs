@@ -0,0 +1,101 @@
+mod common;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use instant_folio::client::read::{ReadError, ReadResults, ReadTx};
+
+use common::*;
+
+fn return_log(program_id: &Pubkey, data: &[u8]) -> String {
+    format!("Program return: {} {}", program_id, STANDARD.encode(data))
+}
+
+#[tokio::test]
+async fn test_read_tx_simulates_in_one_transaction() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "test-name".to_string(),
+    ).await;
+
+    let read = ReadTx::new(program_id)
+        .contract_owner(config_account)
+        .registration_fee(config_account)
+        .pending_contract_owner(config_account)
+        .resolve_address(name_account);
+    assert_eq!(read.instructions().len(), 4);
+
+    let transaction = read.transaction(&initializer.pubkey(), context.last_blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    assert!(simulation.result.unwrap().is_ok());
+
+    // The last getter's result is what the transaction reports as return data
+    // (with trailing zero bytes trimmed by the bank)
+    let mut return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, program_id);
+    return_data.data.resize(32, 0);
+    assert_eq!(return_data.data, initializer.pubkey().to_bytes());
+}
+
+#[test]
+fn test_read_tx_decodes_logs_in_order() {
+    let program_id = Pubkey::new_unique();
+    let other_program = Pubkey::new_unique();
+    let config = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let name_a = Pubkey::new_unique();
+    let name_b = Pubkey::new_unique();
+    let address_a = Pubkey::new_unique();
+    let address_b = Pubkey::new_unique();
+
+    let read = ReadTx::new(program_id)
+        .contract_owner(config)
+        .registration_fee(config)
+        .resolve_address(name_a)
+        .pending_contract_owner(config)
+        .resolve_address(name_b);
+
+    let logs = vec![
+        format!("Program {} invoke [1]", program_id),
+        return_log(&program_id, owner.as_ref()),
+        return_log(&other_program, &[7; 8]),
+        return_log(&program_id, &REGISTRATION_FEE.to_le_bytes()),
+        return_log(&program_id, address_a.as_ref()),
+        return_log(&program_id, Pubkey::default().as_ref()),
+        return_log(&program_id, address_b.as_ref()),
+    ];
+
+    assert_eq!(
+        read.decode(&logs).unwrap(),
+        ReadResults {
+            contract_owner: Some(owner),
+            registration_fee: Some(REGISTRATION_FEE),
+            pending_contract_owner: Some(Pubkey::default()),
+            resolved_addresses: vec![(name_a, address_a), (name_b, address_b)],
+        }
+    );
+}
+
+#[test]
+fn test_read_tx_decode_errors() {
+    let program_id = Pubkey::new_unique();
+    let config = Pubkey::new_unique();
+    let read = ReadTx::new(program_id)
+        .contract_owner(config)
+        .registration_fee(config);
+
+    let logs = vec![return_log(&program_id, Pubkey::new_unique().as_ref())];
+    assert_eq!(read.decode(&logs), Err(ReadError::MissingReturnData(1)));
+
+    let logs = vec![
+        return_log(&program_id, Pubkey::new_unique().as_ref()),
+        return_log(&program_id, &[1; 9]),
+    ];
+    assert_eq!(read.decode(&logs), Err(ReadError::InvalidReturnData(1)));
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use solana_program::pubkey::Pubkey;
+use instant_folio::{
+    client::{
+        grind_vanity_seed,
+        vanity::{grind_commitment_salt, grind_promo_claim_preimage, VanityError},
+    },
+    pda::{find_commitment_address, find_promo_claim_address, name_commitment, promo_claim_commitment},
+};
+
+const BUDGET: Duration = Duration::from_secs(30);
+
+#[test]
+fn test_grind_commitment_salt() {
+    let program_id = Pubkey::new_unique();
+    let committer = Pubkey::new_unique();
+    let start = [7; 32];
+
+    let found = grind_commitment_salt(&program_id, &committer, "alice", "A", start, BUDGET)
+        .unwrap()
+        .unwrap();
+    assert!(found.address.to_string().starts_with('A'));
+    let commitment = name_commitment("alice", &found.seed, &committer);
+    assert_eq!(found.address, find_commitment_address(&committer, &commitment, &program_id).0);
+    // Only the leading counter bytes vary, the rest of the secret is kept
+    assert_eq!(found.seed[8..], start[8..]);
+}
+
+#[test]
+fn test_grind_promo_claim_preimage() {
+    let program_id = Pubkey::new_unique();
+    let config = Pubkey::new_unique();
+
+    let found = grind_promo_claim_preimage(&program_id, &config, "z", [3; 32], BUDGET)
+        .unwrap()
+        .unwrap();
+    assert!(found.address.to_string().starts_with('z'));
+    let commitment = promo_claim_commitment(&found.seed);
+    assert_eq!(found.address, find_promo_claim_address(&config, &commitment, &program_id).0);
+}
+
+#[test]
+fn test_grind_vanity_seed_limits() {
+    let derive = |seed: &[u8; 32]| Pubkey::new_from_array(*seed);
+    assert_eq!(grind_vanity_seed("0x", [0; 32], BUDGET, derive), Err(VanityError::InvalidCharacter('0')));
+    assert_eq!(grind_vanity_seed(&"1".repeat(45), [0; 32], BUDGET, derive), Err(VanityError::PrefixTooLong));
+    assert_eq!(grind_vanity_seed("abc", [0; 32], Duration::ZERO, derive), Ok(None));
+
+    // An empty prefix matches the first seed tried
+    let found = grind_vanity_seed("", [9; 32], BUDGET, derive).unwrap().unwrap();
+    assert_eq!((found.seed, found.attempts), ([9; 32], 1));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use solana_program::{clock::Clock, program_error::ProgramError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::{instructions, read::ReadTx, view},
+    error::NameRegistryError,
+    formats,
+    pda::find_name_address,
+    state::{NameAccount, ProgramConfig},
+};
+
+use common::*;
+
+/// Simulates a single getter and returns its return data, padded back to
+/// `len` bytes since the bank trims trailing zeros.
+async fn getter(context: &mut ProgramTestContext, read: ReadTx, payer: &Keypair, len: usize) -> Vec<u8> {
+    let transaction = read.transaction(&payer.pubkey(), context.last_blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    let mut data = simulation.simulation_details.unwrap().return_data.unwrap().data;
+    data.resize(len, 0);
+    data
+}
+
+#[tokio::test]
+async fn test_views_match_getters() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = register_name(&mut context, &program_id, &initializer, &config_account, "test-name".to_string()).await;
+
+    let successor = Keypair::new();
+    let ix = instructions::change_program_owner(&program_id, &initializer.pubkey(), &config, successor.pubkey());
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let config_data = context.banks_client.get_account(config).await.unwrap().unwrap().data;
+    let name_data = context.banks_client.get_account(name_account).await.unwrap().unwrap().data;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+
+    let owner = getter(&mut context, ReadTx::new(program_id).contract_owner(config), &initializer, 32).await;
+    assert_eq!(formats::decode_address(&owner), Some(view::contract_owner(&config_data).unwrap()));
+    let fee = getter(&mut context, ReadTx::new(program_id).registration_fee(config), &initializer, 8).await;
+    assert_eq!(formats::decode_fee(&fee), Some(view::registration_fee(&config_data, now).unwrap()));
+    let pending = getter(&mut context, ReadTx::new(program_id).pending_contract_owner(config), &initializer, 32).await;
+    assert_eq!(formats::decode_address(&pending), Some(successor.pubkey()));
+    assert_eq!(view::pending_contract_owner(&config_data).unwrap(), successor.pubkey());
+    let address = getter(&mut context, ReadTx::new(program_id).resolve_address(name_account), &initializer, 32).await;
+    assert_eq!(
+        formats::decode_address(&address),
+        Some(view::resolve_address(&program_id, &name_account, &name_data).unwrap()),
+    );
+}
+
+#[test]
+fn test_views_reject_what_getters_reject() {
+    let program_id = Pubkey::new_unique();
+    let name = NameAccount {
+        is_initialized: true,
+        owner: Pubkey::new_unique(),
+        name: "alice".to_string(),
+        address: Pubkey::new_unique(),
+        ..NameAccount::default()
+    };
+    let data = packed_account(name.clone(), &program_id).data;
+    let name_account = find_name_address("alice", &program_id).0;
+    assert_eq!(view::resolve_address(&program_id, &name_account, &data), Ok(name.address));
+
+    // A name stored anywhere but its own PDA is a forgery
+    let forged = find_name_address("bob", &program_id).0;
+    assert_eq!(
+        view::resolve_address(&program_id, &forged, &data),
+        Err(ProgramError::from(NameRegistryError::WrongDerivedAccount)),
+    );
+    let blank = vec![0; data.len()];
+    assert_eq!(
+        view::name_from_account_data(&program_id, &name_account, &blank),
+        Err(ProgramError::from(NameRegistryError::NameNotFound)),
+    );
+    assert!(view::config_from_account_data(&data).is_err());
+
+    let retired = ProgramConfig {
+        is_initialized: true,
+        successor: Pubkey::new_unique(),
+        ..ProgramConfig::default()
+    };
+    let data = packed_account(retired, &program_id).data;
+    assert_eq!(view::contract_owner(&data), Err(ProgramError::from(NameRegistryError::ConfigMigrated)));
+}
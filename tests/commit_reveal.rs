@@ -0,0 +1,73 @@
+mod common;
+
+use solana_program::clock::Clock;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_commitment_address, find_name_address, name_commitment},
+    state::{NameAccount, NameCommitment},
+    validation::MIN_COMMITMENT_AGE,
+};
+
+use common::*;
+
+async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_reveal_registers_committed_name() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    let mallory = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &mallory.pubkey(), 1_000_000_000).await;
+
+    // Once required, names can only be registered through commit-reveal
+    let ix = instructions::set_commit_reveal_required(&program_id, &alice.pubkey(), &config, true);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_commit_reveal_required(&program_id, &initializer.pubkey(), &config, true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::register_name(&program_id, &mallory.pubkey(), &config, "alice");
+    let result = process(&mut context, ix, &mallory, &[&mallory]).await;
+    assert_registry_error(result, NameRegistryError::CommitRevealRequired);
+
+    let salt = [7; 32];
+    let name_hash = name_commitment("alice", &salt, &alice.pubkey());
+    let commitment_account = find_commitment_address(&alice.pubkey(), &name_hash, &program_id).0;
+    let ix = instructions::commit_name(&program_id, &alice.pubkey(), name_hash);
+    process(&mut context, ix.clone(), &alice, &[&alice]).await.unwrap();
+    let commitment: NameCommitment = get_account_data(&mut context, &commitment_account).await;
+    assert_eq!(commitment.committer, alice.pubkey());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::AlreadyInitialized);
+
+    let reveal = instructions::reveal_and_register(&program_id, &alice.pubkey(), &config, "alice", salt);
+    let result = process(&mut context, reveal.clone(), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::CommitmentTooNew);
+    warp_to(&mut context, commitment.committed_at + MIN_COMMITMENT_AGE).await;
+
+    // A copied reveal does not match any commitment of another wallet, nor
+    // does a wrong salt
+    let ix = instructions::reveal_and_register(&program_id, &mallory.pubkey(), &config, "alice", salt);
+    let result = process(&mut context, ix, &mallory, &[&mallory]).await;
+    assert_registry_error(result, NameRegistryError::InvalidCommitment);
+    let mut wrong_salt = instructions::reveal_and_register(&program_id, &alice.pubkey(), &config, "alice", [8; 32]);
+    wrong_salt.accounts[0] = reveal.accounts[0].clone();
+    let result = process(&mut context, wrong_salt, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidCommitment);
+
+    process(&mut context, reveal, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("alice", &program_id).0).await;
+    assert_eq!(name.owner, alice.pubkey());
+    let account = context.banks_client.get_account(commitment_account).await.unwrap();
+    assert!(account.is_none());
+}
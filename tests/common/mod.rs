@@ -0,0 +1,310 @@
+#![allow(dead_code)]
+
+use solana_program::{
+    bpf_loader_upgradeable,
+    instruction::{AccountMeta, InstructionError},
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+    instruction::Instruction,
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    instruction::NameRegistryInstruction,
+    pda::{
+        fee_bucket, find_address_account_address, find_config_address, find_name_address, find_program_data_address,
+        find_treasury_address,
+    },
+    state::{NameAccount, PendingUpdateAccount, ProgramConfig, ReverseRecord},
+};
+
+pub const REGISTRATION_FEE: u64 = 1_000_000; // 0.001 SOL
+pub const HIGH_FEE: u64 = 2_000_000; // 0.002 SOL
+
+pub fn program_test(program_id: &Pubkey) -> ProgramTest {
+    ProgramTest::new(
+        "instant_folio",
+        *program_id,
+        processor!(instant_folio::process_instruction),
+    )
+}
+
+pub async fn setup_program() -> (ProgramTestContext, Keypair, Pubkey, Pubkey) {
+    setup_program_with_accounts(Pubkey::new_unique(), Vec::new()).await
+}
+
+/// Like `setup_program`, but preloads `accounts` at genesis so tests can inject
+/// arbitrary (including forged) account state.
+pub async fn setup_program_with_accounts(
+    program_id: Pubkey,
+    accounts: Vec<(Pubkey, Account)>,
+) -> (ProgramTestContext, Keypair, Pubkey, Pubkey) {
+    let mut program_test = program_test(&program_id);
+    for (address, account) in accounts {
+        program_test.add_account(address, account);
+    }
+    start_program_test(program_test, program_id).await
+}
+
+/// Starts a caller-assembled `ProgramTest` (e.g. with extra programs) with the
+/// usual funded initializer, returning the config PDA Initialize creates.
+pub async fn start_program_test(
+    mut program_test: ProgramTest,
+    program_id: Pubkey,
+) -> (ProgramTestContext, Keypair, Pubkey, Pubkey) {
+    let initializer = Keypair::new();
+
+    program_test.add_account(
+        initializer.pubkey(),
+        Account {
+            lamports: 1_000_000_000,
+            owner: solana_program::system_program::id(),
+            ..Account::default()
+        },
+    );
+
+    program_test.add_account(
+        find_program_data_address(&program_id).0,
+        program_data_account(&initializer.pubkey()),
+    );
+
+    let context = program_test.start_with_context().await;
+    (context, initializer, find_config_address(&program_id).0, program_id)
+}
+
+/// Program data account of the upgradeable loader naming `upgrade_authority`,
+/// which Initialize checks its signer against.
+pub fn program_data_account(upgrade_authority: &Pubkey) -> Account {
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(upgrade_authority.as_ref());
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: bpf_loader_upgradeable::id(),
+        ..Account::default()
+    }
+}
+
+pub fn convert_instruction(
+    ix: NameRegistryInstruction,
+    program_id: &Pubkey,
+    accounts: &[(&Keypair, bool)],
+    system_program: &Pubkey,
+) -> Instruction {
+    let accounts = accounts
+        .iter()
+        .map(|(keypair, is_signer)| (keypair.pubkey(), *is_signer))
+        .collect::<Vec<_>>();
+    convert_instruction_with_keys(ix, program_id, &accounts, system_program)
+}
+
+/// Like `convert_instruction`, for accounts without a keypair (e.g. PDAs).
+pub fn convert_instruction_with_keys(
+    ix: NameRegistryInstruction,
+    program_id: &Pubkey,
+    accounts: &[(Pubkey, bool)],
+    system_program: &Pubkey,
+) -> Instruction {
+    let mut account_metas = accounts
+        .iter()
+        .map(|(pubkey, is_signer)| {
+            AccountMeta::new(
+                *pubkey,
+                *is_signer,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Add system program if needed
+    account_metas.push(AccountMeta::new_readonly(*system_program, false));
+
+    Instruction {
+        program_id: *program_id,
+        accounts: account_metas,
+        data: ix.try_to_vec().unwrap(),
+    }
+}
+
+pub async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+    transaction.sign(&signers.to_vec(), blockhash);
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// Processes `instruction`, which must succeed, and returns its log messages.
+pub async fn process_with_logs(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    payer: &Keypair,
+) -> Vec<String> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&payer.pubkey()), &[payer], blockhash);
+    let result = context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+    result.result.unwrap();
+    result.metadata.unwrap().log_messages
+}
+
+pub async fn initialize_program(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    initializer: &Keypair,
+    config_account: &Pubkey,
+    registration_fee: u64,
+) {
+    let initialize_ix = instructions::initialize(
+        program_id,
+        &initializer.pubkey(),
+        config_account,
+        registration_fee,
+    );
+
+    // Create transaction
+    let mut transaction = Transaction::new_with_payer(&[initialize_ix], Some(&initializer.pubkey()));
+    transaction.sign(&[initializer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+}
+
+/// Registers `name` for `registrant` and returns its name account PDA.
+pub async fn register_name(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    registrant: &Keypair,
+    config_account: &Pubkey,
+    name: String,
+) -> Pubkey {
+    let name_account = find_name_address(&name, program_id).0;
+    let instruction = register_name_ix(
+        program_id,
+        &registrant.pubkey(),
+        &name_account,
+        config_account,
+        name,
+    );
+    process(context, instruction, registrant, &[registrant]).await.unwrap();
+    name_account
+}
+
+pub fn register_name_ix(
+    program_id: &Pubkey,
+    registrant: &Pubkey,
+    name_account: &Pubkey,
+    config_account: &Pubkey,
+    name: String,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*registrant, true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new(find_address_account_address(name_account, program_id).0, false),
+            AccountMeta::new(*config_account, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new(find_treasury_address(config_account, fee_bucket(&name), program_id).0, false),
+        ],
+        data: NameRegistryInstruction::RegisterName { name, proof: None }.try_to_vec().unwrap(),
+    }
+}
+
+/// Every treasury bucket of `config_account`, in bucket order.
+pub fn treasury_buckets(config_account: &Pubkey, program_id: &Pubkey) -> Vec<Pubkey> {
+    instructions::treasury_buckets(config_account, program_id)
+}
+
+pub fn withdraw_ix(program_id: &Pubkey, owner: &Pubkey, config_account: &Pubkey) -> Instruction {
+    instructions::withdraw(program_id, owner, config_account, owner, None)
+}
+
+/// Creates a program account sized for `account_type`, funded with `lamports`
+/// on top of its rent-exempt minimum.
+pub async fn add_account(
+    context: &mut ProgramTestContext,
+    keypair: &Keypair,
+    owner: &Pubkey,
+    lamports: u64,
+    account_type: &str,
+) {
+    let space = match account_type {
+        "config" => ProgramConfig::LEN,
+        "name" => NameAccount::LEN,
+        "address" => ReverseRecord::LEN,
+        "pending_update" => PendingUpdateAccount::LEN,
+        _ => panic!("Unknown account type: {}", account_type),
+    };
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let create_account_ix = system_instruction::create_account(
+        &context.payer.pubkey(),
+        &keypair.pubkey(),
+        rent.minimum_balance(space) + lamports,
+        space as u64,
+        owner,
+    );
+
+    let mut transaction = Transaction::new_with_payer(
+        &[create_account_ix],
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer, keypair], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+}
+
+/// Funds a system-owned wallet so it can pay fees and sign transactions.
+pub async fn fund_account(context: &mut ProgramTestContext, wallet: &Pubkey, lamports: u64) {
+    let transfer_ix = system_instruction::transfer(&context.payer.pubkey(), wallet, lamports);
+    let mut transaction = Transaction::new_with_payer(
+        &[transfer_ix],
+        Some(&context.payer.pubkey()),
+    );
+    transaction.sign(&[&context.payer], context.last_blockhash);
+    context.banks_client.process_transaction(transaction).await.unwrap();
+}
+
+/// Builds a rent-exempt genesis account holding `state`, owned by `owner`.
+pub fn packed_account<T: Pack>(state: T, owner: &Pubkey) -> Account {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    Account {
+        lamports: Rent::default().minimum_balance(T::LEN),
+        data,
+        owner: *owner,
+        ..Account::default()
+    }
+}
+
+pub async fn get_account_data<T: Pack + IsInitialized>(context: &mut ProgramTestContext, address: &Pubkey) -> T {
+    let account = context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .unwrap();
+    T::unpack(&account.data).unwrap()
+}
+
+pub fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, err) => assert_eq!(err, expected),
+        err => panic!("Unexpected transaction error: {:?}", err),
+    }
+}
+
+pub fn assert_registry_error(result: Result<(), BanksClientError>, expected: NameRegistryError) {
+    assert_instruction_error(result, InstructionError::Custom(expected as u32));
+}
@@ -0,0 +1,87 @@
+//! Compute unit budgets of the hot instructions.
+//!
+//! Builtin test processors are not metered, so this suite runs the SBF build
+//! of the program instead. Build it with `cargo build-sbf`; the tests look
+//! for `instant_folio.so` in `SBF_OUT_DIR`, falling back to `target/deploy`,
+//! and skip when it is missing. Raise a budget only together with the change
+//! that needs it.
+
+mod common;
+
+use std::path::PathBuf;
+
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use instant_folio::{client::instructions, pda::find_name_address};
+
+use common::*;
+
+const REGISTER_NAME_BUDGET: u64 = 60_000;
+const RENAME_NAME_BUDGET: u64 = 60_000;
+/// Kept low for programs resolving names through CPI on a tight budget.
+const RESOLVE_ADDRESS_BUDGET: u64 = 10_000;
+const RESOLVE_DETAILS_BUDGET: u64 = 10_000;
+
+/// Loads the SBF build of the program, or `None` if it has not been built.
+fn sbf_program_test(program_id: &Pubkey) -> Option<ProgramTest> {
+    let out_dir = std::env::var_os("SBF_OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/deploy"));
+    if !out_dir.join("instant_folio.so").exists() {
+        eprintln!("skipping: no instant_folio.so in {}, run cargo build-sbf", out_dir.display());
+        return None;
+    }
+    std::env::set_var("SBF_OUT_DIR", &out_dir);
+    let mut program_test = ProgramTest::new("instant_folio", *program_id, None);
+    program_test.prefer_bpf(true);
+    Some(program_test)
+}
+
+/// Compute units `instruction` consumes, simulated against the current bank.
+async fn units_consumed(context: &mut ProgramTestContext, instruction: Instruction, signers: &[&Keypair]) -> u64 {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction =
+        Transaction::new_signed_with_payer(&[instruction], Some(&signers[0].pubkey()), signers, blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    simulation.result.unwrap().unwrap();
+    simulation.simulation_details.unwrap().units_consumed
+}
+
+fn assert_within_budget(instruction: &str, consumed: u64, budget: u64) {
+    println!("{instruction}: {consumed} compute units (budget {budget})");
+    assert!(consumed <= budget, "{instruction} used {consumed} compute units, over its budget of {budget}");
+}
+
+#[tokio::test]
+async fn test_hot_instructions_stay_within_budget() {
+    let program_id = Pubkey::new_unique();
+    let Some(program_test) = sbf_program_test(&program_id) else {
+        return;
+    };
+    let (mut context, initializer, config_account, program_id) = start_program_test(program_test, program_id).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let consumed = units_consumed(&mut context, ix.clone(), &[&alice]).await;
+    assert_within_budget("RegisterName", consumed, REGISTER_NAME_BUDGET);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+
+    let name_account = find_name_address("alice", &program_id).0;
+    let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+    let consumed = units_consumed(&mut context, ix, &[&alice]).await;
+    assert_within_budget("ResolveAddress", consumed, RESOLVE_ADDRESS_BUDGET);
+    let ix = instructions::resolve_details(&program_id, &name_account, &[]);
+    let consumed = units_consumed(&mut context, ix, &[&alice]).await;
+    assert_within_budget("ResolveDetails", consumed, RESOLVE_DETAILS_BUDGET);
+
+    let ix = instructions::rename_name(&program_id, &alice.pubkey(), "alice", &config, "alice-2");
+    let consumed = units_consumed(&mut context, ix, &[&alice]).await;
+    assert_within_budget("RenameName", consumed, RENAME_NAME_BUDGET);
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use solana_program::{instruction::InstructionError, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    state::{ConfigLink, FeeSchedule, ProgramConfig},
+};
+
+use common::*;
+
+async fn treasury_total(context: &mut ProgramTestContext, config: &Pubkey, program_id: &Pubkey) -> u64 {
+    let mut total = 0;
+    for treasury in treasury_buckets(config, program_id) {
+        total += context.banks_client.get_account(treasury).await.unwrap().map_or(0, |account| account.lamports);
+    }
+    total
+}
+
+#[tokio::test]
+async fn test_migrate_config_moves_registry_to_pda() {
+    let (mut context, initializer, new_config, program_id) = setup_program().await;
+
+    // A config from before the config PDA, holding fees of one registration
+    let config = Pubkey::new_unique();
+    let old_state = ProgramConfig {
+        is_initialized: true,
+        owner: initializer.pubkey(),
+        fee_schedule: FeeSchedule::flat(REGISTRATION_FEE),
+        total_names: 1,
+        cooldown_seconds: 60,
+        version: ProgramConfig::VERSION,
+        ..ProgramConfig::default()
+    };
+    context.set_account(&config, &packed_account(old_state.clone(), &program_id).into());
+    for treasury in treasury_buckets(&config, &program_id) {
+        let rent = Rent::default().minimum_balance(0);
+        let account = Account { lamports: rent + REGISTRATION_FEE, owner: program_id, ..Account::default() };
+        context.set_account(&treasury, &account.into());
+    }
+    let fees = treasury_total(&mut context, &config, &program_id).await;
+
+    // Handlers other than MigrateConfig refuse it already
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    // Only the program owner migrates the config
+    let ix = instructions::migrate_config(&program_id, &alice.pubkey(), &config);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    // Nor while a multisig keyed to the old config is set
+    let with_multisig = ProgramConfig { multisig_enabled: true, ..old_state.clone() };
+    context.set_account(&config, &packed_account(with_multisig, &program_id).into());
+    let ix = instructions::migrate_config(&program_id, &initializer.pubkey(), &config);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::MultisigActive);
+    context.set_account(&config, &packed_account(old_state.clone(), &program_id).into());
+
+    let ix = instructions::migrate_config(&program_id, &initializer.pubkey(), &config);
+    process(&mut context, ix.clone(), &initializer, &[&initializer]).await.unwrap();
+    let new_state: ProgramConfig = get_account_data(&mut context, &new_config).await;
+    assert_eq!(new_state, old_state);
+    let retired: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(retired.successor, new_config);
+    let link: ConfigLink = get_account_data(&mut context, &ConfigLink::find_address(&program_id).0).await;
+    assert_eq!(link.config, new_config);
+
+    // Fees follow the config; the new buckets also hold their own rent
+    assert_eq!(treasury_total(&mut context, &config, &program_id).await, 0);
+    assert!(treasury_total(&mut context, &new_config, &program_id).await >= fees);
+
+    // Handlers only accept the new config from now on
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::ConfigMigrated);
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::ConfigMigrated);
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &new_config, "alice");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let new_state: ProgramConfig = get_account_data(&mut context, &new_config).await;
+    assert_eq!(new_state.total_names, 2);
+
+    let ix = instructions::migrate_config(&program_id, &initializer.pubkey(), &new_config);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AlreadyMigrated);
+}
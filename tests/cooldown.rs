@@ -0,0 +1,218 @@
+mod common;
+
+use solana_program::{
+    clock::Clock,
+    instruction::AccountMeta,
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    instruction::Instruction,
+    transaction::Transaction,
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    formats,
+    instruction::NameRegistryInstruction,
+    pda::{fee_bucket, find_name_address, find_treasury_address},
+    state::{NameAccount, NameCosigner, PendingUpdateAccount, ProgramConfig, ReverseRecord, WalletSettings},
+    validation::{validate_cooldown_period, DEFAULT_COOLDOWN_PERIOD, MAX_COOLDOWN_PERIOD, MIN_COOLDOWN_PERIOD},
+};
+
+use common::*;
+
+struct Fixture {
+    context: solana_program_test::ProgramTestContext,
+    program_id: Pubkey,
+    owner: Keypair,
+    name_account: Pubkey,
+    address_account: Pubkey,
+    config_account: Pubkey,
+}
+
+/// Starts a program with a name owned by a funded wallet whose cooldown never
+/// expires, so only the first-assignment exemption can let updates through.
+async fn setup_name(target_set: bool) -> Fixture {
+    let program_id = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let name_account = find_name_address("test-name", &program_id).0;
+    let address_account = Pubkey::new_unique();
+    let name = NameAccount {
+        is_initialized: true,
+        owner: owner.pubkey(),
+        name: "test-name".to_string(),
+        address: owner.pubkey(),
+        cooldown_until: i64::MAX,
+        target_set,
+        parent: Pubkey::default(),
+        pending_owner: Pubkey::default(),
+        wildcard_subdomains: false,
+        tokenized: false,
+        registered_at: 0,
+        transfer_count: 0,
+        resolve_count: 0,
+        frozen: false,
+        frozen_by: Pubkey::default(),
+        frozen_at: 0,
+    };
+    let address = ReverseRecord::new(owner.pubkey(), "test-name".to_string());
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![
+            (name_account, packed_account(name, &program_id)),
+            (address_account, packed_account(address, &program_id)),
+        ],
+    ).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    fund_account(&mut context, &owner.pubkey(), 1_000_000_000).await;
+
+    Fixture {
+        context,
+        program_id,
+        owner,
+        name_account,
+        address_account,
+        config_account,
+    }
+}
+
+fn request_ix(fixture: &Fixture, signer: &Pubkey, new_address: Pubkey) -> Instruction {
+    Instruction {
+        program_id: fixture.program_id,
+        accounts: vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(fixture.name_account, false),
+            AccountMeta::new(PendingUpdateAccount::find_address(&fixture.name_account, &fixture.program_id).0, false),
+            AccountMeta::new_readonly(fixture.config_account, false),
+            AccountMeta::new(WalletSettings::find_address(signer, &fixture.program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(&fixture.name_account, &fixture.program_id).0, false),
+        ],
+        data: NameRegistryInstruction::RequestAddressUpdate { new_address }.try_to_vec().unwrap(),
+    }
+}
+
+fn complete_ix(fixture: &Fixture, signer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: fixture.program_id,
+        accounts: vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(fixture.name_account, false),
+            AccountMeta::new(fixture.address_account, false),
+            AccountMeta::new(PendingUpdateAccount::find_address(&fixture.name_account, &fixture.program_id).0, false),
+            AccountMeta::new_readonly(fixture.config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&fixture.owner.pubkey(), &fixture.program_id).0, false),
+            AccountMeta::new(find_treasury_address(&fixture.config_account, fee_bucket("test-name"), &fixture.program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: NameRegistryInstruction::CompleteAddressUpdate.try_to_vec().unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn test_first_address_set_skips_cooldown() {
+    let mut fixture = setup_name(false).await;
+    let new_owner = Keypair::new();
+    fund_account(&mut fixture.context, &new_owner.pubkey(), 1_000_000_000).await;
+
+    let owner = fixture.owner.insecure_clone();
+    let ix = request_ix(&fixture, &owner.pubkey(), new_owner.pubkey());
+    process(&mut fixture.context, ix, &owner, &[&owner]).await.unwrap();
+
+    let ix = complete_ix(&fixture, &new_owner.pubkey());
+    process(&mut fixture.context, ix, &new_owner, &[&new_owner]).await.unwrap();
+
+    // The first assignment neither requires nor consumes the cooldown
+    let name_data: NameAccount = get_account_data(&mut fixture.context, &fixture.name_account).await;
+    assert_eq!(name_data.address, new_owner.pubkey());
+    assert!(name_data.target_set);
+    assert_eq!(name_data.cooldown_until, i64::MAX);
+
+    // Subsequent changes are subject to the cooldown again
+    let ix = request_ix(&fixture, &new_owner.pubkey(), owner.pubkey());
+    let result = process(&mut fixture.context, ix, &new_owner, &[&new_owner]).await;
+    assert_registry_error(result, NameRegistryError::CooldownNotOver);
+}
+
+#[tokio::test]
+async fn test_subsequent_address_set_requires_cooldown() {
+    let mut fixture = setup_name(true).await;
+    let owner = fixture.owner.insecure_clone();
+    let ix = request_ix(&fixture, &owner.pubkey(), Pubkey::new_unique());
+    let result = process(&mut fixture.context, ix, &owner, &[&owner]).await;
+    assert_registry_error(result, NameRegistryError::CooldownNotOver);
+}
+
+#[tokio::test]
+async fn test_registration_leaves_target_unset() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "test-name".to_string(),
+    ).await;
+
+    let name_data: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(!name_data.target_set);
+}
+
+#[tokio::test]
+async fn test_cooldown_bounds_are_queryable() {
+    let (mut context, initializer, _, program_id) = setup_program().await;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[instructions::get_cooldown_bounds(&program_id)],
+        Some(&initializer.pubkey()),
+        &[&initializer],
+        context.last_blockhash,
+    );
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(
+        formats::decode_cooldown_bounds(&return_data.data),
+        Some((MIN_COOLDOWN_PERIOD, MAX_COOLDOWN_PERIOD)),
+    );
+
+    assert!(validate_cooldown_period(MAX_COOLDOWN_PERIOD).is_ok());
+    assert_eq!(
+        validate_cooldown_period(MAX_COOLDOWN_PERIOD + 1),
+        Err(NameRegistryError::InvalidCooldownPeriod.into()),
+    );
+}
+
+#[tokio::test]
+async fn test_cooldown_period_is_configurable() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let config_data: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_data.cooldown_period(), DEFAULT_COOLDOWN_PERIOD);
+
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let name_account = find_name_address("alice", &program_id).0;
+
+    let ix = instructions::set_cooldown(&program_id, &alice.pubkey(), &config, 3_600);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_cooldown(&program_id, &initializer.pubkey(), &config, MAX_COOLDOWN_PERIOD + 1);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidCooldownPeriod);
+    let ix = instructions::set_cooldown(&program_id, &initializer.pubkey(), &config, 3_600);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, initializer.pubkey(), false);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let name_data: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name_data.cooldown_until, now + 3_600);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_address_account_address, find_name_address},
+    state::{NameAccount, NameCosigner},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_cosigner_guards_transfers_and_renames() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = find_name_address("alice", &program_id).0;
+    let renamed_account = find_name_address("alice-2", &program_id).0;
+
+    let [alice, bob, guard] = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for wallet in [&alice, &bob, &guard] {
+        fund_account(&mut context, &wallet.pubkey(), 1_000_000_000).await;
+    }
+    register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+
+    // The co-signer has to sign its own appointment
+    let ix = instructions::set_cosigner(&program_id, &alice.pubkey(), &name_account, guard.pubkey(), &[]);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::CosignerRequired);
+    let ix = instructions::set_cosigner(&program_id, &alice.pubkey(), &name_account, alice.pubkey(), &[]);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidAddress);
+    let ix = instructions::set_cosigner(&program_id, &alice.pubkey(), &name_account, guard.pubkey(), &[guard.pubkey()]);
+    process(&mut context, ix, &alice, &[&alice, &guard]).await.unwrap();
+
+    // The owner key alone can neither remove the co-signer nor move or drop the name
+    let ix = instructions::set_cosigner(&program_id, &alice.pubkey(), &name_account, Pubkey::default(), &[]);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::CosignerRequired);
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob.pubkey(), false);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::CosignerRequired);
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    let ix =
+        instructions::unregister_name(&program_id, &alice.pubkey(), &name_account, &address_account, &config, &alice.pubkey());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::CosignerRequired);
+
+    // A rename carries the co-signer over to the new name
+    let ix = instructions::rename_name(&program_id, &alice.pubkey(), "alice", &config, "alice-2");
+    let result = process(&mut context, ix.clone(), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::CosignerRequired);
+    process(&mut context, instructions::with_cosigner(ix, &guard.pubkey()), &alice, &[&alice, &guard])
+        .await
+        .unwrap();
+    let old_cosigner = NameCosigner::find_address(&name_account, &program_id).0;
+    assert!(context.banks_client.get_account(old_cosigner).await.unwrap().is_none());
+    let cosigner: NameCosigner =
+        get_account_data(&mut context, &NameCosigner::find_address(&renamed_account, &program_id).0).await;
+    assert_eq!(cosigner.name_account, renamed_account);
+    assert_eq!(cosigner.cosigner, guard.pubkey());
+
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &renamed_account, &config, bob.pubkey(), false);
+    process(&mut context, instructions::with_cosigner(ix, &guard.pubkey()), &alice, &[&alice, &guard])
+        .await
+        .unwrap();
+    let name: NameAccount = get_account_data(&mut context, &renamed_account).await;
+    assert_eq!(name.owner, bob.pubkey());
+
+    // The co-signer died with alice's ownership; bob appoints a new one freely
+    let ix = instructions::set_cosigner(&program_id, &bob.pubkey(), &renamed_account, alice.pubkey(), &[alice.pubkey()]);
+    process(&mut context, ix, &bob, &[&bob, &alice]).await.unwrap();
+    let ix = instructions::set_cosigner(&program_id, &bob.pubkey(), &renamed_account, Pubkey::default(), &[alice.pubkey()]);
+    process(&mut context, ix, &bob, &[&bob, &alice]).await.unwrap();
+    let renamed_cosigner = NameCosigner::find_address(&renamed_account, &program_id).0;
+    assert!(context.banks_client.get_account(renamed_cosigner).await.unwrap().is_none());
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, InstructionError},
+    program::{get_return_data, invoke},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solana_program_test::{processor, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    cpi::{self, RESOLVE_RESULT_VERSION},
+    instruction::NameRegistryInstruction,
+    pda::find_name_address,
+    state::NameAccount,
+};
+
+use common::*;
+
+/// Example consumer: resolves the name account through CPI and fails unless
+/// the registry returned the address given in the instruction data.
+fn resolving_caller(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let registry = next_account_info(account_info_iter)?;
+    let name_account = next_account_info(account_info_iter)?;
+
+    invoke(
+        &Instruction {
+            program_id: *registry.key,
+            accounts: vec![AccountMeta::new_readonly(*name_account.key, false)],
+            data: NameRegistryInstruction::ResolveAddress.try_to_vec().unwrap(),
+        },
+        &[name_account.clone(), registry.clone()],
+    )?;
+
+    let (returned_by, mut address) = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+    if returned_by != *registry.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    // Return data loses trailing zero bytes
+    address.resize(32, 0);
+    if address != data {
+        return Err(ProgramError::Custom(0));
+    }
+    Ok(())
+}
+
+/// Consumer built on the `cpi` helpers: fails unless the name resolves to
+/// the address and owner given in the instruction data, in that order.
+fn details_caller(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let registry = next_account_info(account_info_iter)?;
+    let name_account = next_account_info(account_info_iter)?;
+
+    let address = cpi::resolve_address(registry, name_account, &[])?;
+    let details = cpi::resolve_details(registry, name_account, &[])?;
+    let expected = (RESOLVE_RESULT_VERSION, address, 0, false);
+    if (details.version, details.address, details.expires_at, details.frozen) != expected {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if [address.as_ref(), details.owner.as_ref()].concat() != data {
+        return Err(ProgramError::Custom(0));
+    }
+    Ok(())
+}
+
+async fn setup_with_caller() -> (ProgramTestContext, Keypair, Pubkey, Pubkey, Pubkey) {
+    let program_id = Pubkey::new_unique();
+    let caller_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    program_test.add_program("resolving_caller", caller_id, processor!(resolving_caller));
+    let (context, initializer, config_account, program_id) = start_program_test(program_test, program_id).await;
+    (context, initializer, config_account, program_id, caller_id)
+}
+
+#[tokio::test]
+async fn test_resolve_details_through_cpi_helpers() {
+    let program_id = Pubkey::new_unique();
+    let caller_id = Pubkey::new_unique();
+    let (owner, address) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let name_account = find_name_address("test-name", &program_id).0;
+    let name = NameAccount {
+        is_initialized: true,
+        owner,
+        name: "test-name".to_string(),
+        address,
+        ..NameAccount::default()
+    };
+    let mut program_test = program_test(&program_id);
+    program_test.add_program("details_caller", caller_id, processor!(details_caller));
+    program_test.add_account(name_account, packed_account(name, &program_id));
+    let (mut context, initializer, _, program_id) = start_program_test(program_test, program_id).await;
+
+    let mut ix = caller_ix(&caller_id, &program_id, &name_account, &address);
+    ix.data.extend_from_slice(owner.as_ref());
+    process(&mut context, ix.clone(), &initializer, &[&initializer]).await.unwrap();
+
+    ix.data.truncate(32);
+    ix.data.extend_from_slice(address.as_ref());
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::Custom(0));
+}
+
+async fn registered_name(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    initializer: &Keypair,
+    config_account: &Pubkey,
+) -> Pubkey {
+    initialize_program(context, program_id, initializer, config_account, REGISTRATION_FEE).await;
+    register_name(
+        context,
+        program_id,
+        initializer,
+        config_account,
+        "test-name".to_string(),
+    ).await
+}
+
+fn caller_ix(caller_id: &Pubkey, program_id: &Pubkey, name_account: &Pubkey, expected: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *caller_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*program_id, false),
+            AccountMeta::new_readonly(*name_account, false),
+        ],
+        data: expected.to_bytes().to_vec(),
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_address_through_cpi() {
+    let (mut context, initializer, config_account, program_id, caller_id) = setup_with_caller().await;
+    let name_account = registered_name(&mut context, &program_id, &initializer, &config_account).await;
+
+    let ix = caller_ix(&caller_id, &program_id, &name_account, &initializer.pubkey());
+    let cpi_logs = process_with_logs(&mut context, ix, &initializer).await;
+    assert!(!cpi_logs.iter().any(|log| log.contains("Resolved")));
+
+    let ix = caller_ix(&caller_id, &program_id, &name_account, &Pubkey::new_unique());
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::Custom(0));
+}
+
+#[tokio::test]
+async fn test_top_level_resolve_logs_result() {
+    let (mut context, initializer, config_account, program_id, _caller_id) = setup_with_caller().await;
+    let name_account = registered_name(&mut context, &program_id, &initializer, &config_account).await;
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new_readonly(name_account, false)],
+        data: NameRegistryInstruction::ResolveAddress.try_to_vec().unwrap(),
+    };
+    let top_level_logs = process_with_logs(&mut context, ix, &initializer).await;
+    let expected = format!("Resolved test-name to {}", initializer.pubkey());
+    assert!(top_level_logs.iter().any(|log| log.contains(&expected)));
+}
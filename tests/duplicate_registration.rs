@@ -0,0 +1,111 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_name_address, name_commitment},
+    state::{NameAccount, ProgramConfig},
+    validation::MIN_COMMITMENT_AGE,
+};
+
+use common::*;
+
+async fn process_all(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), signers, blockhash);
+    context.banks_client.process_transaction(transaction).await
+}
+
+/// The second instruction of the transaction failed with `NameTaken`.
+fn assert_second_name_taken(result: Result<(), BanksClientError>) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(1, InstructionError::Custom(NameRegistryError::NameTaken as u32)),
+    );
+}
+
+#[tokio::test]
+async fn test_duplicate_registration_in_one_transaction_fails() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = find_name_address("alice", &program_id).0;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+
+    // The duplicate is reported as taken even when the first registration
+    // filled the registry
+    let ix = instructions::set_max_total_names(&program_id, &initializer.pubkey(), &config, 1);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let batch = [
+        instructions::register_name(&program_id, &alice.pubkey(), &config, "alice"),
+        instructions::register_name(&program_id, &bob.pubkey(), &config, "alice"),
+    ];
+    let result = process_all(&mut context, &batch, &alice, &[&alice, &bob]).await;
+    assert_second_name_taken(result);
+    assert!(context.banks_client.get_account(name_account).await.unwrap().is_none());
+
+    let batch = [
+        instructions::register_name(&program_id, &alice.pubkey(), &config, "alice"),
+        instructions::register_name(&program_id, &alice.pubkey(), &config, "alice"),
+    ];
+    let result = process_all(&mut context, &batch, &alice, &[&alice]).await;
+    assert_second_name_taken(result);
+
+    process_all(&mut context, &batch[..1], &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, alice.pubkey());
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.total_names, 1);
+}
+
+#[tokio::test]
+async fn test_competing_reveals_in_one_transaction_fail() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+
+    let salt = [7; 32];
+    for committer in [&alice, &bob] {
+        let ix = instructions::commit_name(&program_id, &committer.pubkey(), name_commitment("alice", &salt, &committer.pubkey()));
+        process(&mut context, ix, committer, &[committer]).await.unwrap();
+    }
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += MIN_COMMITMENT_AGE;
+    context.set_sysvar(&clock);
+
+    let batch = [
+        instructions::reveal_and_register(&program_id, &alice.pubkey(), &config, "alice", salt),
+        instructions::reveal_and_register(&program_id, &bob.pubkey(), &config, "alice", salt),
+    ];
+    let result = process_all(&mut context, &batch, &alice, &[&alice, &bob]).await;
+    assert_second_name_taken(result);
+
+    // Outside the batch the first reveal wins and the other stays taken
+    process_all(&mut context, &batch[..1], &alice, &[&alice]).await.unwrap();
+    let result = process_all(&mut context, &batch[1..], &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("alice", &program_id).0).await;
+    assert_eq!(name.owner, alice.pubkey());
+}
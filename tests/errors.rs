@@ -0,0 +1,55 @@
+mod common;
+
+use solana_program::{decode_error::DecodeError, program_error::ProgramError};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use instant_folio::{
+    client::instructions,
+    error::{NameRegistryError, ERROR_CODE_OFFSET},
+};
+
+use common::*;
+
+#[test]
+fn test_error_codes_are_stable() {
+    // Released codes must never move
+    assert_eq!(NameRegistryError::InvalidNameFormat as u32, ERROR_CODE_OFFSET);
+    assert_eq!(NameRegistryError::NameTaken as u32, 6001);
+    assert_eq!(NameRegistryError::NotContractOwner as u32, 6009);
+    assert_eq!(NameRegistryError::TransferOfferOpen as u32, 6066);
+    assert_eq!(ProgramError::from(NameRegistryError::NameTaken), ProgramError::Custom(6001));
+}
+
+#[test]
+fn test_codes_map_back_to_errors() {
+    let mut code = ERROR_CODE_OFFSET;
+    while let Some(error) = NameRegistryError::from_u32(code) {
+        assert_eq!(error as u32, code);
+        let decoded: Option<NameRegistryError> = NameRegistryError::decode_custom_error_to_enum(code);
+        assert_eq!(decoded, Some(error));
+        code += 1;
+    }
+    assert_eq!(code, NameRegistryError::TooManyRecordWriters as u32 + 1);
+    assert_eq!(NameRegistryError::from_u32(0), None);
+    assert_eq!(NameRegistryError::from_u32(ERROR_CODE_OFFSET - 1), None);
+    assert_eq!(<NameRegistryError as DecodeError<NameRegistryError>>::type_of(), "NameRegistryError");
+}
+
+#[tokio::test]
+async fn test_failures_log_the_error_message() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::set_cooldown(&program_id, &alice.pubkey(), &config, 3_600);
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&alice.pubkey()), &[&alice], blockhash);
+    let result = context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+    assert!(result.result.is_err());
+    let logs = result.metadata.unwrap().log_messages;
+    assert!(logs.contains(&format!("Program log: Error: {}", NameRegistryError::NotContractOwner)));
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use solana_program::instruction::AccountMeta;
+use solana_sdk::{instruction::Instruction, signature::Signer};
+use borsh::BorshSerialize;
+use instant_folio::{
+    error::NameRegistryError,
+    extensions::EXTENSIONS,
+    instruction::NameRegistryInstruction,
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_unknown_extension_rejected() {
+    let (mut context, initializer, _config_account, program_id) = setup_program().await;
+
+    let tag = u16::MAX;
+    assert!(EXTENSIONS.iter().all(|(registered, _)| *registered != tag));
+    let ix = Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new_readonly(initializer.pubkey(), true)],
+        data: NameRegistryInstruction::Extension { tag, payload: vec![1, 2, 3] }
+            .try_to_vec()
+            .unwrap(),
+    };
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::UnknownExtension);
+}
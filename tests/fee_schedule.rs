@@ -0,0 +1,84 @@
+mod common;
+
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    formats,
+    pda::{fee_bucket, find_treasury_address},
+    state::{FeeSchedule, ProgramConfig},
+};
+
+use common::*;
+
+const SCHEDULE: FeeSchedule = FeeSchedule {
+    one_char: 500_000_000,
+    two_char: 200_000_000,
+    three_to_four_char: 50_000_000,
+    five_plus_char: REGISTRATION_FEE,
+};
+
+/// Registers `name` and returns the fee the treasury received.
+async fn fee_paid(context: &mut ProgramTestContext, program_id: &Pubkey, registrant: &Keypair, config: &Pubkey, name: &str) -> u64 {
+    let treasury = find_treasury_address(config, fee_bucket(name), program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    register_name(context, program_id, registrant, config, name.to_string()).await;
+    context.banks_client.get_balance(treasury).await.unwrap() - before
+}
+
+#[test]
+fn test_fee_schedule_tiers_by_length() {
+    assert_eq!(SCHEDULE.fee_for("x"), SCHEDULE.one_char);
+    assert_eq!(SCHEDULE.fee_for("xy"), SCHEDULE.two_char);
+    assert_eq!(SCHEDULE.fee_for("xyz"), SCHEDULE.three_to_four_char);
+    assert_eq!(SCHEDULE.fee_for("wxyz"), SCHEDULE.three_to_four_char);
+    assert_eq!(SCHEDULE.fee_for("vwxyz"), SCHEDULE.five_plus_char);
+    assert_eq!(FeeSchedule::flat(7).fee_for("x"), 7);
+
+    let config = ProgramConfig {
+        fee_schedule: SCHEDULE,
+        promo_start: 0,
+        promo_end: 10,
+        promo_fee_override_bps: 5_000,
+        ..ProgramConfig::default()
+    };
+    assert_eq!(config.effective_registration_fee("xy", 5), SCHEDULE.two_char / 2);
+    assert_eq!(config.effective_registration_fee("xy", 10), SCHEDULE.two_char);
+}
+
+#[tokio::test]
+async fn test_registration_charges_scheduled_fee() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.fee_schedule, FeeSchedule::flat(REGISTRATION_FEE));
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = instructions::set_fee_schedule(&program_id, &stranger.pubkey(), &config, FeeSchedule::default());
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    let ix = instructions::set_fee_schedule(&program_id, &initializer.pubkey(), &config, SCHEDULE);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    assert_eq!(fee_paid(&mut context, &program_id, &initializer, &config_account, "a").await, SCHEDULE.one_char);
+    assert_eq!(fee_paid(&mut context, &program_id, &initializer, &config_account, "ab").await, SCHEDULE.two_char);
+    assert_eq!(fee_paid(&mut context, &program_id, &initializer, &config_account, "abcd").await, SCHEDULE.three_to_four_char);
+    assert_eq!(fee_paid(&mut context, &program_id, &initializer, &config_account, "abcde").await, SCHEDULE.five_plus_char);
+
+    // GetRegistrationFee reports the standard, five-plus character tier
+    let ix = instructions::get_registration_fee(&program_id, &config);
+    let transaction =
+        Transaction::new_signed_with_payer(&[ix], Some(&initializer.pubkey()), &[&initializer], context.last_blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    let mut return_data = simulation.simulation_details.unwrap().return_data.unwrap().data;
+    return_data.resize(8, 0);
+    assert_eq!(return_data, formats::encode_fee(SCHEDULE.five_plus_char));
+}
@@ -0,0 +1,98 @@
+use std::{env, fs, path::PathBuf};
+
+use solana_program::pubkey::Pubkey;
+use instant_folio::{
+    analytics::CharsetClass,
+    cpi::{ResolveResult, RESOLVE_RESULT_VERSION},
+    formats::{self, FORMATS_VERSION},
+};
+
+/// Renders every payload and event for fixed inputs, one per line, in the
+/// form SDK generators consume: `<kind> <hex payload>` or `<kind> <log line>`.
+fn render() -> String {
+    let address = Pubkey::new_from_array(std::array::from_fn(|i| i as u8 + 1));
+    let lines = [
+        format!("# formats version {}", FORMATS_VERSION),
+        format!("return.address {}", hex(&formats::encode_address(&address))),
+        format!("return.registration_fee {}", hex(&formats::encode_fee(1_000_000))),
+        format!("return.primary_name {}", hex(formats::encode_primary_name("alice"))),
+        format!(
+            "return.resolve_result {}",
+            hex(&formats::encode_resolve_result(&ResolveResult {
+                version: RESOLVE_RESULT_VERSION,
+                address,
+                owner: address,
+                expires_at: 1_700_000_000,
+                frozen: true,
+            })),
+        ),
+        format!("return.cooldown_bounds {}", hex(&formats::encode_cooldown_bounds(0, 2_592_000))),
+        format!("return.record_keys {}", hex(&formats::encode_record_keys(&[address, address]))),
+        format!("event.resolved {}", formats::resolved_event("alice", &address)),
+        format!(
+            "event.registration_analytics {}",
+            formats::registration_analytics_event(9, CharsetClass::Alphanumeric, true),
+        ),
+        format!("event.referral {}", formats::referral_event("alice", &address, 250_000)),
+        format!("event.fee {}", formats::fee_event("alice", 1_000_000)),
+        format!("event.cooldown {}", formats::cooldown_event("alice", 1_700_086_400)),
+        format!("event.expiry {}", formats::expiry_event("alice", 1_700_000_000)),
+    ];
+    lines.join("\n") + "\n"
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Compares against `tests/golden/formats.txt`; run with
+/// `UPDATE_GOLDEN=1` to accept an intentional layout change.
+#[test]
+fn test_formats_match_golden_file() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/formats.txt");
+    let rendered = render();
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, &rendered).unwrap();
+    }
+    assert_eq!(rendered, fs::read_to_string(&path).unwrap());
+}
+
+#[test]
+fn test_decoders_round_trip_trimmed_payloads() {
+    let mut bytes = [9; 32];
+    bytes[31] = 0;
+    let address = Pubkey::new_from_array(bytes);
+    let encoded = formats::encode_address(&address);
+    assert_eq!(formats::decode_address(&encoded), Some(address));
+    // The runtime drops trailing zero bytes from return data
+    assert_eq!(formats::decode_address(&encoded[..31]), Some(address));
+    assert_eq!(formats::decode_address(&[0; 33]), None);
+
+    assert_eq!(formats::decode_fee(&formats::encode_fee(256)[..2]), Some(256));
+    assert_eq!(formats::decode_fee(&[0; 9]), None);
+
+    let result =
+        ResolveResult { version: RESOLVE_RESULT_VERSION, address, owner: address, expires_at: 0, frozen: false };
+    let encoded = formats::encode_resolve_result(&result);
+    assert_eq!(formats::decode_resolve_result(&encoded[..64]), Some(result));
+    // Version 1 payloads end before `frozen`
+    let frozen = ResolveResult { frozen: true, ..result };
+    let v1 = [&[1][..], &formats::encode_resolve_result(&frozen)[1..73]].concat();
+    assert_eq!(formats::decode_resolve_result(&v1), Some(ResolveResult { version: 1, ..result }));
+    assert!(formats::decode_resolve_result(&formats::encode_resolve_result(&frozen)).unwrap().frozen);
+    // Fields appended by later versions are skipped
+    let newer = [&[RESOLVE_RESULT_VERSION + 1][..], &encoded[1..], &[7; 8]].concat();
+    assert_eq!(formats::decode_resolve_result(&newer).map(|result| result.owner), Some(address));
+    assert_eq!(formats::decode_resolve_result(&[]), None);
+
+    assert_eq!(formats::decode_cooldown_bounds(&formats::encode_cooldown_bounds(60, 0)[..1]), Some((60, 0)));
+    assert_eq!(formats::decode_cooldown_bounds(&[0; 9]), None);
+
+    assert_eq!(formats::decode_primary_name(formats::encode_primary_name("alice")), Some("alice".to_string()));
+    assert_eq!(formats::decode_primary_name(&[0xff]), None);
+
+    let records = [Pubkey::new_unique(), address];
+    let encoded = formats::encode_record_keys(&records);
+    assert_eq!(formats::decode_record_keys(&encoded[..63]), records);
+    assert!(formats::decode_record_keys(&[]).is_empty());
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use instant_folio::{
+    client::instructions,
+    cpi::ResolveResult,
+    error::NameRegistryError,
+    formats,
+    pda::find_name_address,
+    state::NameAccount,
+};
+
+use common::*;
+
+async fn resolve_details(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    name_account: &Pubkey,
+    ancestors: &[Pubkey],
+) -> ResolveResult {
+    let ix = instructions::resolve_details(program_id, name_account, ancestors);
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], context.last_blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    formats::decode_resolve_result(&return_data.data).unwrap()
+}
+
+#[tokio::test]
+async fn test_frozen_name_neither_resolves_nor_moves() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    let name_account = register_name(&mut context, &program_id, &alice, &config, "alice".to_string()).await;
+    let ix = instructions::register_subname(&program_id, &alice.pubkey(), "alice", &config, "pay");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let subname_account = find_name_address("pay.alice", &program_id).0;
+
+    let ix = instructions::freeze_name(&program_id, &alice.pubkey(), &config, &name_account);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::unfreeze_name(&program_id, &owner, &config, &name_account);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameNotFrozen);
+
+    let ix = instructions::freeze_name(&program_id, &owner, &config, &name_account);
+    let logs = process_with_logs(&mut context, ix, &initializer).await;
+    assert!(logs.iter().any(|log| log.ends_with(&format!(
+        "registry: event=freeze name=alice frozen=true authority={}",
+        owner
+    ))));
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(name.frozen);
+    assert_eq!(name.frozen_by, owner);
+    assert!(name.frozen_at > 0);
+    let ix = instructions::freeze_name(&program_id, &owner, &config, &name_account);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameFrozen);
+
+    // Neither the name nor its subnames resolve
+    let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameFrozen);
+    let ix = instructions::resolve_address(&program_id, &subname_account, &[name_account]);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameFrozen);
+
+    // ResolveDetails answers for them, flagged
+    let details = resolve_details(&mut context, &program_id, &alice, &name_account, &[]).await;
+    assert!(details.frozen);
+    assert_eq!(details.address, alice.pubkey());
+    let details = resolve_details(&mut context, &program_id, &alice, &subname_account, &[name_account]).await;
+    assert!(details.frozen);
+
+    let bob = Keypair::new().pubkey();
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob, false);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameFrozen);
+    let ix = instructions::rename_name(&program_id, &alice.pubkey(), "alice", &config, "alicia");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameFrozen);
+
+    // Unfreezing keeps the record of the freeze
+    let ix = instructions::unfreeze_name(&program_id, &owner, &config, &name_account);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::resolve_address(&program_id, &subname_account, &[name_account]);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let details = resolve_details(&mut context, &program_id, &alice, &subname_account, &[name_account]).await;
+    assert!(!details.frozen);
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob, false);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(!name.frozen);
+    assert_eq!(name.frozen_by, owner);
+    assert_eq!(name.owner, bob);
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{fee_bucket, find_address_account_address, find_name_address, find_treasury_address},
+    state::{NameAccount, PaidOperation, ReceiptAccount, ReverseRecord},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_register_name_for_another_wallet() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let sponsor = Keypair::new();
+    let user = Keypair::new();
+    fund_account(&mut context, &sponsor.pubkey(), 1_000_000_000).await;
+
+    // The sponsor pays the full fee and keeps the receipt
+    let treasury = find_treasury_address(&config, fee_bucket("alice"), &program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    let ix = instructions::register_name_for(&program_id, &sponsor.pubkey(), &config, "alice", user.pubkey());
+    let ix = instructions::with_receipt(ix, &program_id, &sponsor.pubkey(), "alice");
+    process(&mut context, ix, &sponsor, &[&sponsor]).await.unwrap();
+    let after = context.banks_client.get_balance(treasury).await.unwrap();
+    assert_eq!(after - before, REGISTRATION_FEE);
+
+    let name_account = find_name_address("alice", &program_id).0;
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!((name.owner, name.address), (user.pubkey(), user.pubkey()));
+    let record: ReverseRecord =
+        get_account_data(&mut context, &find_address_account_address(&name_account, &program_id).0).await;
+    assert_eq!(record.wallet, user.pubkey());
+    let receipt = ReceiptAccount::find_address(&sponsor.pubkey(), &name_account, PaidOperation::Registration, &program_id).0;
+    let receipt: ReceiptAccount = get_account_data(&mut context, &receipt).await;
+    assert_eq!(receipt.payer, sponsor.pubkey());
+
+    // Gifts get no way around commit-reveal
+    let ix = instructions::set_commit_reveal_required(&program_id, &initializer.pubkey(), &config, true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::register_name_for(&program_id, &sponsor.pubkey(), &config, "bob", user.pubkey());
+    let result = process(&mut context, ix, &sponsor, &[&sponsor]).await;
+    assert_registry_error(result, NameRegistryError::CommitRevealRequired);
+}
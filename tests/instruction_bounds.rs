@@ -0,0 +1,99 @@
+mod common;
+
+use borsh::BorshSerialize;
+use solana_program::{instruction::AccountMeta, program_error::ProgramError};
+use solana_sdk::{instruction::Instruction, signature::Signer};
+use instant_folio::{
+    error::NameRegistryError,
+    instruction::{NameProof, NameRegistryInstruction, PromoClaimEntry},
+    validation::{
+        MAX_CLAIM_PREIMAGE_LENGTH, MAX_EXTENSION_PAYLOAD_LENGTH, MAX_INSTRUCTION_DATA_LENGTH, MAX_NAME_PROOF_DEPTH,
+        MAX_NOTE_LENGTH, MAX_PROMO_CLAIMS_PER_INSTRUCTION,
+    },
+};
+
+use common::*;
+
+fn claims(count: usize) -> NameRegistryInstruction {
+    let claims = (0..count)
+        .map(|i| PromoClaimEntry { commitment: [i as u8; 32], name: format!("name{}", i), proof: None })
+        .collect();
+    NameRegistryInstruction::CreatePromoClaims { expires_at: 0, claims }
+}
+
+fn unpack(instruction: NameRegistryInstruction) -> Result<NameRegistryInstruction, ProgramError> {
+    NameRegistryInstruction::unpack(&instruction.try_to_vec().unwrap())
+}
+
+fn registry_error(error: NameRegistryError) -> Result<(), ProgramError> {
+    Err(error.into())
+}
+
+#[test]
+fn test_unpack_enforces_collection_limits() {
+    assert!(unpack(claims(MAX_PROMO_CLAIMS_PER_INSTRUCTION)).is_ok());
+    assert_eq!(
+        unpack(claims(MAX_PROMO_CLAIMS_PER_INSTRUCTION + 1)).map(drop),
+        registry_error(NameRegistryError::BatchTooLarge),
+    );
+
+    let note = |len| NameRegistryInstruction::SetNote { data: vec![0; len] };
+    assert!(unpack(note(MAX_NOTE_LENGTH)).is_ok());
+    assert_eq!(unpack(note(MAX_NOTE_LENGTH + 1)).map(drop), registry_error(NameRegistryError::NoteTooLong));
+
+    let preimage = |len| NameRegistryInstruction::ClaimPromoName { preimage: vec![0; len] };
+    assert!(unpack(preimage(MAX_CLAIM_PREIMAGE_LENGTH)).is_ok());
+    assert_eq!(
+        unpack(preimage(MAX_CLAIM_PREIMAGE_LENGTH + 1)).map(drop),
+        registry_error(NameRegistryError::InvalidPromoClaim),
+    );
+
+    let extension = |len| NameRegistryInstruction::Extension { tag: 1, payload: vec![0; len] };
+    assert!(unpack(extension(MAX_EXTENSION_PAYLOAD_LENGTH)).is_ok());
+    assert_eq!(
+        unpack(extension(MAX_EXTENSION_PAYLOAD_LENGTH + 1)).map(drop),
+        registry_error(NameRegistryError::InstructionTooLarge),
+    );
+
+    let proof = |depth| NameRegistryInstruction::RegisterName {
+        name: "alice".to_string(),
+        proof: Some(NameProof { low: [0; 32], high: [0xff; 32], path: vec![[0; 32]; depth] }),
+    };
+    assert!(unpack(proof(MAX_NAME_PROOF_DEPTH)).is_ok());
+    assert_eq!(
+        unpack(proof(MAX_NAME_PROOF_DEPTH + 1)).map(drop),
+        registry_error(NameRegistryError::InvalidNameProof),
+    );
+}
+
+#[test]
+fn test_unpack_refuses_oversized_data_before_decoding() {
+    // A valid banner at the byte budget decodes; one more byte is refused
+    // even though it would decode
+    let banner = |len| NameRegistryInstruction::SetBannerMessage { message: "a".repeat(len) };
+    let overhead = banner(0).try_to_vec().unwrap().len();
+    assert!(unpack(banner(MAX_INSTRUCTION_DATA_LENGTH - overhead)).is_ok());
+    assert_eq!(
+        unpack(banner(MAX_INSTRUCTION_DATA_LENGTH - overhead + 1)).map(drop),
+        registry_error(NameRegistryError::InstructionTooLarge),
+    );
+}
+
+#[tokio::test]
+async fn test_oversized_batch_fails_before_touching_accounts() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    // No claim accounts are passed: the batch is refused before they are read
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(initializer.pubkey(), true),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: claims(MAX_PROMO_CLAIMS_PER_INSTRUCTION + 1).try_to_vec().unwrap(),
+    };
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::BatchTooLarge);
+}
@@ -0,0 +1,267 @@
+#![allow(deprecated)]
+
+mod common;
+
+use solana_program::{instruction::AccountMeta, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::find_name_address,
+    state::{AccountType, AddressAccount, NameAccount, NamespaceAccount, ProgramConfig, ReverseRecord, LAYOUT_VERSION},
+    validation::DEFAULT_COOLDOWN_PERIOD,
+};
+
+use common::*;
+
+fn alice(owner: &Pubkey) -> NameAccount {
+    NameAccount {
+        is_initialized: true,
+        owner: *owner,
+        name: "alice".to_string(),
+        address: *owner,
+        cooldown_until: 0,
+        target_set: false,
+        parent: Pubkey::default(),
+        pending_owner: Pubkey::default(),
+        wildcard_subdomains: false,
+        tokenized: false,
+        registered_at: 0,
+        transfer_count: 0,
+        resolve_count: 0,
+        frozen: false,
+        frozen_by: Pubkey::default(),
+        frozen_at: 0,
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_address_account() {
+    let program_id = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let name_account = find_name_address("alice", &program_id).0;
+    // Registered before address accounts were PDAs, in the old layout
+    let legacy_account = Pubkey::new_unique();
+    let legacy = AddressAccount { is_initialized: true, name: "alice".to_string() };
+
+    let (mut context, initializer, config_account, program_id) = setup_program_with_accounts(
+        program_id,
+        vec![
+            (name_account, packed_account(alice(&owner.pubkey()), &program_id)),
+            (legacy_account, packed_account(legacy.clone(), &program_id)),
+        ],
+    ).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    fund_account(&mut context, &owner.pubkey(), 1_000_000_000).await;
+    let config = config_account;
+
+    // The read-compat shim still decodes the old layout
+    let mut legacy_data = vec![0; AddressAccount::LEN];
+    legacy.pack_into_slice(&mut legacy_data);
+    let compat = ReverseRecord::unpack_compat(&legacy_data).unwrap();
+    assert_eq!(compat.name, "alice");
+    assert_eq!(compat.wallet, Pubkey::default());
+
+    // Writers refuse the old layout
+    let mut rename = instructions::rename_name(&program_id, &owner.pubkey(), "alice", &config, "alicia");
+    rename.accounts[3] = AccountMeta::new(legacy_account, false);
+    let result = process(&mut context, rename.clone(), &owner, &[&owner]).await;
+    assert_registry_error(result, NameRegistryError::LegacyAccountLayout);
+
+    // A legacy account off the PDA must hold the name it is migrated for
+    let bob = register_name(&mut context, &program_id, &initializer, &config_account, "bob".to_string()).await;
+    let ix = instructions::migrate_address_account(&program_id, &initializer.pubkey(), &bob, &legacy_account);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::WrongDerivedAccount);
+
+    // Anyone can pay for the migration; the content comes from the name
+    let ix = instructions::migrate_address_account(&program_id, &initializer.pubkey(), &name_account, &legacy_account);
+    process(&mut context, ix.clone(), &initializer, &[&initializer]).await.unwrap();
+    let reverse: ReverseRecord = get_account_data(&mut context, &legacy_account).await;
+    assert_eq!(reverse, ReverseRecord::new(owner.pubkey(), "alice".to_string()));
+    let account = context.banks_client.get_account(legacy_account).await.unwrap().unwrap();
+    assert_eq!(account.lamports, Rent::default().minimum_balance(ReverseRecord::LEN));
+
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AlreadyMigrated);
+
+    process(&mut context, rename, &owner, &[&owner]).await.unwrap();
+    let reverse: ReverseRecord = get_account_data(&mut context, &legacy_account).await;
+    assert_eq!(reverse.name, "alicia");
+    assert_eq!(reverse.wallet, owner.pubkey());
+}
+
+/// `state` packed as it was in an earlier layout `len` bytes long.
+fn earlier_account<T: Pack>(state: T, (version, len): (u8, usize), program_id: &Pubkey) -> Account {
+    let mut account = packed_account(state, program_id);
+    if version == 1 {
+        account.data.remove(1);
+    }
+    assert!(account.data[len..].iter().all(|byte| *byte == 0));
+    account.data.truncate(len);
+    account.lamports = Rent::default().minimum_balance(len);
+    account
+}
+
+#[tokio::test]
+async fn test_migrate_version_1_account() {
+    let program_id = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let name_account = find_name_address("alice", &program_id).0;
+    // Written before accounts carried a layout version, by the last release
+    // without one
+    let account = earlier_account(alice(&owner.pubkey()), (1, 177), &program_id);
+
+    let (mut context, initializer, config_account, program_id) =
+        setup_program_with_accounts(program_id, vec![(name_account, account)]).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    fund_account(&mut context, &owner.pubkey(), 1_000_000_000).await;
+
+    // Readers accept the old layout, writers refuse it
+    let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+    process(&mut context, ix, &owner, &[&owner]).await.unwrap();
+    let wildcard = instructions::set_wildcard_subdomains(&program_id, &owner.pubkey(), &name_account, true);
+    let result = process(&mut context, wildcard.clone(), &owner, &[&owner]).await;
+    assert_registry_error(result, NameRegistryError::LegacyAccountLayout);
+
+    // Anyone can pay for the migration, which keeps the content
+    let ix = instructions::migrate_account(&program_id, &initializer.pubkey(), &name_account);
+    process(&mut context, ix.clone(), &initializer, &[&initializer]).await.unwrap();
+    let account = context.banks_client.get_account(name_account).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), NameAccount::LEN);
+    assert_eq!(account.data[1], LAYOUT_VERSION);
+    assert_eq!(account.lamports, Rent::default().minimum_balance(NameAccount::LEN));
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name, alice(&owner.pubkey()));
+
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AlreadyMigrated);
+    let ix = instructions::migrate_account(&program_id, &initializer.pubkey(), &config_account);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AlreadyMigrated);
+
+    process(&mut context, wildcard, &owner, &[&owner]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(name.wildcard_subdomains);
+}
+
+#[tokio::test]
+async fn test_migrate_names_from_every_earlier_layout() {
+    let program_id = Pubkey::new_unique();
+    let owner = Keypair::new();
+    let names: Vec<_> = NameAccount::EARLIER_LAYOUTS
+        .iter()
+        .map(|&(version, len)| {
+            let mut state = alice(&owner.pubkey());
+            state.name = format!("v{}-{}", version, len);
+            let name_account = find_name_address(&state.name, &program_id).0;
+            let account = earlier_account(state.clone(), (version, len), &program_id);
+            (name_account, state, account)
+        })
+        .collect();
+    let accounts = names.iter().map(|(address, _, account)| (*address, account.clone())).collect();
+
+    let (mut context, initializer, config_account, program_id) =
+        setup_program_with_accounts(program_id, accounts).await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    for (name_account, state, _) in names {
+        let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+        process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+        let name: NameAccount = get_account_data(&mut context, &name_account).await;
+        assert_eq!(name, state);
+
+        let ix = instructions::migrate_account(&program_id, &initializer.pubkey(), &name_account);
+        process(&mut context, ix.clone(), &initializer, &[&initializer]).await.unwrap();
+        let account = context.banks_client.get_account(name_account).await.unwrap().unwrap();
+        assert_eq!(account.data.len(), NameAccount::LEN);
+        assert_eq!(account.data[1], LAYOUT_VERSION);
+        assert_eq!(account.lamports, Rent::default().minimum_balance(NameAccount::LEN));
+        let name: NameAccount = get_account_data(&mut context, &name_account).await;
+        assert_eq!(name, state);
+
+        let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+        assert_registry_error(result, NameRegistryError::AlreadyMigrated);
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_config_from_earlier_layouts() {
+    for layout in ProgramConfig::EARLIER_LAYOUTS.iter().copied() {
+        let (mut context, initializer, config_account, program_id) = setup_program().await;
+        initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+        let config: ProgramConfig = get_account_data(&mut context, &config_account).await;
+        context.set_account(&config_account, &earlier_account(config.clone(), layout, &program_id).into());
+
+        // Registering updates the config, which must be migrated first
+        let ix = instructions::register_name(&program_id, &initializer.pubkey(), &config_account, "alice");
+        let result = process(&mut context, ix.clone(), &initializer, &[&initializer]).await;
+        assert_registry_error(result, NameRegistryError::LegacyAccountLayout);
+        let stored: ProgramConfig = get_account_data(&mut context, &config_account).await;
+        assert_eq!(stored, config);
+
+        let migrate = instructions::migrate_account(&program_id, &initializer.pubkey(), &config_account);
+        process(&mut context, migrate, &initializer, &[&initializer]).await.unwrap();
+        let stored: ProgramConfig = get_account_data(&mut context, &config_account).await;
+        assert_eq!(stored, config);
+        process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+        let stored: ProgramConfig = get_account_data(&mut context, &config_account).await;
+        assert_eq!(stored.total_registrations, 1);
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_namespace_before_delegation() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let admin = initializer.pubkey();
+    let ix = instructions::create_namespace(&program_id, &admin, &config_account, "dao", 0, admin);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let namespace_account = NamespaceAccount::find_address(&find_name_address("dao", &program_id).0, &program_id).0;
+    let namespace: NamespaceAccount = get_account_data(&mut context, &namespace_account).await;
+    let layout = NamespaceAccount::EARLIER_LAYOUTS[0];
+    context.set_account(&namespace_account, &earlier_account(namespace.clone(), layout, &program_id).into());
+
+    // Registering only reads the namespace, delegating writes it
+    let ix = instructions::register_name(&program_id, &admin, &config_account, "alice.dao");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let delegate = instructions::delegate_namespace_authority(&program_id, &admin, "dao", Pubkey::new_unique());
+    let result = process(&mut context, delegate.clone(), &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::LegacyAccountLayout);
+
+    let migrate = instructions::migrate_account(&program_id, &admin, &namespace_account);
+    process(&mut context, migrate, &initializer, &[&initializer]).await.unwrap();
+    let stored: NamespaceAccount = get_account_data(&mut context, &namespace_account).await;
+    assert_eq!(stored, namespace);
+    process(&mut context, delegate, &initializer, &[&initializer]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_migrated_config_keeps_its_settings_version() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    // Deployed before configs had a cooldown setting
+    let mut config: ProgramConfig = get_account_data(&mut context, &config_account).await;
+    config.version = 1;
+    config.cooldown_seconds = 0;
+    context.set_account(&config_account, &earlier_account(config.clone(), (1, 205), &program_id).into());
+
+    // The layout moves to the current version, the settings stay put
+    let ix = instructions::migrate_account(&program_id, &initializer.pubkey(), &config_account);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let account = context.banks_client.get_account(config_account).await.unwrap().unwrap();
+    assert_eq!(account.data[1], LAYOUT_VERSION);
+    let stored: ProgramConfig = get_account_data(&mut context, &config_account).await;
+    assert_eq!(stored, config);
+    assert_eq!(stored.cooldown_period(), DEFAULT_COOLDOWN_PERIOD);
+
+    // Setting the cooldown does not move the settings version either
+    let ix = instructions::set_cooldown(&program_id, &initializer.pubkey(), &config_account, 60);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let stored: ProgramConfig = get_account_data(&mut context, &config_account).await;
+    assert_eq!((stored.version, stored.cooldown_seconds), (1, 60));
+    assert_eq!(stored.cooldown_period(), DEFAULT_COOLDOWN_PERIOD);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{fee_bucket, find_auction_address, find_name_address, find_treasury_address},
+    state::{AuctionAccount, FeeSchedule, MultisigConfig, NameAccount, ProgramConfig, Role},
+};
+
+use common::*;
+
+async fn warp_past(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp + 1;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_multisig_gates_owner_instructions() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let multisig_account = MultisigConfig::find_address(&config, &program_id).0;
+
+    let [a, b, c] = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for member in [&a, &b, &c] {
+        fund_account(&mut context, &member.pubkey(), 1_000_000_000).await;
+    }
+    let members = vec![a.pubkey(), b.pubkey(), c.pubkey()];
+
+    // Only the program owner sets up the multisig, and only a sound one
+    let ix = instructions::set_multisig(&program_id, &a.pubkey(), &config, members.clone(), 2, &[]);
+    let result = process(&mut context, ix, &a, &[&a]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    for (signers, threshold) in [(members.clone(), 4), (members.clone(), 0), (vec![a.pubkey(), a.pubkey()], 1)] {
+        let ix = instructions::set_multisig(&program_id, &initializer.pubkey(), &config, signers, threshold, &[]);
+        let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+        assert_registry_error(result, NameRegistryError::InvalidMultisig);
+    }
+    let ix = instructions::set_multisig(&program_id, &initializer.pubkey(), &config, members.clone(), 2, &[]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let multisig: MultisigConfig = get_account_data(&mut context, &multisig_account).await;
+    assert_eq!(multisig.signers, members);
+    assert_eq!(multisig.threshold, 2);
+
+    // The config owner alone, or one member with an outsider, falls short
+    let set_fee = |signer: &Keypair, cosigners: &[&Keypair]| {
+        let ix = instructions::set_registration_fee(&program_id, &signer.pubkey(), &config, HIGH_FEE);
+        let cosigners: Vec<_> = cosigners.iter().map(|cosigner| cosigner.pubkey()).collect();
+        instructions::with_multisig(ix, &program_id, &config, &cosigners)
+    };
+    let result = process(&mut context, set_fee(&initializer, &[]), &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::MultisigThresholdNotMet);
+    let result = process(&mut context, set_fee(&a, &[&initializer]), &a, &[&a, &initializer]).await;
+    assert_registry_error(result, NameRegistryError::MultisigThresholdNotMet);
+
+    process(&mut context, set_fee(&a, &[&b]), &a, &[&a, &b]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.fee_schedule, FeeSchedule::flat(HIGH_FEE));
+    let schedule = FeeSchedule::flat(REGISTRATION_FEE);
+    let set_schedule = |signer: &Keypair, cosigners: &[Pubkey]| {
+        let ix = instructions::set_fee_schedule(&program_id, &signer.pubkey(), &config, schedule);
+        instructions::with_multisig(ix, &program_id, &config, cosigners)
+    };
+    let result = process(&mut context, set_schedule(&initializer, &[]), &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::MultisigThresholdNotMet);
+    process(&mut context, set_schedule(&b, &[c.pubkey()]), &b, &[&b, &c]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.fee_schedule, schedule);
+
+    // Fees go to the member submitting the withdrawal
+    register_name(&mut context, &program_id, &a, &config_account, "alice".to_string()).await;
+    let before = context.banks_client.get_balance(c.pubkey()).await.unwrap();
+    let ix = instructions::with_multisig(
+        instructions::withdraw(&program_id, &c.pubkey(), &config, &c.pubkey(), None),
+        &program_id,
+        &config,
+        &[b.pubkey()],
+    );
+    process(&mut context, ix, &c, &[&c, &b]).await.unwrap();
+    assert!(context.banks_client.get_balance(c.pubkey()).await.unwrap() > before);
+
+    let ix = instructions::with_multisig(
+        instructions::change_program_owner(&program_id, &b.pubkey(), &config, c.pubkey()),
+        &program_id,
+        &config,
+        &[c.pubkey()],
+    );
+    process(&mut context, ix, &b, &[&b, &c]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.pending_owner, c.pubkey());
+
+    // The multisig hands control back to the config owner
+    let ix = instructions::set_multisig(&program_id, &initializer.pubkey(), &config, vec![], 0, &[]);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::MultisigThresholdNotMet);
+    let ix = instructions::set_multisig(&program_id, &a.pubkey(), &config, vec![], 0, &[c.pubkey()]);
+    process(&mut context, ix, &a, &[&a, &c]).await.unwrap();
+    assert!(context.banks_client.get_account(multisig_account).await.unwrap().is_none());
+    let ix = instructions::set_registration_fee(&program_id, &initializer.pubkey(), &config, REGISTRATION_FEE);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_multisig_gates_every_admin_instruction() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let [a, b] = [Keypair::new(), Keypair::new()];
+    for member in [&a, &b] {
+        fund_account(&mut context, &member.pubkey(), 1_000_000_000).await;
+    }
+    let members = vec![a.pubkey(), b.pubkey()];
+    let ix = instructions::set_multisig(&program_id, &initializer.pubkey(), &config, members, 2, &[]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let name_account = register_name(&mut context, &program_id, &a, &config, "alice".to_string()).await;
+
+    let admin_instructions = |owner: &Pubkey| {
+        vec![
+            instructions::schedule_promo(&program_id, owner, &config, 0, 0, 0),
+            instructions::set_referral_bps(&program_id, owner, &config, 500),
+            instructions::set_cooldown(&program_id, owner, &config, 60),
+            instructions::set_banner_message(&program_id, owner, &config, "gm"),
+            instructions::grant_role(&program_id, owner, &config, Role::Registrar, Pubkey::new_unique(), 0),
+            instructions::freeze_name(&program_id, owner, &config, &name_account),
+            instructions::reserve_name(&program_id, owner, &config, "brand"),
+            instructions::release_reserved_name(&program_id, owner, &config, "brand", None),
+            instructions::create_promo_claims(&program_id, owner, &config, i64::MAX, &[(b"secret", "promo")]),
+            instructions::start_auction(&program_id, owner, &config, "gm", 1),
+            instructions::pause(&program_id, owner, &config),
+        ]
+    };
+
+    // The config owner alone no longer passes for the program owner
+    for ix in admin_instructions(&initializer.pubkey()) {
+        let result = process(&mut context, ix.clone(), &initializer, &[&initializer]).await;
+        assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+        let ix = instructions::with_multisig(ix, &program_id, &config, &[]);
+        let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+        assert_registry_error(result, NameRegistryError::MultisigThresholdNotMet);
+    }
+    for ix in admin_instructions(&a.pubkey()) {
+        let ix = instructions::with_multisig(ix, &program_id, &config, &[b.pubkey()]);
+        process(&mut context, ix, &a, &[&a, &b]).await.unwrap();
+    }
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert!(config_state.paused);
+    assert_eq!(config_state.referral_bps, 500);
+    assert_eq!(config_state.cooldown_seconds, 60);
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(name.frozen);
+    let ix = instructions::unpause(&program_id, &a.pubkey(), &config);
+    let ix = instructions::with_multisig(ix, &program_id, &config, &[b.pubkey()]);
+    process(&mut context, ix, &a, &[&a, &b]).await.unwrap();
+
+    // Rent the program owner paid goes to the treasury, not the config owner
+    let auction_account = find_auction_address(&find_name_address("gm", &program_id).0, &program_id).0;
+    let auction: AuctionAccount = get_account_data(&mut context, &auction_account).await;
+    warp_past(&mut context, auction.ends_at).await;
+    let ix = instructions::settle_auction(&program_id, &a.pubkey(), &config, &initializer.pubkey(), "gm");
+    let result = process(&mut context, ix, &a, &[&a]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+    let treasury = find_treasury_address(&config, fee_bucket("gm"), &program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    let ix = instructions::settle_auction(&program_id, &a.pubkey(), &config, &treasury, "gm");
+    process(&mut context, ix, &a, &[&a]).await.unwrap();
+    assert!(context.banks_client.get_balance(treasury).await.unwrap() > before);
+}
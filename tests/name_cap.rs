@@ -0,0 +1,40 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{client::instructions, error::NameRegistryError, state::ProgramConfig};
+
+use common::*;
+
+#[tokio::test]
+async fn test_registrations_stop_at_name_cap() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+
+    // Only the program owner caps the registry
+    let ix = instructions::set_max_total_names(&program_id, &alice.pubkey(), &config, 2);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_max_total_names(&program_id, &initializer.pubkey(), &config, 2);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    register_name(&mut context, &program_id, &alice, &config_account, "one".to_string()).await;
+    register_name(&mut context, &program_id, &alice, &config_account, "two".to_string()).await;
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.total_names, 2);
+
+    let three = instructions::register_name(&program_id, &alice.pubkey(), &config, "three");
+    let result = process(&mut context, three.clone(), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::RegistryFull);
+
+    // Lifting the cap reopens registration without losing the count
+    let ix = instructions::set_max_total_names(&program_id, &initializer.pubkey(), &config, 0);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    process(&mut context, three, &alice, &[&alice]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.total_names, 3);
+    assert_eq!(config_state.max_total_names, 0);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::find_name_address,
+    state::{NameAccount, ProgramConfig},
+    validation::{validate_name, validate_name_for},
+};
+
+use common::*;
+
+#[test]
+fn test_hyphens_only_separate_characters() {
+    for name in ["alice", "pay-day", "a-b-c", "0x-1"] {
+        assert!(validate_name(name).is_ok(), "{} should be valid", name);
+    }
+    for name in ["-alice", "alice-", "pay--day", "-", "Alice", "al ice", "al.ice"] {
+        assert!(validate_name(name).is_err(), "{} should be invalid", name);
+    }
+}
+
+#[test]
+fn test_unicode_subset_excludes_other_scripts() {
+    let unicode = ProgramConfig { allow_unicode_names: true, ..ProgramConfig::default() };
+    assert!(validate_name("café").is_err());
+    assert!(validate_name_for(&unicode, "café").is_ok());
+    assert!(validate_name_for(&unicode, "straße").is_ok());
+
+    // Cyrillic а, decomposed é, uppercase É, ÷ and fullwidth ａ
+    for name in ["\u{430}lice", "cafe\u{301}", "caf\u{c9}", "a\u{f7}b", "\u{ff41}lice"] {
+        assert!(validate_name_for(&unicode, name).is_err(), "{:?} should be invalid", name);
+    }
+}
+
+#[tokio::test]
+async fn test_unicode_names_need_config_flag() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+
+    let register = instructions::register_name(&program_id, &alice.pubkey(), &config, "café");
+    let result = process(&mut context, register.clone(), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameFormat);
+
+    // Only the program owner widens the policy
+    let ix = instructions::set_unicode_names(&program_id, &alice.pubkey(), &config, true);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_unicode_names(&program_id, &initializer.pubkey(), &config, true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    process(&mut context, register, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("café", &program_id).0).await;
+    assert_eq!(name.name, "café");
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "\u{430}lice");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameFormat);
+}
@@ -1,175 +1,29 @@
+mod common;
+
 use solana_program::{
     instruction::AccountMeta,
     program_pack::Pack,
     pubkey::Pubkey,
-    system_instruction,
+    rent::Rent,
+    system_program,
 };
 use solana_program_test::*;
 use solana_sdk::{
-    account::Account,
+    instruction::InstructionError,
     signature::{Keypair, Signer},
     transaction::Transaction,
-    instruction::Instruction,
 };
-use borsh::BorshSerialize;
 use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
     instruction::NameRegistryInstruction,
-    state::{AddressAccount, NameAccount, ProgramConfig},
+    pda::{fee_bucket, find_address_account_address, find_name_address, find_treasury_address, FEE_BUCKETS},
+    state::{
+        ConfigLink, FeeSchedule, NameAccount, NameCosigner, PendingUpdateAccount, ProgramConfig, ReverseRecord, WalletSettings,
+    },
 };
 
-const REGISTRATION_FEE: u64 = 1_000_000; // 0.001 SOL
-const HIGH_FEE: u64 = 2_000_000; // 0.002 SOL
-const COOLDOWN_PERIOD: i64 = 86400; // 1 day in seconds
-
-async fn setup_program() -> (ProgramTestContext, Keypair, Keypair, Pubkey) {
-    let program_id = Pubkey::new_unique();
-    let mut program_test = ProgramTest::new(
-        "instant_folio",
-        program_id,
-        processor!(instant_folio::process_instruction),
-    );
-
-    let initializer = Keypair::new();
-    let config_account = Keypair::new();
-
-    program_test.add_account(
-        initializer.pubkey(),
-        Account {
-            lamports: 1_000_000_000,
-            owner: solana_program::system_program::id(),
-            ..Account::default()
-        },
-    );
-
-    program_test.add_account(
-        config_account.pubkey(),
-        Account {
-            lamports: 0,
-            owner: program_id,
-            ..Account::default()
-        },
-    );
-
-    let context = program_test.start_with_context().await;
-    (context, initializer, config_account, program_id)
-}
-
-fn convert_instruction(
-    ix: NameRegistryInstruction,
-    program_id: &Pubkey,
-    accounts: &[(&Keypair, bool)],
-    system_program: &Pubkey,
-) -> Instruction {
-    let mut account_metas = accounts
-        .iter()
-        .map(|(keypair, is_signer)| {
-            AccountMeta::new(
-                keypair.pubkey(),
-                *is_signer,
-            )
-        })
-        .collect::<Vec<_>>();
-
-    // Add system program if needed
-    account_metas.push(AccountMeta::new_readonly(*system_program, false));
-
-    Instruction {
-        program_id: *program_id,
-        accounts: account_metas,
-        data: ix.try_to_vec().unwrap(),
-    }
-}
-
-async fn initialize_program(
-    context: &mut ProgramTestContext,
-    program_id: &Pubkey,
-    initializer: &Keypair,
-    config_account: &Keypair,
-    registration_fee: u64,
-) {
-    // Create initialize instruction
-    let instruction = NameRegistryInstruction::Initialize {
-        registration_fee,
-    };
-
-    // Create transaction
-    let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            instruction,
-            program_id,
-            &[
-                (&initializer, true),
-                (&config_account, false),
-            ],
-            &solana_program::system_program::id(),
-        )],
-        Some(&initializer.pubkey()),
-    );
-    transaction.sign(&[&initializer], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
-}
-
-async fn register_name(
-    context: &mut ProgramTestContext,
-    program_id: &Pubkey,
-    registrant: &Keypair,
-    name_account: &Keypair,
-    address_account: &Keypair,
-    config_account: &Keypair,
-    name: String,
-) {
-    // Create register name instruction
-    let instruction = NameRegistryInstruction::RegisterName { name };
-
-    // Create transaction
-    let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            instruction,
-            program_id,
-            &[
-                (&registrant, true),
-                (&name_account, false),
-                (&address_account, false),
-                (&config_account, false),
-            ],
-            &solana_program::system_program::id(),
-        )],
-        Some(&registrant.pubkey()),
-    );
-    transaction.sign(&[&registrant], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
-}
-
-async fn add_account(
-    context: &mut ProgramTestContext,
-    keypair: &Keypair,
-    owner: &Pubkey,
-    lamports: u64,
-    account_type: &str,
-) {
-    let space = match account_type {
-        "config" => ProgramConfig::LEN,
-        "name" => NameAccount::LEN,
-        "address" => AddressAccount::LEN,
-        "pending_update" => PendingUpdateAccount::LEN,
-        _ => panic!("Unknown account type: {}", account_type),
-    };
-    
-    let create_account_ix = system_instruction::create_account(
-        &context.payer.pubkey(),
-        &keypair.pubkey(),
-        lamports,
-        space as u64,
-        owner,
-    );
-
-    let mut transaction = Transaction::new_with_payer(
-        &[create_account_ix],
-        Some(&context.payer.pubkey()),
-    );
-    transaction.sign(&[&context.payer], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
-}
+use common::*;
 
 #[tokio::test]
 async fn test_initialize() {
@@ -178,23 +32,41 @@ async fn test_initialize() {
     // Initialize program
     initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
 
-    // Create name and address accounts
-    let name_account = Keypair::new();
-    let address_account = Keypair::new();
-    add_account(&mut context, &name_account, &program_id, 0, "name").await;
-    add_account(&mut context, &address_account, &program_id, 0, "address").await;
-
     // Verify config account
     let config_account = context
         .banks_client
-        .get_account(config_account.pubkey())
+        .get_account(config_account)
         .await
         .unwrap()
         .unwrap();
     let config = ProgramConfig::unpack(&config_account.data).unwrap();
     assert!(config.is_initialized);
     assert_eq!(config.owner, initializer.pubkey());
-    assert_eq!(config.registration_fee, REGISTRATION_FEE);
+    assert_eq!(config.fee_schedule, FeeSchedule::flat(REGISTRATION_FEE));
+}
+
+#[tokio::test]
+async fn test_initialize_requires_upgrade_authority() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = instructions::initialize(&program_id, &stranger.pubkey(), &config_account, 0);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotUpgradeAuthority);
+
+    // The config lives at its PDA and nowhere else
+    let ix = instructions::initialize(&program_id, &initializer.pubkey(), &Pubkey::new_unique(), 0);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let link: ConfigLink = get_account_data(&mut context, &ConfigLink::find_address(&program_id).0).await;
+    assert_eq!(link.config, config_account);
+
+    let ix = instructions::initialize(&program_id, &initializer.pubkey(), &config_account, 0);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::AlreadyInitialized);
 }
 
 #[tokio::test]
@@ -204,38 +76,36 @@ async fn test_register_name() {
     // Initialize program
     initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
 
-    // Create name and address accounts
-    let name_account = Keypair::new();
-    let address_account = Keypair::new();
-    add_account(&mut context, &name_account, &program_id, 0, "name").await;
-    add_account(&mut context, &address_account, &program_id, 0, "address").await;
-
     // Register name
+    let name_account = find_name_address("test-name", &program_id).0;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    let treasury = find_treasury_address(&config_account, fee_bucket("test-name"), &program_id).0;
     let instruction = NameRegistryInstruction::RegisterName {
         name: "test-name".to_string(),
+        proof: None,
     };
 
-    let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            instruction,
-            &program_id,
-            &[
-                (&initializer, true),
-                (&name_account, false),
-                (&address_account, false),
-                (&config_account, false),
-            ],
-            &solana_program::system_program::id(),
-        )],
-        Some(&initializer.pubkey()),
+    let mut register_ix = convert_instruction_with_keys(
+        instruction,
+        &program_id,
+        &[
+            (initializer.pubkey(), true),
+            (name_account, false),
+            (address_account, false),
+            (config_account, false),
+        ],
+        &solana_program::system_program::id(),
     );
+    register_ix.accounts.push(AccountMeta::new(treasury, false));
+
+    let mut transaction = Transaction::new_with_payer(&[register_ix], Some(&initializer.pubkey()));
     transaction.sign(&[&initializer], context.last_blockhash);
     context.banks_client.process_transaction(transaction).await.unwrap();
 
     // Verify name account
     let name_account = context
         .banks_client
-        .get_account(name_account.pubkey())
+        .get_account(name_account)
         .await
         .unwrap()
         .unwrap();
@@ -248,11 +118,11 @@ async fn test_register_name() {
     // Verify address account
     let address_account = context
         .banks_client
-        .get_account(address_account.pubkey())
+        .get_account(address_account)
         .await
         .unwrap()
         .unwrap();
-    let address_data = AddressAccount::unpack(&address_account.data).unwrap();
+    let address_data = ReverseRecord::unpack(&address_account.data).unwrap();
     assert!(address_data.is_initialized);
     assert_eq!(address_data.name, "test-name");
 }
@@ -264,61 +134,54 @@ async fn test_request_address_update() {
     // Initialize program
     initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
 
-    // Create name and address accounts
-    let name_account = Keypair::new();
-    let address_account = Keypair::new();
-    add_account(&mut context, &name_account, &program_id, 0, "name").await;
-    add_account(&mut context, &address_account, &program_id, 0, "address").await;
-
     // Register name
-    register_name(
+    let name_account = register_name(
         &mut context,
         &program_id,
         &initializer,
-        &name_account,
-        &address_account,
         &config_account,
         "test-name".to_string(),
     ).await;
 
     // Create new owner
     let new_owner = Keypair::new();
-    add_account(&mut context, &new_owner, &program_id, 0, "name").await;
 
-    // Create pending update account
-    let pending_update_account = Keypair::new();
-    add_account(&mut context, &pending_update_account, &program_id, 0, "pending_update").await;
+    // Pending update PDA of the name
+    let pending_update_account = PendingUpdateAccount::find_address(&name_account, &program_id).0;
 
     // Request address update
     let instruction = NameRegistryInstruction::RequestAddressUpdate {
         new_address: new_owner.pubkey(),
     };
 
-    let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            instruction,
-            &program_id,
-            &[
-                (&initializer, true),  // [signer] current name owner
-                (&name_account, false),  // [writable] name account
-                (&pending_update_account, false),  // [writable] pending update account
-            ],
-            &solana_program::system_program::id(),
-        )],
-        Some(&initializer.pubkey()),
+    let mut request_ix = convert_instruction_with_keys(
+        instruction,
+        &program_id,
+        &[
+            (initializer.pubkey(), true),  // [signer] current name owner
+            (name_account, false),  // [writable] name account
+            (pending_update_account, false),  // [writable] pending update PDA
+            (config_account, false),  // [] config account
+            (WalletSettings::find_address(&initializer.pubkey(), &program_id).0, false),  // [writable] owner's wallet settings
+        ],
+        &system_program::id(),  // [] system program
     );
+    request_ix.accounts.push(AccountMeta::new_readonly(NameCosigner::find_address(&name_account, &program_id).0, false));  // [] name's co-signer PDA
+    let mut transaction = Transaction::new_with_payer(&[request_ix], Some(&initializer.pubkey()));
     transaction.sign(&[&initializer], context.last_blockhash);
     context.banks_client.process_transaction(transaction).await.unwrap();
 
-    // Verify name account
-    let name_account = context
-        .banks_client
-        .get_account(name_account.pubkey())
-        .await
-        .unwrap()
-        .unwrap();
-    let name_data = NameAccount::unpack(&name_account.data).unwrap();
-    assert_eq!(name_data.address, new_owner.pubkey());
+    // Verify pending update account
+    let pending_update: PendingUpdateAccount =
+        get_account_data(&mut context, &pending_update_account).await;
+    assert!(pending_update.is_initialized);
+    assert_eq!(pending_update.new_address, new_owner.pubkey());
+    assert_eq!(pending_update.name_account, name_account);
+    assert!(pending_update.requested_at > 0);
+
+    // Name keeps resolving to the current address until the update completes
+    let name_data: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name_data.address, initializer.pubkey());
 }
 
 #[tokio::test]
@@ -328,76 +191,71 @@ async fn test_complete_address_update() {
     // Initialize program
     initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
 
-    // Create name and address accounts
-    let name_account = Keypair::new();
-    let address_account = Keypair::new();
-    add_account(&mut context, &name_account, &program_id, 0, "name").await;
-    add_account(&mut context, &address_account, &program_id, 0, "address").await;
-
     // Register name
-    register_name(
+    let name_account = register_name(
         &mut context,
         &program_id,
         &initializer,
-        &name_account,
-        &address_account,
         &config_account,
         "test-name".to_string(),
     ).await;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
 
     // Create new owner
     let new_owner = Keypair::new();
-    add_account(&mut context, &new_owner, &program_id, 0, "name").await;
+    fund_account(&mut context, &new_owner.pubkey(), 1_000_000_000).await;
 
-    // Create pending update account
-    let pending_update_account = Keypair::new();
-    add_account(&mut context, &pending_update_account, &program_id, 0, "pending_update").await;
+    // Pending update PDA of the name
+    let pending_update_account = PendingUpdateAccount::find_address(&name_account, &program_id).0;
 
     // Request address update
     let request_ix = NameRegistryInstruction::RequestAddressUpdate {
         new_address: new_owner.pubkey(),
     };
 
-    let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            request_ix,
-            &program_id,
-            &[
-                (&initializer, true),  // [signer] current name owner
-                (&name_account, false),  // [writable] name account
-                (&pending_update_account, false),  // [writable] pending update account
-            ],
-            &solana_program::system_program::id(),
-        )],
-        Some(&initializer.pubkey()),
+    let mut request_ix = convert_instruction_with_keys(
+        request_ix,
+        &program_id,
+        &[
+            (initializer.pubkey(), true),  // [signer] current name owner
+            (name_account, false),  // [writable] name account
+            (pending_update_account, false),  // [writable] pending update PDA
+            (config_account, false),  // [] config account
+            (WalletSettings::find_address(&initializer.pubkey(), &program_id).0, false),  // [writable] owner's wallet settings
+        ],
+        &system_program::id(),  // [] system program
     );
+    request_ix.accounts.push(AccountMeta::new_readonly(NameCosigner::find_address(&name_account, &program_id).0, false));  // [] name's co-signer PDA
+    let mut transaction = Transaction::new_with_payer(&[request_ix], Some(&initializer.pubkey()));
     transaction.sign(&[&initializer], context.last_blockhash);
     context.banks_client.process_transaction(transaction).await.unwrap();
 
     // Complete address update
     let complete_ix = NameRegistryInstruction::CompleteAddressUpdate;
 
-    let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            complete_ix,
-            &program_id,
-            &[
-                (&new_owner, true),  // [signer] new owner
-                (&name_account, false),  // [writable] name account
-                (&address_account, false),  // [writable] address account
-                (&pending_update_account, false),  // [writable] pending update account
-            ],
-            &solana_program::system_program::id(),
-        )],
-        Some(&new_owner.pubkey()),
+    let mut complete_ix = convert_instruction_with_keys(
+        complete_ix,
+        &program_id,
+        &[
+            (new_owner.pubkey(), true),  // [signer] new owner
+            (name_account, false),  // [writable] name account
+            (address_account, false),  // [writable] address account
+            (pending_update_account, false),  // [writable] pending update PDA
+            (config_account, false),  // [] config account
+            (WalletSettings::find_address(&initializer.pubkey(), &program_id).0, false),  // [writable] previous owner's wallet settings
+            (find_treasury_address(&config_account, fee_bucket("test-name"), &program_id).0, false),  // [writable] name's treasury bucket
+        ],
+        &system_program::id(),  // [] system program
     );
+    complete_ix.accounts.push(AccountMeta::new_readonly(WalletSettings::find_address(&new_owner.pubkey(), &program_id).0, false));  // [] new owner's wallet settings
+    let mut transaction = Transaction::new_with_payer(&[complete_ix], Some(&new_owner.pubkey()));
     transaction.sign(&[&new_owner], context.last_blockhash);
     context.banks_client.process_transaction(transaction).await.unwrap();
 
     // Verify name account
     let name_account = context
         .banks_client
-        .get_account(name_account.pubkey())
+        .get_account(name_account)
         .await
         .unwrap()
         .unwrap();
@@ -412,58 +270,49 @@ async fn test_rename_name() {
     // Initialize program
     initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
 
-    // Create name and address accounts
-    let name_account = Keypair::new();
-    let address_account = Keypair::new();
-    add_account(&mut context, &name_account, &program_id, 0, "name").await;
-    add_account(&mut context, &address_account, &program_id, 0, "address").await;
-
     // Register name
-    register_name(
+    let name_account = register_name(
         &mut context,
         &program_id,
         &initializer,
-        &name_account,
-        &address_account,
         &config_account,
         "test-name".to_string(),
     ).await;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
 
-    // Create new name account
-    let new_name_account = Keypair::new();
-    add_account(&mut context, &new_name_account, &program_id, 0, "name").await;
-
-    // Create pending update account
-    let pending_update_account = Keypair::new();
-    add_account(&mut context, &pending_update_account, &program_id, 0, "pending_update").await;
+    // New name account is derived from the new name
+    let new_name_account = find_name_address("new-test-name", &program_id).0;
 
     // Rename name
     let instruction = NameRegistryInstruction::RenameName {
         new_name: "new-test-name".to_string(),
+        proof: None,
     };
 
-    let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            instruction,
-            &program_id,
-            &[
-                (&initializer, true),  // [signer] current name owner
-                (&name_account, false),  // [writable] old name account
-                (&new_name_account, false),  // [writable] new name account
-                (&address_account, false),  // [writable] address account
-                (&pending_update_account, false),  // [writable] pending update account
-            ],
-            &solana_program::system_program::id(),
-        )],
-        Some(&initializer.pubkey()),
+    let mut rename_ix = convert_instruction_with_keys(
+        instruction,
+        &program_id,
+        &[
+            (initializer.pubkey(), true),  // [signer] current name owner
+            (name_account, false),  // [writable] old name account
+            (new_name_account, false),  // [writable] new name account
+            (address_account, false),  // [writable] address account
+        ],
+        &solana_program::system_program::id(),
     );
+    rename_ix.accounts.push(AccountMeta::new_readonly(config_account, false));  // [] config account
+    rename_ix.accounts.push(AccountMeta::new(NameCosigner::find_address(&name_account, &program_id).0, false));  // [writable] old name's co-signer PDA
+    rename_ix.accounts.push(AccountMeta::new(NameCosigner::find_address(&new_name_account, &program_id).0, false));  // [writable] new name's co-signer PDA
+    rename_ix.accounts.push(AccountMeta::new(find_treasury_address(&config_account, fee_bucket("new-test-name"), &program_id).0, false));  // [writable] new name's treasury bucket
+
+    let mut transaction = Transaction::new_with_payer(&[rename_ix], Some(&initializer.pubkey()));
     transaction.sign(&[&initializer], context.last_blockhash);
     context.banks_client.process_transaction(transaction).await.unwrap();
 
     // Verify new name account
     let new_name_account = context
         .banks_client
-        .get_account(new_name_account.pubkey())
+        .get_account(new_name_account)
         .await
         .unwrap()
         .unwrap();
@@ -476,11 +325,11 @@ async fn test_rename_name() {
     // Verify address account
     let address_account = context
         .banks_client
-        .get_account(address_account.pubkey())
+        .get_account(address_account)
         .await
         .unwrap()
         .unwrap();
-    let address_data = AddressAccount::unpack(&address_account.data).unwrap();
+    let address_data = ReverseRecord::unpack(&address_account.data).unwrap();
     assert_eq!(address_data.name, "new-test-name");
 }
 
@@ -501,13 +350,13 @@ async fn test_set_registration_fee() {
         new_fee,
     };
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
+        &[convert_instruction_with_keys(
             set_fee_ix,
             &program_id,
             &[
-                (&initializer, true),  // [signer] program owner
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
+                (initializer.pubkey(), true),  // [signer] program owner
+                (config_account, false),  // [writable] config account
+                (pending_update_account.pubkey(), false),  // [writable] pending update account
             ],
             &solana_program::system_program::id(),
         )],
@@ -519,12 +368,12 @@ async fn test_set_registration_fee() {
     // Verify config account
     let config_account = context
         .banks_client
-        .get_account(config_account.pubkey())
+        .get_account(config_account)
         .await
         .unwrap()
         .unwrap();
     let config = ProgramConfig::unpack(&config_account.data).unwrap();
-    assert_eq!(config.registration_fee, new_fee);
+    assert_eq!(config.fee_schedule, FeeSchedule::flat(new_fee));
 }
 
 #[tokio::test]
@@ -536,7 +385,7 @@ async fn test_change_program_owner() {
 
     // Create new owner
     let new_owner = Keypair::new();
-    add_account(&mut context, &new_owner, &program_id, 10_000_000_000, "name").await;
+    fund_account(&mut context, &new_owner.pubkey(), 10_000_000_000).await;
 
     // Create pending update account
     let pending_update_account = Keypair::new();
@@ -547,13 +396,13 @@ async fn test_change_program_owner() {
         new_owner: new_owner.pubkey(),
     };
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
+        &[convert_instruction_with_keys(
             change_owner_ix,
             &program_id,
             &[
-                (&initializer, true),  // [signer] current owner
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
+                (initializer.pubkey(), true),  // [signer] current owner
+                (config_account, false),  // [writable] config account
+                (pending_update_account.pubkey(), false),  // [writable] pending update account
             ],
             &solana_program::system_program::id(),
         )],
@@ -565,7 +414,7 @@ async fn test_change_program_owner() {
     // Verify config account
     let config_account_data = context
         .banks_client
-        .get_account(config_account.pubkey())
+        .get_account(config_account)
         .await
         .unwrap()
         .unwrap();
@@ -575,13 +424,13 @@ async fn test_change_program_owner() {
     // Accept ownership
     let accept_ix = NameRegistryInstruction::AcceptProgramOwnership;
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
+        &[convert_instruction_with_keys(
             accept_ix,
             &program_id,
             &[
-                (&new_owner, true),  // [signer] new owner
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
+                (new_owner.pubkey(), true),  // [signer] new owner
+                (config_account, false),  // [writable] config account
+                (pending_update_account.pubkey(), false),  // [writable] pending update account
             ],
             &solana_program::system_program::id(),
         )],
@@ -593,7 +442,7 @@ async fn test_change_program_owner() {
     // Verify config account again
     let config_account_data = context
         .banks_client
-        .get_account(config_account.pubkey())
+        .get_account(config_account)
         .await
         .unwrap()
         .unwrap();
@@ -609,19 +458,11 @@ async fn test_resolve_address() {
     // Initialize program
     initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
 
-    // Create name and address accounts
-    let name_account = Keypair::new();
-    let address_account = Keypair::new();
-    add_account(&mut context, &name_account, &program_id, 0, "name").await;
-    add_account(&mut context, &address_account, &program_id, 0, "address").await;
-
     // Register name
-    register_name(
+    let name_account = register_name(
         &mut context,
         &program_id,
         &initializer,
-        &name_account,
-        &address_account,
         &config_account,
         "test-name".to_string(),
     ).await;
@@ -633,12 +474,12 @@ async fn test_resolve_address() {
     // Resolve address
     let resolve_ix = NameRegistryInstruction::ResolveAddress;
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
+        &[convert_instruction_with_keys(
             resolve_ix,
             &program_id,
             &[
-                (&name_account, false),  // [writable] name account
-                (&pending_update_account, false),  // [writable] pending update account
+                (name_account, false),  // [writable] name account
+                (pending_update_account.pubkey(), false),  // [writable] pending update account
             ],
             &solana_program::system_program::id(),
         )],
@@ -650,7 +491,7 @@ async fn test_resolve_address() {
     // Verify return data
     let account = context
         .banks_client
-        .get_account(name_account.pubkey())
+        .get_account(name_account)
         .await
         .unwrap()
         .unwrap();
@@ -666,27 +507,15 @@ async fn test_withdraw() {
     // Initialize program
     initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
 
-    // Create name and address accounts
-    let name_account = Keypair::new();
-    let address_account = Keypair::new();
-    add_account(&mut context, &name_account, &program_id, 0, "name").await;
-    add_account(&mut context, &address_account, &program_id, 0, "address").await;
-
     // Register name to accumulate fees
     register_name(
         &mut context,
         &program_id,
         &initializer,
-        &name_account,
-        &address_account,
         &config_account,
         "test-name".to_string(),
     ).await;
 
-    // Create pending update account
-    let pending_update_account = Keypair::new();
-    add_account(&mut context, &pending_update_account, &program_id, 0, "pending_update").await;
-
     // Get initial balance
     let initial_account = context
         .banks_client
@@ -697,18 +526,9 @@ async fn test_withdraw() {
     let initial_balance = initial_account.lamports;
 
     // Withdraw
-    let withdraw_ix = NameRegistryInstruction::Withdraw;
+    let treasury = find_treasury_address(&config_account, fee_bucket("test-name"), &program_id).0;
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            withdraw_ix,
-            &program_id,
-            &[
-                (&initializer, true),  // [signer] program owner
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
-            ],
-            &solana_program::system_program::id(),
-        )],
+        &[withdraw_ix(&program_id, &initializer.pubkey(), &config_account)],
         Some(&initializer.pubkey()),
     );
     transaction.sign(&[&initializer], context.last_blockhash);
@@ -724,14 +544,63 @@ async fn test_withdraw() {
     let final_balance = final_account.lamports;
     assert!(final_balance > initial_balance);
 
-    // Verify config account is empty
+    // Verify the treasury only retains its rent-exempt minimum
+    let treasury_account = context
+        .banks_client
+        .get_account(treasury)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(treasury_account.lamports, Rent::default().minimum_balance(0));
+
+    // Config rent is untouched
     let config_account = context
         .banks_client
-        .get_account(config_account.pubkey())
+        .get_account(config_account)
         .await
         .unwrap()
         .unwrap();
-    assert_eq!(config_account.lamports, 0);
+    assert_eq!(config_account.lamports, Rent::default().minimum_balance(ProgramConfig::LEN));
+}
+
+#[tokio::test]
+async fn test_withdraw_sweeps_all_fee_buckets() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    // One name per bucket, so every bucket holds a fee
+    for bucket in 0..FEE_BUCKETS {
+        let name = (0..).map(|i| format!("bucket-{}", i)).find(|name| fee_bucket(name) == bucket).unwrap();
+        register_name(&mut context, &program_id, &initializer, &config_account, name).await;
+    }
+
+    let treasuries = treasury_buckets(&config_account, &program_id);
+    for treasury in &treasuries {
+        let balance = context.banks_client.get_balance(*treasury).await.unwrap();
+        assert_eq!(balance, Rent::default().minimum_balance(0) + REGISTRATION_FEE);
+    }
+
+    let initial_balance = context.banks_client.get_balance(initializer.pubkey()).await.unwrap();
+    let ix = withdraw_ix(&program_id, &initializer.pubkey(), &config_account);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let final_balance = context.banks_client.get_balance(initializer.pubkey()).await.unwrap();
+    assert!(final_balance > initial_balance + REGISTRATION_FEE * (FEE_BUCKETS as u64 - 1));
+    for treasury in &treasuries {
+        let balance = context.banks_client.get_balance(*treasury).await.unwrap();
+        assert_eq!(balance, Rent::default().minimum_balance(0));
+    }
+
+    // A registration paying into the wrong bucket is rejected
+    let mut ix = register_name_ix(
+        &program_id,
+        &initializer.pubkey(),
+        &find_name_address("fresh", &program_id).0,
+        &config_account,
+        "fresh".to_string(),
+    );
+    ix.accounts[5].pubkey = treasuries[((fee_bucket("fresh") + 1) % FEE_BUCKETS) as usize];
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
 }
 
 #[tokio::test]
@@ -739,72 +608,58 @@ async fn test_error_cases() {
     let (mut context, initializer, config_account, program_id) = setup_program().await;
 
     // Test registering with insufficient fee
-    let name_account = Keypair::new();
-    let address_account = Keypair::new();
-    add_account(&mut context, &name_account, &program_id, 0, "name").await;
-    add_account(&mut context, &address_account, &program_id, 0, "address").await;
+    let name_account = find_name_address("test-name", &program_id).0;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
 
     // Create pending update account
     let pending_update_account = Keypair::new();
     add_account(&mut context, &pending_update_account, &program_id, 0, "pending_update").await;
 
     // Initialize with higher fee
-    let init_ix = NameRegistryInstruction::Initialize {
-        registration_fee: HIGH_FEE,
-    };
-    let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            init_ix,
-            &program_id,
-            &[
-                (&initializer, true),  // [signer] initializer
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
-            ],
-            &solana_program::system_program::id(),
-        )],
-        Some(&initializer.pubkey()),
-    );
-    transaction.sign(&[&initializer], context.last_blockhash);
-    context.banks_client.process_transaction(transaction).await.unwrap();
+    initialize_program(&mut context, &program_id, &initializer, &config_account, HIGH_FEE).await;
+
+    // Try to register with insufficient balance (less than the fee)
+    let poor_registrant = Keypair::new();
+    fund_account(&mut context, &poor_registrant.pubkey(), HIGH_FEE / 2).await;
 
-    // Try to register with insufficient balance (0 lamports)
     let register_ix = NameRegistryInstruction::RegisterName {
         name: "test-name".to_string(),
+        proof: None,
     };
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
+        &[convert_instruction_with_keys(
             register_ix,
             &program_id,
             &[
-                (&initializer, true),  // [signer] registrant
-                (&name_account, false),  // [writable] name account
-                (&address_account, false),  // [writable] address account
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
+                (poor_registrant.pubkey(), true),  // [signer] registrant
+                (name_account, false),  // [writable] name account
+                (address_account, false),  // [writable] address account
+                (config_account, false),  // [writable] config account
+                (pending_update_account.pubkey(), false),  // [writable] pending update account
             ],
             &solana_program::system_program::id(),
         )],
-        Some(&initializer.pubkey()),
+        Some(&poor_registrant.pubkey()),
     );
-    transaction.sign(&[&initializer], context.last_blockhash);
+    transaction.sign(&[&poor_registrant], context.last_blockhash);
     let result = context.banks_client.process_transaction(transaction).await;
     assert!(result.is_err());
 
     // Test invalid name format
     let register_ix = NameRegistryInstruction::RegisterName {
         name: "INVALID-NAME".to_string(), // Uppercase not allowed
+        proof: None,
     };
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
+        &[convert_instruction_with_keys(
             register_ix,
             &program_id,
             &[
-                (&initializer, true),  // [signer] registrant
-                (&name_account, false),  // [writable] name account
-                (&address_account, false),  // [writable] address account
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
+                (initializer.pubkey(), true),  // [signer] registrant
+                (name_account, false),  // [writable] name account
+                (address_account, false),  // [writable] address account
+                (config_account, false),  // [writable] config account
+                (pending_update_account.pubkey(), false),  // [writable] pending update account
             ],
             &solana_program::system_program::id(),
         )],
@@ -816,19 +671,19 @@ async fn test_error_cases() {
 
     // Test unauthorized owner change
     let unauthorized = Keypair::new();
-    add_account(&mut context, &unauthorized, &program_id, 10_000_000_000, "name").await;
+    fund_account(&mut context, &unauthorized.pubkey(), 10_000_000_000).await;
 
     let change_owner_ix = NameRegistryInstruction::ChangeProgramOwner {
         new_owner: unauthorized.pubkey(),
     };
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
+        &[convert_instruction_with_keys(
             change_owner_ix,
             &program_id,
             &[
-                (&unauthorized, true),  // [signer] unauthorized user
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
+                (unauthorized.pubkey(), true),  // [signer] unauthorized user
+                (config_account, false),  // [writable] config account
+                (pending_update_account.pubkey(), false),  // [writable] pending update account
             ],
             &solana_program::system_program::id(),
         )],
@@ -860,18 +715,8 @@ async fn test_error_cases() {
     assert!(result.is_err());
 
     // Test withdrawing with empty balance
-    let withdraw_ix = NameRegistryInstruction::Withdraw;
     let mut transaction = Transaction::new_with_payer(
-        &[convert_instruction(
-            withdraw_ix,
-            &program_id,
-            &[
-                (&initializer, true),  // [signer] program owner
-                (&config_account, false),  // [writable] config account
-                (&pending_update_account, false),  // [writable] pending update account
-            ],
-            &solana_program::system_program::id(),
-        )],
+        &[withdraw_ix(&program_id, &initializer.pubkey(), &config_account)],
         Some(&initializer.pubkey()),
     );
     transaction.sign(&[&initializer], context.last_blockhash);
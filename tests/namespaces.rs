@@ -0,0 +1,186 @@
+mod common;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solana_program_test::processor;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_address_account_address, find_name_address},
+    state::{NameAccount, NamespaceAccount, ReverseRecord, NAMESPACE_AUTHORITY_SEED},
+};
+
+use common::*;
+
+const NAMESPACE_FEE: u64 = 5_000_000;
+
+/// Example game minting handles under its namespace: forwards the registry
+/// instruction in its data, after the namespace's key, signing for its
+/// namespace authority. Takes the registry, then the forwarded accounts.
+fn delegate(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (registry, forwarded) = accounts.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let (namespace, data) = data.split_at(32);
+    let (authority, bump) = Pubkey::find_program_address(&[NAMESPACE_AUTHORITY_SEED, namespace], program_id);
+    let accounts = forwarded
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer || account.key == &authority,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    invoke_signed(
+        &Instruction { program_id: *registry.key, accounts, data: data.to_vec() },
+        forwarded,
+        &[&[NAMESPACE_AUTHORITY_SEED, namespace, &[bump]]],
+    )
+}
+
+/// Wraps a registry instruction signed by the namespace authority of
+/// `delegate_id` into a call of `delegate`.
+fn through_delegate(ix: Instruction, delegate_id: &Pubkey, namespace: &Pubkey, authority: &Pubkey) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(ix.program_id, false)];
+    accounts.extend(ix.accounts.into_iter().map(|mut meta| {
+        meta.is_signer &= meta.pubkey != *authority;
+        meta
+    }));
+    Instruction { program_id: *delegate_id, accounts, data: [namespace.as_ref(), &ix.data].concat() }
+}
+
+#[tokio::test]
+async fn test_namespace_registrations() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let suffix_account = find_name_address("dao", &program_id).0;
+    let namespace_account = NamespaceAccount::find_address(&suffix_account, &program_id).0;
+
+    let [alice, bob, admin] = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for wallet in [&alice, &bob, &admin] {
+        fund_account(&mut context, &wallet.pubkey(), 1_000_000_000).await;
+    }
+
+    let ix = instructions::create_namespace(&program_id, &alice.pubkey(), &config, "dao", NAMESPACE_FEE, admin.pubkey());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix =
+        instructions::create_namespace(&program_id, &initializer.pubkey(), &config, "dao", NAMESPACE_FEE, admin.pubkey());
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let namespace: NamespaceAccount = get_account_data(&mut context, &namespace_account).await;
+    assert_eq!((namespace.admin, namespace.fee, namespace.config), (admin.pubkey(), NAMESPACE_FEE, config));
+
+    // The namespace holds its suffix
+    let suffix: NameAccount = get_account_data(&mut context, &suffix_account).await;
+    assert_eq!(suffix.owner, namespace_account);
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "dao");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+
+    // Names of the namespace pay its fee into it
+    let before = context.banks_client.get_balance(namespace_account).await.unwrap();
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice.dao");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let after = context.banks_client.get_balance(namespace_account).await.unwrap();
+    assert_eq!(after - before, NAMESPACE_FEE);
+    let name_account = find_name_address("alice.dao", &program_id).0;
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(name.is_namespaced());
+    assert_eq!(name.owner, alice.pubkey());
+    let record: ReverseRecord =
+        get_account_data(&mut context, &find_address_account_address(&name_account, &program_id).0).await;
+    assert_eq!(record.name, "alice.dao");
+
+    let ix = instructions::register_name(&program_id, &bob.pubkey(), &config, "bob.xyz");
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NamespaceNotFound);
+    let ix = instructions::register_name(&program_id, &bob.pubkey(), &config, "bob.alice.dao");
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameFormat);
+
+    // Renaming would leave the namespace
+    let ix = instructions::rename_name(&program_id, &alice.pubkey(), "alice.dao", &config, "alice");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotTopLevelName);
+
+    // Only the admin sets the namespace's policy
+    let ix = instructions::set_namespace_policy(&program_id, &bob.pubkey(), "dao", 0, bob.pubkey(), true);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotNamespaceAdmin);
+    let ix = instructions::set_namespace_policy(&program_id, &admin.pubkey(), "dao", NAMESPACE_FEE, admin.pubkey(), true);
+    process(&mut context, ix, &admin, &[&admin]).await.unwrap();
+    let ix = instructions::register_name(&program_id, &bob.pubkey(), &config, "bob.dao");
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NamespacePaused);
+
+    // The admin collects the namespace's fees, and only those
+    let ix = instructions::withdraw_namespace_fees(&program_id, &bob.pubkey(), "dao");
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotNamespaceAdmin);
+    let ix = instructions::withdraw_namespace_fees(&program_id, &admin.pubkey(), "dao");
+    process(&mut context, ix, &admin, &[&admin]).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(namespace_account).await.unwrap(), before);
+    let ix = instructions::withdraw_namespace_fees(&program_id, &admin.pubkey(), "dao");
+    let result = process(&mut context, ix, &admin, &[&admin]).await;
+    assert_registry_error(result, NameRegistryError::NothingToWithdraw);
+}
+
+#[tokio::test]
+async fn test_delegated_namespace_registers_through_its_program() {
+    let program_id = Pubkey::new_unique();
+    let delegate_id = Pubkey::new_unique();
+    let mut program_test = program_test(&program_id);
+    program_test.add_program("delegate", delegate_id, processor!(delegate));
+    let (mut context, initializer, config, program_id) = start_program_test(program_test, program_id).await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let namespace_account = NamespaceAccount::find_address(&find_name_address("game", &program_id).0, &program_id).0;
+    let authority = NamespaceAccount::find_authority_address(&namespace_account, &delegate_id).0;
+
+    let [alice, bob, admin] = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for wallet in [&alice, &bob, &admin] {
+        fund_account(&mut context, &wallet.pubkey(), 1_000_000_000).await;
+    }
+    fund_account(&mut context, &authority, 1_000_000_000).await;
+    let ix =
+        instructions::create_namespace(&program_id, &initializer.pubkey(), &config, "game", NAMESPACE_FEE, admin.pubkey());
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    // Only the admin delegates, and never to the registry itself
+    let ix = instructions::delegate_namespace_authority(&program_id, &bob.pubkey(), "game", delegate_id);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotNamespaceAdmin);
+    let ix = instructions::delegate_namespace_authority(&program_id, &admin.pubkey(), "game", program_id);
+    let result = process(&mut context, ix, &admin, &[&admin]).await;
+    assert_instruction_error(result, InstructionError::InvalidArgument);
+    let ix = instructions::delegate_namespace_authority(&program_id, &admin.pubkey(), "game", delegate_id);
+    process(&mut context, ix, &admin, &[&admin]).await.unwrap();
+    let namespace: NamespaceAccount = get_account_data(&mut context, &namespace_account).await;
+    assert_eq!(namespace.delegate_program, delegate_id);
+
+    // Wallets no longer register there on their own
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice.game");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotNamespaceAuthority);
+
+    // The game registers for them, paying from its authority
+    let before = context.banks_client.get_balance(namespace_account).await.unwrap();
+    let ix = instructions::register_name_for(&program_id, &authority, &config, "alice.game", alice.pubkey());
+    let ix = through_delegate(ix, &delegate_id, &namespace_account, &authority);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("alice.game", &program_id).0).await;
+    assert_eq!((name.owner, name.address), (alice.pubkey(), alice.pubkey()));
+    let after = context.banks_client.get_balance(namespace_account).await.unwrap();
+    assert_eq!(after - before, NAMESPACE_FEE);
+
+    // Taking the namespace back opens it again
+    let ix = instructions::delegate_namespace_authority(&program_id, &admin.pubkey(), "game", Pubkey::default());
+    process(&mut context, ix, &admin, &[&admin]).await.unwrap();
+    let ix = instructions::register_name(&program_id, &bob.pubkey(), &config, "bob.game");
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+}
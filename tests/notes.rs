@@ -0,0 +1,59 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::find_note_address,
+    state::NoteAccount,
+    validation::MAX_NOTE_LENGTH,
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_notes_belong_to_name_owner() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    let name_account = register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let alice_note = find_note_address(&name_account, &alice.pubkey(), &program_id).0;
+
+    // Ciphertext is stored as given, up to the size limit
+    let ciphertext = vec![0xa5; MAX_NOTE_LENGTH];
+    let ix = instructions::set_note(&program_id, &alice.pubkey(), &name_account, ciphertext.clone());
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let note: NoteAccount = get_account_data(&mut context, &alice_note).await;
+    assert_eq!(note.data, ciphertext);
+    assert_eq!(note.owner, alice.pubkey());
+
+    let ix = instructions::set_note(&program_id, &alice.pubkey(), &name_account, vec![0; MAX_NOTE_LENGTH + 1]);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NoteTooLong);
+
+    let ix = instructions::set_note(&program_id, &bob.pubkey(), &name_account, vec![1, 2, 3]);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    // After a transfer the new owner starts from an empty slot, and the
+    // previous owner can still recover the rent of theirs
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob.pubkey(), false);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::set_note(&program_id, &bob.pubkey(), &name_account, vec![1, 2, 3]);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let ix = instructions::set_note(&program_id, &alice.pubkey(), &name_account, vec![4]);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    let ix = instructions::delete_note(&program_id, &alice.pubkey(), &name_account);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    assert!(context.banks_client.get_account(alice_note).await.unwrap().is_none());
+    let note: NoteAccount =
+        get_account_data(&mut context, &find_note_address(&name_account, &bob.pubkey(), &program_id).0).await;
+    assert_eq!(note.data, vec![1, 2, 3]);
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use solana_program::instruction::InstructionError;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::find_record_address,
+    state::{NameOperator, OperatorScope, RecordAccount},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_operator_manages_records() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let [alice, bob, hot_wallet] = [Keypair::new(), Keypair::new(), Keypair::new()];
+    for wallet in [&alice, &bob, &hot_wallet] {
+        fund_account(&mut context, &wallet.pubkey(), 1_000_000_000).await;
+    }
+    let name_account = register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let scope = OperatorScope::Records;
+    let approval = NameOperator::find_address(&name_account, scope, &hot_wallet.pubkey(), &program_id).0;
+
+    // Only the owner approves, and never themselves
+    let ix = instructions::approve_operator(&program_id, &bob.pubkey(), &name_account, hot_wallet.pubkey(), scope);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+    let ix = instructions::approve_operator(&program_id, &alice.pubkey(), &name_account, alice.pubkey(), scope);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidAddress);
+
+    // Without an approval the hot wallet is just another stranger
+    let ix = instructions::set_record(&program_id, &hot_wallet.pubkey(), &name_account, "url", "https://a.example");
+    let result = process(&mut context, ix.clone(), &hot_wallet, &[&hot_wallet]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+    let result =
+        process(&mut context, instructions::with_operator(ix.clone(), &program_id), &hot_wallet, &[&hot_wallet]).await;
+    assert_registry_error(result, NameRegistryError::OperatorNotApproved);
+
+    let approve = instructions::approve_operator(&program_id, &alice.pubkey(), &name_account, hot_wallet.pubkey(), scope);
+    process(&mut context, approve, &alice, &[&alice]).await.unwrap();
+    let operator: NameOperator = get_account_data(&mut context, &approval).await;
+    assert_eq!((operator.owner, operator.operator), (alice.pubkey(), hot_wallet.pubkey()));
+
+    process(&mut context, instructions::with_operator(ix, &program_id), &hot_wallet, &[&hot_wallet]).await.unwrap();
+    let record_account = find_record_address(&name_account, "url", &program_id).0;
+    let record: RecordAccount = get_account_data(&mut context, &record_account).await;
+    assert_eq!(record.value, "https://a.example");
+
+    // Someone else's approval does not stand in for the signer's own
+    let ix = instructions::set_record(&program_id, &bob.pubkey(), &name_account, "url", "https://b.example");
+    let mut ix = instructions::with_operator(ix, &program_id);
+    ix.accounts[5].pubkey = approval;
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    let ix = instructions::delete_record(&program_id, &hot_wallet.pubkey(), &name_account, "url");
+    process(&mut context, instructions::with_operator(ix, &program_id), &hot_wallet, &[&hot_wallet]).await.unwrap();
+    assert!(context.banks_client.get_account(record_account).await.unwrap().is_none());
+
+    // The approval lapses once the name changes hands
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob.pubkey(), false);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::set_record(&program_id, &hot_wallet.pubkey(), &name_account, "url", "https://a.example");
+    let ix = instructions::with_operator(ix, &program_id);
+    let result = process(&mut context, ix, &hot_wallet, &[&hot_wallet]).await;
+    assert_registry_error(result, NameRegistryError::OperatorNotApproved);
+
+    // The new owner sweeps the stale approval
+    let ix = instructions::revoke_operator(&program_id, &alice.pubkey(), &name_account, hot_wallet.pubkey(), scope);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+    let ix = instructions::revoke_operator(&program_id, &bob.pubkey(), &name_account, hot_wallet.pubkey(), scope);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    assert!(context.banks_client.get_account(approval).await.unwrap().is_none());
+}
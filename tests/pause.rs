@@ -0,0 +1,118 @@
+mod common;
+
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey, system_program};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    error::NameRegistryError,
+    instruction::NameRegistryInstruction,
+    pda::{fee_bucket, find_address_account_address, find_name_address, find_treasury_address},
+    state::{NameCosigner, PendingUpdateAccount, ProgramConfig, WalletSettings},
+};
+
+use common::*;
+
+fn config_ix(program_id: &Pubkey, owner: &Pubkey, config: &Pubkey, instruction: NameRegistryInstruction) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*config, false),
+        ],
+        data: instruction.try_to_vec().unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn test_pause_blocks_mutations_but_not_getters() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "alice".to_string(),
+    ).await;
+
+    let ix = config_ix(&program_id, &initializer.pubkey(), &config_account, NameRegistryInstruction::Pause);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config: ProgramConfig = get_account_data(&mut context, &config_account).await;
+    assert!(config.paused);
+
+    let ix = register_name_ix(
+        &program_id,
+        &initializer.pubkey(),
+        &find_name_address("bob", &program_id).0,
+        &config_account,
+        "bob".to_string(),
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::ProgramPaused);
+
+    let pending = PendingUpdateAccount::find_address(&name_account, &program_id).0;
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(initializer.pubkey(), true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(pending, false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&initializer.pubkey(), &program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(&name_account, &program_id).0, false),
+        ],
+        data: NameRegistryInstruction::RequestAddressUpdate { new_address: Pubkey::new_unique() }
+            .try_to_vec()
+            .unwrap(),
+    };
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::ProgramPaused);
+
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(initializer.pubkey(), true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(find_name_address("alice-2", &program_id).0, false),
+            AccountMeta::new(find_address_account_address(&name_account, &program_id).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(NameCosigner::find_address(&name_account, &program_id).0, false),
+            AccountMeta::new(NameCosigner::find_address(&find_name_address("alice-2", &program_id).0, &program_id).0, false),
+            AccountMeta::new(find_treasury_address(&config_account, fee_bucket("alice-2"), &program_id).0, false),
+        ],
+        data: NameRegistryInstruction::RenameName { new_name: "alice-2".to_string(), proof: None }.try_to_vec().unwrap(),
+    };
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::ProgramPaused);
+
+    // Reads keep working while paused
+    let ix = Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new_readonly(name_account, false)],
+        data: NameRegistryInstruction::ResolveAddress.try_to_vec().unwrap(),
+    };
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let ix = config_ix(&program_id, &initializer.pubkey(), &config_account, NameRegistryInstruction::Unpause);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    register_name(&mut context, &program_id, &initializer, &config_account, "bob".to_string()).await;
+}
+
+#[tokio::test]
+async fn test_only_owner_can_pause() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    for instruction in [NameRegistryInstruction::Pause, NameRegistryInstruction::Unpause] {
+        let ix = config_ix(&program_id, &stranger.pubkey(), &config_account, instruction);
+        let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+        assert_registry_error(result, NameRegistryError::NotContractOwner);
+    }
+}
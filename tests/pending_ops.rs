@@ -0,0 +1,232 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::find_name_address,
+    state::{PendingUpdateAccount, ProgramConfig, WalletSettings},
+    validation::{DEFAULT_MAX_PENDING_OPERATIONS, DEFAULT_PENDING_UPDATE_TTL},
+};
+
+use common::*;
+
+async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_pending_operations_are_capped_per_wallet() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.max_pending_operations, DEFAULT_MAX_PENDING_OPERATIONS);
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    let settings = WalletSettings::find_address(&alice.pubkey(), &program_id).0;
+
+    // Only the program owner sets the cap
+    let ix = instructions::set_max_pending_operations(&program_id, &alice.pubkey(), &config, 0);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_max_pending_operations(&program_id, &initializer.pubkey(), &config, 2);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let names: Vec<Pubkey> = ["one", "two", "three"]
+        .iter()
+        .map(|name| find_name_address(name, &program_id).0)
+        .collect();
+    for name in ["one", "two", "three"] {
+        let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, name);
+        process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    }
+
+    // An address update and a transfer offer fill the cap
+    let ix = instructions::request_address_update(
+        &program_id, &alice.pubkey(), &names[0], &config, bob.pubkey(),
+    );
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &names[1], &config, bob.pubkey(), true);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let wallet: WalletSettings = get_account_data(&mut context, &settings).await;
+    assert_eq!(wallet.pending_operations, 2);
+
+    let offer_three = instructions::transfer_name(&program_id, &alice.pubkey(), &names[2], &config, bob.pubkey(), true);
+    let result = process(&mut context, offer_three.clone(), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::TooManyPendingOperations);
+
+    // Retargeting an open update or offer does not open another
+    let ix = instructions::request_address_update(
+        &program_id, &alice.pubkey(), &names[0], &config, Pubkey::new_unique(),
+    );
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::request_address_update(
+        &program_id, &alice.pubkey(), &names[0], &config, bob.pubkey(),
+    );
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &names[1], &config, Pubkey::new_unique(), true);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &names[1], &config, bob.pubkey(), true);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let wallet: WalletSettings = get_account_data(&mut context, &settings).await;
+    assert_eq!(wallet.pending_operations, 2);
+
+    // Accepting the offer frees a slot
+    let ix = instructions::accept_name_transfer(&program_id, &bob.pubkey(), &alice.pubkey(), &names[1], &config);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    process(&mut context, offer_three, &alice, &[&alice]).await.unwrap();
+
+    // Completing the update releases both it and the open offer on the name
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &names[0], &config, bob.pubkey(), true);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::TooManyPendingOperations);
+    let ix = instructions::complete_address_update(
+        &program_id, &bob.pubkey(), &alice.pubkey(), "one", &config,
+    );
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let wallet: WalletSettings = get_account_data(&mut context, &settings).await;
+    assert_eq!(wallet.pending_operations, 1);
+
+    // A direct transfer withdraws the remaining offer
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &names[2], &config, bob.pubkey(), false);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let wallet: WalletSettings = get_account_data(&mut context, &settings).await;
+    assert_eq!(wallet.pending_operations, 0);
+}
+
+#[tokio::test]
+async fn test_pending_update_belongs_to_its_requester() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    let mallory = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &mallory.pubkey(), 1_000_000_000).await;
+    let alice_name = register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let mallory_name = register_name(&mut context, &program_id, &mallory, &config_account, "mallory".to_string()).await;
+    let ix = instructions::request_address_update(
+        &program_id, &alice.pubkey(), &alice_name, &config, Pubkey::new_unique(),
+    );
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+
+    // Another wallet cannot take over an armed update
+    let mut ix = instructions::request_address_update(
+        &program_id, &mallory.pubkey(), &mallory_name, &config, mallory.pubkey(),
+    );
+    ix.accounts[2].pubkey = PendingUpdateAccount::find_address(&alice_name, &program_id).0;
+    let result = process(&mut context, ix, &mallory, &[&mallory]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    let wallet: WalletSettings =
+        get_account_data(&mut context, &WalletSettings::find_address(&alice.pubkey(), &program_id).0).await;
+    assert_eq!(wallet.pending_operations, 1);
+    assert!(!wallet.privacy_mode);
+
+    // A later owner of the name replaces the update left behind
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &alice_name, &config, mallory.pubkey(), false);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::request_address_update(&program_id, &mallory.pubkey(), &alice_name, &config, mallory.pubkey());
+    process(&mut context, ix, &mallory, &[&mallory]).await.unwrap();
+    let pending: PendingUpdateAccount =
+        get_account_data(&mut context, &PendingUpdateAccount::find_address(&alice_name, &program_id).0).await;
+    assert_eq!((pending.requester, pending.name_account), (mallory.pubkey(), alice_name));
+}
+
+#[tokio::test]
+async fn test_cancel_address_update() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    let mallory = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &mallory.pubkey(), 1_000_000_000).await;
+    let alice_name = register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let settings = WalletSettings::find_address(&alice.pubkey(), &program_id).0;
+    let pending = PendingUpdateAccount::find_address(&alice_name, &program_id).0;
+    let request = instructions::request_address_update(
+        &program_id, &alice.pubkey(), &alice_name, &config, mallory.pubkey(),
+    );
+    process(&mut context, request.clone(), &alice, &[&alice]).await.unwrap();
+
+    let ix = instructions::cancel_address_update(&program_id, &mallory.pubkey(), &alice_name, true);
+    let result = process(&mut context, ix, &mallory, &[&mallory]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    // Cancelling disarms the update and releases the pending operation
+    let ix = instructions::cancel_address_update(&program_id, &alice.pubkey(), &alice_name, false);
+    process(&mut context, ix.clone(), &alice, &[&alice]).await.unwrap();
+    let wallet: WalletSettings = get_account_data(&mut context, &settings).await;
+    assert_eq!(wallet.pending_operations, 0);
+    let complete = instructions::complete_address_update(
+        &program_id, &mallory.pubkey(), &alice.pubkey(), "alice", &config,
+    );
+    let result = process(&mut context, complete, &mallory, &[&mallory]).await;
+    assert_instruction_error(result, InstructionError::UninitializedAccount);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NoPendingUpdate);
+
+    // Closing refunds the account's rent to the owner
+    process(&mut context, request, &alice, &[&alice]).await.unwrap();
+    let rent = context.banks_client.get_balance(pending).await.unwrap();
+    let balance = context.banks_client.get_balance(alice.pubkey()).await.unwrap();
+    let ix = instructions::cancel_address_update(&program_id, &alice.pubkey(), &alice_name, true);
+    process(&mut context, ix, &initializer, &[&initializer, &alice]).await.unwrap();
+    assert!(context.banks_client.get_account(pending).await.unwrap().is_none());
+    assert_eq!(context.banks_client.get_balance(alice.pubkey()).await.unwrap(), balance + rent);
+    let wallet: WalletSettings = get_account_data(&mut context, &settings).await;
+    assert_eq!(wallet.pending_operations, 0);
+}
+
+#[tokio::test]
+async fn test_pending_updates_expire() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.pending_update_ttl, DEFAULT_PENDING_UPDATE_TTL);
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    let alice_name = register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let pending = PendingUpdateAccount::find_address(&alice_name, &program_id).0;
+
+    let ix = instructions::set_pending_update_ttl(&program_id, &alice.pubkey(), &config, 3_600);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_pending_update_ttl(&program_id, &initializer.pubkey(), &config, 3_600);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let request = instructions::request_address_update(&program_id, &alice.pubkey(), &alice_name, &config, bob.pubkey());
+    process(&mut context, request.clone(), &alice, &[&alice]).await.unwrap();
+    let update: PendingUpdateAccount = get_account_data(&mut context, &pending).await;
+    assert_eq!(update.expires_at, update.requested_at + 3_600);
+
+    // Once expired the update can no longer be completed
+    warp_to(&mut context, update.expires_at).await;
+    let complete = instructions::complete_address_update(&program_id, &bob.pubkey(), &alice.pubkey(), "alice", &config);
+    let result = process(&mut context, complete.clone(), &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::PendingUpdateExpired);
+
+    // Without a TTL a re-armed update waits until completed
+    let ix = instructions::set_pending_update_ttl(&program_id, &initializer.pubkey(), &config, 0);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    process(&mut context, request, &alice, &[&alice]).await.unwrap();
+    let update: PendingUpdateAccount = get_account_data(&mut context, &pending).await;
+    assert_eq!(update.expires_at, 0);
+    warp_to(&mut context, update.requested_at + 365 * 24 * 60 * 60).await;
+    process(&mut context, complete, &bob, &[&bob]).await.unwrap();
+}
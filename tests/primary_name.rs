@@ -0,0 +1,157 @@
+mod common;
+
+use solana_program::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    error::NameRegistryError,
+    instruction::NameRegistryInstruction,
+    pda::{fee_bucket, find_address_account_address, find_primary_name_address, find_treasury_address},
+    state::{NameCosigner, PendingUpdateAccount, PrimaryNameRecord, WalletSettings},
+};
+
+use common::*;
+
+fn set_primary_name_ix(program_id: &Pubkey, wallet: &Pubkey, name_account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*wallet, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(find_primary_name_address(wallet, program_id).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: NameRegistryInstruction::SetPrimaryName.try_to_vec().unwrap(),
+    }
+}
+
+/// Simulates ResolveName and returns the primary name it reports.
+async fn resolve_name(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    wallet: &Pubkey,
+    name_account: &Pubkey,
+) -> Result<String, BanksClientError> {
+    let ix = Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(find_primary_name_address(wallet, program_id).0, false),
+            AccountMeta::new_readonly(*name_account, false),
+        ],
+        data: NameRegistryInstruction::ResolveName.try_to_vec().unwrap(),
+    };
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await?;
+    simulation.result.unwrap()?;
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    Ok(String::from_utf8(return_data.data).unwrap())
+}
+
+async fn setup_registered() -> (ProgramTestContext, Keypair, Pubkey, Pubkey, Pubkey, Pubkey) {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "alice".to_string(),
+    ).await;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    (context, initializer, program_id, address_account, name_account, config_account)
+}
+
+#[tokio::test]
+async fn test_set_and_resolve_primary_name() {
+    let (mut context, initializer, program_id, _address_account, name_account, _config_account) = setup_registered().await;
+
+    let ix = set_primary_name_ix(&program_id, &initializer.pubkey(), &name_account);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let record: PrimaryNameRecord = get_account_data(
+        &mut context,
+        &find_primary_name_address(&initializer.pubkey(), &program_id).0,
+    ).await;
+    assert_eq!(record.wallet, initializer.pubkey());
+    assert_eq!(record.name, "alice");
+
+    let name = resolve_name(&mut context, &program_id, &initializer, &initializer.pubkey(), &name_account).await;
+    assert_eq!(name.unwrap(), "alice");
+}
+
+#[tokio::test]
+async fn test_primary_name_must_resolve_to_wallet() {
+    let (mut context, initializer, program_id, address_account, name_account, config_account) = setup_registered().await;
+
+    // A stranger cannot claim somebody else's name
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = set_primary_name_ix(&program_id, &stranger.pubkey(), &name_account);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::PrimaryNameMismatch);
+
+    // Once the name moves away, the old record no longer resolves
+    let ix = set_primary_name_ix(&program_id, &initializer.pubkey(), &name_account);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let pending = PendingUpdateAccount::find_address(&name_account, &program_id).0;
+    let request_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(initializer.pubkey(), true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(pending, false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&initializer.pubkey(), &program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(&name_account, &program_id).0, false),
+        ],
+        data: NameRegistryInstruction::RequestAddressUpdate { new_address: stranger.pubkey() }
+            .try_to_vec()
+            .unwrap(),
+    };
+    process(&mut context, request_ix, &initializer, &[&initializer]).await.unwrap();
+    let complete_ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(stranger.pubkey(), true),
+            AccountMeta::new(name_account, false),
+            AccountMeta::new(address_account, false),
+            AccountMeta::new(pending, false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&initializer.pubkey(), &program_id).0, false),
+            AccountMeta::new(find_treasury_address(&config_account, fee_bucket("alice"), &program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: NameRegistryInstruction::CompleteAddressUpdate.try_to_vec().unwrap(),
+    };
+    process(&mut context, complete_ix, &stranger, &[&stranger]).await.unwrap();
+
+    let result = resolve_name(&mut context, &program_id, &initializer, &initializer.pubkey(), &name_account).await;
+    assert_registry_error(result.map(|_| ()), NameRegistryError::PrimaryNameMismatch);
+
+    // The reverse record belongs to its wallet's PDA only
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(stranger.pubkey(), true),
+            AccountMeta::new_readonly(name_account, false),
+            AccountMeta::new(find_primary_name_address(&initializer.pubkey(), &program_id).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: NameRegistryInstruction::SetPrimaryName.try_to_vec().unwrap(),
+    };
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+}
@@ -0,0 +1,219 @@
+mod common;
+
+use solana_program::{
+    instruction::{AccountMeta, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    instruction::Instruction,
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    instruction::NameRegistryInstruction,
+    pda::{fee_bucket, find_address_account_address, find_name_address, find_treasury_address},
+    state::{NameAccount, NameCosigner, PendingUpdateAccount, ReverseRecord, WalletSettings},
+};
+
+use common::*;
+
+fn set_privacy_mode_ix(program_id: &Pubkey, wallet: &Pubkey, enabled: bool) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*wallet, true),
+            AccountMeta::new(WalletSettings::find_address(wallet, program_id).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+        data: NameRegistryInstruction::SetPrivacyMode { enabled }.try_to_vec().unwrap(),
+    }
+}
+
+fn register_ix(
+    program_id: &Pubkey,
+    registrant: &Pubkey,
+    name_account: &Pubkey,
+    config_account: &Pubkey,
+    wallet_settings: &Pubkey,
+    name: &str,
+) -> Instruction {
+    let mut ix = register_name_ix(program_id, registrant, name_account, config_account, name.to_string());
+    ix.accounts.push(AccountMeta::new_readonly(*wallet_settings, false));
+    ix
+}
+
+async fn address_record(context: &mut ProgramTestContext, address_account: &Pubkey) -> ReverseRecord {
+    let account = context.banks_client.get_account(*address_account).await.unwrap().unwrap();
+    ReverseRecord::unpack_unchecked(&account.data).unwrap()
+}
+
+/// Moves `name_account` from `owner` to `new_owner`, passing the new owner's
+/// wallet settings.
+async fn transfer(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    owner: &Keypair,
+    new_owner: &Keypair,
+    name_account: &Pubkey,
+    address_account: &Pubkey,
+    config_account: &Pubkey,
+) {
+    let pending = PendingUpdateAccount::find_address(name_account, program_id).0;
+
+    let request_ix = Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(owner.pubkey(), true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new(pending, false),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&owner.pubkey(), program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(name_account, program_id).0, false),
+        ],
+        data: NameRegistryInstruction::RequestAddressUpdate { new_address: new_owner.pubkey() }
+            .try_to_vec()
+            .unwrap(),
+    };
+    process(context, request_ix, owner, &[owner]).await.unwrap();
+
+    let complete_ix = Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(new_owner.pubkey(), true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new(*address_account, false),
+            AccountMeta::new(pending, false),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(WalletSettings::find_address(&owner.pubkey(), program_id).0, false),
+            AccountMeta::new(find_treasury_address(config_account, fee_bucket("test-name"), program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(WalletSettings::find_address(&new_owner.pubkey(), program_id).0, false),
+        ],
+        data: NameRegistryInstruction::CompleteAddressUpdate.try_to_vec().unwrap(),
+    };
+    process(context, complete_ix, new_owner, &[new_owner]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_set_privacy_mode_creates_and_toggles_settings() {
+    let (mut context, initializer, _config_account, program_id) = setup_program().await;
+    let settings_address = WalletSettings::find_address(&initializer.pubkey(), &program_id).0;
+
+    let ix = set_privacy_mode_ix(&program_id, &initializer.pubkey(), true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let settings: WalletSettings = get_account_data(&mut context, &settings_address).await;
+    assert_eq!(settings.wallet, initializer.pubkey());
+    assert!(settings.privacy_mode);
+
+    let ix = set_privacy_mode_ix(&program_id, &initializer.pubkey(), false);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let settings: WalletSettings = get_account_data(&mut context, &settings_address).await;
+    assert!(!settings.privacy_mode);
+}
+
+#[tokio::test]
+async fn test_private_registration_skips_reverse_record() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let ix = set_privacy_mode_ix(&program_id, &initializer.pubkey(), true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let name_account = find_name_address("secret", &program_id).0;
+    let ix = register_ix(
+        &program_id,
+        &initializer.pubkey(),
+        &name_account,
+        &config_account,
+        &WalletSettings::find_address(&initializer.pubkey(), &program_id).0,
+        "secret",
+    );
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let name_data: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name_data.owner, initializer.pubkey());
+    assert_eq!(name_data.name, "secret");
+
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    let address_data = address_record(&mut context, &address_account).await;
+    assert!(!address_data.is_initialized);
+    assert!(address_data.name.is_empty());
+}
+
+#[tokio::test]
+async fn test_transfer_follows_new_owner_privacy() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let private_wallet = Keypair::new();
+    fund_account(&mut context, &private_wallet.pubkey(), 1_000_000_000).await;
+    let ix = set_privacy_mode_ix(&program_id, &private_wallet.pubkey(), true);
+    process(&mut context, ix, &private_wallet, &[&private_wallet]).await.unwrap();
+
+    let public_wallet = Keypair::new();
+    fund_account(&mut context, &public_wallet.pubkey(), 1_000_000_000).await;
+
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "test-name".to_string(),
+    ).await;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+
+    // Public -> private clears the reverse record
+    transfer(
+        &mut context,
+        &program_id,
+        &initializer,
+        &private_wallet,
+        &name_account,
+        &address_account,
+        &config_account,
+    ).await;
+    let address_data = address_record(&mut context, &address_account).await;
+    assert!(!address_data.is_initialized);
+
+    // Private -> public publishes it again
+    transfer(
+        &mut context,
+        &program_id,
+        &private_wallet,
+        &public_wallet,
+        &name_account,
+        &address_account,
+        &config_account,
+    ).await;
+    let address_data = address_record(&mut context, &address_account).await;
+    assert!(address_data.is_initialized);
+    assert_eq!(address_data.name, "test-name");
+}
+
+#[tokio::test]
+async fn test_foreign_wallet_settings_rejected() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let ix = register_ix(
+        &program_id,
+        &initializer.pubkey(),
+        &find_name_address("test-name", &program_id).0,
+        &config_account,
+        &WalletSettings::find_address(&Pubkey::new_unique(), &program_id).0,
+        "test-name",
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    // Settings can only be written by their own wallet
+    let other = Pubkey::new_unique();
+    let mut ix = set_privacy_mode_ix(&program_id, &initializer.pubkey(), true);
+    ix.accounts[1] = AccountMeta::new(WalletSettings::find_address(&other, &program_id).0, false);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::AccountMeta, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    error::NameRegistryError,
+    instruction::NameRegistryInstruction,
+    pda::{fee_bucket, find_treasury_address},
+    state::ProgramConfig,
+};
+
+use common::*;
+
+fn schedule_promo_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: &Pubkey,
+    start: i64,
+    end: i64,
+    fee_override_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*config, false),
+        ],
+        data: NameRegistryInstruction::SchedulePromo { start, end, fee_override_bps }
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+async fn now(context: &mut ProgramTestContext) -> i64 {
+    context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp
+}
+
+/// Registers `name` and returns the fee the treasury received.
+async fn fee_paid(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    registrant: &Keypair,
+    config_account: &Pubkey,
+    name: &str,
+) -> u64 {
+    let treasury = find_treasury_address(config_account, fee_bucket(name), program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    register_name(context, program_id, registrant, config_account, name.to_string()).await;
+    context.banks_client.get_balance(treasury).await.unwrap() - before
+}
+
+#[tokio::test]
+async fn test_promo_window_discounts_registration() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let now = now(&mut context).await;
+
+    // Scheduled for later: full fee for now
+    let ix = schedule_promo_ix(&program_id, &initializer.pubkey(), &config_account, now + 3_600, now + 7_200, 5_000);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let paid = fee_paid(&mut context, &program_id, &initializer, &config_account, "before-promo").await;
+    assert_eq!(paid, REGISTRATION_FEE);
+
+    // Running window: half price
+    let ix = schedule_promo_ix(&program_id, &initializer.pubkey(), &config_account, now - 60, now + 3_600, 5_000);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let paid = fee_paid(&mut context, &program_id, &initializer, &config_account, "launch-week").await;
+    assert_eq!(paid, REGISTRATION_FEE / 2);
+
+    // Cancelled: full fee again
+    let ix = schedule_promo_ix(&program_id, &initializer.pubkey(), &config_account, 0, 0, 0);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let paid = fee_paid(&mut context, &program_id, &initializer, &config_account, "after-promo").await;
+    assert_eq!(paid, REGISTRATION_FEE);
+}
+
+#[tokio::test]
+async fn test_schedule_promo_rejections() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = schedule_promo_ix(&program_id, &stranger.pubkey(), &config_account, 0, i64::MAX, 0);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    let ix = schedule_promo_ix(&program_id, &initializer.pubkey(), &config_account, 10, 5, 5_000);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidPromo);
+
+    let ix = schedule_promo_ix(&program_id, &initializer.pubkey(), &config_account, 0, 10, 10_001);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidPromo);
+
+    let config: ProgramConfig = get_account_data(&mut context, &config_account).await;
+    assert_eq!(config.promo_end, 0);
+}
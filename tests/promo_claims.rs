@@ -0,0 +1,181 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::{blocklist::BlocklistTree, instructions},
+    error::NameRegistryError,
+    pda::{fee_bucket, find_address_account_address, find_name_address, find_promo_claim_address, find_treasury_address, promo_claim_commitment},
+    state::{NameAccount, ProgramConfig, ReverseRecord},
+};
+
+use common::*;
+
+const PREIMAGE: &[u8] = b"qr-code-secret-0001";
+
+fn claim_address(program_id: &Pubkey, config: &Pubkey, preimage: &[u8]) -> Pubkey {
+    find_promo_claim_address(config, &promo_claim_commitment(preimage), program_id).0
+}
+
+async fn now(context: &mut ProgramTestContext) -> i64 {
+    context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp
+}
+
+async fn warp_to(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+#[tokio::test]
+async fn test_claim_promo_name_once_and_fee_free() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let expires_at = now(&mut context).await + 3_600;
+
+    let ix = instructions::create_promo_claims(&program_id, &initializer.pubkey(), &config, expires_at, &[(PREIMAGE, "giveaway")]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let claim = claim_address(&program_id, &config, PREIMAGE);
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("giveaway", &program_id).0).await;
+    assert_eq!(name.owner, claim);
+
+    // Reserved names cannot be registered the normal way
+    let ix = register_name_ix(
+        &program_id,
+        &initializer.pubkey(),
+        &find_name_address("giveaway", &program_id).0,
+        &config,
+        "giveaway".to_string(),
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+
+    let claimer = Keypair::new();
+    fund_account(&mut context, &claimer.pubkey(), 1_000_000_000).await;
+    let claim_ix = |preimage: &[u8]| instructions::claim_promo_name(
+        &program_id,
+        &claimer.pubkey(),
+        &config,
+        &initializer.pubkey(),
+        "giveaway",
+        preimage,
+    );
+
+    // A guessed preimage leads to no claim
+    let result = process(&mut context, claim_ix(b"qr-code-secret-0002"), &claimer, &[&claimer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidPromoClaim);
+    let result = process(&mut context, claim_ix(b"short"), &claimer, &[&claimer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidPromoClaim);
+
+    let treasury = find_treasury_address(&config, fee_bucket("giveaway"), &program_id).0;
+    let treasury_before = context.banks_client.get_balance(treasury).await.unwrap();
+    process(&mut context, claim_ix(PREIMAGE), &claimer, &[&claimer]).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(treasury).await.unwrap(), treasury_before);
+
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("giveaway", &program_id).0).await;
+    assert_eq!(name.owner, claimer.pubkey());
+    assert_eq!(name.address, claimer.pubkey());
+    let address_account = find_address_account_address(&find_name_address("giveaway", &program_id).0, &program_id).0;
+    let address: ReverseRecord = get_account_data(&mut context, &address_account).await;
+    assert_eq!(address.name, "giveaway");
+    assert!(context.banks_client.get_account(claim).await.unwrap().is_none());
+
+    // One-time: the claim is gone
+    let result = process(&mut context, claim_ix(PREIMAGE), &claimer, &[&claimer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidPromoClaim);
+}
+
+#[tokio::test]
+async fn test_expired_promo_names_are_reclaimed_by_owner() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let now = now(&mut context).await;
+
+    let ix = instructions::create_promo_claims(&program_id, &initializer.pubkey(), &config, now, &[(PREIMAGE, "giveaway")]);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::PromoClaimExpired);
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = instructions::create_promo_claims(&program_id, &stranger.pubkey(), &config, now + 60, &[(PREIMAGE, "giveaway")]);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    let ix = instructions::create_promo_claims(&program_id, &initializer.pubkey(), &config, now + 60, &[(PREIMAGE, "giveaway")]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let claim = claim_address(&program_id, &config, PREIMAGE);
+
+    let ix = instructions::reclaim_promo_names(&program_id, &initializer.pubkey(), &config, &[(claim, "giveaway")]);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::PromoClaimNotExpired);
+
+    warp_to(&mut context, now + 60).await;
+    let ix = instructions::claim_promo_name(
+        &program_id,
+        &stranger.pubkey(),
+        &config,
+        &initializer.pubkey(),
+        "giveaway",
+        PREIMAGE,
+    );
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::PromoClaimExpired);
+
+    let ix = instructions::reclaim_promo_names(&program_id, &initializer.pubkey(), &config, &[(claim, "giveaway")]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("giveaway", &program_id).0).await;
+    assert_eq!(name.owner, initializer.pubkey());
+    assert!(context.banks_client.get_account(claim).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_promo_names_are_checked_and_counted_like_registrations() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+    let expires_at = now(&mut context).await + 3_600;
+    let create = |preimage: &'static [u8], name: &'static str| {
+        instructions::create_promo_claims(&program_id, &owner, &config, expires_at, &[(preimage, name)])
+    };
+
+    // Paused configs and blocklisted names get no promo claims
+    let ix = instructions::pause(&program_id, &owner, &config);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let result = process(&mut context, create(PREIMAGE, "gift"), &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::ProgramPaused);
+    let ix = instructions::unpause(&program_id, &owner, &config);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let tree = BlocklistTree::new(["scam"]);
+    let ix = instructions::set_blocklist_root(&program_id, &owner, &config, tree.root());
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let result = process(&mut context, create(PREIMAGE, "gift"), &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let proof = tree.prove_unlisted("gift").unwrap();
+    let ix = instructions::with_promo_name_proofs(create(PREIMAGE, "scam"), &program_id, &config, vec![proof.clone()]);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameProof);
+    let ix = instructions::with_promo_name_proofs(create(PREIMAGE, "gift"), &program_id, &config, vec![proof]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    const SECOND: &[u8] = b"qr-code-secret-0002";
+    let proof = tree.prove_unlisted("gift-2").unwrap();
+    let ix = instructions::with_promo_name_proofs(create(SECOND, "gift-2"), &program_id, &config, vec![proof]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    // Claimed names count towards the cap like registered ones
+    let ix = instructions::set_max_total_names(&program_id, &owner, &config, 1);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let claimer = Keypair::new();
+    fund_account(&mut context, &claimer.pubkey(), 1_000_000_000).await;
+    let claim = |preimage, name| {
+        instructions::claim_promo_name(&program_id, &claimer.pubkey(), &config, &owner, name, preimage)
+    };
+    process(&mut context, claim(PREIMAGE, "gift"), &claimer, &[&claimer]).await.unwrap();
+    let config_data: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!((config_data.total_names, config_data.total_registrations), (1, 1));
+    let result = process(&mut context, claim(SECOND, "gift-2"), &claimer, &[&claimer]).await;
+    assert_registry_error(result, NameRegistryError::RegistryFull);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use solana_program::{clock::Clock, program_pack::Pack, rent::Rent};
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_name_address, name_commitment},
+    state::{PaidOperation, ReceiptAccount},
+    validation::MIN_COMMITMENT_AGE,
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_registrations_issue_receipts_on_request() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let partner = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &partner.pubkey(), 1_000_000_000).await;
+    let receipt_address = |name: &str| {
+        let name_account = find_name_address(name, &program_id).0;
+        ReceiptAccount::find_address(&alice.pubkey(), &name_account, PaidOperation::Registration, &program_id).0
+    };
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    process(&mut context, instructions::with_receipt(ix, &program_id, &alice.pubkey(), "alice"), &alice, &[&alice])
+        .await
+        .unwrap();
+    let receipt: ReceiptAccount = get_account_data(&mut context, &receipt_address("alice")).await;
+    let slot = context.banks_client.get_sysvar::<Clock>().await.unwrap().slot;
+    assert_eq!(receipt.payer, alice.pubkey());
+    assert_eq!(receipt.name_account, find_name_address("alice", &program_id).0);
+    assert_eq!(receipt.operation, PaidOperation::Registration);
+    assert_eq!(receipt.amount, REGISTRATION_FEE);
+    assert!(receipt.slot > 0 && receipt.slot <= slot);
+
+    // A referrer's share still counts toward the amount paid
+    let ix = instructions::register_name_with_referrer(&program_id, &alice.pubkey(), &config, "alice-2", &partner.pubkey());
+    process(&mut context, instructions::with_receipt(ix, &program_id, &alice.pubkey(), "alice-2"), &alice, &[&alice])
+        .await
+        .unwrap();
+    let receipt: ReceiptAccount = get_account_data(&mut context, &receipt_address("alice-2")).await;
+    assert_eq!(receipt.amount, REGISTRATION_FEE);
+
+    let salt = [7; 32];
+    let ix = instructions::commit_name(&program_id, &alice.pubkey(), name_commitment("alice-3", &salt, &alice.pubkey()));
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp += MIN_COMMITMENT_AGE;
+    context.set_sysvar(&clock);
+    let ix = instructions::reveal_and_register(&program_id, &alice.pubkey(), &config, "alice-3", salt);
+    process(&mut context, instructions::with_receipt(ix, &program_id, &alice.pubkey(), "alice-3"), &alice, &[&alice])
+        .await
+        .unwrap();
+    let receipt: ReceiptAccount = get_account_data(&mut context, &receipt_address("alice-3")).await;
+    assert_eq!(receipt.amount, REGISTRATION_FEE);
+
+    // Only the payer reclaims the rent, for any number of receipts at once
+    let receipts = ["alice", "alice-2", "alice-3"].map(receipt_address);
+    let ix = instructions::close_receipts(&program_id, &bob.pubkey(), &receipts);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotReceiptPayer);
+
+    let before = context.banks_client.get_balance(alice.pubkey()).await.unwrap();
+    let ix = instructions::close_receipts(&program_id, &alice.pubkey(), &receipts);
+    process(&mut context, ix, &bob, &[&bob, &alice]).await.unwrap();
+    for receipt in receipts {
+        assert!(context.banks_client.get_account(receipt).await.unwrap().is_none());
+    }
+    let refund = 3 * Rent::default().minimum_balance(ReceiptAccount::LEN);
+    assert_eq!(context.banks_client.get_balance(alice.pubkey()).await.unwrap(), before + refund);
+}
@@ -0,0 +1,299 @@
+mod common;
+
+use solana_program::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    formats,
+    instruction::NameRegistryInstruction,
+    pda::{find_config_address, find_name_address, find_record_address, find_record_index_address},
+    state::{RecordAccount, RecordIndex},
+    validation::{MAX_RECORDS_PER_NAME, MAX_RECORD_VALUE_LENGTH, MAX_RECORD_WRITERS},
+};
+
+use common::*;
+
+fn set_record_ix(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey, kind: &str, value: &str) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(find_record_address(name_account, kind, program_id).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new(find_record_index_address(name_account, program_id).0, false),
+        ],
+        data: NameRegistryInstruction::SetRecord { kind: kind.to_string(), value: value.to_string() }
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+fn delete_record_ix(program_id: &Pubkey, owner: &Pubkey, name_account: &Pubkey, kind: &str) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*name_account, false),
+            AccountMeta::new(find_record_address(name_account, kind, program_id).0, false),
+            AccountMeta::new(find_record_index_address(name_account, program_id).0, false),
+        ],
+        data: NameRegistryInstruction::DeleteRecord { kind: kind.to_string() }.try_to_vec().unwrap(),
+    }
+}
+
+async fn setup_registered() -> (ProgramTestContext, Keypair, Pubkey, Pubkey) {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "alice".to_string(),
+    ).await;
+    (context, initializer, program_id, name_account)
+}
+
+#[tokio::test]
+async fn test_set_overwrite_and_delete_record() {
+    let (mut context, initializer, program_id, name_account) = setup_registered().await;
+    let record_account = find_record_address(&name_account, "avatar", &program_id).0;
+
+    let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "avatar", "https://example.com/a-long-avatar.png");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    // A shorter value must not leave bytes of the old one behind
+    let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "avatar", "ipfs://cid");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let record: RecordAccount = get_account_data(&mut context, &record_account).await;
+    assert_eq!(record.name_account, name_account);
+    assert_eq!(record.kind, "avatar");
+    assert_eq!(record.value, "ipfs://cid");
+
+    let ix = delete_record_ix(&program_id, &initializer.pubkey(), &name_account, "avatar");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    assert!(context.banks_client.get_account(record_account).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_record_rejections() {
+    let (mut context, initializer, program_id, name_account) = setup_registered().await;
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = set_record_ix(&program_id, &stranger.pubkey(), &name_account, "twitter", "@mallory");
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "Twitter", "@alice");
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidRecord);
+
+    let too_long = "x".repeat(MAX_RECORD_VALUE_LENGTH + 1);
+    let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "url", &too_long);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidRecord);
+
+    // Records live at their (name, kind) PDA only
+    let mut ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "url", "https://alice.dev");
+    ix.accounts[2].pubkey = find_record_address(&name_account, "avatar", &program_id).0;
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "twitter", "@alice");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = delete_record_ix(&program_id, &stranger.pubkey(), &name_account, "twitter");
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+}
+
+async fn list_record_keys(context: &mut ProgramTestContext, program_id: &Pubkey, payer: &Keypair, name_account: &Pubkey) -> Vec<Pubkey> {
+    let ix = instructions::list_record_keys(program_id, name_account);
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], context.last_blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    let return_data = simulation.simulation_details.unwrap().return_data.map_or(vec![], |data| data.data);
+    formats::decode_record_keys(&return_data)
+}
+
+#[tokio::test]
+async fn test_record_index_tracks_records() {
+    let (mut context, initializer, program_id, name_account) = setup_registered().await;
+    let index_account = find_record_index_address(&name_account, &program_id).0;
+    assert!(list_record_keys(&mut context, &program_id, &initializer, &name_account).await.is_empty());
+
+    let kinds: Vec<String> = (0..MAX_RECORDS_PER_NAME).map(|i| format!("kind-{}", i)).collect();
+    for kind in &kinds {
+        let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, kind, "value");
+        process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    }
+    // Overwriting a record does not index it twice
+    let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "kind-0", "other");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let expected: Vec<Pubkey> = kinds.iter().map(|kind| find_record_address(&name_account, kind, &program_id).0).collect();
+    let index: RecordIndex = get_account_data(&mut context, &index_account).await;
+    assert_eq!(index.records, expected);
+    assert_eq!(list_record_keys(&mut context, &program_id, &initializer, &name_account).await, expected);
+
+    let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "one-too-many", "value");
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::TooManyRecords);
+
+    // Deleting drops the record from the index, and the last one closes it
+    let ix = delete_record_ix(&program_id, &initializer.pubkey(), &name_account, "kind-3");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let remaining: Vec<Pubkey> = expected.iter().copied().filter(|record| *record != expected[3]).collect();
+    assert_eq!(list_record_keys(&mut context, &program_id, &initializer, &name_account).await, remaining);
+    for kind in kinds.iter().filter(|kind| kind.as_str() != "kind-3") {
+        let ix = delete_record_ix(&program_id, &initializer.pubkey(), &name_account, kind);
+        process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    }
+    assert!(context.banks_client.get_account(index_account).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_register_name_with_records() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = find_name_address("alice", &program_id).0;
+
+    // Records are checked as SetRecord checks them, before anything is
+    // registered
+    let records = [("avatar", "ipfs://cid"), ("Twitter", "@alice")];
+    let ix = instructions::register_name_with_records(&program_id, &initializer.pubkey(), &config, "alice", &records);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidRecord);
+    let kinds: Vec<String> = (0..=MAX_RECORDS_PER_NAME).map(|i| format!("kind-{}", i)).collect();
+    let records: Vec<(&str, &str)> = kinds.iter().map(|kind| (kind.as_str(), "value")).collect();
+    let ix = instructions::register_name_with_records(&program_id, &initializer.pubkey(), &config, "alice", &records);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::TooManyRecords);
+    assert!(context.banks_client.get_account(name_account).await.unwrap().is_none());
+
+    let records = [("avatar", "ipfs://cid"), ("twitter", "@alice")];
+    let ix = instructions::register_name_with_records(&program_id, &initializer.pubkey(), &config, "alice", &records);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let expected: Vec<Pubkey> =
+        records.iter().map(|(kind, _)| find_record_address(&name_account, kind, &program_id).0).collect();
+    assert_eq!(list_record_keys(&mut context, &program_id, &initializer, &name_account).await, expected);
+    for ((kind, value), record_account) in records.iter().zip(&expected) {
+        let record: RecordAccount = get_account_data(&mut context, record_account).await;
+        assert_eq!((record.name_account, record.kind.as_str(), record.value.as_str()), (name_account, *kind, *value));
+    }
+
+    // The records belong to the new owner like any other
+    let ix = set_record_ix(&program_id, &initializer.pubkey(), &name_account, "avatar", "ipfs://other");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let record: RecordAccount = get_account_data(&mut context, &expected[0]).await;
+    assert_eq!(record.value, "ipfs://other");
+}
+
+#[tokio::test]
+async fn test_transfers_can_wipe_records() {
+    let (mut context, owner, program_id, name_account) = setup_registered().await;
+    let config = find_config_address(&program_id).0;
+    let buyer = Keypair::new();
+    fund_account(&mut context, &buyer.pubkey(), 1_000_000_000).await;
+    for kind in ["url", "avatar"] {
+        let ix = set_record_ix(&program_id, &owner.pubkey(), &name_account, kind, "x");
+        process(&mut context, ix, &owner, &[&owner]).await.unwrap();
+    }
+    let records: Vec<_> =
+        ["url", "avatar"].iter().map(|kind| find_record_address(&name_account, kind, &program_id).0).collect();
+
+    // Every record in the index must go
+    let transfer =
+        || instructions::transfer_name(&program_id, &owner.pubkey(), &name_account, &config, buyer.pubkey(), true);
+    let ix = instructions::with_record_wipe(transfer(), &program_id, &owner.pubkey(), &records[..1]);
+    let result = process(&mut context, ix, &owner, &[&owner]).await;
+    assert_registry_error(result, NameRegistryError::NameHasRecords);
+
+    // The buyer wipes them on accepting, the rent going back to the owner
+    let ix = transfer();
+    process(&mut context, ix, &owner, &[&owner]).await.unwrap();
+    let balance = context.banks_client.get_balance(owner.pubkey()).await.unwrap();
+    let ix = instructions::with_record_wipe(
+        instructions::accept_name_transfer(&program_id, &buyer.pubkey(), &owner.pubkey(), &name_account, &config),
+        &program_id,
+        &owner.pubkey(),
+        &records,
+    );
+    process(&mut context, ix, &buyer, &[&buyer]).await.unwrap();
+    assert!(context.banks_client.get_balance(owner.pubkey()).await.unwrap() > balance);
+    for account in records.iter().chain([&find_record_index_address(&name_account, &program_id).0]) {
+        assert!(context.banks_client.get_account(*account).await.unwrap().is_none());
+    }
+
+}
+
+#[tokio::test]
+async fn test_granted_writers_overwrite_one_record() {
+    let (mut context, owner, program_id, name_account) = setup_registered().await;
+    let config = find_config_address(&program_id).0;
+    let record_account = find_record_address(&name_account, "game", &program_id).0;
+    let game = Keypair::new();
+    fund_account(&mut context, &game.pubkey(), 1_000_000_000).await;
+    let ix = set_record_ix(&program_id, &owner.pubkey(), &name_account, "game", "level 1");
+    process(&mut context, ix, &owner, &[&owner]).await.unwrap();
+
+    let write =
+        |kind: &str, value: &str| instructions::set_record(&program_id, &game.pubkey(), &name_account, kind, value);
+    let result = process(&mut context, write("game", "level 2"), &game, &[&game]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+    let game_key = game.pubkey();
+    let ix = instructions::grant_record_writer(&program_id, &game_key, &name_account, "game", &game_key);
+    let result = process(&mut context, ix, &game, &[&game]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    let grant = |wallet: &Pubkey| {
+        instructions::grant_record_writer(&program_id, &owner.pubkey(), &name_account, "game", wallet)
+    };
+    process(&mut context, grant(&game.pubkey()), &owner, &[&owner]).await.unwrap();
+    process(&mut context, write("game", "level 2"), &game, &[&game]).await.unwrap();
+
+    // The owner's own writes keep the grants
+    let ix = set_record_ix(&program_id, &owner.pubkey(), &name_account, "game", "level 3");
+    process(&mut context, ix, &owner, &[&owner]).await.unwrap();
+    process(&mut context, write("game", "level 4"), &game, &[&game]).await.unwrap();
+    let record: RecordAccount = get_account_data(&mut context, &record_account).await;
+    assert_eq!(record.value, "level 4");
+    assert_eq!(record.writers, vec![game.pubkey()]);
+
+    // Only that record, and only overwriting it
+    let result = process(&mut context, write("avatar", "https://game.example/a.png"), &game, &[&game]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+    let ix = delete_record_ix(&program_id, &game.pubkey(), &name_account, "game");
+    let result = process(&mut context, ix, &game, &[&game]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    for _ in 1..MAX_RECORD_WRITERS {
+        process(&mut context, grant(&Pubkey::new_unique()), &owner, &[&owner]).await.unwrap();
+    }
+    let result = process(&mut context, grant(&Pubkey::new_unique()), &owner, &[&owner]).await;
+    assert_registry_error(result, NameRegistryError::TooManyRecordWriters);
+
+    let ix = instructions::revoke_record_writer(&program_id, &owner.pubkey(), &name_account, "game", &game_key);
+    process(&mut context, ix, &owner, &[&owner]).await.unwrap();
+    let result = process(&mut context, write("game", "level 5"), &game, &[&game]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    // Grants lapse with the name, even when its records stay
+    process(&mut context, grant(&game.pubkey()), &owner, &[&owner]).await.unwrap();
+    let buyer = Pubkey::new_unique();
+    let ix = instructions::transfer_name(&program_id, &owner.pubkey(), &name_account, &config, buyer, false);
+    process(&mut context, ix, &owner, &[&owner]).await.unwrap();
+    let result = process(&mut context, write("game", "level 5"), &game, &[&game]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use solana_program::pubkey::Pubkey;
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    formats,
+    pda::{fee_bucket, find_treasury_address},
+    state::ProgramConfig,
+};
+
+use common::*;
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context.banks_client.get_account(*address).await.unwrap().map_or(0, |account| account.lamports)
+}
+
+#[tokio::test]
+async fn test_referrer_earns_share_of_registration_fee() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    let partner = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &partner.pubkey(), 1_000_000_000).await;
+
+    // Only the program owner sets the share, and never above the fee
+    let ix = instructions::set_referral_bps(&program_id, &alice.pubkey(), &config, 2_000);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_referral_bps(&program_id, &initializer.pubkey(), &config, 10_001);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidReferral);
+    let ix = instructions::set_referral_bps(&program_id, &initializer.pubkey(), &config, 2_000);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.referral_bps, 2_000);
+
+    let ix = instructions::register_name_with_referrer(&program_id, &alice.pubkey(), &config, "alice", &alice.pubkey());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidReferral);
+
+    // The share comes out of the fee; the registrant pays the same total
+    let treasury = find_treasury_address(&config, fee_bucket("alice"), &program_id).0;
+    let treasury_before = lamports(&mut context, &treasury).await;
+    let partner_before = lamports(&mut context, &partner.pubkey()).await;
+    let ix = instructions::register_name_with_referrer(&program_id, &alice.pubkey(), &config, "alice", &partner.pubkey());
+    let logs = process_with_logs(&mut context, ix, &alice).await;
+    let payout = REGISTRATION_FEE / 5;
+    assert_eq!(lamports(&mut context, &partner.pubkey()).await, partner_before + payout);
+    assert_eq!(lamports(&mut context, &treasury).await, treasury_before + REGISTRATION_FEE - payout);
+    let event = formats::referral_event("alice", &partner.pubkey(), payout);
+    assert!(logs.iter().any(|log| log.ends_with(&event)));
+
+    // Without a referrer the treasury keeps the whole fee
+    let treasury = find_treasury_address(&config, fee_bucket("bob"), &program_id).0;
+    let treasury_before = lamports(&mut context, &treasury).await;
+    register_name(&mut context, &program_id, &alice, &config_account, "bob".to_string()).await;
+    assert_eq!(lamports(&mut context, &treasury).await, treasury_before + REGISTRATION_FEE);
+}
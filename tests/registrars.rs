@@ -0,0 +1,56 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{fee_bucket, find_name_address, find_treasury_address},
+    state::{NameAccount, Role, RoleGrant},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_registrars_register_for_users_at_their_fee_share() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let registrar = Keypair::new();
+    let user = Keypair::new();
+    fund_account(&mut context, &registrar.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::grant_role(&program_id, &registrar.pubkey(), &config, Role::Registrar, registrar.pubkey(), 0);
+    let result = process(&mut context, ix, &registrar, &[&registrar]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::grant_role(&program_id, &initializer.pubkey(), &config, Role::Registrar, registrar.pubkey(), 10_001);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidRoleGrant);
+
+    // Without the role a registrar cannot register for someone else
+    let ix = instructions::register_name_as_registrar(&program_id, &registrar.pubkey(), &config, "alice", user.pubkey());
+    let result = process(&mut context, ix, &registrar, &[&registrar]).await;
+    assert_registry_error(result, NameRegistryError::RoleNotGranted);
+
+    let ix = instructions::grant_role(&program_id, &initializer.pubkey(), &config, Role::Registrar, registrar.pubkey(), 2_500);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let role_account = RoleGrant::find_address(&config, Role::Registrar, &registrar.pubkey(), &program_id).0;
+    let grant: RoleGrant = get_account_data(&mut context, &role_account).await;
+    assert_eq!((grant.account, grant.role, grant.fee_bps), (registrar.pubkey(), Role::Registrar, 2_500));
+
+    let treasury = find_treasury_address(&config, fee_bucket("alice"), &program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    let ix = instructions::register_name_as_registrar(&program_id, &registrar.pubkey(), &config, "alice", user.pubkey());
+    process(&mut context, ix, &registrar, &[&registrar]).await.unwrap();
+    let after = context.banks_client.get_balance(treasury).await.unwrap();
+    assert_eq!(after - before, REGISTRATION_FEE / 4);
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("alice", &program_id).0).await;
+    assert_eq!((name.owner, name.address), (user.pubkey(), user.pubkey()));
+
+    // Revoking closes the grant and stops further registrations
+    let ix = instructions::revoke_role(&program_id, &initializer.pubkey(), &config, Role::Registrar, registrar.pubkey());
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    assert!(context.banks_client.get_account(role_account).await.unwrap().is_none());
+    let ix = instructions::register_name_as_registrar(&program_id, &registrar.pubkey(), &config, "bob", user.pubkey());
+    let result = process(&mut context, ix, &registrar, &[&registrar]).await;
+    assert_registry_error(result, NameRegistryError::RoleNotGranted);
+}
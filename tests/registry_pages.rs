@@ -0,0 +1,61 @@
+mod common;
+
+use solana_sdk::{instruction::InstructionError, signature::Signer};
+use instant_folio::{
+    client::instructions,
+    pda::find_name_address,
+    state::{ProgramConfig, RegistryPage, REGISTRY_PAGE_CAPACITY},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_registrations_fill_registry_pages_in_order() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+
+    // Names registered before indexing starts stay out of the pages
+    register_name(&mut context, &program_id, &initializer, &config, "early".to_string()).await;
+    let ix = instructions::set_registry_pages(&program_id, &owner, &config, true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let ix = instructions::register_name(&program_id, &owner, &config, "unpaged");
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::with_registry_page(
+        instructions::register_name(&program_id, &owner, &config, "unpaged"),
+        &program_id,
+        &config,
+        1,
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    let names: Vec<String> = (0..=REGISTRY_PAGE_CAPACITY).map(|i| format!("name-{}", i)).collect();
+    for name in &names {
+        let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+        let ix = instructions::with_registry_page(
+            instructions::register_name(&program_id, &owner, &config, name),
+            &program_id,
+            &config,
+            config_state.registry_page(),
+        );
+        process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    }
+
+    let name_accounts: Vec<_> = names.iter().map(|name| find_name_address(name, &program_id).0).collect();
+    let first: RegistryPage =
+        get_account_data(&mut context, &RegistryPage::find_address(&config, 0, &program_id).0).await;
+    assert_eq!(first.config, config);
+    assert_eq!(first.index, 0);
+    assert_eq!(first.names, name_accounts[..REGISTRY_PAGE_CAPACITY]);
+    let second: RegistryPage =
+        get_account_data(&mut context, &RegistryPage::find_address(&config, 1, &program_id).0).await;
+    assert_eq!(second.index, 1);
+    assert_eq!(second.names, name_accounts[REGISTRY_PAGE_CAPACITY..]);
+
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.indexed_names, REGISTRY_PAGE_CAPACITY as u64 + 1);
+    assert_eq!(config_state.total_registrations, REGISTRY_PAGE_CAPACITY as u64 + 2);
+}
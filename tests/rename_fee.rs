@@ -0,0 +1,71 @@
+mod common;
+
+use solana_program::instruction::InstructionError;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{fee_bucket, find_name_address, find_treasury_address, FEE_BUCKETS},
+    state::{AdminAction, NameAccount, PendingAdminAction, ProgramConfig},
+};
+
+use common::*;
+
+const RENAME_FEE: u64 = 25_000_000;
+
+#[tokio::test]
+async fn test_rename_pays_rename_fee_into_new_names_bucket() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+    register_name(&mut context, &program_id, &initializer, &config, "alice".to_string()).await;
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = instructions::set_rename_fee(&program_id, &stranger.pubkey(), &config, 0);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    let ix = instructions::set_rename_fee(&program_id, &owner, &config, RENAME_FEE);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.rename_fee, RENAME_FEE);
+
+    // Only the bucket of the new name takes the fee
+    let mut ix = instructions::rename_name(&program_id, &owner, "alice", &config, "alice-2");
+    let wrong_bucket = (fee_bucket("alice-2") + 1) % FEE_BUCKETS;
+    ix.accounts[8].pubkey = find_treasury_address(&config, wrong_bucket, &program_id).0;
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    let treasury = find_treasury_address(&config, fee_bucket("alice-2"), &program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    let ix = instructions::rename_name(&program_id, &owner, "alice", &config, "alice-2");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let after = context.banks_client.get_balance(treasury).await.unwrap();
+    assert_eq!(after - before, RENAME_FEE);
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("alice-2", &program_id).0).await;
+    assert_eq!(name.owner, owner);
+}
+
+#[tokio::test]
+async fn test_rename_fee_waits_out_admin_timelock() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+
+    let ix = instructions::set_admin_timelock(&program_id, &owner, &config, 60);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let ix = instructions::with_timelock(
+        instructions::set_rename_fee(&program_id, &owner, &config, RENAME_FEE),
+        &program_id,
+        &config,
+    );
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.rename_fee, 0);
+    let pending: PendingAdminAction =
+        get_account_data(&mut context, &PendingAdminAction::find_address(&config, &program_id).0).await;
+    assert_eq!(pending.action, AdminAction::SetRenameFee(RENAME_FEE));
+}
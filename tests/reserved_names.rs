@@ -0,0 +1,105 @@
+mod common;
+
+use solana_program::{instruction::InstructionError, program_pack::Pack, rent::Rent};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_address_account_address, find_name_address},
+    state::{NameAccount, ProgramConfig, ReservedName, ReverseRecord},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_reserved_name_cannot_be_registered_until_released() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = find_name_address("acme", &program_id).0;
+    let reservation = ReservedName::find_address(&name_account, &program_id).0;
+
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::reserve_name(&program_id, &alice.pubkey(), &config, "acme");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    let ix = instructions::reserve_name(&program_id, &initializer.pubkey(), &config, "acme");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, reservation);
+    let reserved: ReservedName = get_account_data(&mut context, &reservation).await;
+    assert_eq!((reserved.config, reserved.name_account), (config, name_account));
+
+    // Neither registering nor renaming onto the name gets through
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "acme");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+    register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let ix = instructions::rename_name(&program_id, &alice.pubkey(), "alice", &config, "acme");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+
+    // Only a name held by its reservation can be released
+    let ix = instructions::reserve_name(&program_id, &initializer.pubkey(), &config, "alice");
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+
+    let before = context.banks_client.get_balance(initializer.pubkey()).await.unwrap();
+    let ix = instructions::release_reserved_name(&program_id, &initializer.pubkey(), &config, "acme", None);
+    process(&mut context, ix, &alice, &[&alice, &initializer]).await.unwrap();
+    assert!(context.banks_client.get_account(name_account).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(reservation).await.unwrap().is_none());
+    let rent = Rent::default();
+    let refund = rent.minimum_balance(NameAccount::LEN) + rent.minimum_balance(ReservedName::LEN);
+    assert_eq!(context.banks_client.get_balance(initializer.pubkey()).await.unwrap(), before + refund);
+
+    register_name(&mut context, &program_id, &alice, &config_account, "acme".to_string()).await;
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, alice.pubkey());
+}
+
+#[tokio::test]
+async fn test_reserved_name_assigned_to_wallet() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = find_name_address("brand", &program_id).0;
+    let bob = Keypair::new();
+
+    // Reserving and releasing to a wallet at once assigns the name directly
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            instructions::reserve_name(&program_id, &initializer.pubkey(), &config, "brand"),
+            instructions::release_reserved_name(&program_id, &initializer.pubkey(), &config, "brand", Some(bob.pubkey())),
+        ],
+        Some(&initializer.pubkey()),
+        &[&initializer],
+        blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await.unwrap();
+
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!((name.owner, name.address), (bob.pubkey(), bob.pubkey()));
+    let reverse: ReverseRecord =
+        get_account_data(&mut context, &find_address_account_address(&name_account, &program_id).0).await;
+    assert_eq!((reverse.wallet, reverse.name.as_str()), (bob.pubkey(), "brand"));
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.total_names, 1);
+    let reservation = ReservedName::find_address(&name_account, &program_id).0;
+    assert!(context.banks_client.get_account(reservation).await.unwrap().is_none());
+
+    // The assignee owns the name outright
+    let ix = instructions::release_reserved_name(&program_id, &initializer.pubkey(), &config, "brand", None);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+    let ix = instructions::transfer_name(&program_id, &bob.pubkey(), &name_account, &config, initializer.pubkey(), false);
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use solana_program::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    error::NameRegistryError,
+    pda::{fee_bucket, find_address_account_address, find_name_address, find_treasury_address},
+    state::FeeSchedule,
+    testing::{assert_diff, assert_unchanged, AccountState, Snapshot},
+};
+
+use common::*;
+
+/// Registrant, config, name, address and treasury accounts touched by
+/// registering `name`.
+fn register_pdas(program_id: &Pubkey, config: &Pubkey, registrant: &Pubkey, name: &str) -> Vec<Pubkey> {
+    let name_account = find_name_address(name, program_id).0;
+    vec![
+        *registrant,
+        *config,
+        name_account,
+        find_address_account_address(&name_account, program_id).0,
+        find_treasury_address(config, fee_bucket(name), program_id).0,
+    ]
+}
+
+#[tokio::test]
+async fn test_register_name_snapshot() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let registrant = Keypair::new();
+    fund_account(&mut context, &registrant.pubkey(), 1_000_000_000).await;
+
+    let pdas = register_pdas(&program_id, &config, &registrant.pubkey(), "alice");
+    let [_, _, name_account, address_account, treasury] = pdas[..] else { unreachable!() };
+    let before = Snapshot::capture(&mut context.banks_client, &pdas).await;
+    assert_eq!(before.state(&name_account), &AccountState::Missing);
+    assert_eq!(before.config(&config).fee_schedule, FeeSchedule::flat(REGISTRATION_FEE));
+
+    register_name(&mut context, &program_id, &registrant, &config_account, "alice".to_string()).await;
+    let after = Snapshot::capture(&mut context.banks_client, &pdas).await;
+    assert_diff(&before, &after, &[registrant.pubkey(), config, name_account, address_account, treasury]);
+    assert_eq!(after.config(&config).total_names, 1);
+    assert_eq!(after.name(&name_account).owner, registrant.pubkey());
+    assert_eq!(after.name(&name_account).address, registrant.pubkey());
+    assert_eq!(after.reverse_record(&address_account).name, "alice");
+    assert_eq!(after.get(&name_account).owner, program_id);
+    assert_eq!(after.lamports(&treasury), before.lamports(&treasury) + REGISTRATION_FEE);
+
+    // A rejected registration leaves registry state as it was; only the
+    // registrant pays the transaction fee
+    let before = Snapshot::capture(&mut context.banks_client, &pdas[1..]).await;
+    let ix = register_name_ix(&program_id, &registrant.pubkey(), &name_account, &config, "alice".to_string());
+    let result = process(&mut context, ix, &registrant, &[&registrant]).await;
+    assert_registry_error(result, NameRegistryError::NameTaken);
+    let after = Snapshot::capture(&mut context.banks_client, &pdas[1..]).await;
+    assert_unchanged(&before, &after);
+}
@@ -0,0 +1,237 @@
+mod common;
+
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use solana_sdk::signature::Signer;
+use borsh::BorshSerialize;
+use instant_folio::{
+    error::NameRegistryError,
+    state::{
+        AccountType, BannerMessage, MultisigConfig, NameAccount, NoteAccount, PackChecked, PendingUpdateAccount,
+        PrimaryNameRecord, RecordAccount, RecordIndex, RegistryPage, ReverseRecord, LAYOUT_VERSION, REGISTRY_PAGE_CAPACITY,
+    },
+    validation::{
+        MAX_BANNER_LENGTH, MAX_MULTISIG_SIGNERS, MAX_NAME_LENGTH, MAX_NOTE_LENGTH, MAX_RECORDS_PER_NAME,
+        MAX_RECORD_KIND_LENGTH, MAX_RECORD_VALUE_LENGTH, MAX_RECORD_WRITERS,
+    },
+};
+
+use common::*;
+
+fn name_account(name: &str) -> NameAccount {
+    NameAccount {
+        is_initialized: true,
+        owner: Pubkey::new_unique(),
+        name: name.to_string(),
+        address: Pubkey::new_unique(),
+        cooldown_until: 0,
+        target_set: false,
+        parent: Pubkey::default(),
+        pending_owner: Pubkey::default(),
+        wildcard_subdomains: false,
+        tokenized: false,
+        registered_at: 0,
+        transfer_count: 0,
+        resolve_count: 0,
+        frozen: false,
+        frozen_by: Pubkey::default(),
+        frozen_at: 0,
+    }
+}
+
+#[test]
+fn test_pack_checked_rejects_oversized_name() {
+    let mut dst = vec![0; NameAccount::LEN];
+    let result = NameAccount::pack_checked(name_account(&"a".repeat(33)), &mut dst);
+    assert_eq!(result, Err(ProgramError::AccountDataTooSmall));
+    assert!(dst.iter().all(|byte| *byte == 0));
+
+    let mut dst = vec![0; ReverseRecord::LEN];
+    let reverse = ReverseRecord::new(Pubkey::new_unique(), "a".repeat(33));
+    assert_eq!(
+        ReverseRecord::pack_checked(reverse, &mut dst),
+        Err(ProgramError::AccountDataTooSmall),
+    );
+}
+
+#[test]
+fn test_pack_checked_round_trips_max_length_name() {
+    let mut dst = vec![0; NameAccount::LEN];
+    let name = "a".repeat(32);
+    NameAccount::pack_checked(name_account(&name), &mut dst).unwrap();
+    assert_eq!(NameAccount::unpack(&dst).unwrap().name, name);
+}
+
+#[test]
+fn test_longest_valid_state_fills_its_account() {
+    // Curated Unicode letters take two bytes each
+    for name in ["a".repeat(MAX_NAME_LENGTH), "\u{df}".repeat(MAX_NAME_LENGTH / 2)] {
+        assert_eq!(name_account(&name).packed_len(), NameAccount::LEN);
+        assert_eq!(ReverseRecord::new(Pubkey::new_unique(), name.clone()).packed_len(), ReverseRecord::LEN);
+        let primary = PrimaryNameRecord { is_initialized: true, wallet: Pubkey::new_unique(), name };
+        assert_eq!(primary.packed_len(), PrimaryNameRecord::LEN);
+    }
+    let record = RecordAccount {
+        is_initialized: true,
+        name_account: Pubkey::new_unique(),
+        kind: "k".repeat(MAX_RECORD_KIND_LENGTH),
+        value: "v".repeat(MAX_RECORD_VALUE_LENGTH),
+        writers_granted_by: Pubkey::new_unique(),
+        writers: vec![Pubkey::new_unique(); MAX_RECORD_WRITERS],
+    };
+    assert_eq!(record.packed_len(), RecordAccount::LEN);
+    let index = RecordIndex {
+        is_initialized: true,
+        name_account: Pubkey::new_unique(),
+        records: vec![Pubkey::new_unique(); MAX_RECORDS_PER_NAME],
+    };
+    assert_eq!(index.packed_len(), RecordIndex::LEN);
+    let banner = BannerMessage { is_initialized: true, message: "b".repeat(MAX_BANNER_LENGTH) };
+    assert_eq!(banner.packed_len(), BannerMessage::LEN);
+    let note = NoteAccount {
+        is_initialized: true,
+        name_account: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        data: vec![0; MAX_NOTE_LENGTH],
+    };
+    assert_eq!(note.packed_len(), NoteAccount::LEN);
+    let multisig = MultisigConfig {
+        is_initialized: true,
+        config: Pubkey::new_unique(),
+        threshold: 1,
+        signers: vec![Pubkey::new_unique(); MAX_MULTISIG_SIGNERS],
+    };
+    assert_eq!(multisig.packed_len(), MultisigConfig::LEN);
+    let page = RegistryPage {
+        is_initialized: true,
+        config: Pubkey::new_unique(),
+        index: u32::MAX,
+        names: vec![Pubkey::new_unique(); REGISTRY_PAGE_CAPACITY],
+    };
+    assert_eq!(page.packed_len(), RegistryPage::LEN);
+
+    assert_eq!(name_account(&"a".repeat(MAX_NAME_LENGTH + 1)).packed_len(), NameAccount::LEN + 1);
+}
+
+#[test]
+fn test_pack_checked_clears_stale_bytes() {
+    let mut dst = vec![0; ReverseRecord::LEN];
+    let wallet = Pubkey::new_unique();
+    let long = ReverseRecord::new(wallet, "a".repeat(32));
+    let short = ReverseRecord::new(wallet, "b".to_string());
+    ReverseRecord::pack_checked(long, &mut dst).unwrap();
+    ReverseRecord::pack_checked(short, &mut dst).unwrap();
+
+    let mut expected = vec![ReverseRecord::DISCRIMINATOR, LAYOUT_VERSION];
+    expected.extend(ReverseRecord::new(wallet, "b".to_string()).try_to_vec().unwrap());
+    assert_eq!(&dst[..expected.len()], expected.as_slice());
+    assert!(dst[expected.len()..].iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn test_unpack_rejects_other_account_types() {
+    let mut dst = vec![0; ReverseRecord::LEN];
+    let primary = PrimaryNameRecord { is_initialized: true, wallet: Pubkey::new_unique(), name: "alice".to_string() };
+    PrimaryNameRecord::pack_checked(primary, &mut dst).unwrap();
+    assert_eq!(
+        ReverseRecord::unpack(&dst),
+        Err(NameRegistryError::InvalidAccountType.into()),
+    );
+
+    // A freshly allocated account carries no discriminator yet
+    let blank = vec![0; ReverseRecord::LEN];
+    assert_eq!(ReverseRecord::unpack_unchecked(&blank), Ok(ReverseRecord::default()));
+}
+
+#[test]
+fn test_pack_checked_rejects_wrong_buffer_size() {
+    let mut dst = vec![0; NameAccount::LEN - 1];
+    assert_eq!(
+        NameAccount::pack_checked(name_account("test-name"), &mut dst),
+        Err(ProgramError::InvalidAccountData),
+    );
+}
+
+#[test]
+fn test_pending_update_without_expiry_reads_as_unexpiring() {
+    let update = PendingUpdateAccount {
+        is_initialized: true,
+        new_address: Pubkey::new_unique(),
+        requester: Pubkey::new_unique(),
+        name_account: Pubkey::new_unique(),
+        requested_at: 1_700_000_000,
+        expires_at: 0,
+    };
+    let mut dst = vec![0; PendingUpdateAccount::LEN];
+    PendingUpdateAccount::pack_checked(update.clone(), &mut dst).unwrap();
+    dst.truncate(PendingUpdateAccount::LEN - 8);
+    assert_eq!(PendingUpdateAccount::unpack(&dst), Ok(update.clone()));
+    assert_eq!(
+        PendingUpdateAccount::pack_checked(update, &mut dst),
+        Err(NameRegistryError::LegacyAccountLayout.into())
+    );
+}
+
+#[test]
+fn test_zero_copy_reads_match_unpack() {
+    for name in ["a", "test-name", &"a".repeat(MAX_NAME_LENGTH), &"\u{df}".repeat(MAX_NAME_LENGTH / 2)] {
+        let mut state = name_account(name);
+        state.parent = Pubkey::new_unique();
+        let mut dst = vec![0; NameAccount::LEN];
+        NameAccount::pack_checked(state.clone(), &mut dst).unwrap();
+
+        assert_eq!(NameAccount::is_initialized_from_slice(&dst), Ok(true));
+        assert_eq!(NameAccount::owner_from_slice(&dst), Ok(state.owner));
+        assert_eq!(NameAccount::name_from_slice(&dst), Ok(name));
+        assert_eq!(NameAccount::address_from_slice(&dst), Ok(state.address));
+        assert_eq!(NameAccount::parent_from_slice(&dst), Ok(state.parent));
+
+        // Version 1 accounts, without the layout version byte, read the same
+        let mut v1 = dst.clone();
+        v1.remove(1);
+        v1.truncate(NameAccount::EARLIER_LAYOUTS[2].1);
+        assert_eq!(NameAccount::unpack(&v1), Ok(state.clone()));
+        assert_eq!(NameAccount::owner_from_slice(&v1), Ok(state.owner));
+        assert_eq!(NameAccount::name_from_slice(&v1), Ok(name));
+        assert_eq!(NameAccount::address_from_slice(&v1), Ok(state.address));
+        assert_eq!(NameAccount::parent_from_slice(&v1), Ok(state.parent));
+        // but are not written until migrated
+        assert_eq!(
+            NameAccount::pack_checked(state, &mut v1),
+            Err(NameRegistryError::LegacyAccountLayout.into())
+        );
+    }
+
+    let blank = vec![0; NameAccount::LEN];
+    assert_eq!(NameAccount::is_initialized_from_slice(&blank), Ok(false));
+    let mut dst = vec![0; ReverseRecord::LEN];
+    ReverseRecord::pack_checked(ReverseRecord::new(Pubkey::new_unique(), "alice".to_string()), &mut dst).unwrap();
+    assert_eq!(NameAccount::address_from_slice(&dst), Err(NameRegistryError::InvalidAccountType.into()));
+
+    // A length prefix running past the account is refused, not trusted
+    let mut dst = vec![0; NameAccount::LEN];
+    NameAccount::pack_checked(name_account("alice"), &mut dst).unwrap();
+    dst[35..39].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(NameAccount::name_from_slice(&dst), Err(ProgramError::InvalidAccountData));
+    assert_eq!(NameAccount::parent_from_slice(&dst), Err(ProgramError::InvalidAccountData));
+}
+
+#[tokio::test]
+async fn test_register_oversized_name_fails_cleanly() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+
+
+    let ix = register_name_ix(
+        &program_id,
+        &initializer.pubkey(),
+        &Pubkey::new_unique(),
+        &config_account,
+        "a".repeat(33),
+    );
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameFormat);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::collections::HashMap;
+
+use solana_program::clock::Clock;
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use instant_folio::{client::instructions, pda::find_name_address};
+
+use common::*;
+
+/// Parses the `registry:` lines of a transaction's logs into their
+/// `key=value` pairs.
+fn registry_events(logs: &[String]) -> Vec<HashMap<String, String>> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program log: registry: "))
+        .map(|line| {
+            line.split(' ')
+                .map(|pair| {
+                    let (key, value) = pair.split_once('=').expect("key=value pair");
+                    (key.to_string(), value.to_string())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn event(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+}
+
+#[tokio::test]
+#[cfg_attr(feature = "no-log-ix-name", ignore = "registry events are compiled out")]
+async fn test_handlers_log_fee_and_cooldown() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let name_account = find_name_address("alice", &program_id).0;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let logs = process_with_logs(&mut context, ix, &alice).await;
+    let fee = REGISTRATION_FEE.to_string();
+    assert_eq!(registry_events(&logs), vec![event(&[("event", "fee"), ("name", "alice"), ("lamports", &fee)])]);
+
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob.pubkey(), false);
+    let logs = process_with_logs(&mut context, ix, &alice).await;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let events = registry_events(&logs);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["event"], "cooldown");
+    assert_eq!(events[0]["name"], "alice");
+    let cooldown_until: i64 = events[0]["cooldown_until"].parse().unwrap();
+    assert!(cooldown_until > now);
+
+    // A rejected transfer tells the owner when they can retry
+    let ix = instructions::transfer_name(&program_id, &bob.pubkey(), &name_account, &config, alice.pubkey(), false);
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&bob.pubkey()), &[&bob], blockhash);
+    let result = context.banks_client.process_transaction_with_metadata(transaction).await.unwrap();
+    assert!(result.result.is_err());
+    let events = registry_events(&result.metadata.unwrap().log_messages);
+    let expected = cooldown_until.to_string();
+    assert_eq!(events, vec![event(&[("event", "cooldown"), ("name", "alice"), ("cooldown_until", &expected)])]);
+}
@@ -0,0 +1,241 @@
+mod common;
+
+use solana_program::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    instruction::NameRegistryInstruction,
+    pda::{fee_bucket, find_name_address, find_treasury_address},
+    state::{NameAccount, NameCosigner},
+};
+
+use common::*;
+
+fn register_subname_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    parent: &Pubkey,
+    config_account: &Pubkey,
+    full_name: &str,
+) -> Instruction {
+    let label = full_name.split('.').next().unwrap().to_string();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*parent, false),
+            AccountMeta::new(find_name_address(full_name, program_id).0, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(*config_account, false),
+        ],
+        data: NameRegistryInstruction::RegisterSubname { label }.try_to_vec().unwrap(),
+    }
+}
+
+fn revoke_subname_ix(program_id: &Pubkey, owner: &Pubkey, parent: &Pubkey, subname: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(*parent, false),
+            AccountMeta::new(*subname, false),
+        ],
+        data: NameRegistryInstruction::RevokeSubname.try_to_vec().unwrap(),
+    }
+}
+
+/// Simulates ResolveAddress for `name_account` with its ancestors appended.
+async fn resolve(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    name_account: &Pubkey,
+    ancestors: &[Pubkey],
+) -> Result<Pubkey, BanksClientError> {
+    let mut accounts = vec![AccountMeta::new_readonly(*name_account, false)];
+    accounts.extend(ancestors.iter().map(|ancestor| AccountMeta::new_readonly(*ancestor, false)));
+    let ix = Instruction {
+        program_id: *program_id,
+        accounts,
+        data: NameRegistryInstruction::ResolveAddress.try_to_vec().unwrap(),
+    };
+    simulate_resolve(context, payer, ix).await
+}
+
+/// Simulates a resolve instruction and returns the address it resolved to.
+async fn simulate_resolve(
+    context: &mut ProgramTestContext,
+    payer: &Keypair,
+    ix: Instruction,
+) -> Result<Pubkey, BanksClientError> {
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await?;
+    simulation.result.unwrap()?;
+    // The bank trims trailing zero bytes from return data
+    let mut data = simulation.simulation_details.unwrap().return_data.unwrap().data;
+    data.resize(32, 0);
+    Ok(Pubkey::try_from(data.as_slice()).unwrap())
+}
+
+async fn setup_parent() -> (ProgramTestContext, Keypair, Pubkey, Pubkey, Pubkey) {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let parent = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "alice".to_string(),
+    ).await;
+    (context, initializer, program_id, parent, config_account)
+}
+
+#[tokio::test]
+async fn test_register_and_resolve_subnames() {
+    let (mut context, initializer, program_id, parent, config_account) = setup_parent().await;
+
+    let ix = register_subname_ix(&program_id, &initializer.pubkey(), &parent, &config_account, "pay.alice");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let pay = find_name_address("pay.alice", &program_id).0;
+    let sub: NameAccount = get_account_data(&mut context, &pay).await;
+    assert_eq!(sub.name, "pay.alice");
+    assert_eq!(sub.owner, initializer.pubkey());
+    assert_eq!(sub.parent, parent);
+
+    // Nested subnames walk every ancestor
+    let ix = register_subname_ix(&program_id, &initializer.pubkey(), &pay, &config_account, "eur.pay.alice");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let eur = find_name_address("eur.pay.alice", &program_id).0;
+    let resolved = resolve(&mut context, &program_id, &initializer, &eur, &[pay, parent]).await;
+    assert_eq!(resolved.unwrap(), initializer.pubkey());
+
+    let result = resolve(&mut context, &program_id, &initializer, &eur, &[pay]).await;
+    assert_instruction_error(result.map(|_| ()), InstructionError::NotEnoughAccountKeys);
+    let result = resolve(&mut context, &program_id, &initializer, &eur, &[parent, pay]).await;
+    assert_registry_error(result.map(|_| ()), NameRegistryError::InvalidParent);
+
+    // Only the parent owner can add subnames, and labels follow the name rules
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = register_subname_ix(&program_id, &stranger.pubkey(), &parent, &config_account, "nft.alice");
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    let ix = register_subname_ix(&program_id, &initializer.pubkey(), &parent, &config_account, "Bad.alice");
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameFormat);
+
+    // Subnames cannot be renamed out from under their parent
+    let address_account = Keypair::new();
+    add_account(&mut context, &address_account, &program_id, 0, "address").await;
+    let ix = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(initializer.pubkey(), true),
+            AccountMeta::new(pay, false),
+            AccountMeta::new(find_name_address("pay", &program_id).0, false),
+            AccountMeta::new(address_account.pubkey(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(config_account, false),
+            AccountMeta::new(NameCosigner::find_address(&pay, &program_id).0, false),
+            AccountMeta::new(NameCosigner::find_address(&find_name_address("pay", &program_id).0, &program_id).0, false),
+            AccountMeta::new(find_treasury_address(&config_account, fee_bucket("pay"), &program_id).0, false),
+        ],
+        data: NameRegistryInstruction::RenameName { new_name: "pay".to_string(), proof: None }.try_to_vec().unwrap(),
+    };
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NotTopLevelName);
+}
+
+#[tokio::test]
+async fn test_revoke_subname() {
+    let (mut context, initializer, program_id, parent, config_account) = setup_parent().await;
+
+    let ix = register_subname_ix(&program_id, &initializer.pubkey(), &parent, &config_account, "pay.alice");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let pay = find_name_address("pay.alice", &program_id).0;
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = revoke_subname_ix(&program_id, &stranger.pubkey(), &parent, &pay);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    let rent = context.banks_client.get_balance(pay).await.unwrap();
+    let before = context.banks_client.get_balance(initializer.pubkey()).await.unwrap();
+    let ix = revoke_subname_ix(&program_id, &initializer.pubkey(), &parent, &pay);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let after = context.banks_client.get_balance(initializer.pubkey()).await.unwrap();
+    assert_eq!(after + 5_000, before + rent);
+    assert!(context.banks_client.get_account(pay).await.unwrap().is_none());
+
+    let result = resolve(&mut context, &program_id, &initializer, &pay, &[parent]).await;
+    assert!(result.is_err());
+
+    // A revoked subname can be issued again
+    let ix = register_subname_ix(&program_id, &initializer.pubkey(), &parent, &config_account, "pay.alice");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let resolved = resolve(&mut context, &program_id, &initializer, &pay, &[parent]).await;
+    assert_eq!(resolved.unwrap(), initializer.pubkey());
+}
+
+#[tokio::test]
+async fn test_wildcard_subdomains_fall_back_to_parent() {
+    let (mut context, initializer, program_id, parent, config_account) = setup_parent().await;
+    let ix = register_subname_ix(&program_id, &initializer.pubkey(), &parent, &config_account, "pay.alice");
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    // Without wildcards only existing subnames resolve
+    let ix = instructions::resolve_subname(&program_id, "pay", "alice", &[]);
+    let resolved = simulate_resolve(&mut context, &initializer, ix).await;
+    assert_eq!(resolved.unwrap(), initializer.pubkey());
+    let ix = instructions::resolve_subname(&program_id, "shop", "alice", &[]);
+    let result = simulate_resolve(&mut context, &initializer, ix).await;
+    assert_registry_error(result.map(|_| ()), NameRegistryError::NameNotFound);
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = instructions::set_wildcard_subdomains(&program_id, &stranger.pubkey(), &parent, true);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+    let ix = instructions::set_wildcard_subdomains(&program_id, &initializer.pubkey(), &parent, true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &parent).await;
+    assert!(name.wildcard_subdomains);
+
+    // Missing subnames now resolve to the parent, existing ones to themselves
+    let ix = instructions::resolve_subname(&program_id, "shop", "alice", &[]);
+    let resolved = simulate_resolve(&mut context, &initializer, ix).await;
+    assert_eq!(resolved.unwrap(), name.address);
+    let ix = instructions::resolve_subname(&program_id, "pay", "alice", &[]);
+    let resolved = simulate_resolve(&mut context, &initializer, ix).await;
+    assert_eq!(resolved.unwrap(), initializer.pubkey());
+
+    // The fallback only covers direct children of a wildcard name
+    let ix = instructions::resolve_subname(&program_id, "eur", "pay.alice", &[parent]);
+    let result = simulate_resolve(&mut context, &initializer, ix).await;
+    assert_registry_error(result.map(|_| ()), NameRegistryError::NameNotFound);
+
+    // The subname account must be the PDA of the label under the parent
+    let mut ix = instructions::resolve_subname(&program_id, "shop", "alice", &[]);
+    ix.accounts[0].pubkey = find_name_address("bob", &program_id).0;
+    let result = simulate_resolve(&mut context, &initializer, ix).await;
+    assert_instruction_error(result.map(|_| ()), InstructionError::InvalidSeeds);
+
+    let ix = instructions::set_wildcard_subdomains(&program_id, &initializer.pubkey(), &parent, false);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::resolve_subname(&program_id, "shop", "alice", &[]);
+    let result = simulate_resolve(&mut context, &initializer, ix).await;
+    assert_registry_error(result.map(|_| ()), NameRegistryError::NameNotFound);
+}
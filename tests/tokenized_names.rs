@@ -0,0 +1,143 @@
+mod common;
+
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    formats,
+    pda::{find_address_account_address, find_name_address, find_name_mint_address},
+    state::NameAccount,
+};
+
+use common::*;
+
+async fn get_token_account(context: &mut ProgramTestContext, address: &Pubkey) -> spl_token::state::Account {
+    let account = context.banks_client.get_account(*address).await.unwrap().unwrap();
+    spl_token::state::Account::unpack(&account.data).unwrap()
+}
+
+async fn resolve(context: &mut ProgramTestContext, ix: Instruction, payer: &Keypair) -> Pubkey {
+    let transaction = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[payer], context.last_blockhash);
+    let simulation = context.banks_client.simulate_transaction(transaction).await.unwrap();
+    let data = simulation.simulation_details.unwrap().return_data.unwrap().data;
+    formats::decode_address(&data).unwrap()
+}
+
+#[tokio::test]
+async fn test_tokenized_names_follow_their_token() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let name_account = find_name_address("alice", &program_id).0;
+    let mint = find_name_mint_address(&name_account, &program_id).0;
+
+    let ix = instructions::tokenize_name(&program_id, &bob.pubkey(), &name_account, &config);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    let ix = instructions::tokenize_name(&program_id, &alice.pubkey(), &name_account, &config);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(name.tokenized);
+    let alice_tokens = get_associated_token_address(&alice.pubkey(), &mint);
+    assert_eq!(get_token_account(&mut context, &alice_tokens).await.amount, 1);
+    let mint_account = context.banks_client.get_account(mint).await.unwrap().unwrap();
+    let mint_data = spl_token::state::Mint::unpack(&mint_account.data).unwrap();
+    assert_eq!((mint_data.supply, mint_data.decimals), (1, 0));
+    assert!(mint_data.mint_authority.is_none());
+
+    // The name no longer moves on its owner's word
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob.pubkey(), false);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameTokenized);
+    // nor is it given up while its token is out
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    let ix = instructions::unregister_name(
+        &program_id,
+        &alice.pubkey(),
+        &name_account,
+        &address_account,
+        &config,
+        &alice.pubkey(),
+    );
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameTokenized);
+
+    // Selling the token hands the name over once anyone syncs it
+    let ix = create_associated_token_account(&bob.pubkey(), &bob.pubkey(), &mint, &spl_token::id());
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let bob_tokens = get_associated_token_address(&bob.pubkey(), &mint);
+    let ix = spl_token::instruction::transfer(&spl_token::id(), &alice_tokens, &bob_tokens, &alice.pubkey(), &[], 1)
+        .unwrap();
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+
+    // Resolution through the token follows it before any sync
+    let ix = instructions::resolve_token_holder(&program_id, &name_account, &bob.pubkey());
+    assert_eq!(resolve(&mut context, ix, &bob).await, bob.pubkey());
+    let ix = instructions::resolve_token_holder(&program_id, &name_account, &alice.pubkey());
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameToken);
+
+    let ix = instructions::sync_tokenized_owner(&program_id, &name_account, &config, &alice.pubkey());
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameToken);
+    let ix = instructions::sync_tokenized_owner(&program_id, &name_account, &config, &bob.pubkey());
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, bob.pubkey());
+
+    let ix = instructions::tokenize_name(&program_id, &bob.pubkey(), &name_account, &config);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NameTokenized);
+}
+
+#[tokio::test]
+async fn test_burned_name_token_hands_name_back() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let name_account = find_name_address("alice", &program_id).0;
+    let mint = find_name_mint_address(&name_account, &program_id).0;
+
+    // Only tokenized names with their token gone are finalized
+    let ix = instructions::finalize_burned_name(&program_id, &name_account);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::InvalidNameToken);
+    let ix = instructions::tokenize_name(&program_id, &alice.pubkey(), &name_account, &config);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::finalize_burned_name(&program_id, &name_account);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NameTokenNotBurned);
+
+    let alice_tokens = get_associated_token_address(&alice.pubkey(), &mint);
+    let ix = spl_token::instruction::burn(&spl_token::id(), &alice_tokens, &mint, &alice.pubkey(), &[], 1).unwrap();
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::finalize_burned_name(&program_id, &name_account);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert!(!name.tokenized);
+    assert_eq!(name.owner, alice.pubkey());
+
+    // The last holder moves the name on its own word again
+    let ix = instructions::transfer_name(&program_id, &alice.pubkey(), &name_account, &config, bob.pubkey(), false);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, bob.pubkey());
+}
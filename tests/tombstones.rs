@@ -0,0 +1,105 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::find_address_account_address,
+    state::NameTombstone,
+};
+
+use common::*;
+
+const TOMBSTONE_PERIOD: u32 = 3600;
+
+async fn warp_past(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp + 1;
+    context.set_sysvar(&clock);
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context.banks_client.get_account(*address).await.unwrap().map_or(0, |account| account.lamports)
+}
+
+#[tokio::test]
+async fn test_unregistered_name_resolves_as_recently_released() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+
+    let name_account = register_name(&mut context, &program_id, &alice, &config, "alice".to_string()).await;
+
+    let ix = instructions::set_tombstone_period(&program_id, &alice.pubkey(), &config, TOMBSTONE_PERIOD);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_tombstone_period(&program_id, &initializer.pubkey(), &config, TOMBSTONE_PERIOD);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    let tombstone_account = NameTombstone::find_address(&name_account, &program_id).0;
+    let unregister = |owner: &Keypair| {
+        let owner = owner.pubkey();
+        instructions::unregister_name(&program_id, &owner, &name_account, &address_account, &config, &owner)
+    };
+
+    // The tombstone is not optional while the config has a period
+    let result = process(&mut context, unregister(&alice), &alice, &[&alice]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::with_tombstone(unregister(&alice), &program_id);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let tombstone: NameTombstone = get_account_data(&mut context, &tombstone_account).await;
+    assert_eq!(tombstone.name_account, name_account);
+    assert_eq!(tombstone.previous_owner, alice.pubkey());
+    assert_eq!(tombstone.previous_address, alice.pubkey());
+    assert_eq!(tombstone.expires_at, tombstone.released_at + i64::from(TOMBSTONE_PERIOD));
+    assert_eq!(tombstone.payer, alice.pubkey());
+
+    let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NameRecentlyReleased);
+
+    let ix = instructions::close_tombstone(&program_id, &name_account, &bob.pubkey());
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+    let ix = instructions::close_tombstone(&program_id, &name_account, &alice.pubkey());
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::TombstoneNotExpired);
+
+    // Nobody registers the name again while its tombstone is live
+    let register = || instructions::register_name(&program_id, &bob.pubkey(), &config, "alice");
+    let result = process(&mut context, register(), &bob, &[&bob]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::with_tombstone(register(), &program_id);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NameRecentlyReleased);
+    warp_past(&mut context, tombstone.expires_at).await;
+    let ix = instructions::with_tombstone(register(), &program_id);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+
+    // Unregistering again renews the tombstone, whose rent stays alice's
+    let ix = instructions::with_tombstone(unregister(&bob), &program_id);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let tombstone: NameTombstone = get_account_data(&mut context, &tombstone_account).await;
+    assert_eq!(tombstone.previous_owner, bob.pubkey());
+    assert_eq!(tombstone.payer, alice.pubkey());
+
+    // Once expired the name resolves as one that never existed
+    warp_past(&mut context, tombstone.expires_at).await;
+    let ix = instructions::resolve_address(&program_id, &name_account, &[]);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+
+    let refund = lamports(&mut context, &tombstone_account).await;
+    let before = lamports(&mut context, &alice.pubkey()).await;
+    let ix = instructions::close_tombstone(&program_id, &name_account, &alice.pubkey());
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    assert!(context.banks_client.get_account(tombstone_account).await.unwrap().is_none());
+    assert_eq!(lamports(&mut context, &alice.pubkey()).await, before + refund);
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey, system_program};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+use borsh::BorshSerialize;
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    instruction::NameRegistryInstruction,
+    pda::{find_record_address, find_record_index_address},
+    state::{NameAccount, NameCosigner, WalletSettings},
+};
+
+use common::*;
+
+fn transfer_name_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    config_account: &Pubkey,
+    new_owner: &Pubkey,
+    require_acceptance: bool,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(WalletSettings::find_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(NameCosigner::find_address(name_account, program_id).0, false),
+        ],
+        data: NameRegistryInstruction::TransferName { new_owner: *new_owner, require_acceptance, wipe_records: false }
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+fn accept_name_transfer_ix(
+    program_id: &Pubkey,
+    new_owner: &Pubkey,
+    owner: &Pubkey,
+    name_account: &Pubkey,
+    config_account: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*new_owner, true),
+            AccountMeta::new(*name_account, false),
+            AccountMeta::new_readonly(*config_account, false),
+            AccountMeta::new(WalletSettings::find_address(owner, program_id).0, false),
+        ],
+        data: NameRegistryInstruction::AcceptNameTransfer { wipe_records: false }.try_to_vec().unwrap(),
+    }
+}
+
+async fn setup_registered() -> (ProgramTestContext, Keypair, Pubkey, Pubkey, Pubkey) {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let name_account = register_name(
+        &mut context,
+        &program_id,
+        &initializer,
+        &config_account,
+        "alice".to_string(),
+    ).await;
+    (context, initializer, program_id, name_account, config_account)
+}
+
+#[tokio::test]
+async fn test_direct_transfer() {
+    let (mut context, initializer, program_id, name_account, config_account) = setup_registered().await;
+    let buyer = Keypair::new();
+    fund_account(&mut context, &buyer.pubkey(), 1_000_000_000).await;
+
+    let ix = transfer_name_ix(&program_id, &buyer.pubkey(), &name_account, &config_account, &buyer.pubkey(), false);
+    let result = process(&mut context, ix, &buyer, &[&buyer]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    let ix = transfer_name_ix(&program_id, &initializer.pubkey(), &name_account, &config_account, &Pubkey::default(), false);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::InvalidAddress);
+
+    let ix = transfer_name_ix(&program_id, &initializer.pubkey(), &name_account, &config_account, &buyer.pubkey(), false);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, buyer.pubkey());
+    assert_eq!(name.address, initializer.pubkey());
+
+    // The new owner cannot flip the name straight away
+    let ix = transfer_name_ix(&program_id, &buyer.pubkey(), &name_account, &config_account, &initializer.pubkey(), false);
+    let result = process(&mut context, ix, &buyer, &[&buyer]).await;
+    assert_registry_error(result, NameRegistryError::CooldownNotOver);
+}
+
+#[tokio::test]
+async fn test_two_step_transfer() {
+    let (mut context, initializer, program_id, name_account, config_account) = setup_registered().await;
+    let buyer = Keypair::new();
+    let stranger = Keypair::new();
+    fund_account(&mut context, &buyer.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+
+    let ix = accept_name_transfer_ix(&program_id, &buyer.pubkey(), &initializer.pubkey(), &name_account, &config_account);
+    let result = process(&mut context, ix, &buyer, &[&buyer]).await;
+    assert_registry_error(result, NameRegistryError::NotPendingNameOwner);
+
+    let ix = transfer_name_ix(&program_id, &initializer.pubkey(), &name_account, &config_account, &buyer.pubkey(), true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, initializer.pubkey());
+    assert_eq!(name.pending_owner, buyer.pubkey());
+
+    let ix = accept_name_transfer_ix(&program_id, &stranger.pubkey(), &initializer.pubkey(), &name_account, &config_account);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotPendingNameOwner);
+
+    let ix = accept_name_transfer_ix(&program_id, &buyer.pubkey(), &initializer.pubkey(), &name_account, &config_account);
+    process(&mut context, ix, &buyer, &[&buyer]).await.unwrap();
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, buyer.pubkey());
+    assert_eq!(name.pending_owner, Pubkey::default());
+}
+
+#[tokio::test]
+async fn test_accepting_with_record_wipe_refunds_the_seller() {
+    let (mut context, seller, program_id, name_account, config_account) = setup_registered().await;
+    let buyer = Keypair::new();
+    fund_account(&mut context, &buyer.pubkey(), 1_000_000_000).await;
+    let ix = instructions::set_record(&program_id, &seller.pubkey(), &name_account, "url", "https://alice.example");
+    process(&mut context, ix, &seller, &[&seller]).await.unwrap();
+    let records = [find_record_address(&name_account, "url", &program_id).0];
+    let index = find_record_index_address(&name_account, &program_id).0;
+    let ix = transfer_name_ix(&program_id, &seller.pubkey(), &name_account, &config_account, &buyer.pubkey(), true);
+    process(&mut context, ix, &seller, &[&seller]).await.unwrap();
+
+    let accept = |refund_to: &Pubkey| {
+        let ix = accept_name_transfer_ix(&program_id, &buyer.pubkey(), &seller.pubkey(), &name_account, &config_account);
+        instructions::with_record_wipe(ix, &program_id, refund_to, &records)
+    };
+    let result = process(&mut context, accept(&buyer.pubkey()), &buyer, &[&buyer]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    // The seller paid the records' rent and gets it back, to the lamport
+    let mut rent = 0;
+    for account in [records[0], index] {
+        rent += context.banks_client.get_balance(account).await.unwrap();
+    }
+    let seller_balance = context.banks_client.get_balance(seller.pubkey()).await.unwrap();
+    let buyer_balance = context.banks_client.get_balance(buyer.pubkey()).await.unwrap();
+    process(&mut context, accept(&seller.pubkey()), &buyer, &[&buyer]).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(seller.pubkey()).await.unwrap(), seller_balance + rent);
+    assert!(context.banks_client.get_balance(buyer.pubkey()).await.unwrap() < buyer_balance);
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, buyer.pubkey());
+}
@@ -0,0 +1,249 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_address_account_address, find_auction_address, find_name_address},
+    state::{AddressClaim, AuctionAccount, ProgramConfig, ReverseRecord},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_one_registered_name_per_wallet() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    let register = |name: &str| {
+        let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, name);
+        instructions::with_address_claim(ix, &program_id, &alice.pubkey())
+    };
+
+    let ix = instructions::set_enforce_unique_address(&program_id, &alice.pubkey(), &config, true);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_enforce_unique_address(&program_id, &initializer.pubkey(), &config, true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_data: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert!(config_data.enforce_unique_address);
+
+    // Registrations must bring the claim along
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+
+    process(&mut context, register("alice"), &alice, &[&alice]).await.unwrap();
+    let alice_account = find_name_address("alice", &program_id).0;
+    let claim_account = AddressClaim::find_address(&alice.pubkey(), &program_id).0;
+    let claim: AddressClaim = get_account_data(&mut context, &claim_account).await;
+    assert_eq!((claim.wallet, claim.name_account), (alice.pubkey(), alice_account));
+
+    let result = process(&mut context, register("alice-2"), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameAlreadyRegistered);
+
+    // The claim stands while alice owns the name
+    let release = instructions::release_address_claim(&program_id, &alice.pubkey(), &alice_account);
+    let result = process(&mut context, release.clone(), &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::NameAlreadyRegistered);
+
+    let address_account = find_address_account_address(&alice_account, &program_id).0;
+    let ix = instructions::unregister_name(
+        &program_id,
+        &alice.pubkey(),
+        &alice_account,
+        &address_account,
+        &config,
+        &alice.pubkey(),
+    );
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+
+    // Anyone can then release it, refunding alice
+    let balance = context.banks_client.get_balance(alice.pubkey()).await.unwrap();
+    process(&mut context, release, &initializer, &[&initializer]).await.unwrap();
+    assert!(context.banks_client.get_account(claim_account).await.unwrap().is_none());
+    let refund = Rent::default().minimum_balance(AddressClaim::LEN);
+    assert_eq!(context.banks_client.get_balance(alice.pubkey()).await.unwrap(), balance + refund);
+    process(&mut context, register("alice-2"), &alice, &[&alice]).await.unwrap();
+
+    // Lifting the limit lets registrations go without a claim
+    let ix = instructions::set_enforce_unique_address(&program_id, &initializer.pubkey(), &config, false);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice-3");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_address_claims_do_not_collide_with_reverse_records() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    let ix = instructions::set_enforce_unique_address(&program_id, &initializer.pubkey(), &config, true);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let ix = instructions::with_address_claim(ix, &program_id, &alice.pubkey());
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let alice_account = find_name_address("alice", &program_id).0;
+
+    // A name gifted to alice's name account claims that account as a wallet,
+    // leaving the reverse record of "alice" alone
+    let ix = instructions::register_name_for(&program_id, &alice.pubkey(), &config, "vault", alice_account);
+    let ix = instructions::with_address_claim(ix, &program_id, &alice_account);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let claim_account = AddressClaim::find_address(&alice_account, &program_id).0;
+    let claim: AddressClaim = get_account_data(&mut context, &claim_account).await;
+    assert_eq!((claim.wallet, claim.name_account), (alice_account, find_name_address("vault", &program_id).0));
+    let reverse: ReverseRecord =
+        get_account_data(&mut context, &find_address_account_address(&alice_account, &program_id).0).await;
+    assert_eq!(reverse, ReverseRecord::new(alice.pubkey(), "alice".to_string()));
+}
+
+async fn enforce_unique_address(context: &mut ProgramTestContext, program_id: &Pubkey, owner: &Keypair, config: &Pubkey) {
+    let ix = instructions::set_enforce_unique_address(program_id, &owner.pubkey(), config, true);
+    process(context, ix, owner, &[owner]).await.unwrap();
+}
+
+async fn register_claimed(context: &mut ProgramTestContext, program_id: &Pubkey, owner: &Keypair, config: &Pubkey, name: &str) {
+    let ix = instructions::register_name(program_id, &owner.pubkey(), config, name);
+    let ix = instructions::with_address_claim(ix, program_id, &owner.pubkey());
+    process(context, ix, owner, &[owner]).await.unwrap();
+}
+
+async fn claimed_name(context: &mut ProgramTestContext, program_id: &Pubkey, wallet: &Pubkey) -> Option<Pubkey> {
+    let claim_account = AddressClaim::find_address(wallet, program_id).0;
+    context.banks_client.get_account(claim_account).await.unwrap()?;
+    let claim: AddressClaim = get_account_data(context, &claim_account).await;
+    Some(claim.name_account)
+}
+
+#[tokio::test]
+async fn test_transfers_move_the_address_claim() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let [alice, bob, carol, dave] = [Keypair::new(), Keypair::new(), Keypair::new(), Keypair::new()];
+    for wallet in [&alice, &bob, &carol, &dave] {
+        fund_account(&mut context, &wallet.pubkey(), 1_000_000_000).await;
+    }
+    enforce_unique_address(&mut context, &program_id, &initializer, &config).await;
+    register_claimed(&mut context, &program_id, &alice, &config, "alice").await;
+    register_claimed(&mut context, &program_id, &bob, &config, "bob").await;
+    let alice_account = find_name_address("alice", &program_id).0;
+    let bob_account = find_name_address("bob", &program_id).0;
+    let transfer = |new_owner: &Keypair| {
+        instructions::transfer_name(&program_id, &alice.pubkey(), &alice_account, &config, new_owner.pubkey(), false)
+    };
+
+    let result = process(&mut context, transfer(&carol), &alice, &[&alice]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    // Bob already holds a name
+    let ix = instructions::with_moved_address_claim(transfer(&bob), &program_id, &alice.pubkey(), &bob.pubkey());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameAlreadyRegistered);
+
+    let ix = instructions::with_moved_address_claim(transfer(&carol), &program_id, &alice.pubkey(), &carol.pubkey());
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    assert_eq!(claimed_name(&mut context, &program_id, &alice.pubkey()).await, None);
+    assert_eq!(claimed_name(&mut context, &program_id, &carol.pubkey()).await, Some(alice_account));
+    register_claimed(&mut context, &program_id, &alice, &config, "alice-2").await;
+
+    // An offer moves nothing until it is accepted
+    let ix = instructions::transfer_name(&program_id, &bob.pubkey(), &bob_account, &config, dave.pubkey(), true);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    assert_eq!(claimed_name(&mut context, &program_id, &bob.pubkey()).await, Some(bob_account));
+    let accept = || instructions::accept_name_transfer(&program_id, &dave.pubkey(), &bob.pubkey(), &bob_account, &config);
+    let result = process(&mut context, accept(), &dave, &[&dave]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::with_moved_address_claim(accept(), &program_id, &bob.pubkey(), &dave.pubkey());
+    process(&mut context, ix, &dave, &[&dave]).await.unwrap();
+    assert_eq!(claimed_name(&mut context, &program_id, &bob.pubkey()).await, None);
+    assert_eq!(claimed_name(&mut context, &program_id, &dave.pubkey()).await, Some(bob_account));
+}
+
+#[tokio::test]
+async fn test_address_updates_move_the_address_claim() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    // Registered before the limit, alice holds no claim on her name
+    register_name(&mut context, &program_id, &alice, &config, "legacy".to_string()).await;
+    enforce_unique_address(&mut context, &program_id, &initializer, &config).await;
+    register_claimed(&mut context, &program_id, &alice, &config, "alice").await;
+    let alice_account = find_name_address("alice", &program_id).0;
+    let legacy_account = find_name_address("legacy", &program_id).0;
+
+    let update = |name_account: &Pubkey, name: &str| {
+        let ix = instructions::request_address_update(&program_id, &alice.pubkey(), name_account, &config, bob.pubkey());
+        let complete = instructions::complete_address_update(&program_id, &bob.pubkey(), &alice.pubkey(), name, &config);
+        (ix, complete)
+    };
+    let (request, complete) = update(&alice_account, "alice");
+    process(&mut context, request, &alice, &[&alice]).await.unwrap();
+    let result = process(&mut context, complete.clone(), &bob, &[&bob]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::with_moved_address_claim(complete, &program_id, &alice.pubkey(), &bob.pubkey());
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    assert_eq!(claimed_name(&mut context, &program_id, &alice.pubkey()).await, None);
+    assert_eq!(claimed_name(&mut context, &program_id, &bob.pubkey()).await, Some(alice_account));
+
+    // Bob cannot take a second name by address update either
+    let (request, complete) = update(&legacy_account, "legacy");
+    process(&mut context, request, &alice, &[&alice]).await.unwrap();
+    let ix = instructions::with_moved_address_claim(complete, &program_id, &alice.pubkey(), &bob.pubkey());
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NameAlreadyRegistered);
+}
+
+#[tokio::test]
+async fn test_promo_claims_and_auctions_take_the_address_claim() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    enforce_unique_address(&mut context, &program_id, &initializer, &config).await;
+    register_claimed(&mut context, &program_id, &alice, &config, "alice").await;
+
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let preimage: &[u8] = b"qr-code-secret-0001";
+    let ix = instructions::create_promo_claims(&program_id, &initializer.pubkey(), &config, now + 3_600, &[(preimage, "gift")]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let claim = |claimer: &Keypair| {
+        let ix = instructions::claim_promo_name(&program_id, &claimer.pubkey(), &config, &initializer.pubkey(), "gift", preimage);
+        instructions::with_address_claim(ix, &program_id, &claimer.pubkey())
+    };
+    let result = process(&mut context, claim(&alice), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameAlreadyRegistered);
+    process(&mut context, claim(&bob), &bob, &[&bob]).await.unwrap();
+    let gift_account = find_name_address("gift", &program_id).0;
+    assert_eq!(claimed_name(&mut context, &program_id, &bob.pubkey()).await, Some(gift_account));
+
+    // The winner of an auction gets their claim taken on settlement
+    let carol = Keypair::new();
+    fund_account(&mut context, &carol.pubkey(), 1_000_000_000).await;
+    let ix = instructions::start_auction(&program_id, &initializer.pubkey(), &config, "sol", REGISTRATION_FEE);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::place_bid(&program_id, &carol.pubkey(), &config, "sol", None, REGISTRATION_FEE);
+    process(&mut context, ix, &carol, &[&carol]).await.unwrap();
+    let sol_account = find_name_address("sol", &program_id).0;
+    let auction: AuctionAccount =
+        get_account_data(&mut context, &find_auction_address(&sol_account, &program_id).0).await;
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = auction.ends_at;
+    context.set_sysvar(&clock);
+    let settle = instructions::settle_auction(&program_id, &carol.pubkey(), &config, &initializer.pubkey(), "sol");
+    let result = process(&mut context, settle.clone(), &carol, &[&carol]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::with_address_claim(settle, &program_id, &carol.pubkey());
+    process(&mut context, ix, &carol, &[&carol]).await.unwrap();
+    assert_eq!(claimed_name(&mut context, &program_id, &carol.pubkey()).await, Some(sol_account));
+}
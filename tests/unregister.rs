@@ -0,0 +1,84 @@
+mod common;
+
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_address_account_address, find_name_address},
+    state::{NameAccount, ProgramConfig, ReverseRecord},
+};
+
+use common::*;
+
+#[tokio::test]
+async fn test_unregister_name_refunds_rent() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+    let name_account = register_name(&mut context, &program_id, &alice, &config_account, "alice".to_string()).await;
+    let address_account = find_address_account_address(&name_account, &program_id).0;
+    let destination = Pubkey::new_unique();
+    let unregister = |owner: &Keypair| {
+        instructions::unregister_name(&program_id, &owner.pubkey(), &name_account, &address_account, &config, &destination)
+    };
+
+    let result = process(&mut context, unregister(&bob), &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotNameOwner);
+
+    // Records go first so they cannot outlive the name
+    let ix = instructions::set_record(&program_id, &alice.pubkey(), &name_account, "url", "https://alice.example");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let result = process(&mut context, unregister(&alice), &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NameHasRecords);
+    let ix = instructions::delete_record(&program_id, &alice.pubkey(), &name_account, "url");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+
+    process(&mut context, unregister(&alice), &alice, &[&alice]).await.unwrap();
+    let rent = Rent::default();
+    let refund = rent.minimum_balance(NameAccount::LEN) + rent.minimum_balance(ReverseRecord::LEN);
+    assert_eq!(context.banks_client.get_balance(destination).await.unwrap(), refund);
+    assert!(context.banks_client.get_account(name_account).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(address_account).await.unwrap().is_none());
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.total_names, 0);
+
+    // The name is free for anyone again
+    register_name(&mut context, &program_id, &bob, &config_account, "alice".to_string()).await;
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, bob.pubkey());
+}
+
+#[tokio::test]
+async fn test_unregister_renamed_name_closes_original_reverse_record() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    let original = register_name(&mut context, &program_id, &alice, &config_account, "carol".to_string()).await;
+    register_name(&mut context, &program_id, &alice, &config_account, "erin".to_string()).await;
+    let ix = instructions::rename_name(&program_id, &alice.pubkey(), "carol", &config, "dave");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let renamed = find_name_address("dave", &program_id).0;
+    let original_record = find_address_account_address(&original, &program_id).0;
+
+    // Another name's reverse record is not this name's to close
+    let other_record = find_address_account_address(&find_name_address("erin", &program_id).0, &program_id).0;
+    let ix = instructions::unregister_name(&program_id, &alice.pubkey(), &renamed, &other_record, &config, &alice.pubkey());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    let ix = instructions::unregister_name(&program_id, &alice.pubkey(), &renamed, &original_record, &config, &alice.pubkey());
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    assert!(context.banks_client.get_account(renamed).await.unwrap().is_none());
+    assert!(context.banks_client.get_account(original_record).await.unwrap().is_none());
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.total_names, 1);
+}
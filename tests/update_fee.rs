@@ -0,0 +1,66 @@
+mod common;
+
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{fee_bucket, find_treasury_address},
+    state::{AdminAction, NameAccount, PendingAdminAction, ProgramConfig},
+};
+
+use common::*;
+
+const UPDATE_FEE: u64 = 10_000_000;
+
+#[tokio::test]
+async fn test_new_owner_pays_update_fee_into_names_bucket() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+    let name_account = register_name(&mut context, &program_id, &initializer, &config, "alice".to_string()).await;
+    let bob = Keypair::new();
+    fund_account(&mut context, &bob.pubkey(), 1_000_000_000).await;
+
+    let ix = instructions::set_update_fee(&program_id, &bob.pubkey(), &config, 0);
+    let result = process(&mut context, ix, &bob, &[&bob]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    let ix = instructions::set_update_fee(&program_id, &owner, &config, UPDATE_FEE);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.update_fee, UPDATE_FEE);
+
+    let ix = instructions::request_address_update(&program_id, &owner, &name_account, &config, bob.pubkey());
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let treasury = find_treasury_address(&config, fee_bucket("alice"), &program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    let ix = instructions::complete_address_update(&program_id, &bob.pubkey(), &owner, "alice", &config);
+    process(&mut context, ix, &bob, &[&bob]).await.unwrap();
+    let after = context.banks_client.get_balance(treasury).await.unwrap();
+    assert_eq!(after - before, UPDATE_FEE);
+    let name: NameAccount = get_account_data(&mut context, &name_account).await;
+    assert_eq!(name.owner, bob.pubkey());
+}
+
+#[tokio::test]
+async fn test_update_fee_waits_out_admin_timelock() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+
+    let ix = instructions::set_admin_timelock(&program_id, &owner, &config, 60);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let ix = instructions::with_timelock(
+        instructions::set_update_fee(&program_id, &owner, &config, UPDATE_FEE),
+        &program_id,
+        &config,
+    );
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.update_fee, 0);
+    let pending: PendingAdminAction =
+        get_account_data(&mut context, &PendingAdminAction::find_address(&config, &program_id).0).await;
+    assert_eq!(pending.action, AdminAction::SetUpdateFee(UPDATE_FEE));
+}
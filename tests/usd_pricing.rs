@@ -0,0 +1,120 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    account::Account,
+    signature::{Keypair, Signer},
+};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    oracle::Price,
+    pda::{fee_bucket, find_treasury_address},
+    state::{FeeSchedule, ProgramConfig},
+};
+
+use common::*;
+
+/// $150.00 per SOL, give or take 15 cents.
+const SOL_PRICE: i64 = 15_000_000_000;
+const SOL_PRICE_CONFIDENCE: u64 = 15_000_000;
+/// $5.00 at `SOL_PRICE`.
+const FEE_CENTS: u64 = 500;
+const FEE_LAMPORTS: u64 = 33_333_333;
+
+async fn set_price(context: &mut ProgramTestContext, feed: &Pubkey, confidence: u64, age: i64) {
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    let price = Price { price: SOL_PRICE, confidence, exponent: -8, publish_time: now - age };
+    let data = price.pack();
+    let account = Account { lamports: 1_000_000_000, data, owner: Pubkey::new_unique(), ..Account::default() };
+    context.set_account(feed, &account.into());
+}
+
+#[tokio::test]
+async fn test_registration_fee_in_usd() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let feed = Pubkey::new_unique();
+    set_price(&mut context, &feed, SOL_PRICE_CONFIDENCE, 0).await;
+
+    let alice = Keypair::new();
+    fund_account(&mut context, &alice.pubkey(), 1_000_000_000).await;
+    let schedule = FeeSchedule::flat(FEE_CENTS);
+
+    let ix = instructions::set_usd_pricing(&program_id, &alice.pubkey(), &config, feed, schedule, 60, 50);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+    let ix = instructions::set_usd_pricing(&program_id, &initializer.pubkey(), &config, feed, schedule, 60, 50);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let config_data: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert!(config_data.usd_pricing_enabled);
+
+    // Registrations have to bring the price along
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let ix = instructions::with_usd_pricing(ix, &program_id, &config, &Pubkey::new_unique());
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::InvalidPriceFeed);
+
+    let treasury = find_treasury_address(&config, fee_bucket("alice"), &program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice");
+    let ix = instructions::with_usd_pricing(ix, &program_id, &config, &feed);
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+    let after = context.banks_client.get_balance(treasury).await.unwrap();
+    assert_eq!(after - before, FEE_LAMPORTS);
+
+    // Stale and uncertain prices are refused
+    set_price(&mut context, &feed, SOL_PRICE_CONFIDENCE, 61).await;
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice-2");
+    let ix = instructions::with_usd_pricing(ix, &program_id, &config, &feed);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::StalePrice);
+    set_price(&mut context, &feed, SOL_PRICE_CONFIDENCE * 10, 0).await;
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice-2");
+    let ix = instructions::with_usd_pricing(ix, &program_id, &config, &feed);
+    let result = process(&mut context, ix, &alice, &[&alice]).await;
+    assert_registry_error(result, NameRegistryError::PriceTooUncertain);
+
+    // The default feed goes back to lamport pricing
+    let ix =
+        instructions::set_usd_pricing(&program_id, &initializer.pubkey(), &config, Pubkey::default(), schedule, 60, 50);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::register_name(&program_id, &alice.pubkey(), &config, "alice-2");
+    process(&mut context, ix, &alice, &[&alice]).await.unwrap();
+
+    // Pricing changes cannot skip an admin timelock
+    let ix = instructions::set_admin_timelock(&program_id, &initializer.pubkey(), &config, 3_600);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::set_usd_pricing(&program_id, &initializer.pubkey(), &config, feed, schedule, 60, 50);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::TimelockActive);
+}
+
+#[tokio::test]
+async fn test_usd_pricing_goes_through_the_multisig() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let feed = Pubkey::new_unique();
+    let schedule = FeeSchedule::flat(FEE_CENTS);
+    let [a, b] = [Keypair::new(), Keypair::new()];
+    fund_account(&mut context, &a.pubkey(), 1_000_000_000).await;
+    let members = vec![a.pubkey(), b.pubkey()];
+    let ix = instructions::set_multisig(&program_id, &initializer.pubkey(), &config, members, 2, &[]);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let ix = instructions::set_usd_pricing(&program_id, &initializer.pubkey(), &config, feed, schedule, 60, 50);
+    let ix = instructions::with_multisig(ix, &program_id, &config, &[]);
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::MultisigThresholdNotMet);
+
+    let ix = instructions::set_usd_pricing(&program_id, &a.pubkey(), &config, feed, schedule, 60, 50);
+    let ix = instructions::with_multisig(ix, &program_id, &config, &[b.pubkey()]);
+    process(&mut context, ix, &a, &[&a, &b]).await.unwrap();
+    let config_data: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert!(config_data.usd_pricing_enabled);
+}
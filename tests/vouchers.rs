@@ -0,0 +1,100 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{fee_bucket, find_name_address, find_treasury_address},
+    state::NameAccount,
+    voucher::voucher_message,
+};
+
+use common::*;
+
+/// Signs a voucher as `signer` and submits it with its verify instruction.
+#[allow(clippy::too_many_arguments)]
+async fn register_with_voucher(
+    context: &mut ProgramTestContext,
+    program_id: &Pubkey,
+    config: &Pubkey,
+    signer: &Keypair,
+    registrant: &Keypair,
+    name: &str,
+    discount_bps: u16,
+    expiry: i64,
+) -> Result<(), BanksClientError> {
+    let message = voucher_message(config, &registrant.pubkey(), name, discount_bps, expiry);
+    let signature: [u8; 64] = signer.sign_message(&message).into();
+    let verify =
+        instructions::voucher_verify(&signer.pubkey(), config, &registrant.pubkey(), name, discount_bps, expiry, signature);
+    let register =
+        instructions::register_with_voucher(program_id, &registrant.pubkey(), config, name, discount_bps, expiry, signature);
+    submit(context, &[verify, register], registrant).await
+}
+
+async fn submit(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> Result<(), BanksClientError> {
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &[payer], blockhash);
+    context.banks_client.process_transaction(tx).await
+}
+
+#[tokio::test]
+async fn test_register_with_voucher() {
+    let (mut context, initializer, config_account, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config_account, REGISTRATION_FEE).await;
+    let config = config_account;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+
+    let [alice, bob] = [Keypair::new(), Keypair::new()];
+    for wallet in [&alice, &bob] {
+        fund_account(&mut context, &wallet.pubkey(), 1_000_000_000).await;
+    }
+
+    // A quarter off, paid into the usual treasury bucket
+    let treasury = find_treasury_address(&config, fee_bucket("alice"), &program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    register_with_voucher(&mut context, &program_id, &config, &initializer, &alice, "alice", 2_500, now + 60)
+        .await
+        .unwrap();
+    let after = context.banks_client.get_balance(treasury).await.unwrap();
+    assert_eq!(after - before, REGISTRATION_FEE * 3 / 4);
+    let name: NameAccount = get_account_data(&mut context, &find_name_address("alice", &program_id).0).await;
+    assert_eq!(name.owner, alice.pubkey());
+
+    // A full discount registers for free
+    let treasury = find_treasury_address(&config, fee_bucket("alice-2"), &program_id).0;
+    let before = context.banks_client.get_balance(treasury).await.unwrap();
+    register_with_voucher(&mut context, &program_id, &config, &initializer, &alice, "alice-2", 10_000, now + 60)
+        .await
+        .unwrap();
+    assert_eq!(context.banks_client.get_balance(treasury).await.unwrap(), before);
+
+    // Only the program owner signs vouchers, and only for the registrant
+    let result = register_with_voucher(&mut context, &program_id, &config, &bob, &bob, "bob", 10_000, now + 60).await;
+    assert_registry_error(result, NameRegistryError::InvalidVoucher);
+    let message = voucher_message(&config, &alice.pubkey(), "bob", 10_000, now + 60);
+    let signature: [u8; 64] = initializer.sign_message(&message).into();
+    let verify =
+        instructions::voucher_verify(&initializer.pubkey(), &config, &alice.pubkey(), "bob", 10_000, now + 60, signature);
+    let register =
+        instructions::register_with_voucher(&program_id, &bob.pubkey(), &config, "bob", 10_000, now + 60, signature);
+    let result = submit(&mut context, &[verify, register.clone()], &bob).await;
+    assert_registry_error(result, NameRegistryError::InvalidVoucher);
+
+    // The signature has to be verified in the same transaction
+    let result = submit(&mut context, &[register], &bob).await;
+    assert_registry_error(result, NameRegistryError::InvalidVoucher);
+
+    let result =
+        register_with_voucher(&mut context, &program_id, &config, &initializer, &bob, "bob", 10_000, now - 1).await;
+    assert_registry_error(result, NameRegistryError::VoucherExpired);
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use solana_program::{clock::Clock, instruction::InstructionError, pubkey::Pubkey, rent::Rent};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::signature::{Keypair, Signer};
+use instant_folio::{
+    client::instructions,
+    error::NameRegistryError,
+    pda::{find_fee_vault_address, FEE_BUCKETS},
+    state::ProgramConfig,
+};
+
+use common::*;
+
+async fn warp_past(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    clock.unix_timestamp = unix_timestamp + 1;
+    context.set_sysvar(&clock);
+}
+
+async fn vault_balance(context: &mut ProgramTestContext, config: &Pubkey, program_id: &Pubkey, generation: u8) -> u64 {
+    let mut balance = 0;
+    for bucket in 0..FEE_BUCKETS {
+        let treasury = find_fee_vault_address(config, generation, bucket, program_id).0;
+        balance += context.banks_client.get_balance(treasury).await.unwrap();
+    }
+    balance
+}
+
+#[tokio::test]
+async fn test_partial_withdraw_to_destination() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+    register_name(&mut context, &program_id, &initializer, &config, "alice".to_string()).await;
+    register_name(&mut context, &program_id, &initializer, &config, "bob".to_string()).await;
+    let dao = Keypair::new().pubkey();
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = instructions::withdraw(&program_id, &stranger.pubkey(), &config, &stranger.pubkey(), None);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    // Fees go to the destination, not the signer
+    let ix = instructions::withdraw(&program_id, &owner, &config, &dao, Some(REGISTRATION_FEE * 3 / 2));
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(dao).await.unwrap(), REGISTRATION_FEE * 3 / 2);
+
+    let ix = instructions::withdraw(&program_id, &owner, &config, &dao, Some(REGISTRATION_FEE));
+    let result = process(&mut context, ix, &initializer, &[&initializer]).await;
+    assert_registry_error(result, NameRegistryError::WithdrawExceedsBalance);
+
+    // The rest sweeps out, leaving every bucket rent-exempt
+    let ix = instructions::withdraw(&program_id, &owner, &config, &dao, None);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    assert_eq!(context.banks_client.get_balance(dao).await.unwrap(), REGISTRATION_FEE * 2);
+    for treasury in treasury_buckets(&config, &program_id) {
+        let balance = context.banks_client.get_balance(treasury).await.unwrap();
+        assert_eq!(balance, Rent::default().minimum_balance(0));
+    }
+}
+
+#[tokio::test]
+async fn test_rotated_fee_vault_takes_the_fees() {
+    let (mut context, initializer, config, program_id) = setup_program().await;
+    initialize_program(&mut context, &program_id, &initializer, &config, REGISTRATION_FEE).await;
+    let owner = initializer.pubkey();
+    register_name(&mut context, &program_id, &initializer, &config, "alice".to_string()).await;
+    register_name(&mut context, &program_id, &initializer, &config, "bob".to_string()).await;
+    let rent = Rent::default().minimum_balance(0);
+
+    let stranger = Keypair::new();
+    fund_account(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = instructions::rotate_fee_vault(&program_id, &stranger.pubkey(), &config, 0);
+    let result = process(&mut context, ix, &stranger, &[&stranger]).await;
+    assert_registry_error(result, NameRegistryError::NotContractOwner);
+
+    // Every fee moves into the new buckets and the old ones close
+    let collected = vault_balance(&mut context, &config, &program_id, 0).await;
+    let ix = instructions::rotate_fee_vault(&program_id, &owner, &config, 0);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    assert_eq!(vault_balance(&mut context, &config, &program_id, 0).await, 0);
+    let moved = vault_balance(&mut context, &config, &program_id, 1).await;
+    assert_eq!(moved, collected + rent * FEE_BUCKETS as u64);
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.fee_vault_generation, 1);
+
+    // Fees only go to the new buckets from now on
+    let register = || instructions::register_name(&program_id, &owner, &config, "carol");
+    let result = process(&mut context, register(), &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+    let ix = instructions::with_fee_vault_generation(register(), &program_id, &config, 1);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+
+    let dao = Keypair::new().pubkey();
+    let withdraw = || instructions::withdraw(&program_id, &owner, &config, &dao, None);
+    let result = process(&mut context, withdraw(), &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+    let ix = instructions::with_fee_vault_generation(withdraw(), &program_id, &config, 1);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    // The retired buckets' rent comes out along with the fees
+    let withdrawn = context.banks_client.get_balance(dao).await.unwrap();
+    assert_eq!(withdrawn, REGISTRATION_FEE * 3 + rent * FEE_BUCKETS as u64);
+
+    // With a timelock the rotation queues, and executing it takes the buckets
+    let ix = instructions::set_admin_timelock(&program_id, &owner, &config, 3600);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    let ix = instructions::rotate_fee_vault(&program_id, &owner, &config, 1);
+    process(&mut context, instructions::with_timelock(ix, &program_id, &config), &initializer, &[&initializer])
+        .await
+        .unwrap();
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.fee_vault_generation, 1);
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+    warp_past(&mut context, now + 3600).await;
+
+    let execute = || instructions::execute_admin_action(&program_id, &owner, &config);
+    let result = process(&mut context, execute(), &initializer, &[&initializer]).await;
+    assert_instruction_error(result, InstructionError::NotEnoughAccountKeys);
+    let ix = instructions::with_fee_vault_rotation(execute(), &program_id, &config, 1);
+    process(&mut context, ix, &initializer, &[&initializer]).await.unwrap();
+    assert_eq!(vault_balance(&mut context, &config, &program_id, 1).await, 0);
+    assert_eq!(vault_balance(&mut context, &config, &program_id, 2).await, rent * FEE_BUCKETS as u64 * 2);
+    let config_state: ProgramConfig = get_account_data(&mut context, &config).await;
+    assert_eq!(config_state.fee_vault_generation, 2);
+}